@@ -14,8 +14,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             "proto/product.proto",
             "proto/category.proto",
             "proto/events.proto",
+            "../inventory/proto/inventory.proto",
+            "../price/proto/offer.proto",
+        ],
+        &[
+            "proto/",
+            "../shared-proto/proto/",
+            "../inventory/proto/",
+            "../price/proto/",
         ],
-        &["proto/", "../shared-proto/proto/"],
     )?;
 
     // Now extract NATS configuration from proto files
@@ -169,6 +176,8 @@ fn generate_nats_config() -> Result<(), Box<dyn std::error::Error>> {
     println!("cargo:rerun-if-changed=proto/product.proto");
     println!("cargo:rerun-if-changed=proto/category.proto");
     println!("cargo:rerun-if-changed=proto/events.proto");
+    println!("cargo:rerun-if-changed=../inventory/proto/inventory.proto");
+    println!("cargo:rerun-if-changed=../price/proto/offer.proto");
 
     Ok(())
 }
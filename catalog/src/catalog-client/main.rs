@@ -1,16 +1,35 @@
 use catalog_messages::{
-    CategoryExportRequest, CategoryExportResponse, CategoryImportRequest, CategoryImportResponse,
-    CategoryResponse, CategoryTreeRequest, CategoryTreeResponse, CreateCategoryRequest,
-    DeleteCategoryRequest, GetCategoryBySlugRequest, GetCategoryBySlugResponse, GetCategoryRequest,
-    GetCategoryResponse, GetProductSlugsRequest, GetProductSlugsResponse, ProductCreateRequest,
-    ProductCreateResponse, ProductDeleteRequest, ProductDeleteResponse, ProductExportRequest,
-    ProductExportResponse, ProductGetBySlugRequest, ProductGetBySlugResponse, ProductGetRequest,
-    ProductGetResponse, ProductSearchRequest, ProductSearchResponse, UpdateCategoryRequest,
+    stage_catalog_change_request, CategoryCsvImportRequest, CategoryCsvImportResponse,
+    CategoryExportRequest, CategoryExportResponse, CategoryImportAction, CategoryImportRequest,
+    CategoryImportResponse, CategoryResponse, CategoryTreeRequest, CategoryTreeResponse,
+    CloneProductRequest, CloneProductResponse, CompareProductsRequest, CompareProductsResponse,
+    CreateCategoryRequest, DeleteCategoryRequest, DiscardStagedChangesRequest,
+    DiscardStagedChangesResponse, GenerateSitemapRequest, GenerateSitemapResponse,
+    GenerateVariantsRequest, GenerateVariantsResponse, GetAttributeSchemaRequest,
+    GetAttributeSchemaResponse, GetBreadcrumbsRequest, GetBreadcrumbsResponse,
+    GetBundleQuoteRequest, GetBundleQuoteResponse, GetCategoryBySlugRequest,
+    GetCategoryBySlugResponse, GetCategoryRequest, GetCategoryResponse, GetProductSlugsRequest,
+    GetProductSlugsResponse, GetProductStructuredDataRequest, GetProductStructuredDataResponse,
+    GetRelatedProductsRequest, GetRelatedProductsResponse, ListAttributeSchemasRequest,
+    ListAttributeSchemasResponse, ListCatalogVersionsRequest, ListCatalogVersionsResponse,
+    ListProductRevisionsRequest, ListProductRevisionsResponse, ListReviewsRequest,
+    ListReviewsResponse, ListStagedChangesRequest, ListStagedChangesResponse,
+    MergeCategoriesRequest, MergeCategoriesResponse, ModerateReviewRequest, ModerateReviewResponse,
+    MoveCategoryRequest, MoveCategoryResponse, ProductCreateRequest, ProductCreateResponse,
+    ProductDeleteRequest, ProductDeleteResponse, ProductExportRequest, ProductExportResponse,
+    ProductGetBySlugRequest, ProductGetBySlugResponse, ProductGetRequest, ProductGetResponse,
+    ProductRestoreRequest, ProductRestoreResponse, ProductSearchRequest, ProductSearchResponse,
+    PublishCatalogRequest, PublishCatalogResponse, RestoreCategoryRequest, RestoreCategoryResponse,
+    RevertProductRequest, RevertProductResponse, RollbackCatalogRequest, RollbackCatalogResponse,
+    StageCatalogChangeRequest, StageCatalogChangeResponse, SubmitReviewRequest,
+    SubmitReviewResponse, UpdateCategoryRequest, UpsertAttributeSchemaRequest,
+    UpsertAttributeSchemaResponse,
 };
 use clap::{Parser, Subcommand};
 use log::debug;
 use prost::Message;
-use rust_catalog::Product;
+use rust_catalog::services::{inventory_client, price_client};
+use rust_catalog::{FeedSettings, MerchantFeed, Product};
 use rust_common::load_environment;
 
 use std::collections::HashMap;
@@ -44,6 +63,15 @@ fn product_to_create_request(product: &Product) -> ProductCreateRequest {
         display_on_site: product.display_on_site,
         tax_code: product.tax_code.clone(),
         related_products: product.related_products.clone(),
+        relations: product
+            .relations
+            .iter()
+            .map(|r| catalog_messages::ProductRelation {
+                product_id: r.product_id.clone(),
+                kind: relation_kind_to_proto(r.kind).into(),
+                bidirectional: r.bidirectional,
+            })
+            .collect(),
         reviews: product.reviews.as_ref().map(|r| catalog_messages::Reviews {
             bayesian_avg: r.bayesian_avg.into(),
             count: r.count,
@@ -54,6 +82,13 @@ fn product_to_create_request(product: &Product) -> ProductCreateRequest {
                 lvl0: hc.lvl0.clone(),
                 lvl1: hc.lvl1.clone(),
                 lvl2: hc.lvl2.clone(),
+                levels: hc
+                    .levels
+                    .iter()
+                    .map(|paths| catalog_messages::HierarchicalCategoryLevel {
+                        paths: paths.clone(),
+                    })
+                    .collect(),
             }
         }),
         list_categories: product.list_categories.clone(),
@@ -71,23 +106,403 @@ fn product_to_create_request(product: &Product) -> ProductCreateRequest {
                 height: v.height,
                 width: v.width,
                 length: v.length,
+                dimension_unit: v.dimension_unit.map(String::from),
                 weight: v.weight,
-                weight_unit: v.weight_unit.clone(),
+                weight_unit: v.weight_unit.map(String::from),
                 packaging: v.packaging.as_ref().map(|p| catalog_messages::Packaging {
                     height: p.height,
                     width: p.width,
                     length: p.length,
+                    dimension_unit: p.dimension_unit.map(String::from),
                     weight: p.weight,
-                    weight_unit: p.weight_unit.clone(),
+                    weight_unit: p.weight_unit.map(String::from),
+                    dimensional_weight: None,
                 }),
                 image_urls: v.image_urls.clone(),
             })
             .collect(),
+        publish_at: product.publish_at.map(|dt| prost_types::Timestamp {
+            seconds: dt.timestamp(),
+            nanos: dt.timestamp_subsec_nanos() as i32,
+        }),
+        unpublish_at: product.unpublish_at.map(|dt| prost_types::Timestamp {
+            seconds: dt.timestamp(),
+            nanos: dt.timestamp_subsec_nanos() as i32,
+        }),
+        localizations: product
+            .localizations
+            .iter()
+            .map(|(locale, content)| {
+                (
+                    locale.clone(),
+                    catalog_messages::LocalizedProductContent {
+                        name: content.name.clone(),
+                        long_description: content.long_description.clone(),
+                        seo_title: content.seo_title.clone(),
+                        seo_description: content.seo_description.clone(),
+                        seo_keywords: content.seo_keywords.clone(),
+                        slug: content.slug.clone(),
+                    },
+                )
+            })
+            .collect(),
+        stores: product
+            .stores
+            .iter()
+            .map(|(store_id, settings)| {
+                (
+                    store_id.clone(),
+                    catalog_messages::ProductStoreSettings {
+                        display_on_site: settings.display_on_site,
+                        slug: settings.slug.clone(),
+                    },
+                )
+            })
+            .collect(),
+        bundle: product
+            .bundle
+            .as_ref()
+            .map(|bundle| catalog_messages::ProductBundle {
+                components: bundle
+                    .components
+                    .iter()
+                    .map(|c| catalog_messages::BundleComponent {
+                        sku: c.sku.clone(),
+                        quantity: c.quantity,
+                    })
+                    .collect(),
+                pricing: match bundle.pricing {
+                    rust_catalog::BundlePricing::OwnOffer => {
+                        catalog_messages::BundlePricing::OwnOffer
+                    }
+                    rust_catalog::BundlePricing::ComponentSum => {
+                        catalog_messages::BundlePricing::ComponentSum
+                    }
+                }
+                .into(),
+            }),
     };
     debug!("ProductCreateRequest: {pcr:?}");
     pcr
 }
 
+// Helper function to convert a proto Product back into the domain Product
+fn proto_product_to_domain(proto_product: catalog_messages::Product) -> Product {
+    Product {
+        id: proto_product.id,
+        name: proto_product.name,
+        long_description: proto_product.long_description,
+        brand: proto_product.brand,
+        slug: proto_product.slug,
+        product_ref: proto_product.product_ref,
+        product_type: proto_product.product_type,
+        seo_title: proto_product.seo_title,
+        seo_description: proto_product.seo_description,
+        seo_keywords: proto_product.seo_keywords,
+        display_on_site: proto_product.display_on_site,
+        tax_code: proto_product.tax_code,
+        related_products: proto_product.related_products,
+        relations: proto_product
+            .relations
+            .into_iter()
+            .map(|r| rust_catalog::ProductRelation {
+                kind: match r.kind() {
+                    catalog_messages::RelationKind::Related => rust_catalog::RelationKind::Related,
+                    catalog_messages::RelationKind::Accessory => {
+                        rust_catalog::RelationKind::Accessory
+                    }
+                    catalog_messages::RelationKind::Upsell => rust_catalog::RelationKind::Upsell,
+                    catalog_messages::RelationKind::Replacement => {
+                        rust_catalog::RelationKind::Replacement
+                    }
+                },
+                product_id: r.product_id,
+                bidirectional: r.bidirectional,
+            })
+            .collect(),
+        reviews: proto_product.reviews.map(|r| rust_catalog::Reviews {
+            bayesian_avg: r.bayesian_avg.into(),
+            count: r.count,
+            rating: r.rating,
+        }),
+        hierarchical_categories: proto_product.hierarchical_categories.map(|hc| {
+            rust_catalog::HierarchicalCategories {
+                lvl0: hc.lvl0,
+                lvl1: hc.lvl1,
+                lvl2: hc.lvl2,
+                levels: hc.levels.into_iter().map(|level| level.paths).collect(),
+            }
+        }),
+        list_categories: proto_product.list_categories,
+        created_at: proto_product.created_at.map(|ts| {
+            use chrono::{DateTime, Utc};
+            DateTime::<Utc>::from_timestamp(ts.seconds, ts.nanos as u32).unwrap()
+        }),
+        updated_at: proto_product.updated_at.map(|ts| {
+            use chrono::{DateTime, Utc};
+            DateTime::<Utc>::from_timestamp(ts.seconds, ts.nanos as u32).unwrap()
+        }),
+        created_by: proto_product.created_by,
+        updated_by: proto_product.updated_by,
+        defining_attributes: proto_product.defining_attributes,
+        descriptive_attributes: proto_product.descriptive_attributes,
+        default_variant: proto_product.default_variant,
+        localizations: proto_product
+            .localizations
+            .into_iter()
+            .map(|(locale, content)| {
+                (
+                    locale,
+                    rust_catalog::LocalizedProductContent {
+                        name: content.name,
+                        long_description: content.long_description,
+                        seo_title: content.seo_title,
+                        seo_description: content.seo_description,
+                        seo_keywords: content.seo_keywords,
+                        slug: content.slug,
+                    },
+                )
+            })
+            .collect(),
+        stores: proto_product
+            .stores
+            .into_iter()
+            .map(|(store_id, settings)| {
+                (
+                    store_id,
+                    rust_catalog::ProductStoreSettings {
+                        display_on_site: settings.display_on_site,
+                        slug: settings.slug,
+                    },
+                )
+            })
+            .collect(),
+        variants: proto_product
+            .variants
+            .into_iter()
+            .map(|v| rust_catalog::ProductVariant {
+                sku: v.sku,
+                defining_attributes: Some(v.defining_attributes),
+                abbreviated_color: v.abbreviated_color,
+                abbreviated_size: v.abbreviated_size,
+                height: v.height,
+                width: v.width,
+                length: v.length,
+                // The service only returns units it has normalized
+                dimension_unit: v.dimension_unit.and_then(|unit| unit.parse().ok()),
+                weight: v.weight,
+                weight_unit: v.weight_unit.and_then(|unit| unit.parse().ok()),
+                packaging: v.packaging.map(|p| rust_catalog::Packaging {
+                    height: p.height,
+                    width: p.width,
+                    length: p.length,
+                    dimension_unit: p.dimension_unit.and_then(|unit| unit.parse().ok()),
+                    weight: p.weight,
+                    weight_unit: p.weight_unit.and_then(|unit| unit.parse().ok()),
+                }),
+                image_urls: v.image_urls,
+            })
+            .collect(),
+        publish_at: proto_product.publish_at.map(|ts| {
+            use chrono::{DateTime, Utc};
+            DateTime::<Utc>::from_timestamp(ts.seconds, ts.nanos as u32).unwrap()
+        }),
+        unpublish_at: proto_product.unpublish_at.map(|ts| {
+            use chrono::{DateTime, Utc};
+            DateTime::<Utc>::from_timestamp(ts.seconds, ts.nanos as u32).unwrap()
+        }),
+        bundle: proto_product
+            .bundle
+            .map(|bundle| rust_catalog::ProductBundle {
+                pricing: match bundle.pricing() {
+                    catalog_messages::BundlePricing::OwnOffer => {
+                        rust_catalog::BundlePricing::OwnOffer
+                    }
+                    catalog_messages::BundlePricing::ComponentSum => {
+                        rust_catalog::BundlePricing::ComponentSum
+                    }
+                },
+                components: bundle
+                    .components
+                    .into_iter()
+                    .map(|c| rust_catalog::BundleComponent {
+                        sku: c.sku,
+                        quantity: c.quantity,
+                    })
+                    .collect(),
+            }),
+        deleted_at: None,
+    }
+}
+
+// Helper function to parse "color=Black:BLK,White:WHT" into a variant axis
+fn parse_variant_axis(axis: &str) -> Result<catalog_messages::VariantAxis, String> {
+    let (key, values) = axis
+        .split_once('=')
+        .ok_or_else(|| format!("Invalid axis '{axis}', expected key=value,..."))?;
+
+    let mut variant_axis = catalog_messages::VariantAxis {
+        key: key.trim().to_string(),
+        ..Default::default()
+    };
+    for value in values.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+        match value.split_once(':') {
+            Some((value, code)) => {
+                variant_axis.values.push(value.to_string());
+                variant_axis
+                    .abbreviations
+                    .insert(value.to_string(), code.to_string());
+            }
+            None => variant_axis.values.push(value.to_string()),
+        }
+    }
+    Ok(variant_axis)
+}
+
+// Helper function to convert a domain relation kind into its proto form
+fn relation_kind_to_proto(kind: rust_catalog::RelationKind) -> catalog_messages::RelationKind {
+    match kind {
+        rust_catalog::RelationKind::Related => catalog_messages::RelationKind::Related,
+        rust_catalog::RelationKind::Accessory => catalog_messages::RelationKind::Accessory,
+        rust_catalog::RelationKind::Upsell => catalog_messages::RelationKind::Upsell,
+        rust_catalog::RelationKind::Replacement => catalog_messages::RelationKind::Replacement,
+    }
+}
+
+// Helper function to parse "accessory" into a relation kind
+fn parse_relation_kind(kind: &str) -> Result<catalog_messages::RelationKind, String> {
+    match kind.trim().to_lowercase().as_str() {
+        "related" => Ok(catalog_messages::RelationKind::Related),
+        "accessory" => Ok(catalog_messages::RelationKind::Accessory),
+        "upsell" => Ok(catalog_messages::RelationKind::Upsell),
+        "replacement" => Ok(catalog_messages::RelationKind::Replacement),
+        _ => Err(format!(
+            "Invalid relation kind '{kind}', expected related, accessory, upsell or replacement"
+        )),
+    }
+}
+
+fn print_catalog_version(version: &catalog_messages::CatalogVersion) {
+    let rollback = version
+        .rolled_back_to
+        .map(|to| format!(", rolled back to {to}"))
+        .unwrap_or_default();
+    println!(
+        "📚 Version {} ({:?}{rollback}): {} change(s)",
+        version.version,
+        version.status(),
+        version.changes.len()
+    );
+    for change in &version.changes {
+        println!(
+            "  {:?} {:?} {} {}",
+            change.action(),
+            change.entity_type(),
+            change.entity_id,
+            change.name
+        );
+    }
+    if let Some(error) = &version.error {
+        println!("  ❌ {error}");
+    }
+}
+
+// Helper function to parse "-BLK-=-OLV-" into a SKU rewrite
+fn parse_sku_rewrite(rewrite: &str) -> Result<catalog_messages::SkuRewrite, String> {
+    let (find, replace) = rewrite
+        .split_once('=')
+        .ok_or_else(|| format!("Invalid SKU rewrite '{rewrite}', expected find=replace"))?;
+    if find.is_empty() {
+        return Err(format!(
+            "Invalid SKU rewrite '{rewrite}', find cannot be empty"
+        ));
+    }
+    Ok(catalog_messages::SkuRewrite {
+        find: find.to_string(),
+        replace: replace.to_string(),
+    })
+}
+
+// Helper function to convert a domain attribute definition into its proto form
+fn domain_attribute_definition_to_proto(
+    definition: rust_catalog::AttributeDefinition,
+) -> catalog_messages::AttributeDefinition {
+    use rust_catalog::{AttributeDataType, AttributeScope};
+
+    catalog_messages::AttributeDefinition {
+        key: definition.key,
+        scope: match definition.scope {
+            AttributeScope::Defining => catalog_messages::AttributeScope::Defining,
+            AttributeScope::Descriptive => catalog_messages::AttributeScope::Descriptive,
+        }
+        .into(),
+        data_type: match definition.data_type {
+            AttributeDataType::String => catalog_messages::AttributeDataType::String,
+            AttributeDataType::Integer => catalog_messages::AttributeDataType::Integer,
+            AttributeDataType::Decimal => catalog_messages::AttributeDataType::Decimal,
+            AttributeDataType::Boolean => catalog_messages::AttributeDataType::Boolean,
+            AttributeDataType::Enum => catalog_messages::AttributeDataType::Enum,
+        }
+        .into(),
+        allowed_values: definition.allowed_values,
+        required: definition.required,
+    }
+}
+
+fn print_attribute_schema(schema: &catalog_messages::AttributeSchema) {
+    println!("  📐 {}", schema.product_type);
+    for definition in &schema.attributes {
+        print!(
+            "    {:?}.{} ({:?})",
+            definition.scope(),
+            definition.key,
+            definition.data_type()
+        );
+        if definition.required {
+            print!(" required");
+        }
+        if !definition.allowed_values.is_empty() {
+            print!(" [{}]", definition.allowed_values.join(", "));
+        }
+        println!();
+    }
+}
+
+/// Parse a `locale=url` pair
+fn parse_locale_url(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((locale, url)) if !locale.is_empty() && !url.is_empty() => {
+            Ok((locale.to_string(), url.to_string()))
+        }
+        _ => Err(format!("Invalid locale URL '{value}', expected locale=url")),
+    }
+}
+
+fn parse_review_status(status: &str) -> Result<catalog_messages::ReviewStatus, String> {
+    match status.to_lowercase().as_str() {
+        "pending" => Ok(catalog_messages::ReviewStatus::Pending),
+        "approved" | "approve" => Ok(catalog_messages::ReviewStatus::Approved),
+        "rejected" | "reject" => Ok(catalog_messages::ReviewStatus::Rejected),
+        _ => Err(format!(
+            "Invalid review status '{status}', expected pending, approved or rejected"
+        )),
+    }
+}
+
+fn print_review(review: &catalog_messages::Review) {
+    println!(
+        "  ⭐ {} [{:?}] {}/5 by {} - {}",
+        review.id,
+        review.status(),
+        review.rating,
+        review.author.as_deref().unwrap_or("anonymous"),
+        review.title.as_deref().unwrap_or("(no title)")
+    );
+    if let Some(body) = &review.body {
+        println!("    {body}");
+    }
+}
+
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 struct Cli {
@@ -108,15 +523,28 @@ enum Commands {
     ProductGet {
         #[arg(short, long)]
         id: String,
+        #[arg(long, help = "Resolve localized content, e.g. fr-CA")]
+        locale: Option<String>,
+        #[arg(
+            long,
+            help = "Read staged edits ('draft') or a published version number; only gets by id are versioned"
+        )]
+        catalog_version: Option<String>,
     },
     ProductGetBySlug {
         #[arg(short, long)]
         slug: String,
+        #[arg(long, help = "Also match localized slugs, e.g. fr-CA")]
+        locale: Option<String>,
     },
     ProductDelete {
         #[arg(short, long)]
         id: String,
     },
+    ProductRestore {
+        #[arg(short, long)]
+        id: String,
+    },
     ProductSearch {
         #[arg(short, long)]
         query: Option<String>,
@@ -124,6 +552,10 @@ enum Commands {
         category: Option<String>,
         #[arg(short, long)]
         brand: Option<String>,
+        #[arg(long, help = "Also search localized content, e.g. fr-CA")]
+        locale: Option<String>,
+        #[arg(long, help = "Only products in this store's assortment")]
+        store_id: Option<String>,
     },
     Import {
         #[arg(short, long)]
@@ -150,10 +582,19 @@ enum Commands {
     CategoryGet {
         #[arg(short, long)]
         id: String,
+        #[arg(long, help = "Resolve localized content, e.g. fr-CA")]
+        locale: Option<String>,
+        #[arg(
+            long,
+            help = "Read staged edits ('draft') or a published version number; only gets by id are versioned"
+        )]
+        catalog_version: Option<String>,
     },
     CategoryGetBySlug {
         #[arg(short, long)]
         slug: String,
+        #[arg(long, help = "Also match localized slugs, e.g. fr-CA")]
+        locale: Option<String>,
     },
     CategoryUpdate {
         #[arg(short, long)]
@@ -173,6 +614,28 @@ enum Commands {
         #[arg(short, long)]
         id: String,
     },
+    CategoryRestore {
+        #[arg(short, long)]
+        id: String,
+    },
+    CategoryMove {
+        #[arg(short, long)]
+        id: String,
+        #[arg(long, help = "New parent category ID; omit to move to the root")]
+        parent_id: Option<String>,
+    },
+    CategoryMerge {
+        #[arg(short, long, help = "Category to fold into the target and delete")]
+        source_id: String,
+        #[arg(
+            short,
+            long,
+            help = "Category that takes over the source's children and products"
+        )]
+        target_id: String,
+        #[arg(long, help = "Only report what would change")]
+        dry_run: bool,
+    },
     CategoryExport {
         #[arg(short, long)]
         file: PathBuf,
@@ -185,9 +648,25 @@ enum Commands {
         #[arg(short, long, default_value = "false")]
         dry_run: bool,
     },
+    CategoryImportCsv {
+        #[arg(
+            short,
+            long,
+            help = "CSV with a path column, e.g. \"Apparel > Men > Shirts\""
+        )]
+        file: PathBuf,
+        #[arg(long, help = "Separator between names in the path column, default >")]
+        separator: Option<String>,
+        #[arg(long, help = "Only report what would change")]
+        dry_run: bool,
+    },
     CategoryGetTree {
         #[arg(long, help = "Rebuild the tree cache from scratch")]
         rebuild: bool,
+        #[arg(long, help = "Localize node names and slugs, e.g. fr-CA")]
+        locale: Option<String>,
+        #[arg(long, help = "Only the branches this store carries")]
+        store_id: Option<String>,
     },
     GetProductSlugs {
         #[arg(
@@ -200,6 +679,242 @@ enum Commands {
         cursor: Option<String>,
         #[arg(long, help = "Include inactive products", default_value = "false")]
         include_inactive: bool,
+        #[arg(long, help = "Only this store's products, with its slugs")]
+        store_id: Option<String>,
+    },
+    Sitemap {
+        #[arg(
+            long,
+            help = "Site root for the default locale, e.g. https://shop.example.com"
+        )]
+        base_url: String,
+        #[arg(
+            long = "locale-url",
+            help = "Site root for a locale as locale=url, e.g. fr-CA=https://shop.example.com/fr-ca (repeatable)"
+        )]
+        locale_urls: Vec<String>,
+        #[arg(short, long, help = "Directory to write the sitemap files to")]
+        out_dir: PathBuf,
+        #[arg(long, help = "URLs per urlset file (default and max: 50000)")]
+        urls_per_file: Option<i32>,
+    },
+    ProductFeed {
+        #[arg(
+            long,
+            help = "Site root product links point at, e.g. https://shop.example.com"
+        )]
+        base_url: String,
+        #[arg(
+            long,
+            default_value = "products",
+            help = "Path segment before product slugs"
+        )]
+        product_path: String,
+        #[arg(short, long, default_value = "tsv", help = "Feed format: tsv or xml")]
+        format: String,
+        #[arg(short, long, help = "File to write the feed to")]
+        out: PathBuf,
+        #[arg(
+            long,
+            default_value = "USD",
+            help = "Currency to quote prices in (USD or EUR)"
+        )]
+        currency: String,
+        #[arg(
+            long,
+            default_value = "Product feed",
+            help = "Channel title for the XML feed"
+        )]
+        title: String,
+        #[arg(short, long, default_value = "100")]
+        batch_size: i32,
+    },
+    ProductJsonLd {
+        #[arg(short, long)]
+        id: String,
+        #[arg(
+            long,
+            help = "Site root product links point at, e.g. https://shop.example.com"
+        )]
+        base_url: String,
+        #[arg(
+            long,
+            default_value = "USD",
+            help = "Currency to quote offer prices in"
+        )]
+        currency: String,
+        #[arg(long, help = "Localize the product, e.g. fr-CA")]
+        locale: Option<String>,
+    },
+    BundleQuote {
+        #[arg(short, long)]
+        id: String,
+        #[arg(long, default_value = "USD", help = "Currency to quote the price in")]
+        currency: String,
+    },
+    Breadcrumbs {
+        #[arg(long, help = "Trail down to this category")]
+        category_id: Option<String>,
+        #[arg(long, help = "Trail down to this product")]
+        product_id: Option<String>,
+        #[arg(long, help = "Site root links point at, e.g. https://shop.example.com")]
+        base_url: String,
+        #[arg(long, help = "Localize names and slugs, e.g. fr-CA")]
+        locale: Option<String>,
+    },
+    ProductRevisions {
+        #[arg(short, long)]
+        id: String,
+        #[arg(short, long, default_value = "20")]
+        limit: i32,
+    },
+    ProductRevert {
+        #[arg(short, long)]
+        id: String,
+        #[arg(short, long)]
+        revision: i64,
+        #[arg(long, help = "Who is performing the revert")]
+        reverted_by: Option<String>,
+    },
+    ProductDiff {
+        #[arg(short, long)]
+        id: String,
+        #[arg(long, help = "Older revision number")]
+        from: i64,
+        #[arg(long, help = "Newer revision number")]
+        to: i64,
+    },
+    ProductRelated {
+        #[arg(short, long)]
+        id: String,
+        #[arg(
+            long = "kind",
+            help = "Only related, accessory, upsell or replacement links (repeatable)"
+        )]
+        kinds: Vec<String>,
+        #[arg(long, help = "Localize the related products, e.g. fr-CA")]
+        locale: Option<String>,
+    },
+    ProductCompare {
+        #[arg(long = "id", help = "Product to compare (repeatable, up to 4)")]
+        ids: Vec<String>,
+        #[arg(long, help = "Compare localized values, e.g. fr-CA")]
+        locale: Option<String>,
+        #[arg(long, help = "Length unit for measurements (default: cm)")]
+        length_unit: Option<String>,
+        #[arg(long, help = "Weight unit for measurements (default: kg)")]
+        weight_unit: Option<String>,
+    },
+    ProductClone {
+        #[arg(short, long)]
+        id: String,
+        #[arg(short, long, help = "product_ref of the new product")]
+        product_ref: String,
+        #[arg(
+            long,
+            help = "Slug of the new product; derived from the name when unset"
+        )]
+        slug: Option<String>,
+        #[arg(long, help = "Name of the new product; copied when unset")]
+        name: Option<String>,
+        #[arg(
+            long = "sku-rewrite",
+            help = "SKU rewrite as find=replace, applied in order (repeatable)"
+        )]
+        sku_rewrites: Vec<String>,
+    },
+    CatalogStageDelete {
+        #[arg(
+            short,
+            long,
+            help = "Product to delete when the change set is published"
+        )]
+        id: String,
+        #[arg(long)]
+        change_set: Option<String>,
+    },
+    CatalogStaged {
+        #[arg(long, help = "Only this change set")]
+        change_set: Option<String>,
+    },
+    CatalogDiscard {
+        #[arg(long, help = "Only this change set")]
+        change_set: Option<String>,
+        #[arg(long = "change-id", help = "Only these staged changes (repeatable)")]
+        change_ids: Vec<String>,
+    },
+    CatalogPublish {
+        #[arg(long, help = "Only this change set; everything staged when unset")]
+        change_set: Option<String>,
+        #[arg(long)]
+        published_by: Option<String>,
+    },
+    CatalogVersions {
+        #[arg(short, long)]
+        limit: Option<i32>,
+    },
+    CatalogRollback {
+        #[arg(short, long, help = "Published version to return the catalog to")]
+        version: i64,
+        #[arg(long)]
+        published_by: Option<String>,
+    },
+    ProductGenerateVariants {
+        #[arg(short, long)]
+        id: String,
+        #[arg(
+            short,
+            long = "axis",
+            help = "Axis as key=value[:code],... e.g. color=Black:BLK,White:WHT (repeatable)"
+        )]
+        axes: Vec<String>,
+        #[arg(short, long, default_value = "{product_ref}-{color}-{size}")]
+        sku_pattern: String,
+        #[arg(long, help = "Create zero-quantity inventory records for new SKUs")]
+        seed_inventory: bool,
+        #[arg(long, help = "Inventory location for seeded records")]
+        location: Option<String>,
+    },
+    AttributeSchemaSet {
+        #[arg(short, long)]
+        product_type: String,
+        #[arg(short, long, help = "JSON file with the list of attribute definitions")]
+        file: PathBuf,
+        #[arg(long, help = "Who is changing the schema")]
+        updated_by: Option<String>,
+    },
+    AttributeSchemaGet {
+        #[arg(short, long)]
+        product_type: String,
+    },
+    AttributeSchemaList,
+    ReviewSubmit {
+        #[arg(short, long)]
+        product_id: String,
+        #[arg(short, long, help = "1-5 stars")]
+        rating: i32,
+        #[arg(short, long)]
+        title: Option<String>,
+        #[arg(long)]
+        body: Option<String>,
+        #[arg(short, long)]
+        author: Option<String>,
+    },
+    ReviewList {
+        #[arg(short, long)]
+        product_id: Option<String>,
+        #[arg(short, long, help = "pending, approved or rejected")]
+        status: Option<String>,
+    },
+    ReviewModerate {
+        #[arg(short, long)]
+        review_id: String,
+        #[arg(short, long, help = "approved or rejected")]
+        status: String,
+        #[arg(short, long, help = "Who is moderating the review")]
+        moderated_by: Option<String>,
+        #[arg(short, long)]
+        note: Option<String>,
     },
 }
 
@@ -243,6 +958,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 display_on_site: true,
                 tax_code: Some("txcd_99999999".to_string()),
                 related_products: vec![],
+                relations: vec![],
                 reviews: None,
                 hierarchical_categories: None,
                 list_categories: vec!["Sample Category".to_string()],
@@ -250,6 +966,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 descriptive_attributes: HashMap::new(),
                 default_variant: None,
                 variants: vec![],
+                publish_at: None,
+                unpublish_at: None,
+                localizations: HashMap::new(),
+                bundle: None,
+                stores: HashMap::new(),
             };
 
             let request_bytes = product_request.encode_to_vec();
@@ -265,8 +986,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             let create_response = ProductCreateResponse::decode(&*response.payload)?;
             println!("Create response: {create_response:?}");
         }
-        Some(Commands::ProductGet { id }) => {
-            let get_request = ProductGetRequest { id: id.clone() };
+        Some(Commands::ProductGet {
+            id,
+            locale,
+            catalog_version,
+        }) => {
+            let get_request = ProductGetRequest {
+                id: id.clone(),
+                locale: locale.clone(),
+                catalog_version: catalog_version.clone(),
+            };
 
             let request_bytes = get_request.encode_to_vec();
 
@@ -281,8 +1010,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             let get_response = ProductGetResponse::decode(&*response.payload)?;
             println!("Get response: {get_response:?}");
         }
-        Some(Commands::ProductGetBySlug { slug }) => {
-            let get_request = ProductGetBySlugRequest { slug: slug.clone() };
+        Some(Commands::ProductGetBySlug { slug, locale }) => {
+            let get_request = ProductGetBySlugRequest {
+                slug: slug.clone(),
+                locale: locale.clone(),
+            };
 
             let request_bytes = get_request.encode_to_vec();
 
@@ -313,10 +1045,28 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             let delete_response = ProductDeleteResponse::decode(&*response.payload)?;
             println!("Delete response: {delete_response:?}");
         }
+        Some(Commands::ProductRestore { id }) => {
+            let restore_request = ProductRestoreRequest { id: id.clone() };
+
+            let request_bytes = restore_request.encode_to_vec();
+
+            println!("Sending restore_product request for ID: {id}");
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::RESTORE_PRODUCT,
+                    request_bytes.into(),
+                )
+                .await?;
+
+            let restore_response = ProductRestoreResponse::decode(&*response.payload)?;
+            println!("Restore response: {restore_response:?}");
+        }
         Some(Commands::ProductSearch {
             query,
             category,
             brand,
+            locale,
+            store_id,
         }) => {
             let categories = if let Some(cat) = category {
                 vec![cat.clone()]
@@ -328,6 +1078,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 query: query.clone(),
                 categories,
                 brand: brand.clone(),
+                locale: locale.clone(),
+                store_id: store_id.clone(),
                 limit: Some(10),
                 offset: Some(0),
             };
@@ -477,72 +1229,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
                         // Convert proto products to domain products
                         for proto_product in export_response.products {
-                            let product = Product {
-                                id: proto_product.id,
-                                name: proto_product.name,
-                                long_description: proto_product.long_description,
-                                brand: proto_product.brand,
-                                slug: proto_product.slug,
-                                product_ref: proto_product.product_ref,
-                                product_type: proto_product.product_type,
-                                seo_title: proto_product.seo_title,
-                                seo_description: proto_product.seo_description,
-                                seo_keywords: proto_product.seo_keywords,
-                                display_on_site: proto_product.display_on_site,
-                                tax_code: proto_product.tax_code,
-                                related_products: proto_product.related_products,
-                                reviews: proto_product.reviews.map(|r| rust_catalog::Reviews {
-                                    bayesian_avg: r.bayesian_avg.into(),
-                                    count: r.count,
-                                    rating: r.rating,
-                                }),
-                                hierarchical_categories: proto_product.hierarchical_categories.map(
-                                    |hc| rust_catalog::HierarchicalCategories {
-                                        lvl0: hc.lvl0,
-                                        lvl1: hc.lvl1,
-                                        lvl2: hc.lvl2,
-                                    },
-                                ),
-                                list_categories: proto_product.list_categories,
-                                created_at: proto_product.created_at.map(|ts| {
-                                    use chrono::{DateTime, Utc};
-                                    DateTime::<Utc>::from_timestamp(ts.seconds, ts.nanos as u32)
-                                        .unwrap()
-                                }),
-                                updated_at: proto_product.updated_at.map(|ts| {
-                                    use chrono::{DateTime, Utc};
-                                    DateTime::<Utc>::from_timestamp(ts.seconds, ts.nanos as u32)
-                                        .unwrap()
-                                }),
-                                created_by: proto_product.created_by,
-                                updated_by: proto_product.updated_by,
-                                defining_attributes: proto_product.defining_attributes,
-                                descriptive_attributes: proto_product.descriptive_attributes,
-                                default_variant: proto_product.default_variant,
-                                variants: proto_product
-                                    .variants
-                                    .into_iter()
-                                    .map(|v| rust_catalog::ProductVariant {
-                                        sku: v.sku,
-                                        defining_attributes: Some(v.defining_attributes),
-                                        abbreviated_color: v.abbreviated_color,
-                                        abbreviated_size: v.abbreviated_size,
-                                        height: v.height,
-                                        width: v.width,
-                                        length: v.length,
-                                        weight: v.weight,
-                                        weight_unit: v.weight_unit,
-                                        packaging: v.packaging.map(|p| rust_catalog::Packaging {
-                                            height: p.height,
-                                            width: p.width,
-                                            length: p.length,
-                                            weight: p.weight,
-                                            weight_unit: p.weight_unit,
-                                        }),
-                                        image_urls: v.image_urls,
-                                    })
-                                    .collect(),
-                            };
+                            let product = proto_product_to_domain(proto_product);
                             all_products.push(product);
                         }
 
@@ -599,6 +1286,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 seo: None,
                 is_active: Some(true),
                 parent_slug: None,
+                localizations: HashMap::new(),
+                stores: HashMap::new(),
             };
 
             let request_bytes = request.encode_to_vec();
@@ -617,8 +1306,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("  📝 Name: {}", category_response.name);
             println!("  🔗 Slug: {}", category_response.slug);
         }
-        Some(Commands::CategoryGet { id }) => {
-            let request = GetCategoryRequest { id: id.clone() };
+        Some(Commands::CategoryGet {
+            id,
+            locale,
+            catalog_version,
+        }) => {
+            let request = GetCategoryRequest {
+                id: id.clone(),
+                locale: locale.clone(),
+                catalog_version: catalog_version.clone(),
+            };
 
             let request_bytes = request.encode_to_vec();
             println!("Getting category with ID: {id}");
@@ -647,12 +1344,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 println!("  📄 Description: {}", category.short_description);
                 println!("  🔢 Level: {}", category.level);
                 println!("  👥 Children: {}", category.children_count);
+                println!(
+                    "  📦 Products: {} ({} incl. subcategories)",
+                    category.product_count, category.subtree_product_count
+                );
             } else {
                 println!("❌ Category not found");
             }
         }
-        Some(Commands::CategoryGetBySlug { slug }) => {
-            let request = GetCategoryBySlugRequest { slug: slug.clone() };
+        Some(Commands::CategoryGetBySlug { slug, locale }) => {
+            let request = GetCategoryBySlugRequest {
+                slug: slug.clone(),
+                locale: locale.clone(),
+            };
 
             let request_bytes = request.encode_to_vec();
             println!("Getting category with slug: {slug}");
@@ -673,6 +1377,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
 
+            if let Some(redirect_slug) = &get_response.redirect_slug {
+                println!("↪️  Slug '{slug}' moved permanently to '{redirect_slug}'");
+            }
+
             if let Some(category) = get_response.category {
                 println!("✅ Category found!");
                 println!("  🆔 ID: {}", category.id);
@@ -700,6 +1408,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 display_order: *display_order,
                 seo: None,
                 is_active: *is_active,
+                localizations: HashMap::new(),
+                remove_locales: vec![],
+                stores: HashMap::new(),
+                remove_stores: vec![],
             };
 
             let request_bytes = request.encode_to_vec();
@@ -738,29 +1450,128 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             // we just check if decoding was successful
             println!("✅ Category deleted successfully!");
         }
-        Some(Commands::CategoryExport { file, batch_size }) => {
-            let request = CategoryExportRequest {
-                batch_size: batch_size.map(|b| b as i32),
-                offset: None,
-            };
+        Some(Commands::CategoryRestore { id }) => {
+            let request = RestoreCategoryRequest { id: id.clone() };
 
             let request_bytes = request.encode_to_vec();
-            println!("Exporting categories...");
+            println!("Restoring category with ID: {id}");
 
             let response = client
                 .request(
-                    rust_catalog::nats_config::category::subjects::EXPORT_CATEGORIES,
+                    rust_catalog::nats_config::category::subjects::RESTORE_CATEGORY,
                     request_bytes.into(),
                 )
                 .await?;
 
-            let export_response = CategoryExportResponse::decode(&*response.payload)?;
+            let restore_response = RestoreCategoryResponse::decode(&*response.payload)?;
 
-            // Write categories to file as JSON (we'll skip serialization for now and just show count)
-            println!("✅ Categories exported!");
-            println!("  📁 File: {file:?}");
-            println!(
-                "  📦 Total categories: {}",
+            if let Some(category) = restore_response.category {
+                println!("✅ Category restored successfully!");
+                println!("  🆔 ID: {}", category.id);
+                println!("  📝 Name: {}", category.name);
+                println!("  🔗 Slug: {}", category.slug);
+            } else if let Some(status) = restore_response.status {
+                println!("❌ Failed to restore category: {}", status.message);
+            }
+        }
+        Some(Commands::CategoryMove { id, parent_id }) => {
+            let request = MoveCategoryRequest {
+                category_id: id.clone(),
+                new_parent_id: parent_id.clone(),
+            };
+
+            let request_bytes = request.encode_to_vec();
+            println!("Moving category {id} under {parent_id:?}");
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::category::subjects::MOVE_CATEGORY,
+                    request_bytes.into(),
+                )
+                .await?;
+
+            let move_response = MoveCategoryResponse::decode(&*response.payload)?;
+            match move_response.status {
+                Some(status) if status.code == common::Code::Ok as i32 => {
+                    println!("✅ Category moved successfully!");
+                }
+                Some(status) => println!("❌ Failed to move category: {}", status.message),
+                None => println!("❌ Failed to move category: no status in response"),
+            }
+        }
+        Some(Commands::CategoryMerge {
+            source_id,
+            target_id,
+            dry_run,
+        }) => {
+            let request = MergeCategoriesRequest {
+                source_id: source_id.clone(),
+                target_id: target_id.clone(),
+                dry_run: *dry_run,
+            };
+
+            let request_bytes = request.encode_to_vec();
+            println!(
+                "Merging category {source_id} into {target_id}{}",
+                if *dry_run { " (dry run)" } else { "" }
+            );
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::category::subjects::MERGE_CATEGORIES,
+                    request_bytes.into(),
+                )
+                .await?;
+
+            let merge_response = MergeCategoriesResponse::decode(&*response.payload)?;
+            match &merge_response.status {
+                Some(status) if status.code == common::Code::Ok as i32 => {
+                    let (moved, reassigned) = if merge_response.dry_run {
+                        ("Would move", "reassign")
+                    } else {
+                        ("Moved", "reassigned")
+                    };
+                    println!(
+                        "✅ {}: {moved} {} subcategories and {reassigned} {} products; slug '{}' redirects to the target",
+                        status.message,
+                        merge_response.moved_subcategory_ids.len(),
+                        merge_response.affected_product_ids.len(),
+                        merge_response.redirected_slug
+                    );
+                    for id in &merge_response.moved_subcategory_ids {
+                        println!("  📁 {id}");
+                    }
+                    for id in &merge_response.affected_product_ids {
+                        println!("  📦 {id}");
+                    }
+                }
+                Some(status) => println!("❌ Failed to merge categories: {}", status.message),
+                None => println!("❌ Failed to merge categories: no status in response"),
+            }
+        }
+        Some(Commands::CategoryExport { file, batch_size }) => {
+            let request = CategoryExportRequest {
+                batch_size: batch_size.map(|b| b as i32),
+                offset: None,
+            };
+
+            let request_bytes = request.encode_to_vec();
+            println!("Exporting categories...");
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::category::subjects::EXPORT_CATEGORIES,
+                    request_bytes.into(),
+                )
+                .await?;
+
+            let export_response = CategoryExportResponse::decode(&*response.payload)?;
+
+            // Write categories to file as JSON (we'll skip serialization for now and just show count)
+            println!("✅ Categories exported!");
+            println!("  📁 File: {file:?}");
+            println!(
+                "  📦 Total categories: {}",
                 export_response.categories.len()
             );
 
@@ -791,6 +1602,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     seo: None,
                     is_active: Some(cat["is_active"].as_bool().unwrap_or(true)),
                     parent_slug: cat["parent_slug"].as_str().map(|s| s.to_string()),
+                    localizations: HashMap::new(),
+                    stores: HashMap::new(),
                 })
                 .collect();
 
@@ -831,13 +1644,78 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
         }
-        Some(Commands::CategoryGetTree { rebuild }) => {
+        Some(Commands::CategoryImportCsv {
+            file,
+            separator,
+            dry_run,
+        }) => {
+            let request = CategoryCsvImportRequest {
+                csv: fs::read_to_string(file)?,
+                dry_run: *dry_run,
+                path_separator: separator.clone(),
+            };
+
+            let request_bytes = request.encode_to_vec();
+            println!(
+                "Importing categories from CSV file: {file:?}{}",
+                if *dry_run { " (dry run)" } else { "" }
+            );
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::category::subjects::IMPORT_CATEGORIES_CSV,
+                    request_bytes.into(),
+                )
+                .await?;
+
+            let import_response = CategoryCsvImportResponse::decode(&*response.payload)?;
+            match &import_response.status {
+                Some(status) if status.code == common::Code::Ok as i32 => {
+                    println!(
+                        "{} {}",
+                        if import_response.applied {
+                            "✅"
+                        } else {
+                            "🧪"
+                        },
+                        status.message
+                    );
+                    for change in &import_response.changes {
+                        let marker = match change.action() {
+                            CategoryImportAction::Create => "+",
+                            CategoryImportAction::Update => "~",
+                            CategoryImportAction::Unchanged => continue,
+                        };
+                        print!("  {marker} {} ({})", change.path, change.slug);
+                        if !change.changed_fields.is_empty() {
+                            print!(" [{}]", change.changed_fields.join(", "));
+                        }
+                        println!();
+                    }
+                    if !import_response.errors.is_empty() {
+                        println!("  🚨 Errors:");
+                        for error in &import_response.errors {
+                            println!("    - {error}");
+                        }
+                    }
+                }
+                Some(status) => println!("❌ Failed to import categories: {}", status.message),
+                None => println!("❌ Failed to import categories: no status in response"),
+            }
+        }
+        Some(Commands::CategoryGetTree {
+            rebuild,
+            locale,
+            store_id,
+        }) => {
             println!("🌳 Retrieving category tree...");
 
             let request = CategoryTreeRequest {
                 max_depth: None,
                 include_inactive: Some(false),
                 rebuild_cache: Some(*rebuild),
+                locale: locale.clone(),
+                store_id: store_id.clone(),
             };
 
             let request_bytes = request.encode_to_vec();
@@ -865,8 +1743,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         let indent = "  ".repeat(depth);
                         println!("{}├─ {} ({})", indent, node.name, node.slug);
                         println!(
-                            "{}   📊 Level: {} | Products: {} | Path: {}",
-                            indent, node.level, node.product_count, node.path
+                            "{}   📊 Level: {} | Products: {} ({} incl. subcategories) | Path: {}",
+                            indent,
+                            node.level,
+                            node.product_count,
+                            node.subtree_product_count,
+                            node.path
                         );
 
                         for child in &node.children {
@@ -892,10 +1774,299 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 println!("❌ Invalid response from server");
             }
         }
+        Some(Commands::Sitemap {
+            base_url,
+            locale_urls,
+            out_dir,
+            urls_per_file,
+        }) => {
+            let locale_base_urls = locale_urls
+                .iter()
+                .map(|value| parse_locale_url(value))
+                .collect::<Result<HashMap<_, _>, _>>()?;
+            let mut request = GenerateSitemapRequest {
+                base_url: base_url.clone(),
+                locale_base_urls,
+                file: None,
+                urls_per_file: *urls_per_file,
+                product_path: None,
+                category_path: None,
+            };
+
+            println!("🗺️ Generating sitemap for {base_url}");
+            fs::create_dir_all(out_dir)?;
+
+            // The index lists the urlset files; each is then rendered on its own
+            // so no single response exceeds the NATS payload limit
+            let mut files = None;
+            loop {
+                let response = client
+                    .request(
+                        rust_catalog::nats_config::product::subjects::GENERATE_SITEMAP,
+                        request.encode_to_vec().into(),
+                    )
+                    .await?;
+                let sitemap_response = GenerateSitemapResponse::decode(&*response.payload)?;
+
+                match &sitemap_response.status {
+                    Some(status) if status.code == catalog_messages::Code::Ok as i32 => {}
+                    Some(status) => {
+                        println!(
+                            "❌ Failed to generate {}: {} ({})",
+                            request.file.as_deref().unwrap_or("sitemap index"),
+                            status.message,
+                            status.code
+                        );
+                        break;
+                    }
+                    None => {
+                        println!("❌ No status in response");
+                        break;
+                    }
+                }
+
+                fs::write(
+                    out_dir.join(&sitemap_response.name),
+                    &sitemap_response.content,
+                )?;
+                println!(
+                    "  📄 Wrote {} ({} bytes)",
+                    sitemap_response.name,
+                    sitemap_response.content.len()
+                );
+
+                let pending = files.get_or_insert_with(|| {
+                    sitemap_response
+                        .files
+                        .iter()
+                        .rev()
+                        .map(|file| file.name.clone())
+                        .collect::<Vec<_>>()
+                });
+                match pending.pop() {
+                    Some(name) => request.file = Some(name),
+                    None => {
+                        println!("✅ Sitemap written to {}", out_dir.display());
+                        break;
+                    }
+                }
+            }
+        }
+        Some(Commands::ProductJsonLd {
+            id,
+            base_url,
+            currency,
+            locale,
+        }) => {
+            let request = GetProductStructuredDataRequest {
+                product_id: id.clone(),
+                base_url: base_url.clone(),
+                currency: currency.clone(),
+                product_path: None,
+                locale: locale.clone(),
+            };
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::GET_PRODUCT_STRUCTURED_DATA,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let data_response = GetProductStructuredDataResponse::decode(&*response.payload)?;
+            match &data_response.status {
+                Some(status) if status.code == common::Code::Ok as i32 => {
+                    println!("{}", data_response.json_ld);
+                    if !data_response.unpriced_skus.is_empty() {
+                        eprintln!(
+                            "⚠️  No {currency} price for: {}",
+                            data_response.unpriced_skus.join(", ")
+                        );
+                    }
+                }
+                Some(status) => println!("❌ Failed to get structured data: {}", status.message),
+                None => println!("❌ Failed to get structured data: no status in response"),
+            }
+        }
+        Some(Commands::BundleQuote { id, currency }) => {
+            let request = GetBundleQuoteRequest {
+                product_id: id.clone(),
+                currency: currency.clone(),
+            };
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::GET_BUNDLE_QUOTE,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let quote = GetBundleQuoteResponse::decode(&*response.payload)?;
+            match &quote.status {
+                Some(status) if status.code == common::Code::Ok as i32 => {
+                    println!("📦 {} available", quote.available_quantity);
+                    match &quote.price {
+                        Some(price) => println!("💰 {price} {currency} ({:?})", quote.pricing()),
+                        None => println!("💰 No {currency} price ({:?})", quote.pricing()),
+                    }
+                    for component in &quote.components {
+                        println!(
+                            "  {} x{}: {} in stock, unit price {}",
+                            component.sku,
+                            component.quantity,
+                            component.available,
+                            component.unit_price.as_deref().unwrap_or("-")
+                        );
+                    }
+                }
+                Some(status) => println!("❌ Failed to quote bundle: {}", status.message),
+                None => println!("❌ Failed to quote bundle: no status in response"),
+            }
+        }
+        Some(Commands::Breadcrumbs {
+            category_id,
+            product_id,
+            base_url,
+            locale,
+        }) => {
+            let request = GetBreadcrumbsRequest {
+                category_id: category_id.clone(),
+                product_id: product_id.clone(),
+                base_url: base_url.clone(),
+                product_path: None,
+                category_path: None,
+                locale: locale.clone(),
+            };
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::GET_BREADCRUMBS,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let breadcrumbs_response = GetBreadcrumbsResponse::decode(&*response.payload)?;
+            match &breadcrumbs_response.status {
+                Some(status) if status.code == common::Code::Ok as i32 => {
+                    for (depth, crumb) in breadcrumbs_response.breadcrumbs.iter().enumerate() {
+                        println!("{}{} <{}>", "  ".repeat(depth), crumb.name, crumb.url);
+                    }
+                    println!("{}", breadcrumbs_response.json_ld);
+                }
+                Some(status) => println!("❌ Failed to get breadcrumbs: {}", status.message),
+                None => println!("❌ Failed to get breadcrumbs: no status in response"),
+            }
+        }
+        Some(Commands::ProductFeed {
+            base_url,
+            product_path,
+            format,
+            out,
+            currency,
+            title,
+            batch_size,
+        }) => {
+            if format != "tsv" && format != "xml" {
+                return Err(format!("Invalid feed format: {format} (expected tsv or xml)").into());
+            }
+
+            println!("🛒 Building {format} product feed for {base_url}");
+
+            let mut feed = MerchantFeed::new(FeedSettings {
+                base_url: base_url.clone(),
+                product_path: product_path.clone(),
+                currency: currency.clone(),
+            });
+            let mut offset = 0i32;
+
+            loop {
+                let export_request = ProductExportRequest {
+                    batch_size: Some(*batch_size),
+                    offset: Some(offset),
+                };
+                let response = client
+                    .request(
+                        rust_catalog::nats_config::product::subjects::EXPORT_PRODUCTS,
+                        export_request.encode_to_vec().into(),
+                    )
+                    .await?;
+                let export_response = ProductExportResponse::decode(&*response.payload)?;
+
+                match &export_response.status {
+                    Some(status) if status.code == catalog_messages::Code::Ok as i32 => {}
+                    Some(status) => {
+                        return Err(format!(
+                            "Failed to export products: {} ({})",
+                            status.message, status.code
+                        )
+                        .into());
+                    }
+                    None => return Err("Invalid response from server".into()),
+                }
+
+                let batch_count = export_response.products.len();
+                if batch_count == 0 {
+                    break;
+                }
+
+                let products: Vec<Product> = export_response
+                    .products
+                    .into_iter()
+                    .map(proto_product_to_domain)
+                    .collect();
+                let skus: Vec<String> = products
+                    .iter()
+                    .flat_map(|product| product.variants.iter().map(|v| v.sku.clone()))
+                    .collect();
+
+                let prices = price_client::get_best_offer_prices(&client, &skus, currency).await?;
+                let available = inventory_client::get_available_quantities(&client, &skus).await?;
+                println!(
+                    "  📦 {batch_count} products, {} SKUs ({} priced)",
+                    skus.len(),
+                    prices.len()
+                );
+
+                let now = chrono::Utc::now();
+                for product in &products {
+                    feed.add_product(product, &prices, &available, now);
+                }
+
+                if (batch_count as i32) < *batch_size {
+                    break;
+                }
+                offset += *batch_size;
+            }
+
+            let content = if format == "xml" {
+                feed.to_xml(title)
+            } else {
+                feed.to_tsv()
+            };
+            fs::write(out, content)?;
+            println!(
+                "✅ Wrote {} feed items to {}",
+                feed.items.len(),
+                out.display()
+            );
+
+            if !feed.skipped.is_empty() {
+                println!("⚠️ Skipped {} SKUs:", feed.skipped.len());
+                for skipped in &feed.skipped {
+                    println!(
+                        "  - {} (product {}): {}",
+                        skipped.sku,
+                        skipped.product_ref,
+                        skipped.reason.as_str()
+                    );
+                }
+            }
+        }
         Some(Commands::GetProductSlugs {
             batch_size,
             cursor,
             include_inactive,
+            store_id,
         }) => {
             println!("🔍 Retrieving product slugs...");
 
@@ -903,6 +2074,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 batch_size: *batch_size,
                 cursor: cursor.clone(),
                 include_inactive: Some(*include_inactive),
+                store_id: store_id.clone(),
             };
 
             let request_bytes = request.encode_to_vec();
@@ -956,6 +2128,688 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 println!("❌ Invalid response from server");
             }
         }
+        Some(Commands::ProductRevisions { id, limit }) => {
+            let request = ListProductRevisionsRequest {
+                product_id: id.clone(),
+                revisions: vec![],
+                limit: Some(*limit),
+                offset: None,
+            };
+
+            println!("Fetching revisions for product: {id}");
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::LIST_PRODUCT_REVISIONS,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let revisions_response = ListProductRevisionsResponse::decode(&*response.payload)?;
+            match revisions_response.status {
+                Some(status) if status.code == catalog_messages::Code::Ok as i32 => {
+                    println!("🕘 {} revision(s):", revisions_response.revisions.len());
+                    for revision in &revisions_response.revisions {
+                        let created_at = revision
+                            .created_at
+                            .as_ref()
+                            .and_then(|ts| {
+                                chrono::DateTime::<chrono::Utc>::from_timestamp(
+                                    ts.seconds,
+                                    ts.nanos as u32,
+                                )
+                            })
+                            .map(|dt| dt.to_rfc3339())
+                            .unwrap_or_default();
+                        let changed_by = revision.changed_by.as_deref().unwrap_or("unknown");
+                        print!("  #{} {created_at} by {changed_by}", revision.revision);
+                        if let Some(reverted_from) = revision.reverted_from {
+                            print!(" (revert to #{reverted_from})");
+                        }
+                        println!();
+                    }
+                }
+                Some(status) => {
+                    println!(
+                        "❌ Failed to list revisions: {} ({})",
+                        status.message, status.code
+                    );
+                }
+                None => println!("❌ Invalid response from server"),
+            }
+        }
+        Some(Commands::ProductRevert {
+            id,
+            revision,
+            reverted_by,
+        }) => {
+            let request = RevertProductRequest {
+                product_id: id.clone(),
+                revision: *revision,
+                reverted_by: reverted_by.clone(),
+            };
+
+            println!("Reverting product {id} to revision #{revision}");
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::REVERT_PRODUCT,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let revert_response = RevertProductResponse::decode(&*response.payload)?;
+            match (revert_response.product, revert_response.status) {
+                (Some(product), _) => {
+                    println!("✅ Product reverted!");
+                    println!("  🆔 ID: {}", product.id.unwrap_or_default());
+                    println!("  📝 Name: {}", product.name);
+                }
+                (None, Some(status)) => {
+                    println!(
+                        "❌ Failed to revert product: {} ({})",
+                        status.message, status.code
+                    );
+                }
+                (None, None) => println!("❌ Invalid response from server"),
+            }
+        }
+        Some(Commands::ProductDiff { id, from, to }) => {
+            let request = ListProductRevisionsRequest {
+                product_id: id.clone(),
+                revisions: vec![*from, *to],
+                limit: None,
+                offset: None,
+            };
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::LIST_PRODUCT_REVISIONS,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let revisions_response = ListProductRevisionsResponse::decode(&*response.payload)?;
+            let snapshot_of = |number: i64| {
+                revisions_response
+                    .revisions
+                    .iter()
+                    .find(|r| r.revision == number)
+                    .and_then(|r| r.snapshot.clone())
+                    .map(proto_product_to_domain)
+            };
+
+            match (snapshot_of(*from), snapshot_of(*to)) {
+                (Some(before), Some(after)) => {
+                    let changes = rust_catalog::diff_products(&before, &after);
+                    if changes.is_empty() {
+                        println!("No differences between revision #{from} and #{to}");
+                    } else {
+                        println!("🔍 Changes from revision #{from} to #{to}:");
+                        for change in changes {
+                            println!("  {}:", change.field);
+                            println!("    - {}", change.before);
+                            println!("    + {}", change.after);
+                        }
+                    }
+                }
+                (before, _) => {
+                    let missing = if before.is_none() { from } else { to };
+                    println!("❌ Revision #{missing} not found for product {id}");
+                }
+            }
+        }
+        Some(Commands::ProductRelated { id, kinds, locale }) => {
+            let kinds = kinds
+                .iter()
+                .map(|kind| parse_relation_kind(kind).map(i32::from))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let request = GetRelatedProductsRequest {
+                product_id: id.clone(),
+                kinds,
+                locale: locale.clone(),
+            };
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::GET_RELATED_PRODUCTS,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let related_response = GetRelatedProductsResponse::decode(&*response.payload)?;
+            match &related_response.status {
+                Some(status) if status.code == common::Code::Ok as i32 => {
+                    println!("🔗 {} related product(s):", related_response.related.len());
+                    for related in &related_response.related {
+                        if let Some(product) = &related.product {
+                            println!(
+                                "  {:?}: {} ({})",
+                                related.kind(),
+                                product.name,
+                                product.id.as_deref().unwrap_or_default()
+                            );
+                        }
+                    }
+                }
+                Some(status) => println!("❌ Failed to get related products: {}", status.message),
+                None => println!("❌ Failed to get related products: no status in response"),
+            }
+        }
+        Some(Commands::ProductCompare {
+            ids,
+            locale,
+            length_unit,
+            weight_unit,
+        }) => {
+            let request = CompareProductsRequest {
+                product_ids: ids.clone(),
+                locale: locale.clone(),
+                length_unit: length_unit.clone(),
+                weight_unit: weight_unit.clone(),
+            };
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::COMPARE_PRODUCTS,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let compare_response = CompareProductsResponse::decode(&*response.payload)?;
+            match &compare_response.status {
+                Some(status) if status.code == common::Code::Ok as i32 => {
+                    let names: Vec<&str> = compare_response
+                        .products
+                        .iter()
+                        .map(|p| p.name.as_str())
+                        .collect();
+                    println!("⚖️  Comparing {}", names.join(" | "));
+                    for row in &compare_response.rows {
+                        let marker = if row.differs { "≠" } else { " " };
+                        let values: Vec<&str> = row
+                            .values
+                            .iter()
+                            .map(|v| if v.is_empty() { "-" } else { v.as_str() })
+                            .collect();
+                        println!("  {marker} {}: {}", row.key, values.join(" | "));
+                    }
+                }
+                Some(status) => println!("❌ Failed to compare products: {}", status.message),
+                None => println!("❌ Failed to compare products: no status in response"),
+            }
+        }
+        Some(Commands::CatalogStageDelete { id, change_set }) => {
+            let request = StageCatalogChangeRequest {
+                edit: Some(stage_catalog_change_request::Edit::DeleteProductId(
+                    id.clone(),
+                )),
+                change_set: change_set.clone(),
+                staged_by: None,
+            };
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::STAGE_CATALOG_CHANGE,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let stage_response = StageCatalogChangeResponse::decode(&*response.payload)?;
+            match (&stage_response.status, &stage_response.change) {
+                (Some(status), Some(change)) if status.code == common::Code::Ok as i32 => {
+                    println!(
+                        "📝 Staged deletion of product {id} in change set '{}'",
+                        change.change_set
+                    );
+                }
+                (Some(status), _) => println!("❌ Failed to stage change: {}", status.message),
+                (None, _) => println!("❌ Failed to stage change: no status in response"),
+            }
+        }
+        Some(Commands::CatalogStaged { change_set }) => {
+            let request = ListStagedChangesRequest {
+                change_set: change_set.clone(),
+            };
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::LIST_STAGED_CHANGES,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let list_response = ListStagedChangesResponse::decode(&*response.payload)?;
+            match &list_response.status {
+                Some(status) if status.code == common::Code::Ok as i32 => {
+                    println!("📝 {} staged change(s):", list_response.changes.len());
+                    for staged in &list_response.changes {
+                        if let Some(change) = &staged.change {
+                            println!(
+                                "  [{}] {:?} {:?} {} {}",
+                                staged.change_set,
+                                change.action(),
+                                change.entity_type(),
+                                change.entity_id,
+                                change.name
+                            );
+                        }
+                    }
+                }
+                Some(status) => println!("❌ Failed to list staged changes: {}", status.message),
+                None => println!("❌ Failed to list staged changes: no status in response"),
+            }
+        }
+        Some(Commands::CatalogDiscard {
+            change_set,
+            change_ids,
+        }) => {
+            let request = DiscardStagedChangesRequest {
+                change_set: change_set.clone(),
+                change_ids: change_ids.clone(),
+            };
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::DISCARD_STAGED_CHANGES,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let discard_response = DiscardStagedChangesResponse::decode(&*response.payload)?;
+            match &discard_response.status {
+                Some(status) if status.code == common::Code::Ok as i32 => {
+                    println!(
+                        "🗑️  Discarded {} staged change(s)",
+                        discard_response.discarded_count
+                    );
+                }
+                Some(status) => println!("❌ Failed to discard changes: {}", status.message),
+                None => println!("❌ Failed to discard changes: no status in response"),
+            }
+        }
+        Some(Commands::CatalogPublish {
+            change_set,
+            published_by,
+        }) => {
+            let request = PublishCatalogRequest {
+                change_set: change_set.clone(),
+                published_by: published_by.clone(),
+            };
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::PUBLISH_CATALOG,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let publish_response = PublishCatalogResponse::decode(&*response.payload)?;
+            match (&publish_response.status, &publish_response.version) {
+                (Some(status), Some(version)) if status.code == common::Code::Ok as i32 => {
+                    print_catalog_version(version);
+                }
+                (Some(status), _) => println!("❌ Failed to publish: {}", status.message),
+                (None, _) => println!("❌ Failed to publish: no status in response"),
+            }
+        }
+        Some(Commands::CatalogVersions { limit }) => {
+            let request = ListCatalogVersionsRequest {
+                limit: *limit,
+                offset: None,
+            };
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::LIST_CATALOG_VERSIONS,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let list_response = ListCatalogVersionsResponse::decode(&*response.payload)?;
+            match &list_response.status {
+                Some(status) if status.code == common::Code::Ok as i32 => {
+                    for version in &list_response.versions {
+                        print_catalog_version(version);
+                    }
+                }
+                Some(status) => println!("❌ Failed to list versions: {}", status.message),
+                None => println!("❌ Failed to list versions: no status in response"),
+            }
+        }
+        Some(Commands::CatalogRollback {
+            version,
+            published_by,
+        }) => {
+            let request = RollbackCatalogRequest {
+                version: *version,
+                published_by: published_by.clone(),
+            };
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::ROLLBACK_CATALOG,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let rollback_response = RollbackCatalogResponse::decode(&*response.payload)?;
+            match (&rollback_response.status, &rollback_response.version) {
+                (Some(status), Some(published)) if status.code == common::Code::Ok as i32 => {
+                    print_catalog_version(published);
+                }
+                (Some(status), _) => println!("❌ Failed to roll back: {}", status.message),
+                (None, _) => println!("❌ Failed to roll back: no status in response"),
+            }
+        }
+        Some(Commands::ProductClone {
+            id,
+            product_ref,
+            slug,
+            name,
+            sku_rewrites,
+        }) => {
+            let sku_rewrites = sku_rewrites
+                .iter()
+                .map(|rewrite| parse_sku_rewrite(rewrite))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let request = CloneProductRequest {
+                product_id: id.clone(),
+                product_ref: product_ref.clone(),
+                slug: slug.clone(),
+                name: name.clone(),
+                sku_rewrites,
+                created_by: None,
+            };
+
+            println!("Cloning product {id} as {product_ref}...");
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::CLONE_PRODUCT,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let clone_response = CloneProductResponse::decode(&*response.payload)?;
+            match (&clone_response.status, &clone_response.product) {
+                (Some(status), Some(product)) if status.code == common::Code::Ok as i32 => {
+                    println!(
+                        "✅ Created hidden product {} ({})",
+                        product.id.as_deref().unwrap_or_default(),
+                        product.slug.as_deref().unwrap_or_default()
+                    );
+                    for variant in &product.variants {
+                        println!("  {}", variant.sku);
+                    }
+                }
+                (Some(status), _) => println!("❌ Failed to clone product: {}", status.message),
+                (None, _) => println!("❌ Failed to clone product: no status in response"),
+            }
+        }
+        Some(Commands::ProductGenerateVariants {
+            id,
+            axes,
+            sku_pattern,
+            seed_inventory,
+            location,
+        }) => {
+            let axes = axes
+                .iter()
+                .map(|axis| parse_variant_axis(axis))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let request = GenerateVariantsRequest {
+                product_id: id.clone(),
+                axes,
+                sku_pattern: sku_pattern.clone(),
+                seed_inventory: *seed_inventory,
+                inventory_location: location.clone(),
+                updated_by: None,
+            };
+
+            println!("Generating variants for product: {id}");
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::GENERATE_VARIANTS,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let generate_response = GenerateVariantsResponse::decode(&*response.payload)?;
+            match generate_response.status {
+                Some(status) if status.code == catalog_messages::Code::Ok as i32 => {
+                    println!(
+                        "✅ Created {} variant(s), skipped {} existing",
+                        generate_response.created_skus.len(),
+                        generate_response.skipped_skus.len()
+                    );
+                    for sku in &generate_response.created_skus {
+                        println!("  ➕ {sku}");
+                    }
+                    for sku in &generate_response.skipped_skus {
+                        println!("  ⏭️  {sku}");
+                    }
+                    if !generate_response.inventory_failed_skus.is_empty() {
+                        println!(
+                            "⚠️  Inventory not seeded for: {}",
+                            generate_response.inventory_failed_skus.join(", ")
+                        );
+                    }
+                }
+                Some(status) => {
+                    println!(
+                        "❌ Failed to generate variants: {} ({})",
+                        status.message, status.code
+                    );
+                }
+                None => println!("❌ Invalid response from server"),
+            }
+        }
+        Some(Commands::AttributeSchemaSet {
+            product_type,
+            file,
+            updated_by,
+        }) => {
+            let file_content = fs::read_to_string(file)?;
+            let definitions: Vec<rust_catalog::AttributeDefinition> =
+                serde_json::from_str(&file_content)?;
+
+            let request = UpsertAttributeSchemaRequest {
+                schema: Some(catalog_messages::AttributeSchema {
+                    product_type: product_type.clone(),
+                    attributes: definitions
+                        .into_iter()
+                        .map(domain_attribute_definition_to_proto)
+                        .collect(),
+                    updated_by: None,
+                    updated_at: None,
+                }),
+                updated_by: updated_by.clone(),
+            };
+
+            println!("Saving attribute schema for product type: {product_type}");
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::UPSERT_ATTRIBUTE_SCHEMA,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let upsert_response = UpsertAttributeSchemaResponse::decode(&*response.payload)?;
+            match (upsert_response.schema, upsert_response.status) {
+                (Some(schema), _) => {
+                    println!("✅ Attribute schema saved!");
+                    print_attribute_schema(&schema);
+                }
+                (None, Some(status)) => {
+                    println!(
+                        "❌ Failed to save attribute schema: {} ({})",
+                        status.message, status.code
+                    );
+                }
+                (None, None) => println!("❌ Invalid response from server"),
+            }
+        }
+        Some(Commands::AttributeSchemaGet { product_type }) => {
+            let request = GetAttributeSchemaRequest {
+                product_type: product_type.clone(),
+            };
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::GET_ATTRIBUTE_SCHEMA,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let get_response = GetAttributeSchemaResponse::decode(&*response.payload)?;
+            match (get_response.schema, get_response.status) {
+                (Some(schema), _) => print_attribute_schema(&schema),
+                (None, Some(status)) => {
+                    println!(
+                        "❌ Failed to get attribute schema: {} ({})",
+                        status.message, status.code
+                    );
+                }
+                (None, None) => println!("❌ Invalid response from server"),
+            }
+        }
+        Some(Commands::AttributeSchemaList) => {
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::LIST_ATTRIBUTE_SCHEMAS,
+                    ListAttributeSchemasRequest {}.encode_to_vec().into(),
+                )
+                .await?;
+
+            let list_response = ListAttributeSchemasResponse::decode(&*response.payload)?;
+            match list_response.status {
+                Some(status) if status.code == catalog_messages::Code::Ok as i32 => {
+                    println!("📐 {} attribute schema(s):", list_response.schemas.len());
+                    for schema in &list_response.schemas {
+                        print_attribute_schema(schema);
+                    }
+                }
+                Some(status) => {
+                    println!(
+                        "❌ Failed to list attribute schemas: {} ({})",
+                        status.message, status.code
+                    );
+                }
+                None => println!("❌ Invalid response from server"),
+            }
+        }
+        Some(Commands::ReviewSubmit {
+            product_id,
+            rating,
+            title,
+            body,
+            author,
+        }) => {
+            let request = SubmitReviewRequest {
+                product_id: product_id.clone(),
+                rating: *rating,
+                title: title.clone(),
+                body: body.clone(),
+                author: author.clone(),
+            };
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::SUBMIT_REVIEW,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let submit_response = SubmitReviewResponse::decode(&*response.payload)?;
+            match (submit_response.review, submit_response.status) {
+                (Some(review), _) => {
+                    println!("✅ Review submitted and awaiting moderation:");
+                    print_review(&review);
+                }
+                (None, Some(status)) => {
+                    println!(
+                        "❌ Failed to submit review: {} ({})",
+                        status.message, status.code
+                    );
+                }
+                (None, None) => println!("❌ Invalid response from server"),
+            }
+        }
+        Some(Commands::ReviewList { product_id, status }) => {
+            let status = status.as_deref().map(parse_review_status).transpose()?;
+            let request = ListReviewsRequest {
+                product_id: product_id.clone(),
+                status: status.map(Into::into),
+                limit: None,
+                offset: None,
+            };
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::LIST_REVIEWS,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let list_response = ListReviewsResponse::decode(&*response.payload)?;
+            match list_response.status {
+                Some(status) if status.code == catalog_messages::Code::Ok as i32 => {
+                    println!("⭐ {} review(s):", list_response.reviews.len());
+                    for review in &list_response.reviews {
+                        print_review(review);
+                    }
+                }
+                Some(status) => {
+                    println!(
+                        "❌ Failed to list reviews: {} ({})",
+                        status.message, status.code
+                    );
+                }
+                None => println!("❌ Invalid response from server"),
+            }
+        }
+        Some(Commands::ReviewModerate {
+            review_id,
+            status,
+            moderated_by,
+            note,
+        }) => {
+            let request = ModerateReviewRequest {
+                review_id: review_id.clone(),
+                status: parse_review_status(status)?.into(),
+                moderated_by: moderated_by.clone(),
+                note: note.clone(),
+            };
+
+            let response = client
+                .request(
+                    rust_catalog::nats_config::product::subjects::MODERATE_REVIEW,
+                    request.encode_to_vec().into(),
+                )
+                .await?;
+
+            let moderate_response = ModerateReviewResponse::decode(&*response.payload)?;
+            match (moderate_response.review, moderate_response.status) {
+                (Some(review), _) => {
+                    println!("✅ Review moderated:");
+                    print_review(&review);
+                    if let Some(reviews) = moderate_response.reviews {
+                        println!(
+                            "  📊 Product now has {} approved review(s), Bayesian average {:.1}",
+                            reviews.count, reviews.bayesian_avg
+                        );
+                    }
+                }
+                (None, Some(status)) => {
+                    println!(
+                        "❌ Failed to moderate review: {} ({})",
+                        status.message, status.code
+                    );
+                }
+                (None, None) => println!("❌ Invalid response from server"),
+            }
+        }
         None => {
             println!("No command specified. Use --help for available commands.");
         }
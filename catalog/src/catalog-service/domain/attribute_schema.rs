@@ -0,0 +1,337 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use super::Product;
+
+/// The kind of value an attribute accepts. Attribute values are always stored
+/// as strings; the data type only governs which strings are accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeDataType {
+    String,
+    Integer,
+    Decimal,
+    Boolean,
+    Enum, // Restricted to `allowed_values`
+}
+
+/// Which of the product's attribute maps an attribute belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeScope {
+    Defining,
+    Descriptive,
+}
+
+impl AttributeScope {
+    fn field_name(&self) -> &'static str {
+        match self {
+            AttributeScope::Defining => "defining_attributes",
+            AttributeScope::Descriptive => "descriptive_attributes",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributeDefinition {
+    pub key: String,
+    pub scope: AttributeScope,
+    pub data_type: AttributeDataType,
+    #[serde(default)]
+    pub allowed_values: Vec<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+impl AttributeDefinition {
+    fn check_value(&self, value: &str) -> Result<(), String> {
+        let valid = match self.data_type {
+            AttributeDataType::String => true,
+            AttributeDataType::Integer => value.parse::<i64>().is_ok(),
+            AttributeDataType::Decimal => value.parse::<f64>().is_ok_and(f64::is_finite),
+            AttributeDataType::Boolean => value == "true" || value == "false",
+            AttributeDataType::Enum => self.allowed_values.iter().any(|v| v == value),
+        };
+
+        if valid {
+            return Ok(());
+        }
+        match self.data_type {
+            AttributeDataType::Enum => Err(format!(
+                "'{value}' is not one of [{}]",
+                self.allowed_values.join(", ")
+            )),
+            data_type => Err(format!("'{value}' is not a valid {data_type:?} value")),
+        }
+    }
+}
+
+/// A single attribute that failed schema validation, e.g.
+/// `defining_attributes.colour: not defined for product type 'shoe'`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeViolation {
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for AttributeViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// The attributes allowed on products of one product_type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributeSchema {
+    #[serde(rename = "_id")]
+    pub product_type: String,
+    pub attributes: Vec<AttributeDefinition>,
+    pub updated_by: Option<String>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AttributeSchema {
+    pub fn new(
+        product_type: String,
+        attributes: Vec<AttributeDefinition>,
+        updated_by: Option<String>,
+    ) -> Self {
+        Self {
+            product_type,
+            attributes,
+            updated_by,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Check the schema itself is usable before it is stored
+    pub fn check_definitions(&self) -> Result<(), String> {
+        if self.product_type.trim().is_empty() {
+            return Err("product_type must not be empty".to_string());
+        }
+
+        let mut seen = HashSet::new();
+        for definition in &self.attributes {
+            if definition.key.trim().is_empty() {
+                return Err("attribute keys must not be empty".to_string());
+            }
+            if !seen.insert((definition.scope, definition.key.as_str())) {
+                return Err(format!(
+                    "{}.{} is defined more than once",
+                    definition.scope.field_name(),
+                    definition.key
+                ));
+            }
+            if definition.data_type == AttributeDataType::Enum
+                && definition.allowed_values.is_empty()
+            {
+                return Err(format!(
+                    "{}.{} is an enum without allowed_values",
+                    definition.scope.field_name(),
+                    definition.key
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate a product's attribute maps, including each variant's defining
+    /// attributes. Returns every violation rather than stopping at the first.
+    pub fn validate(&self, product: &Product) -> Vec<AttributeViolation> {
+        let mut violations = self.validate_map(
+            AttributeScope::Defining,
+            AttributeScope::Defining.field_name(),
+            &product.defining_attributes,
+            true,
+        );
+        violations.extend(self.validate_map(
+            AttributeScope::Descriptive,
+            AttributeScope::Descriptive.field_name(),
+            &product.descriptive_attributes,
+            true,
+        ));
+
+        // Variants carry a subset of the defining attributes, so required
+        // attributes are only enforced at product level
+        for variant in &product.variants {
+            if let Some(attributes) = &variant.defining_attributes {
+                let prefix = format!("variants[{}].defining_attributes", variant.sku);
+                violations.extend(self.validate_map(
+                    AttributeScope::Defining,
+                    &prefix,
+                    attributes,
+                    false,
+                ));
+            }
+        }
+
+        violations
+    }
+
+    fn validate_map(
+        &self,
+        scope: AttributeScope,
+        prefix: &str,
+        attributes: &HashMap<String, String>,
+        enforce_required: bool,
+    ) -> Vec<AttributeViolation> {
+        let definitions: HashMap<&str, &AttributeDefinition> = self
+            .attributes
+            .iter()
+            .filter(|d| d.scope == scope)
+            .map(|d| (d.key.as_str(), d))
+            .collect();
+
+        let mut violations: Vec<AttributeViolation> = attributes
+            .iter()
+            .filter_map(|(key, value)| {
+                let message = match definitions.get(key.as_str()) {
+                    Some(definition) => definition.check_value(value).err()?,
+                    None => format!("not defined for product type '{}'", self.product_type),
+                };
+                Some(AttributeViolation {
+                    field: format!("{prefix}.{key}"),
+                    message,
+                })
+            })
+            .collect();
+
+        if enforce_required {
+            violations.extend(
+                definitions
+                    .values()
+                    .filter(|d| d.required && !attributes.contains_key(&d.key))
+                    .map(|d| AttributeViolation {
+                        field: format!("{prefix}.{}", d.key),
+                        message: "is required".to_string(),
+                    }),
+            );
+        }
+
+        violations.sort_by(|a, b| a.field.cmp(&b.field));
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ProductBuilder, ProductVariantBuilder};
+
+    fn definition(
+        key: &str,
+        scope: AttributeScope,
+        data_type: AttributeDataType,
+    ) -> AttributeDefinition {
+        AttributeDefinition {
+            key: key.to_string(),
+            scope,
+            data_type,
+            allowed_values: vec![],
+            required: false,
+        }
+    }
+
+    fn shoe_schema() -> AttributeSchema {
+        let mut color = definition("color", AttributeScope::Defining, AttributeDataType::Enum);
+        color.allowed_values = vec!["black".to_string(), "white".to_string()];
+        color.required = true;
+
+        AttributeSchema::new(
+            "shoe".to_string(),
+            vec![
+                color,
+                definition("size", AttributeScope::Defining, AttributeDataType::Decimal),
+                definition(
+                    "waterproof",
+                    AttributeScope::Descriptive,
+                    AttributeDataType::Boolean,
+                ),
+            ],
+            None,
+        )
+    }
+
+    fn product(defining: &[(&str, &str)], descriptive: &[(&str, &str)]) -> Product {
+        let to_map = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+        ProductBuilder::new("Trail Runner".to_string(), "TR-001".to_string())
+            .product_type("shoe".to_string())
+            .defining_attributes(to_map(defining))
+            .descriptive_attributes(to_map(descriptive))
+            .build()
+    }
+
+    #[test]
+    fn valid_product_has_no_violations() {
+        let product = product(
+            &[("color", "black"), ("size", "9.5")],
+            &[("waterproof", "true")],
+        );
+        assert!(shoe_schema().validate(&product).is_empty());
+    }
+
+    #[test]
+    fn unknown_keys_bad_values_and_missing_required_are_reported() {
+        let product = product(
+            &[("colour", "black"), ("size", "large")],
+            &[("waterproof", "yes")],
+        );
+
+        let fields: Vec<String> = shoe_schema()
+            .validate(&product)
+            .into_iter()
+            .map(|v| v.field)
+            .collect();
+
+        assert_eq!(
+            fields,
+            vec![
+                "defining_attributes.color",
+                "defining_attributes.colour",
+                "defining_attributes.size",
+                "descriptive_attributes.waterproof",
+            ]
+        );
+    }
+
+    #[test]
+    fn variant_attributes_are_validated_without_required_check() {
+        let mut product = product(&[("color", "black")], &[]);
+        product.variants.push(
+            ProductVariantBuilder::new("TR-001-RED".to_string())
+                .defining_attributes(HashMap::from([("color".to_string(), "red".to_string())]))
+                .build(),
+        );
+
+        let violations = shoe_schema().validate(&product);
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].field,
+            "variants[TR-001-RED].defining_attributes.color"
+        );
+    }
+
+    #[test]
+    fn enum_without_allowed_values_is_rejected() {
+        let schema = AttributeSchema::new(
+            "shoe".to_string(),
+            vec![definition(
+                "color",
+                AttributeScope::Defining,
+                AttributeDataType::Enum,
+            )],
+            None,
+        );
+        assert!(schema.check_definitions().is_err());
+        assert!(shoe_schema().check_definitions().is_ok());
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// How a bundle's price is found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BundlePricing {
+    #[default]
+    OwnOffer, // The bundle's own SKU has an offer in price-service
+    ComponentSum, // Components' best offers times their quantities, added up
+}
+
+/// A variant SKU of another product, and how many of it one bundle holds
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleComponent {
+    pub sku: String,
+    pub quantity: i32,
+}
+
+/// Set on products sold as a kit of other products' variants
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProductBundle {
+    pub components: Vec<BundleComponent>,
+    #[serde(default)]
+    pub pricing: BundlePricing,
+}
+
+impl ProductBundle {
+    /// Check the bundle on its own: at least one component, each SKU listed once
+    /// with a positive quantity, and none of them one of the bundle's `own_skus`
+    pub fn validate(&self, own_skus: &[String]) -> Result<(), String> {
+        if self.components.is_empty() {
+            return Err("A bundle needs at least one component".to_string());
+        }
+
+        let mut seen: Vec<&str> = Vec::new();
+        for component in &self.components {
+            if component.sku.trim().is_empty() {
+                return Err("Bundle component SKUs cannot be empty".to_string());
+            }
+            if component.quantity < 1 {
+                return Err(format!(
+                    "Bundle component {} needs a quantity of at least 1, got {}",
+                    component.sku, component.quantity
+                ));
+            }
+            if own_skus.contains(&component.sku) {
+                return Err(format!(
+                    "Bundle component {} is one of the bundle's own SKUs",
+                    component.sku
+                ));
+            }
+            if seen.contains(&component.sku.as_str()) {
+                return Err(format!(
+                    "Bundle component {} is listed more than once",
+                    component.sku
+                ));
+            }
+            seen.push(&component.sku);
+        }
+        Ok(())
+    }
+
+    pub fn component_skus(&self) -> Vec<String> {
+        self.components.iter().map(|c| c.sku.clone()).collect()
+    }
+
+    /// Whole bundles the components' stock can make up; a component missing
+    /// from `available` has none
+    pub fn available_quantity(&self, available: &HashMap<String, i32>) -> i32 {
+        self.components
+            .iter()
+            .map(|component| {
+                let stock = available.get(&component.sku).copied().unwrap_or(0).max(0);
+                stock / component.quantity.max(1)
+            })
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Each component's unit price times its quantity, added up. Fails with the
+    /// SKUs that have no usable price in `prices`.
+    pub fn component_sum(&self, prices: &HashMap<String, String>) -> Result<Decimal, Vec<String>> {
+        let mut total = Decimal::ZERO;
+        let mut unpriced = Vec::new();
+        for component in &self.components {
+            match prices
+                .get(&component.sku)
+                .and_then(|price| Decimal::from_str(price).ok())
+            {
+                Some(price) => total += price * Decimal::from(component.quantity),
+                None => unpriced.push(component.sku.clone()),
+            }
+        }
+
+        if unpriced.is_empty() {
+            Ok(total)
+        } else {
+            Err(unpriced)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle(components: &[(&str, i32)]) -> ProductBundle {
+        ProductBundle {
+            components: components
+                .iter()
+                .map(|(sku, quantity)| BundleComponent {
+                    sku: sku.to_string(),
+                    quantity: *quantity,
+                })
+                .collect(),
+            pricing: BundlePricing::ComponentSum,
+        }
+    }
+
+    #[test]
+    fn bundles_need_distinct_positive_foreign_components() {
+        let own = vec!["KIT-1".to_string()];
+
+        assert!(bundle(&[("TENT", 1), ("PEG", 8)]).validate(&own).is_ok());
+        assert!(bundle(&[]).validate(&own).is_err());
+        assert!(bundle(&[("PEG", 0)]).validate(&own).is_err());
+        assert!(bundle(&[("PEG", 2), ("PEG", 4)]).validate(&own).is_err());
+        assert!(bundle(&[("KIT-1", 1)]).validate(&own).is_err());
+    }
+
+    #[test]
+    fn availability_is_limited_by_the_scarcest_component() {
+        let kit = bundle(&[("TENT", 1), ("PEG", 8)]);
+        let stock = HashMap::from([("TENT".to_string(), 5), ("PEG".to_string(), 20)]);
+        assert_eq!(kit.available_quantity(&stock), 2);
+
+        let stock = HashMap::from([("TENT".to_string(), 5)]);
+        assert_eq!(kit.available_quantity(&stock), 0);
+    }
+
+    #[test]
+    fn component_sum_multiplies_by_quantity_or_names_missing_prices() {
+        let kit = bundle(&[("TENT", 1), ("PEG", 8)]);
+        let prices = HashMap::from([
+            ("TENT".to_string(), "149.99".to_string()),
+            ("PEG".to_string(), "0.75".to_string()),
+        ]);
+        assert_eq!(kit.component_sum(&prices), Ok(Decimal::new(15599, 2)));
+
+        let prices = HashMap::from([("TENT".to_string(), "149.99".to_string())]);
+        assert_eq!(kit.component_sum(&prices), Err(vec!["PEG".to_string()]));
+    }
+}
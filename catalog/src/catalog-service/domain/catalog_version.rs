@@ -0,0 +1,530 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{Category, Product};
+
+/// Change set staged edits go into when the caller does not name one
+pub const DEFAULT_CHANGE_SET: &str = "default";
+
+/// How long a version may stay publishing. One still publishing after this
+/// long was abandoned: whatever it wrote is undone and it is marked failed.
+pub const PUBLISH_LEASE: Duration = Duration::minutes(10);
+
+/// Which state of the catalog a read is served from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogVersionRef {
+    Live,
+    Draft,          // Live with the staged edits laid over it
+    Published(i64), // As it was right after that version was published
+}
+
+impl FromStr for CatalogVersionRef {
+    type Err = String;
+
+    /// "live", "draft" or a published version number
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "live" => Ok(CatalogVersionRef::Live),
+            "draft" => Ok(CatalogVersionRef::Draft),
+            version => match version.parse::<i64>() {
+                Ok(version) if version > 0 => Ok(CatalogVersionRef::Published(version)),
+                _ => Err(format!(
+                    "Catalog version must be 'live', 'draft' or a version number, got '{s}'"
+                )),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogEntityType {
+    Product,
+    Category,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogChangeAction {
+    Create,
+    Update,
+    Delete,
+}
+
+/// An edit waiting in the staging workspace. Product and category documents
+/// are complete and already validated; publishing writes them as they are.
+/// Categories only have their content staged: creating, moving or deleting
+/// one changes the tree, which is not versioned.
+///
+/// Updates keep the `updated_at` of the live document they were staged
+/// against. Publishing refuses one whose live document has changed since,
+/// rather than overwrite that edit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum StagedEdit {
+    CreateProduct {
+        product: Product,
+    },
+    UpdateProduct {
+        product: Product,
+        #[serde(default)]
+        base_updated_at: Option<DateTime<Utc>>,
+    },
+    DeleteProduct {
+        product_id: String,
+    },
+    UpdateCategory {
+        category: Category,
+        #[serde(default)]
+        base_updated_at: Option<DateTime<Utc>>,
+    },
+}
+
+impl StagedEdit {
+    pub fn entity_type(&self) -> CatalogEntityType {
+        match self {
+            StagedEdit::CreateProduct { .. }
+            | StagedEdit::UpdateProduct { .. }
+            | StagedEdit::DeleteProduct { .. } => CatalogEntityType::Product,
+            StagedEdit::UpdateCategory { .. } => CatalogEntityType::Category,
+        }
+    }
+
+    pub fn entity_id(&self) -> String {
+        match self {
+            StagedEdit::CreateProduct { product } | StagedEdit::UpdateProduct { product, .. } => {
+                product.id.clone().unwrap_or_default()
+            }
+            StagedEdit::DeleteProduct { product_id } => product_id.clone(),
+            StagedEdit::UpdateCategory { category, .. } => category.id.clone().unwrap_or_default(),
+        }
+    }
+
+    pub fn action(&self) -> CatalogChangeAction {
+        match self {
+            StagedEdit::CreateProduct { .. } => CatalogChangeAction::Create,
+            StagedEdit::UpdateProduct { .. } | StagedEdit::UpdateCategory { .. } => {
+                CatalogChangeAction::Update
+            }
+            StagedEdit::DeleteProduct { .. } => CatalogChangeAction::Delete,
+        }
+    }
+}
+
+/// Key of the staged change for an entity; an entity has at most one
+pub fn staged_change_id(entity_type: CatalogEntityType, entity_id: &str) -> String {
+    match entity_type {
+        CatalogEntityType::Product => format!("product:{entity_id}"),
+        CatalogEntityType::Category => format!("category:{entity_id}"),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StagedChange {
+    #[serde(rename = "_id")]
+    pub id: String, // staged_change_id of the edited entity
+    pub change_set: String,
+    pub edit: StagedEdit,
+    pub staged_by: Option<String>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub staged_at: DateTime<Utc>,
+}
+
+impl StagedChange {
+    pub fn new(edit: StagedEdit, change_set: String, staged_by: Option<String>) -> Self {
+        Self {
+            id: staged_change_id(edit.entity_type(), &edit.entity_id()),
+            change_set,
+            edit,
+            staged_by,
+            staged_at: Utc::now(),
+        }
+    }
+}
+
+/// One entity's state on either side of a published version. A product
+/// without a `before` was created by the version, one without an `after`
+/// deleted by it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "entity_type", rename_all = "snake_case")]
+pub enum CatalogChange {
+    Product {
+        product_id: String,
+        before: Option<Box<Product>>,
+        after: Option<Box<Product>>,
+    },
+    Category {
+        category_id: String,
+        before: Box<Category>,
+        after: Box<Category>,
+    },
+}
+
+impl CatalogChange {
+    pub fn entity_type(&self) -> CatalogEntityType {
+        match self {
+            CatalogChange::Product { .. } => CatalogEntityType::Product,
+            CatalogChange::Category { .. } => CatalogEntityType::Category,
+        }
+    }
+
+    pub fn entity_id(&self) -> &str {
+        match self {
+            CatalogChange::Product { product_id, .. } => product_id,
+            CatalogChange::Category { category_id, .. } => category_id,
+        }
+    }
+
+    pub fn action(&self) -> CatalogChangeAction {
+        match self {
+            CatalogChange::Product { before: None, .. } => CatalogChangeAction::Create,
+            CatalogChange::Product { after: None, .. } => CatalogChangeAction::Delete,
+            _ => CatalogChangeAction::Update,
+        }
+    }
+
+    /// Name of the entity after the change, or before it for deletions
+    pub fn name(&self) -> String {
+        match self {
+            CatalogChange::Product { before, after, .. } => after
+                .as_ref()
+                .or(before.as_ref())
+                .map(|product| product.name.clone())
+                .unwrap_or_default(),
+            CatalogChange::Category { after, .. } => after.name.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogVersionStatus {
+    Publishing, // Changes are being written
+    Published,
+    Failed, // What was written has been undone
+}
+
+/// One entity a version changed, as listed on the version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogChangeSummary {
+    pub entity_type: CatalogEntityType,
+    pub entity_id: String,
+    pub action: CatalogChangeAction,
+    pub name: String,
+}
+
+impl From<&CatalogChange> for CatalogChangeSummary {
+    fn from(change: &CatalogChange) -> Self {
+        Self {
+            entity_type: change.entity_type(),
+            entity_id: change.entity_id().to_string(),
+            action: change.action(),
+            name: change.name(),
+        }
+    }
+}
+
+/// A change set promoted to live, numbered in publishing order. The images of
+/// what it changed are kept apart, as snapshot entries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogVersion {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub version: i64, // 1-based, increasing across the catalog
+    pub status: CatalogVersionStatus,
+    pub change_set: Option<String>, // None when everything staged was published
+    pub changes: Vec<CatalogChangeSummary>,
+    pub rolled_back_to: Option<i64>, // Set on versions made by a rollback
+    pub published_by: Option<String>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub started_at: DateTime<Utc>,
+    #[serde(
+        default,
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub published_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+}
+
+impl CatalogVersion {
+    pub fn new(
+        version: i64,
+        changes: &[CatalogChange],
+        change_set: Option<String>,
+        published_by: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            version,
+            status: CatalogVersionStatus::Publishing,
+            change_set,
+            changes: changes.iter().map(CatalogChangeSummary::from).collect(),
+            rolled_back_to: None,
+            published_by,
+            started_at: Utc::now(),
+            published_at: None,
+            error: None,
+        }
+    }
+}
+
+/// One entity a version changed, with its images on either side of the
+/// version. Only changed entities are kept, and only for recent versions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogSnapshotEntry {
+    #[serde(rename = "_id")]
+    pub id: String, // "<version>:<entity>"
+    pub version: i64,
+    pub entity: String, // staged_change_id of the entity
+    pub change: CatalogChange,
+}
+
+impl CatalogSnapshotEntry {
+    pub fn new(version: i64, change: CatalogChange) -> Self {
+        let entity = staged_change_id(change.entity_type(), change.entity_id());
+        Self {
+            id: format!("{version}:{entity}"),
+            version,
+            entity,
+            change,
+        }
+    }
+}
+
+// Fields kept up by the service rather than edited, so versions neither
+// compare nor restore them
+const DERIVED_PRODUCT_FIELDS: &[&str] = &[
+    "reviews",
+    "hierarchical_categories",
+    "updated_at",
+    "deleted_at",
+];
+const DERIVED_CATEGORY_FIELDS: &[&str] = &[
+    "path",
+    "ancestors",
+    "parent_id",
+    "level",
+    "children_count",
+    "product_count",
+    "subtree_product_count",
+    "created_at",
+    "updated_at",
+    "deleted_at",
+];
+
+fn same_content<T: Serialize>(a: &T, b: &T, derived: &[&str]) -> bool {
+    let content = |value: &T| {
+        bson::to_document(value).map(|mut document| {
+            for field in derived {
+                document.remove(*field);
+            }
+            document
+        })
+    };
+    match (content(a), content(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+pub fn same_product_content(a: &Product, b: &Product) -> bool {
+    same_content(a, b, DERIVED_PRODUCT_FIELDS)
+}
+
+pub fn same_category_content(a: &Category, b: &Category) -> bool {
+    same_content(a, b, DERIVED_CATEGORY_FIELDS)
+}
+
+/// `content` as written over `live`: only category content is versioned, so
+/// the tree position and counts stay as they are
+pub fn category_content_onto(live: &Category, content: &Category, now: DateTime<Utc>) -> Category {
+    Category {
+        id: live.id.clone(),
+        path: live.path.clone(),
+        ancestors: live.ancestors.clone(),
+        parent_id: live.parent_id.clone(),
+        level: live.level,
+        children_count: live.children_count,
+        product_count: live.product_count,
+        subtree_product_count: live.subtree_product_count,
+        created_at: live.created_at,
+        updated_at: now,
+        deleted_at: None,
+        ..content.clone()
+    }
+}
+
+/// Changes taking the live catalog back to how a version left it. `first_after`
+/// holds, for each entity a later version changed, the change of the earliest
+/// such version: its before image is the entity as the version left it.
+/// Entities no later version changed are left alone, as are categories
+/// deleted since, since only category content is versioned.
+pub fn changes_back_to(
+    first_after: Vec<CatalogChange>,
+    live_products: &HashMap<String, Product>,
+    live_categories: &HashMap<String, Category>,
+) -> Vec<CatalogChange> {
+    let mut changes = Vec::new();
+    for change in first_after {
+        match change {
+            CatalogChange::Product {
+                product_id, before, ..
+            } => {
+                let live = live_products.get(&product_id);
+                let unchanged = match (live, &before) {
+                    (Some(live), Some(before)) => same_product_content(live, before),
+                    (None, None) => true,
+                    _ => false,
+                };
+                if !unchanged {
+                    changes.push(CatalogChange::Product {
+                        product_id,
+                        before: live.cloned().map(Box::new),
+                        after: before,
+                    });
+                }
+            }
+            CatalogChange::Category {
+                category_id,
+                before,
+                ..
+            } => {
+                let Some(live) = live_categories.get(&category_id) else {
+                    continue;
+                };
+                if !same_category_content(live, &before) {
+                    changes.push(CatalogChange::Category {
+                        category_id,
+                        before: Box::new(live.clone()),
+                        after: before,
+                    });
+                }
+            }
+        }
+    }
+    changes.sort_by(|a, b| a.entity_id().cmp(b.entity_id()));
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ProductBuilder;
+
+    fn product(id: &str, name: &str) -> Product {
+        let mut product = ProductBuilder::new(name.to_string(), id.to_uppercase()).build();
+        product.id = Some(id.to_string());
+        product.created_at = None;
+        product
+    }
+
+    fn category(id: &str, name: &str) -> Category {
+        let mut category = Category::new(id.to_string(), name.to_string(), String::new(), None, 0);
+        category.id = Some(id.to_string());
+        category
+    }
+
+    fn product_change(id: &str, before: Option<&str>, after: Option<&str>) -> CatalogChange {
+        CatalogChange::Product {
+            product_id: id.to_string(),
+            before: before.map(|name| Box::new(product(id, name))),
+            after: after.map(|name| Box::new(product(id, name))),
+        }
+    }
+
+    #[test]
+    fn version_refs_parse_from_names_and_numbers() {
+        assert_eq!("Draft".parse(), Ok(CatalogVersionRef::Draft));
+        assert_eq!("live".parse(), Ok(CatalogVersionRef::Live));
+        assert_eq!(" 12 ".parse(), Ok(CatalogVersionRef::Published(12)));
+        assert!("0".parse::<CatalogVersionRef>().is_err());
+        assert!("latest".parse::<CatalogVersionRef>().is_err());
+    }
+
+    #[test]
+    fn changes_describe_their_action() {
+        assert_eq!(
+            product_change("a", None, Some("Tent")).action(),
+            CatalogChangeAction::Create
+        );
+        assert_eq!(
+            product_change("a", Some("Tent"), None).action(),
+            CatalogChangeAction::Delete
+        );
+        let change = product_change("a", Some("Tent"), Some("Tent 2"));
+        assert_eq!(change.action(), CatalogChangeAction::Update);
+        assert_eq!(change.name(), "Tent 2");
+    }
+
+    #[test]
+    fn rollback_changes_take_live_back_to_the_earliest_before_image() {
+        let mut reviewed = product("same", "Tent");
+        reviewed.updated_at = Some(Utc::now());
+        let mut moved = category("moved", "Tents");
+        moved.parent_id = Some("outdoor".to_string());
+        let live_products: HashMap<String, Product> = [
+            reviewed,
+            product("edited", "Stove 2"),
+            product("new", "Lamp"),
+        ]
+        .into_iter()
+        .map(|product| (product.id.clone().unwrap(), product))
+        .collect();
+        let live_categories: HashMap<String, Category> = [moved, category("renamed", "Cooking 2")]
+            .into_iter()
+            .map(|category| (category.id.clone().unwrap(), category))
+            .collect();
+        let category_change = |id: &str, before: &str, after: &str| CatalogChange::Category {
+            category_id: id.to_string(),
+            before: Box::new(category(id, before)),
+            after: Box::new(category(id, after)),
+        };
+
+        let changes = changes_back_to(
+            vec![
+                product_change("same", Some("Tent"), Some("Tent 2")),
+                product_change("edited", Some("Stove"), Some("Stove 2")),
+                product_change("gone", Some("Mug"), None),
+                product_change("new", None, Some("Lamp")),
+                category_change("moved", "Tents", "Tents"),
+                category_change("renamed", "Cooking", "Cooking 2"),
+                category_change("deleted", "Maps", "Maps 2"),
+            ],
+            &live_products,
+            &live_categories,
+        );
+
+        let summary: Vec<(&str, CatalogChangeAction, String)> = changes
+            .iter()
+            .map(|change| (change.entity_id(), change.action(), change.name()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("edited", CatalogChangeAction::Update, "Stove".to_string()),
+                ("gone", CatalogChangeAction::Create, "Mug".to_string()),
+                ("new", CatalogChangeAction::Delete, "Lamp".to_string()),
+                (
+                    "renamed",
+                    CatalogChangeAction::Update,
+                    "Cooking".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn category_content_keeps_the_live_tree_position() {
+        let mut live = category("tents", "Tents");
+        live.parent_id = Some("outdoor".to_string());
+        live.product_count = 4;
+        let written = category_content_onto(&live, &category("tents", "Tents 2"), Utc::now());
+        assert_eq!(written.name, "Tents 2");
+        assert_eq!(written.parent_id.as_deref(), Some("outdoor"));
+        assert_eq!(written.product_count, 4);
+    }
+}
@@ -0,0 +1,406 @@
+use std::collections::HashMap;
+
+use super::{slugify, Category, CATEGORY_PATH_SEPARATOR};
+
+/// Separator between names in the path column unless the request names another
+pub const DEFAULT_CSV_PATH_SEPARATOR: &str = ">";
+
+/// Columns a category CSV may have; only "path" is required
+const CSV_COLUMNS: [&str; 7] = [
+    "path",
+    "short_description",
+    "full_description",
+    "meta_title",
+    "meta_description",
+    "keywords",
+    "display_order",
+];
+
+/// Optional values from a CSV row. A blank cell leaves the category's value alone.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CategoryCsvFields {
+    pub short_description: Option<String>,
+    pub full_description: Option<String>,
+    pub meta_title: Option<String>,
+    pub meta_description: Option<String>,
+    pub keywords: Option<Vec<String>>, // Split on ',' or ';'
+    pub display_order: Option<i32>,
+}
+
+impl CategoryCsvFields {
+    pub fn has_seo(&self) -> bool {
+        self.meta_title.is_some() || self.meta_description.is_some() || self.keywords.is_some()
+    }
+}
+
+/// One row of a category CSV: a breadcrumb path such as "Apparel > Men > Shirts"
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategoryCsvRow {
+    pub line: u64,
+    pub names: Vec<String>, // Root first
+    pub fields: CategoryCsvFields,
+}
+
+/// Rows that parsed, and a message for each one that did not
+#[derive(Debug, Default)]
+pub struct ParsedCategoryCsv {
+    pub rows: Vec<CategoryCsvRow>,
+    pub errors: Vec<String>,
+}
+
+/// Parse a category CSV with a header row. Header names are matched ignoring case,
+/// with spaces and hyphens read as underscores, so "Meta Title" is meta_title.
+/// Fails as a whole on an unreadable header, a missing path column or an unknown
+/// column; problems in individual rows are collected in `errors`.
+pub fn parse_category_csv(input: &str, separator: &str) -> Result<ParsedCategoryCsv, String> {
+    let separator = if separator.trim().is_empty() {
+        DEFAULT_CSV_PATH_SEPARATOR
+    } else {
+        separator.trim()
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(input.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("Failed to read CSV header: {e}"))?;
+    let mut columns = HashMap::new();
+    for (index, header) in headers.iter().enumerate() {
+        let column = header.to_lowercase().replace([' ', '-'], "_");
+        if !CSV_COLUMNS.contains(&column.as_str()) {
+            return Err(format!(
+                "Unknown CSV column '{header}', expected some of: {}",
+                CSV_COLUMNS.join(", ")
+            ));
+        }
+        columns.insert(column, index);
+    }
+    if !columns.contains_key("path") {
+        return Err("CSV header has no 'path' column".to_string());
+    }
+
+    let mut parsed = ParsedCategoryCsv::default();
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                parsed.errors.push(format!("Failed to read CSV row: {e}"));
+                continue;
+            }
+        };
+        let line = record.position().map(|p| p.line()).unwrap_or_default();
+        let cell = |column: &str| {
+            columns
+                .get(column)
+                .and_then(|&index| record.get(index))
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+
+        let Some(path) = cell("path") else {
+            // Spreadsheets export trailing blank rows
+            if record.iter().all(str::is_empty) {
+                continue;
+            }
+            parsed.errors.push(format!("Line {line}: path is empty"));
+            continue;
+        };
+        let names: Vec<String> = path
+            .split(separator)
+            .map(|name| name.trim().to_string())
+            .collect();
+        if let Some(name) = names.iter().find(|name| slugify(name).is_empty()) {
+            parsed.errors.push(format!(
+                "Line {line}: '{name}' in path '{path}' is not a usable category name"
+            ));
+            continue;
+        }
+
+        let display_order = match cell("display_order").map(|value| value.parse::<i32>()) {
+            None => None,
+            Some(Ok(order)) => Some(order),
+            Some(Err(_)) => {
+                parsed
+                    .errors
+                    .push(format!("Line {line}: display_order must be a whole number"));
+                continue;
+            }
+        };
+        let keywords = cell("keywords").map(|value| {
+            value
+                .split([',', ';'])
+                .map(str::trim)
+                .filter(|keyword| !keyword.is_empty())
+                .map(str::to_string)
+                .collect()
+        });
+
+        parsed.rows.push(CategoryCsvRow {
+            line,
+            names,
+            fields: CategoryCsvFields {
+                short_description: cell("short_description"),
+                full_description: cell("full_description"),
+                meta_title: cell("meta_title"),
+                meta_description: cell("meta_description"),
+                keywords,
+                display_order,
+            },
+        });
+    }
+
+    Ok(parsed)
+}
+
+/// Hierarchical slug for a path of names, as generated for categories created
+/// without one: ["Apparel", "Men's"] is "/apparel/men-s"
+pub fn category_path_slug(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| format!("/{}", slugify(name)))
+        .collect()
+}
+
+/// What importing a CSV does to one category
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CategoryImportAction {
+    Create,
+    Update,
+    Unchanged,
+}
+
+/// A category the CSV names, directly or as an ancestor of a listed path
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategoryImportChange {
+    pub action: CategoryImportAction,
+    pub slug: String,
+    pub parent_slug: Option<String>,
+    pub name: String,
+    pub path: String,                // Breadcrumb, e.g. "Apparel > Men"
+    pub line: Option<u64>,           // None for ancestors no row lists
+    pub category_id: Option<String>, // The existing category, if any
+    pub changed_fields: Vec<String>, // For updates
+    pub fields: CategoryCsvFields,
+}
+
+/// Diff CSV rows against the existing categories, keyed by slug. Every ancestor
+/// of a listed path is planned too, and parents always come before their
+/// children. A path listed on more than one row is an error; the first row wins.
+pub fn plan_category_import(
+    rows: &[CategoryCsvRow],
+    existing: &HashMap<String, Category>,
+) -> (Vec<CategoryImportChange>, Vec<String>) {
+    let mut changes: Vec<CategoryImportChange> = Vec::new();
+    let mut index_by_slug: HashMap<String, usize> = HashMap::new();
+    let mut errors = Vec::new();
+
+    for row in rows {
+        for depth in 0..row.names.len() {
+            let names = &row.names[..=depth];
+            let slug = category_path_slug(names);
+            let is_leaf = depth + 1 == row.names.len();
+
+            let index = match index_by_slug.get(&slug) {
+                Some(&index) => index,
+                None => {
+                    changes.push(CategoryImportChange {
+                        action: CategoryImportAction::Create,
+                        slug: slug.clone(),
+                        parent_slug: (depth > 0).then(|| category_path_slug(&names[..depth])),
+                        name: names[depth].clone(),
+                        path: names.join(CATEGORY_PATH_SEPARATOR),
+                        line: None,
+                        category_id: None,
+                        changed_fields: Vec::new(),
+                        fields: CategoryCsvFields::default(),
+                    });
+                    index_by_slug.insert(slug, changes.len() - 1);
+                    changes.len() - 1
+                }
+            };
+
+            if is_leaf {
+                let change = &mut changes[index];
+                if let Some(first_line) = change.line {
+                    errors.push(format!(
+                        "Line {}: path '{}' is already on line {first_line}",
+                        row.line, change.path
+                    ));
+                } else {
+                    change.line = Some(row.line);
+                    change.fields = row.fields.clone();
+                }
+            }
+        }
+    }
+
+    for change in &mut changes {
+        if let Some(category) = existing.get(&change.slug) {
+            change.category_id = category.id.clone();
+            change.changed_fields = changed_fields(category, &change.name, &change.fields);
+            change.action = if change.changed_fields.is_empty() {
+                CategoryImportAction::Unchanged
+            } else {
+                CategoryImportAction::Update
+            };
+        }
+    }
+
+    (changes, errors)
+}
+
+/// Names of the fields that importing `name` and `fields` would change on `category`
+fn changed_fields(category: &Category, name: &str, fields: &CategoryCsvFields) -> Vec<String> {
+    let mut changed = Vec::new();
+    let mut check = |field: &str, differs: bool| {
+        if differs {
+            changed.push(field.to_string());
+        }
+    };
+
+    check("name", category.name != name);
+    check(
+        "short_description",
+        fields
+            .short_description
+            .as_ref()
+            .is_some_and(|value| *value != category.short_description),
+    );
+    check(
+        "full_description",
+        fields
+            .full_description
+            .as_ref()
+            .is_some_and(|value| Some(value) != category.full_description.as_ref()),
+    );
+    check(
+        "meta_title",
+        fields
+            .meta_title
+            .as_ref()
+            .is_some_and(|value| Some(value) != category.seo.meta_title.as_ref()),
+    );
+    check(
+        "meta_description",
+        fields
+            .meta_description
+            .as_ref()
+            .is_some_and(|value| Some(value) != category.seo.meta_description.as_ref()),
+    );
+    check(
+        "keywords",
+        fields
+            .keywords
+            .as_ref()
+            .is_some_and(|value| *value != category.seo.keywords),
+    );
+    check(
+        "display_order",
+        fields
+            .display_order
+            .is_some_and(|value| value != category.display_order),
+    );
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn existing(slug: &str, name: &str) -> Category {
+        let mut category =
+            Category::new(slug.to_string(), name.to_string(), String::new(), None, 0);
+        category.id = Some(format!("id-{name}"));
+        category
+    }
+
+    #[test]
+    fn rows_parse_with_quoted_cells_and_loose_headers() {
+        let csv = "Path,Meta Title,keywords,display-order\n\
+                   \"Apparel > Men > Shirts\",\"Shirts, for men\",\"cotton; linen\",3\n\
+                   Apparel / Women,,,\n\
+                   ,,,\n";
+        let parsed = parse_category_csv(csv, ">").unwrap();
+
+        assert!(parsed.errors.is_empty());
+        assert_eq!(parsed.rows.len(), 2);
+        let shirts = &parsed.rows[0];
+        assert_eq!(shirts.line, 2);
+        assert_eq!(shirts.names, vec!["Apparel", "Men", "Shirts"]);
+        assert_eq!(shirts.fields.meta_title.as_deref(), Some("Shirts, for men"));
+        assert_eq!(
+            shirts.fields.keywords,
+            Some(vec!["cotton".to_string(), "linen".to_string()])
+        );
+        assert_eq!(shirts.fields.display_order, Some(3));
+        // Blank cells leave values alone; another separator is just part of the name
+        assert_eq!(parsed.rows[1].names, vec!["Apparel / Women"]);
+        assert_eq!(parsed.rows[1].fields, CategoryCsvFields::default());
+    }
+
+    #[test]
+    fn bad_rows_are_reported_and_bad_headers_fail() {
+        let parsed =
+            parse_category_csv("path,display_order\nA > > B,\nShoes,first\nBags,\n", "").unwrap();
+        assert_eq!(parsed.rows.len(), 1);
+        assert_eq!(parsed.errors.len(), 2);
+        assert!(parsed.errors[0].starts_with("Line 2:"));
+
+        assert!(parse_category_csv("name\nShoes\n", ">").is_err());
+        assert!(parse_category_csv("path,colour\nShoes,red\n", ">").is_err());
+    }
+
+    #[test]
+    fn plan_creates_missing_ancestors_and_diffs_existing_categories() {
+        let rows = parse_category_csv(
+            "path,meta_title\nApparel > Men > Shirts,Men's shirts\nApparel,\nApparel > Men,Men\n",
+            ">",
+        )
+        .unwrap()
+        .rows;
+        let existing = HashMap::from([
+            ("/apparel".to_string(), existing("/apparel", "Apparel")),
+            ("/apparel/men".to_string(), existing("/apparel/men", "Men")),
+        ]);
+
+        let (changes, errors) = plan_category_import(&rows, &existing);
+
+        assert!(errors.is_empty());
+        let slugs: Vec<&str> = changes.iter().map(|c| c.slug.as_str()).collect();
+        assert_eq!(
+            slugs,
+            vec!["/apparel", "/apparel/men", "/apparel/men/shirts"]
+        );
+
+        assert_eq!(changes[0].action, CategoryImportAction::Unchanged);
+        assert_eq!(changes[0].line, Some(3));
+        assert_eq!(changes[1].action, CategoryImportAction::Update);
+        assert_eq!(changes[1].changed_fields, vec!["meta_title".to_string()]);
+        assert_eq!(changes[1].category_id.as_deref(), Some("id-Men"));
+
+        let shirts = &changes[2];
+        assert_eq!(shirts.action, CategoryImportAction::Create);
+        assert_eq!(shirts.parent_slug.as_deref(), Some("/apparel/men"));
+        assert_eq!(shirts.path, "Apparel > Men > Shirts");
+        assert_eq!(shirts.fields.meta_title.as_deref(), Some("Men's shirts"));
+    }
+
+    #[test]
+    fn repeated_paths_are_errors() {
+        let rows = parse_category_csv("path\nShoes\nShoes > Boots\nshoes\n", ">")
+            .unwrap()
+            .rows;
+
+        let (changes, errors) = plan_category_import(&rows, &HashMap::new());
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].line, Some(2));
+        assert_eq!(
+            errors,
+            vec!["Line 4: path 'Shoes' is already on line 2".to_string()]
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::Category;
+
+/// A merge that has started and not yet finished. It is recorded before the
+/// first write and removed after the last, so a merge that failed part way can
+/// be found and rerun; every step skips what an earlier attempt already did.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryMerge {
+    #[serde(rename = "_id")]
+    pub source_id: String,
+    pub target_id: String,
+    pub source_slug: String, // The source may be gone by the time a retry runs
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub started_at: DateTime<Utc>,
+}
+
+impl CategoryMerge {
+    pub fn new(source_id: String, target_id: String, source_slug: String) -> Self {
+        Self {
+            source_id,
+            target_id,
+            source_slug,
+            started_at: Utc::now(),
+        }
+    }
+
+    /// A source half merged into one target cannot be merged into another,
+    /// or its products would end up split between the two
+    pub fn check_target(&self, target_id: &str) -> Result<(), String> {
+        if self.target_id == target_id {
+            Ok(())
+        } else {
+            Err(format!(
+                "Category {} is part way through a merge into {}; rerun that merge to finish it",
+                self.source_id, self.target_id
+            ))
+        }
+    }
+}
+
+/// A product's list_categories after `source` is merged into `target`. Entries
+/// naming the source by id or slug name the target the same way; when the
+/// product then lists the target twice, the first entry is kept.
+pub fn merged_list_categories(
+    list_categories: &[String],
+    source: &Category,
+    target: &Category,
+) -> Vec<String> {
+    let target_id = target.id.as_deref().unwrap_or_default();
+    let is_target = |entry: &str| entry == target_id || entry == target.slug;
+
+    let mut merged: Vec<String> = Vec::with_capacity(list_categories.len());
+    for entry in list_categories {
+        let entry = if source.id.as_deref() == Some(entry.as_str()) {
+            target_id.to_string()
+        } else if *entry == source.slug {
+            target.slug.clone()
+        } else {
+            entry.clone()
+        };
+
+        let duplicate = if is_target(&entry) {
+            merged.iter().any(|kept| is_target(kept))
+        } else {
+            merged.contains(&entry)
+        };
+        if !duplicate {
+            merged.push(entry);
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(id: &str, slug: &str) -> Category {
+        let mut category = Category::new(slug.to_string(), id.to_string(), String::new(), None, 0);
+        category.id = Some(id.to_string());
+        category
+    }
+
+    fn list(entries: &[&str]) -> Vec<String> {
+        entries.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn source_refs_become_target_refs_of_the_same_kind() {
+        let source = category("src-id", "src-slug");
+        let target = category("dst-id", "dst-slug");
+
+        assert_eq!(
+            merged_list_categories(&list(&["other", "src-id"]), &source, &target),
+            list(&["other", "dst-id"])
+        );
+        assert_eq!(
+            merged_list_categories(&list(&["src-slug"]), &source, &target),
+            list(&["dst-slug"])
+        );
+    }
+
+    #[test]
+    fn an_unfinished_merge_only_resumes_into_its_own_target() {
+        let merge = CategoryMerge::new(
+            "src-id".to_string(),
+            "dst-id".to_string(),
+            "src-slug".to_string(),
+        );
+        assert!(merge.check_target("dst-id").is_ok());
+        assert!(merge.check_target("other-id").is_err());
+    }
+
+    #[test]
+    fn products_already_in_the_target_list_it_once() {
+        let source = category("src-id", "src-slug");
+        let target = category("dst-id", "dst-slug");
+
+        assert_eq!(
+            merged_list_categories(&list(&["dst-slug", "src-id", "other"]), &source, &target),
+            list(&["dst-slug", "other"])
+        );
+    }
+}
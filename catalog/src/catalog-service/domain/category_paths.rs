@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+
+use super::{CategoryTreeNode, HierarchicalCategories};
+
+/// Separator between category names in a hierarchical path, e.g. "Clothing > Shirts"
+pub const CATEGORY_PATH_SEPARATOR: &str = " > ";
+
+impl HierarchicalCategories {
+    /// Derive the paths for a product's list_categories from the category tree.
+    /// Entries are matched against category ids and slugs; unknown entries, and
+    /// entries in an inactive category or below one, are ignored. `levels[n]` holds the distinct paths n levels deep, in the order
+    /// the categories are listed, and lvl0..lvl2 mirror the first path at each of
+    /// the top three levels. Returns None when no entry matches a category.
+    pub fn from_tree(
+        tree: &HashMap<String, CategoryTreeNode>,
+        list_categories: &[String],
+    ) -> Option<Self> {
+        let mut levels: Vec<Vec<String>> = Vec::new();
+
+        for category_ref in list_categories {
+            let Some(names) = path_names(tree, category_ref) else {
+                continue;
+            };
+            for depth in 0..names.len() {
+                let path = names[..=depth].join(CATEGORY_PATH_SEPARATOR);
+                if levels.len() <= depth {
+                    levels.push(Vec::new());
+                }
+                if !levels[depth].contains(&path) {
+                    levels[depth].push(path);
+                }
+            }
+        }
+
+        if levels.is_empty() {
+            return None;
+        }
+
+        let first_at = |depth: usize| levels.get(depth).and_then(|paths| paths.first().cloned());
+        Some(Self {
+            lvl0: first_at(0),
+            lvl1: first_at(1),
+            lvl2: first_at(2),
+            levels,
+        })
+    }
+}
+
+impl CategoryTreeNode {
+    /// Find the node with the given id anywhere in the tree
+    pub fn find<'a>(
+        tree: &'a HashMap<String, CategoryTreeNode>,
+        category_id: &str,
+    ) -> Option<&'a CategoryTreeNode> {
+        tree.values().find_map(|node| {
+            if node.id == category_id {
+                Some(node)
+            } else {
+                Self::find(&node.children, category_id)
+            }
+        })
+    }
+
+    /// A copy of this node keeping `max_depth` levels, counting this node as the
+    /// first, and leaving out inactive nodes and everything below them unless
+    /// `include_inactive` is set. None when this node itself is left out; a
+    /// `max_depth` of None keeps every level.
+    pub fn pruned(&self, max_depth: Option<usize>, include_inactive: bool) -> Option<Self> {
+        if max_depth == Some(0) || (!include_inactive && !self.is_active) {
+            return None;
+        }
+
+        let child_depth = max_depth.map(|depth| depth - 1);
+        let children = self
+            .children
+            .iter()
+            .filter_map(|(id, child)| {
+                child
+                    .pruned(child_depth, include_inactive)
+                    .map(|child| (id.clone(), child))
+            })
+            .collect();
+
+        Some(Self {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            slug: self.slug.clone(),
+            path: self.path.clone(),
+            level: self.level,
+            product_count: self.product_count,
+            subtree_product_count: self.subtree_product_count,
+            is_active: self.is_active,
+            localizations: self.localizations.clone(),
+            stores: self.stores.clone(),
+            children,
+        })
+    }
+
+    /// Ids and slugs of this node and every descendant: the list_categories
+    /// entries that a change to this node can affect
+    pub fn subtree_refs(&self) -> Vec<String> {
+        let mut refs = vec![self.id.clone(), self.slug.clone()];
+        for child in self.children.values() {
+            refs.extend(child.subtree_refs());
+        }
+        refs
+    }
+}
+
+/// Names from the root down to the category matching `category_ref` by id or slug
+fn path_names(tree: &HashMap<String, CategoryTreeNode>, category_ref: &str) -> Option<Vec<String>> {
+    tree.values()
+        .filter(|node| node.is_active)
+        .find_map(|node| {
+            if node.id == category_ref || node.slug == category_ref {
+                return Some(vec![node.name.clone()]);
+            }
+            path_names(&node.children, category_ref).map(|mut names| {
+                names.insert(0, node.name.clone());
+                names
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str, name: &str, children: Vec<CategoryTreeNode>) -> CategoryTreeNode {
+        CategoryTreeNode {
+            id: id.to_string(),
+            name: name.to_string(),
+            slug: format!("{id}-slug"),
+            path: String::new(),
+            level: 0,
+            product_count: 0,
+            subtree_product_count: 0,
+            is_active: true,
+            localizations: HashMap::new(),
+            stores: HashMap::new(),
+            children: children.into_iter().map(|c| (c.id.clone(), c)).collect(),
+        }
+    }
+
+    fn tree() -> HashMap<String, CategoryTreeNode> {
+        let casual = node("casual", "Casual", vec![node("linen", "Linen", vec![])]);
+        let shirts = node("shirts", "Shirts", vec![casual]);
+        let pants = node("pants", "Pants", vec![]);
+        let clothing = node("clothing", "Clothing", vec![shirts, pants]);
+        HashMap::from([("clothing".to_string(), clothing)])
+    }
+
+    #[test]
+    fn paths_follow_the_tree_to_any_depth() {
+        let categories =
+            HierarchicalCategories::from_tree(&tree(), &["linen".to_string()]).unwrap();
+
+        assert_eq!(categories.lvl0.as_deref(), Some("Clothing"));
+        assert_eq!(categories.lvl1.as_deref(), Some("Clothing > Shirts"));
+        assert_eq!(
+            categories.lvl2.as_deref(),
+            Some("Clothing > Shirts > Casual")
+        );
+        assert_eq!(
+            categories.levels[3],
+            vec!["Clothing > Shirts > Casual > Linen".to_string()]
+        );
+    }
+
+    #[test]
+    fn several_categories_share_their_common_ancestors() {
+        let list = vec![
+            "shirts-slug".to_string(),
+            "pants".to_string(),
+            "unknown".to_string(),
+        ];
+        let categories = HierarchicalCategories::from_tree(&tree(), &list).unwrap();
+
+        assert_eq!(categories.levels[0], vec!["Clothing".to_string()]);
+        assert_eq!(
+            categories.levels[1],
+            vec![
+                "Clothing > Shirts".to_string(),
+                "Clothing > Pants".to_string()
+            ]
+        );
+        assert_eq!(categories.lvl1.as_deref(), Some("Clothing > Shirts"));
+        assert!(HierarchicalCategories::from_tree(&tree(), &["unknown".to_string()]).is_none());
+    }
+
+    #[test]
+    fn inactive_categories_drop_out_of_paths() {
+        let mut tree = tree();
+        let clothing = tree.get_mut("clothing").unwrap();
+        clothing.children.get_mut("shirts").unwrap().is_active = false;
+
+        assert!(HierarchicalCategories::from_tree(&tree, &["linen".to_string()]).is_none());
+        let pants = HierarchicalCategories::from_tree(&tree, &["pants".to_string()]).unwrap();
+        assert_eq!(pants.lvl1.as_deref(), Some("Clothing > Pants"));
+    }
+
+    #[test]
+    fn pruning_limits_depth_and_hides_inactive_subtrees() {
+        let mut tree = tree();
+        let clothing = tree.get_mut("clothing").unwrap();
+        clothing.children.get_mut("shirts").unwrap().is_active = false;
+        let clothing = &tree["clothing"];
+
+        let active = clothing.pruned(None, false).unwrap();
+        assert_eq!(active.children.len(), 1);
+        assert!(active.children.contains_key("pants"));
+
+        let all = clothing.pruned(None, true).unwrap();
+        let casual = &all.children["shirts"].children["casual"];
+        assert!(casual.children.contains_key("linen"));
+
+        let shallow = clothing.pruned(Some(2), true).unwrap();
+        assert!(shallow.children["shirts"].children.is_empty());
+        assert!(clothing.pruned(Some(0), true).is_none());
+        assert!(clothing.children["shirts"].pruned(None, false).is_none());
+    }
+
+    #[test]
+    fn subtree_refs_cover_descendants() {
+        let tree = tree();
+        let shirts = CategoryTreeNode::find(&tree, "shirts").unwrap();
+        let refs = shirts.subtree_refs();
+
+        assert!(refs.contains(&"shirts-slug".to_string()));
+        assert!(refs.contains(&"linen".to_string()));
+        assert!(!refs.contains(&"pants".to_string()));
+    }
+}
@@ -0,0 +1,76 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Claim on a background job that must run on only one instance at a time,
+/// together with how far the job has got. The owner renews the lease on every
+/// run; when it stops doing so the lease expires and another instance takes
+/// over from the stored watermark.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobLease {
+    #[serde(rename = "_id")]
+    pub job: String,
+    pub owner: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub expires_at: DateTime<Utc>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub watermark: Option<DateTime<Utc>>, // End of the last span the job finished
+}
+
+impl JobLease {
+    /// Whether `owner` may run the job at `now`: it already holds the lease,
+    /// or whoever did has let it expire
+    pub fn is_available_to(&self, owner: &str, now: DateTime<Utc>) -> bool {
+        self.owner == owner || self.expires_at <= now
+    }
+
+    /// The span a run at `now` has to cover. The first run starts at `now`,
+    /// since there is nothing to catch up on before the job ever ran.
+    pub fn pending_span(&self, now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        (self.watermark.unwrap_or(now).min(now), now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn lease(owner: &str, expires_at: DateTime<Utc>) -> JobLease {
+        JobLease {
+            job: "publication_scheduler".to_string(),
+            owner: owner.to_string(),
+            expires_at,
+            watermark: None,
+        }
+    }
+
+    #[test]
+    fn a_held_lease_is_only_available_to_its_owner() {
+        let now = Utc::now();
+        let held = lease("a", now + Duration::minutes(1));
+        assert!(held.is_available_to("a", now));
+        assert!(!held.is_available_to("b", now));
+    }
+
+    #[test]
+    fn an_expired_lease_is_available_to_anyone() {
+        let now = Utc::now();
+        let expired = lease("a", now - Duration::seconds(1));
+        assert!(expired.is_available_to("b", now));
+    }
+
+    #[test]
+    fn pending_span_catches_up_from_the_watermark() {
+        let now = Utc::now();
+        let mut job = lease("a", now);
+        assert_eq!(job.pending_span(now), (now, now));
+
+        let down_since = now - Duration::hours(3);
+        job.watermark = Some(down_since);
+        assert_eq!(job.pending_span(now), (down_since, now));
+    }
+}
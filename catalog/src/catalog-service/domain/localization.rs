@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::{Category, CategorySeo, CategoryTreeNode, Product};
+
+/// Per-locale overrides for a product's customer-facing text. Unset fields
+/// fall back along the locale chain and finally to the product's own fields.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LocalizedProductContent {
+    pub name: Option<String>,
+    pub long_description: Option<String>,
+    pub seo_title: Option<String>,
+    pub seo_description: Option<String>,
+    pub seo_keywords: Option<String>,
+    pub slug: Option<String>,
+}
+
+/// Per-locale overrides for a category's customer-facing text
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LocalizedCategoryContent {
+    pub name: Option<String>,
+    pub short_description: Option<String>,
+    pub full_description: Option<String>,
+    pub slug: Option<String>,
+    pub seo: Option<CategorySeo>,
+}
+
+/// Canonical BCP 47-style form of a locale tag: `fr_ca` becomes `fr-CA` and
+/// `zh-hant-tw` becomes `zh-Hant-TW`. Returns None for tags that are not
+/// `language[-script][-region]`.
+pub fn normalize_locale(locale: &str) -> Option<String> {
+    let mut parts = locale.trim().split(['-', '_']);
+
+    let language = parts.next()?;
+    if !(2..=3).contains(&language.len()) || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let mut normalized = language.to_ascii_lowercase();
+
+    for part in parts {
+        let subtag = match part.len() {
+            // Script, e.g. Hant
+            4 if part.chars().all(|c| c.is_ascii_alphabetic()) => {
+                let mut chars = part.chars();
+                let first = chars.next()?.to_ascii_uppercase();
+                std::iter::once(first)
+                    .chain(chars.map(|c| c.to_ascii_lowercase()))
+                    .collect()
+            }
+            // Region, e.g. CA
+            2 if part.chars().all(|c| c.is_ascii_alphabetic()) => part.to_ascii_uppercase(),
+            // UN M.49 region, e.g. 419
+            3 if part.chars().all(|c| c.is_ascii_digit()) => part.to_string(),
+            _ => return None,
+        };
+        normalized.push('-');
+        normalized.push_str(&subtag);
+    }
+
+    Some(normalized)
+}
+
+/// Locales to try, most specific first: `fr-CA` gives `[fr-CA, fr]`. The
+/// default (unlocalized) content is the implicit last step. Invalid tags give
+/// an empty chain so callers fall straight back to the default content.
+pub fn locale_fallback_chain(locale: &str) -> Vec<String> {
+    let Some(normalized) = normalize_locale(locale) else {
+        return Vec::new();
+    };
+
+    let subtags: Vec<&str> = normalized.split('-').collect();
+    (1..=subtags.len())
+        .rev()
+        .map(|len| subtags[..len].join("-"))
+        .collect()
+}
+
+/// Normalize the locale keys of a localizations map, rejecting invalid tags
+pub fn normalize_localizations<T>(
+    localizations: HashMap<String, T>,
+) -> Result<HashMap<String, T>, String> {
+    localizations
+        .into_iter()
+        .map(|(locale, content)| {
+            normalize_locale(&locale)
+                .map(|normalized| (normalized, content))
+                .ok_or_else(|| format!("'{locale}' is not a valid locale"))
+        })
+        .collect()
+}
+
+/// First value `pick` finds along the chain
+fn resolve<T, V: Clone>(
+    localizations: &HashMap<String, T>,
+    chain: &[String],
+    pick: impl Fn(&T) -> Option<&V>,
+) -> Option<V> {
+    chain
+        .iter()
+        .filter_map(|locale| localizations.get(locale))
+        .find_map(|content| pick(content).cloned())
+}
+
+impl Product {
+    /// This product with its customer-facing text resolved for `locale`.
+    /// Fields resolve independently, so a `fr-CA` name can sit alongside a
+    /// `fr` description.
+    pub fn localized(mut self, locale: &str) -> Product {
+        let chain = locale_fallback_chain(locale);
+        if chain.is_empty() || self.localizations.is_empty() {
+            return self;
+        }
+
+        let l10n = &self.localizations;
+        if let Some(name) = resolve(l10n, &chain, |c| c.name.as_ref()) {
+            self.name = name;
+        }
+        if let Some(value) = resolve(l10n, &chain, |c| c.long_description.as_ref()) {
+            self.long_description = Some(value);
+        }
+        if let Some(value) = resolve(l10n, &chain, |c| c.seo_title.as_ref()) {
+            self.seo_title = Some(value);
+        }
+        if let Some(value) = resolve(l10n, &chain, |c| c.seo_description.as_ref()) {
+            self.seo_description = Some(value);
+        }
+        if let Some(value) = resolve(l10n, &chain, |c| c.seo_keywords.as_ref()) {
+            self.seo_keywords = Some(value);
+        }
+        if let Some(value) = resolve(l10n, &chain, |c| c.slug.as_ref()) {
+            self.slug = Some(value);
+        }
+        self
+    }
+}
+
+impl Category {
+    /// This category with its customer-facing text resolved for `locale`
+    pub fn localized(mut self, locale: &str) -> Category {
+        let chain = locale_fallback_chain(locale);
+        if chain.is_empty() || self.localizations.is_empty() {
+            return self;
+        }
+
+        let l10n = &self.localizations;
+        if let Some(name) = resolve(l10n, &chain, |c| c.name.as_ref()) {
+            self.name = name;
+        }
+        if let Some(value) = resolve(l10n, &chain, |c| c.short_description.as_ref()) {
+            self.short_description = value;
+        }
+        if let Some(value) = resolve(l10n, &chain, |c| c.full_description.as_ref()) {
+            self.full_description = Some(value);
+        }
+        if let Some(value) = resolve(l10n, &chain, |c| c.slug.as_ref()) {
+            self.slug = value;
+        }
+        if let Some(seo) = resolve(l10n, &chain, |c| c.seo.as_ref()) {
+            self.seo = seo;
+        }
+        self
+    }
+}
+
+impl CategoryTreeNode {
+    /// This node with its name and slug resolved for `locale`; children are left as they are
+    pub fn localized(mut self, locale: &str) -> CategoryTreeNode {
+        let chain = locale_fallback_chain(locale);
+        if chain.is_empty() || self.localizations.is_empty() {
+            return self;
+        }
+
+        let l10n = &self.localizations;
+        if let Some(name) = resolve(l10n, &chain, |c| c.name.as_ref()) {
+            self.name = name;
+        }
+        if let Some(value) = resolve(l10n, &chain, |c| c.slug.as_ref()) {
+            self.slug = value;
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ProductBuilder;
+
+    #[test]
+    fn locales_are_normalized() {
+        assert_eq!(normalize_locale("fr_ca").as_deref(), Some("fr-CA"));
+        assert_eq!(
+            normalize_locale("ZH-hant-tw").as_deref(),
+            Some("zh-Hant-TW")
+        );
+        assert_eq!(normalize_locale("es-419").as_deref(), Some("es-419"));
+        assert_eq!(normalize_locale(""), None);
+        assert_eq!(normalize_locale("french"), None);
+        assert_eq!(normalize_locale("fr-C"), None);
+    }
+
+    #[test]
+    fn fallback_chain_drops_subtags() {
+        assert_eq!(locale_fallback_chain("fr-CA"), vec!["fr-CA", "fr"]);
+        assert_eq!(
+            locale_fallback_chain("zh-Hant-TW"),
+            vec!["zh-Hant-TW", "zh-Hant", "zh"]
+        );
+        assert!(locale_fallback_chain("not a locale").is_empty());
+    }
+
+    #[test]
+    fn product_fields_fall_back_independently() {
+        let mut product =
+            ProductBuilder::new("Rain Jacket".to_string(), "RJ-001".to_string()).build();
+        product.long_description = Some("Keeps you dry".to_string());
+        product.localizations = HashMap::from([
+            (
+                "fr".to_string(),
+                LocalizedProductContent {
+                    name: Some("Veste de pluie".to_string()),
+                    long_description: Some("Vous garde au sec".to_string()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "fr-CA".to_string(),
+                LocalizedProductContent {
+                    name: Some("Manteau de pluie".to_string()),
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        let fr_ca = product.clone().localized("fr-CA");
+        assert_eq!(fr_ca.name, "Manteau de pluie");
+        assert_eq!(fr_ca.long_description.as_deref(), Some("Vous garde au sec"));
+
+        let de = product.localized("de-DE");
+        assert_eq!(de.name, "Rain Jacket");
+        assert_eq!(de.long_description.as_deref(), Some("Keeps you dry"));
+    }
+
+    #[test]
+    fn invalid_localization_keys_are_rejected() {
+        let valid = HashMap::from([("fr_ca".to_string(), ())]);
+        assert!(normalize_localizations(valid)
+            .unwrap()
+            .contains_key("fr-CA"));
+
+        let invalid = HashMap::from([("français".to_string(), ())]);
+        assert!(normalize_localizations(invalid).is_err());
+    }
+}
@@ -0,0 +1,413 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use super::sitemap::{escape_xml, sitemap_url};
+use super::{Product, ProductVariant};
+
+const MERCHANT_NAMESPACE: &str = "http://base.google.com/ns/1.0";
+
+/// Most additional_image_link values a merchant feed item may carry
+pub const MAX_ADDITIONAL_IMAGES: usize = 10;
+
+/// Columns of the tab-separated feed, in order
+pub const FEED_TSV_COLUMNS: [&str; 11] = [
+    "id",
+    "title",
+    "description",
+    "link",
+    "image_link",
+    "additional_image_link",
+    "availability",
+    "price",
+    "brand",
+    "item_group_id",
+    "product_type",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedAvailability {
+    InStock,
+    OutOfStock,
+}
+
+impl FeedAvailability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedAvailability::InStock => "in_stock",
+            FeedAvailability::OutOfStock => "out_of_stock",
+        }
+    }
+}
+
+/// One feed entry: a single purchasable SKU
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedItem {
+    pub id: String, // The variant SKU
+    pub title: String,
+    pub description: String,
+    pub link: String,
+    pub image_link: String,
+    pub additional_image_links: Vec<String>,
+    pub availability: FeedAvailability,
+    pub price: String, // e.g. "19.99 USD"
+    pub brand: Option<String>,
+    pub item_group_id: Option<String>, // The product_ref, for products with several variants
+    pub product_type: Option<String>,  // Deepest category path, e.g. "Clothing > Shirts"
+}
+
+/// Why a SKU was left out of the feed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedSkipReason {
+    NoVariants,
+    MissingSlug,
+    MissingPrice,
+    MissingImage,
+}
+
+impl FeedSkipReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedSkipReason::NoVariants => "no variants",
+            FeedSkipReason::MissingSlug => "missing slug",
+            FeedSkipReason::MissingPrice => "missing price",
+            FeedSkipReason::MissingImage => "missing image",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedSku {
+    pub sku: String, // The product_ref when the product has no variants
+    pub product_ref: String,
+    pub reason: FeedSkipReason,
+}
+
+/// Where feed links point and which currency prices are quoted in
+#[derive(Debug, Clone)]
+pub struct FeedSettings {
+    pub base_url: String,
+    pub product_path: String, // Path segment before product slugs, e.g. "products"
+    pub currency: String,
+}
+
+/// A merchant feed assembled product by product, with the SKUs that could not be listed
+#[derive(Debug, Clone)]
+pub struct MerchantFeed {
+    pub settings: FeedSettings,
+    pub items: Vec<FeedItem>,
+    pub skipped: Vec<SkippedSku>,
+}
+
+impl MerchantFeed {
+    pub fn new(settings: FeedSettings) -> Self {
+        Self {
+            settings,
+            items: Vec::new(),
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Add one item per variant of a product live on site at `now`. `prices`
+    /// maps SKUs to their offer price in the feed currency and `available` to
+    /// their available quantity; SKUs missing from `available` are out of stock.
+    /// Products that are hidden, deleted or outside their publishing window are
+    /// ignored; SKUs of live products that cannot be listed are recorded in
+    /// `skipped`.
+    pub fn add_product(
+        &mut self,
+        product: &Product,
+        prices: &HashMap<String, String>,
+        available: &HashMap<String, i32>,
+        now: DateTime<Utc>,
+    ) {
+        let live = product.display_on_site
+            && product.deleted_at.is_none()
+            && product.is_within_publish_window(now);
+        if !live {
+            return;
+        }
+
+        let skip = |sku: &str, reason| SkippedSku {
+            sku: sku.to_string(),
+            product_ref: product.product_ref.clone(),
+            reason,
+        };
+
+        if product.variants.is_empty() {
+            self.skipped
+                .push(skip(&product.product_ref, FeedSkipReason::NoVariants));
+            return;
+        }
+
+        let Some(slug) = product.slug.as_deref().filter(|s| !s.is_empty()) else {
+            for variant in &product.variants {
+                self.skipped
+                    .push(skip(&variant.sku, FeedSkipReason::MissingSlug));
+            }
+            return;
+        };
+        let link = sitemap_url(&self.settings.base_url, &self.settings.product_path, slug);
+
+        for variant in &product.variants {
+            let Some(price) = prices.get(&variant.sku) else {
+                self.skipped
+                    .push(skip(&variant.sku, FeedSkipReason::MissingPrice));
+                continue;
+            };
+            let mut images = variant.image_urls.iter().filter(|url| !url.is_empty());
+            let Some(image_link) = images.next() else {
+                self.skipped
+                    .push(skip(&variant.sku, FeedSkipReason::MissingImage));
+                continue;
+            };
+
+            let availability = match available.get(&variant.sku) {
+                Some(quantity) if *quantity > 0 => FeedAvailability::InStock,
+                _ => FeedAvailability::OutOfStock,
+            };
+
+            self.items.push(FeedItem {
+                id: variant.sku.clone(),
+                title: variant_title(product, variant),
+                description: product
+                    .long_description
+                    .clone()
+                    .or_else(|| product.seo_description.clone())
+                    .unwrap_or_else(|| product.name.clone()),
+                link: link.clone(),
+                image_link: image_link.clone(),
+                additional_image_links: images.take(MAX_ADDITIONAL_IMAGES).cloned().collect(),
+                availability,
+                price: format!("{price} {}", self.settings.currency),
+                brand: product.brand.clone(),
+                item_group_id: (product.variants.len() > 1).then(|| product.product_ref.clone()),
+                product_type: deepest_category_path(product),
+            });
+        }
+    }
+
+    /// Render the feed as tab-separated values with a header row
+    pub fn to_tsv(&self) -> String {
+        let mut tsv = FEED_TSV_COLUMNS.join("\t");
+        tsv.push('\n');
+        for item in &self.items {
+            let row = [
+                item.id.as_str(),
+                item.title.as_str(),
+                item.description.as_str(),
+                item.link.as_str(),
+                item.image_link.as_str(),
+                &item.additional_image_links.join(","),
+                item.availability.as_str(),
+                item.price.as_str(),
+                item.brand.as_deref().unwrap_or_default(),
+                item.item_group_id.as_deref().unwrap_or_default(),
+                item.product_type.as_deref().unwrap_or_default(),
+            ];
+            let row: Vec<String> = row.iter().map(|value| tsv_field(value)).collect();
+            tsv.push_str(&row.join("\t"));
+            tsv.push('\n');
+        }
+        tsv
+    }
+
+    /// Render the feed as an RSS 2.0 document using the `g:` merchant namespace
+    pub fn to_xml(&self, title: &str) -> String {
+        let mut xml = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\" xmlns:g=\"{MERCHANT_NAMESPACE}\">\n<channel>\n"
+        );
+        xml.push_str(&format!(
+            "  <title>{0}</title>\n  <link>{1}</link>\n  <description>{0}</description>\n",
+            escape_xml(title),
+            escape_xml(&self.settings.base_url)
+        ));
+
+        for item in &self.items {
+            xml.push_str("  <item>\n");
+            push_element(&mut xml, "g:id", &item.id);
+            push_element(&mut xml, "title", &item.title);
+            push_element(&mut xml, "description", &item.description);
+            push_element(&mut xml, "link", &item.link);
+            push_element(&mut xml, "g:image_link", &item.image_link);
+            for image in &item.additional_image_links {
+                push_element(&mut xml, "g:additional_image_link", image);
+            }
+            push_element(&mut xml, "g:availability", item.availability.as_str());
+            push_element(&mut xml, "g:price", &item.price);
+            for (element, value) in [
+                ("g:brand", &item.brand),
+                ("g:item_group_id", &item.item_group_id),
+                ("g:product_type", &item.product_type),
+            ] {
+                if let Some(value) = value {
+                    push_element(&mut xml, element, value);
+                }
+            }
+            xml.push_str("  </item>\n");
+        }
+
+        xml.push_str("</channel>\n</rss>\n");
+        xml
+    }
+}
+
+/// Product name followed by the variant's defining attribute values in key
+/// order, e.g. "Oxford Shirt - Blue / M"
+fn variant_title(product: &Product, variant: &ProductVariant) -> String {
+    let mut attributes: Vec<(&String, &String)> = variant
+        .defining_attributes
+        .iter()
+        .flatten()
+        .filter(|(_, value)| !value.is_empty())
+        .collect();
+    if attributes.is_empty() {
+        return product.name.clone();
+    }
+    attributes.sort();
+    let values: Vec<&str> = attributes.iter().map(|(_, v)| v.as_str()).collect();
+    format!("{} - {}", product.name, values.join(" / "))
+}
+
+fn deepest_category_path(product: &Product) -> Option<String> {
+    let categories = product.hierarchical_categories.as_ref()?;
+    categories
+        .levels
+        .iter()
+        .rev()
+        .find_map(|paths| paths.first().cloned())
+        .or_else(|| categories.lvl2.clone())
+        .or_else(|| categories.lvl1.clone())
+        .or_else(|| categories.lvl0.clone())
+}
+
+fn tsv_field(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if matches!(c, '\t' | '\r' | '\n') {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+fn push_element(xml: &mut String, element: &str, value: &str) {
+    xml.push_str(&format!(
+        "    <{element}>{}</{element}>\n",
+        escape_xml(value)
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ProductBuilder, ProductVariantBuilder};
+
+    fn variant(sku: &str, color: &str, images: &[&str]) -> ProductVariant {
+        ProductVariantBuilder::new(sku.to_string())
+            .defining_attributes(HashMap::from([("color".to_string(), color.to_string())]))
+            .image_urls(images.iter().map(|i| i.to_string()).collect())
+            .build()
+    }
+
+    fn feed() -> MerchantFeed {
+        MerchantFeed::new(FeedSettings {
+            base_url: "https://shop.example.com".to_string(),
+            product_path: "products".to_string(),
+            currency: "USD".to_string(),
+        })
+    }
+
+    fn shirt() -> Product {
+        ProductBuilder::new("Oxford Shirt".to_string(), "FEED001".to_string())
+            .slug("oxford-shirt".to_string())
+            .brand("Acme".to_string())
+            .long_description("Woven\tcotton\nshirt".to_string())
+            .variants(vec![
+                variant(
+                    "FEED001-BLU",
+                    "Blue",
+                    &["https://img/blue-1.jpg", "https://img/blue-2.jpg"],
+                ),
+                variant("FEED001-RED", "Red", &["https://img/red.jpg"]),
+                variant("FEED001-GRN", "Green", &[]),
+            ])
+            .build()
+    }
+
+    #[test]
+    fn variants_become_items_or_skips() {
+        let prices = HashMap::from([
+            ("FEED001-BLU".to_string(), "19.99".to_string()),
+            ("FEED001-GRN".to_string(), "19.99".to_string()),
+        ]);
+        let available = HashMap::from([("FEED001-BLU".to_string(), 3)]);
+
+        let mut feed = feed();
+        feed.add_product(&shirt(), &prices, &available, Utc::now());
+
+        assert_eq!(feed.items.len(), 1);
+        let item = &feed.items[0];
+        assert_eq!(item.id, "FEED001-BLU");
+        assert_eq!(item.title, "Oxford Shirt - Blue");
+        assert_eq!(item.link, "https://shop.example.com/products/oxford-shirt");
+        assert_eq!(item.image_link, "https://img/blue-1.jpg");
+        assert_eq!(item.additional_image_links, vec!["https://img/blue-2.jpg"]);
+        assert_eq!(item.price, "19.99 USD");
+        assert_eq!(item.availability, FeedAvailability::InStock);
+        assert_eq!(item.item_group_id.as_deref(), Some("FEED001"));
+
+        assert_eq!(
+            feed.skipped
+                .iter()
+                .map(|s| (s.sku.as_str(), s.reason))
+                .collect::<Vec<_>>(),
+            vec![
+                ("FEED001-RED", FeedSkipReason::MissingPrice),
+                ("FEED001-GRN", FeedSkipReason::MissingImage),
+            ]
+        );
+    }
+
+    #[test]
+    fn hidden_products_are_ignored_and_unstocked_skus_are_out_of_stock() {
+        let prices = HashMap::from([("FEED001-RED".to_string(), "5.00".to_string())]);
+
+        let mut hidden = shirt();
+        hidden.display_on_site = false;
+        let mut feed = feed();
+        feed.add_product(&hidden, &prices, &HashMap::new(), Utc::now());
+        assert!(feed.items.is_empty() && feed.skipped.is_empty());
+
+        feed.add_product(&shirt(), &prices, &HashMap::new(), Utc::now());
+        let red = feed.items.iter().find(|i| i.id == "FEED001-RED").unwrap();
+        assert_eq!(red.availability, FeedAvailability::OutOfStock);
+    }
+
+    #[test]
+    fn tsv_and_xml_escape_their_values() {
+        let prices = HashMap::from([("FEED001-BLU".to_string(), "19.99".to_string())]);
+        let mut feed = feed();
+        let mut product = shirt();
+        product.name = "Shirts & Ties".to_string();
+        feed.add_product(&product, &prices, &HashMap::new(), Utc::now());
+
+        let tsv = feed.to_tsv();
+        let lines: Vec<&str> = tsv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("id\ttitle\tdescription"));
+        let row: Vec<&str> = lines[1].split('\t').collect();
+        assert_eq!(row.len(), FEED_TSV_COLUMNS.len());
+        assert_eq!(row[2], "Woven cotton shirt");
+
+        let xml = feed.to_xml("Acme feed");
+        assert!(xml.contains("xmlns:g=\"http://base.google.com/ns/1.0\""));
+        assert!(xml.contains("<title>Shirts &amp; Ties - Blue</title>"));
+        assert!(xml.contains("<g:price>19.99 USD</g:price>"));
+        assert!(xml.contains("<g:availability>out_of_stock</g:availability>"));
+        assert!(!xml.contains("<g:product_type>"));
+    }
+}
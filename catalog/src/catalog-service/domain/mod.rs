@@ -1,7 +1,74 @@
+pub mod attribute_schema;
+pub mod bundle;
+pub mod catalog_version;
+pub mod category_csv;
+pub mod category_merge;
+pub mod category_paths;
+pub mod job_lease;
+pub mod localization;
+pub mod merchant_feed;
 pub mod model;
+pub mod product_clone;
+pub mod product_comparison;
+pub mod product_counts;
 pub mod product_name;
 pub mod product_ref;
+pub mod relations;
+pub mod review;
+pub mod revision;
+pub mod sitemap;
+pub mod slug;
+pub mod store;
+pub mod structured_data;
+pub mod units;
+pub mod variant_matrix;
 
+pub use attribute_schema::{
+    AttributeDataType, AttributeDefinition, AttributeSchema, AttributeScope, AttributeViolation,
+};
+pub use bundle::{BundleComponent, BundlePricing, ProductBundle};
+pub use catalog_version::{
+    category_content_onto, changes_back_to, same_category_content, same_product_content,
+    staged_change_id, CatalogChange, CatalogChangeAction, CatalogChangeSummary, CatalogEntityType,
+    CatalogSnapshotEntry, CatalogVersion, CatalogVersionRef, CatalogVersionStatus, StagedChange,
+    StagedEdit, DEFAULT_CHANGE_SET, PUBLISH_LEASE,
+};
+pub use category_csv::{
+    category_path_slug, parse_category_csv, plan_category_import, CategoryCsvFields,
+    CategoryCsvRow, CategoryImportAction, CategoryImportChange, ParsedCategoryCsv,
+    DEFAULT_CSV_PATH_SEPARATOR,
+};
+pub use category_merge::{merged_list_categories, CategoryMerge};
+pub use category_paths::CATEGORY_PATH_SEPARATOR;
+pub use job_lease::JobLease;
+pub use localization::{
+    locale_fallback_chain, normalize_locale, LocalizedCategoryContent, LocalizedProductContent,
+};
+pub use merchant_feed::{
+    FeedAvailability, FeedItem, FeedSettings, FeedSkipReason, MerchantFeed, SkippedSku,
+};
 pub use model::*;
+pub use product_clone::{clone_product, rewrite_sku, CloneSpec, SkuRewrite};
+pub use product_comparison::{
+    compare_products, normalize_attribute_key, ComparisonRow, ComparisonRowKind, ProductComparison,
+    VariantMeasurements, MAX_COMPARED_PRODUCTS,
+};
+pub use product_counts::ProductCountDeltas;
 pub use product_name::ProductName;
 pub use product_ref::ProductRef;
+pub use relations::{bidirectional_changes, ProductRelation, RelationKind};
+pub use review::{RatingTotals, Review, ReviewPrior, ReviewStatus};
+pub use revision::{diff_products, FieldChange, ProductRevision};
+pub use sitemap::{
+    plan_sitemap_files, render_sitemap_index, render_urlset, sitemap_url, SitemapFile, SitemapKind,
+    SitemapUrl, MAX_SITEMAP_URLS,
+};
+pub use slug::{slugify, SlugEntityType, SlugLookup, SlugRedirect};
+pub use store::{normalize_store_id, CategoryStoreSettings, ProductStoreSettings};
+pub use structured_data::{
+    breadcrumb_list_json_ld, json_ld_script_content, product_json_ld, BreadcrumbItem, ProductOffers,
+};
+pub use units::{
+    validate_measurement, LengthUnit, MassUnit, DIMENSIONAL_WEIGHT_DIVISOR_CM3_PER_KG,
+};
+pub use variant_matrix::{generate_variant_matrix, VariantAxis, VariantMatrix};
@@ -5,10 +5,21 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use super::bundle::ProductBundle;
+use super::localization::{LocalizedCategoryContent, LocalizedProductContent};
+use super::relations::ProductRelation;
+use super::store::{CategoryStoreSettings, ProductStoreSettings};
+use super::units::{
+    deserialize_unit_lenient, validate_measurement, LengthUnit, MassUnit,
+    DIMENSIONAL_WEIGHT_DIVISOR_CM3_PER_KG,
+};
+
 /// A lightweight struct specifically for retrieving product slugs efficiently
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProductSlug {
     pub slug: String,
+    #[serde(default)]
+    pub stores: HashMap<String, ProductStoreSettings>, // Only the store being listed, when there is one
 }
 
 /// A type-safe wrapper for Bayesian average ratings that ensures values are always rounded to one decimal place.
@@ -86,6 +97,8 @@ pub struct Product {
     pub display_on_site: bool,
     pub tax_code: Option<String>,
     pub related_products: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relations: Vec<ProductRelation>, // Typed links, next to the plain related_products
     pub reviews: Option<Reviews>,
     pub hierarchical_categories: Option<HierarchicalCategories>,
     pub list_categories: Vec<String>,
@@ -97,6 +110,27 @@ pub struct Product {
     pub descriptive_attributes: HashMap<String, String>,
     pub default_variant: Option<String>,
     pub variants: Vec<ProductVariant>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub localizations: HashMap<String, LocalizedProductContent>, // Keyed by normalized locale, e.g. "fr-CA"
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub stores: HashMap<String, ProductStoreSettings>, // Keyed by store id; empty means every store
+    #[serde(
+        default,
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub publish_at: Option<DateTime<Utc>>, // Hidden from storefront reads before this moment
+    #[serde(
+        default,
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub unpublish_at: Option<DateTime<Utc>>, // Hidden from storefront reads from this moment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle: Option<ProductBundle>, // Set when the product is a kit of other products' variants
+    #[serde(
+        default,
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub deleted_at: Option<DateTime<Utc>>, // Tombstone; set when soft deleted
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -106,11 +140,14 @@ pub struct Reviews {
     pub rating: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Category paths derived from list_categories and the category tree
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct HierarchicalCategories {
     pub lvl0: Option<String>,
     pub lvl1: Option<String>,
     pub lvl2: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub levels: Vec<Vec<String>>, // Distinct paths at each depth, e.g. levels[1] = ["Clothing > Shirts"]
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -122,8 +159,15 @@ pub struct ProductVariant {
     pub height: Option<f64>,              // Added dimension fields
     pub width: Option<f64>,
     pub length: Option<f64>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_unit_lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub dimension_unit: Option<LengthUnit>, // Unit of height, width and length
     pub weight: Option<f64>,
-    pub weight_unit: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unit_lenient")]
+    pub weight_unit: Option<MassUnit>,
     pub packaging: Option<Packaging>, // Added packaging information
     pub image_urls: Vec<String>,      // Changed from single image to array of URLs
 }
@@ -133,14 +177,30 @@ pub struct Packaging {
     pub height: Option<f64>,
     pub width: Option<f64>,
     pub length: Option<f64>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_unit_lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub dimension_unit: Option<LengthUnit>,
     pub weight: Option<f64>,
-    pub weight_unit: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unit_lenient")]
+    pub weight_unit: Option<MassUnit>,
 }
 
 impl Product {
     pub fn builder() -> ProductBuilder {
         ProductBuilder::default()
     }
+
+    /// Whether `now` falls inside the product's publishing window. Open-ended
+    /// bounds are unrestricted; display_on_site is checked separately.
+    pub fn is_within_publish_window(&self, now: DateTime<Utc>) -> bool {
+        self.publish_at.is_none_or(|publish_at| publish_at <= now)
+            && self
+                .unpublish_at
+                .is_none_or(|unpublish_at| unpublish_at > now)
+    }
 }
 
 #[derive(Default)]
@@ -158,6 +218,7 @@ pub struct ProductBuilder {
     display_on_site: bool,
     tax_code: Option<String>,
     related_products: Vec<String>,
+    relations: Vec<ProductRelation>,
     reviews: Option<Reviews>,
     hierarchical_categories: Option<HierarchicalCategories>,
     list_categories: Vec<String>,
@@ -169,6 +230,11 @@ pub struct ProductBuilder {
     descriptive_attributes: HashMap<String, String>,
     default_variant: Option<String>,
     variants: Vec<ProductVariant>,
+    localizations: HashMap<String, LocalizedProductContent>,
+    stores: HashMap<String, ProductStoreSettings>,
+    publish_at: Option<DateTime<Utc>>,
+    unpublish_at: Option<DateTime<Utc>>,
+    bundle: Option<ProductBundle>,
 }
 
 impl ProductBuilder {
@@ -187,6 +253,7 @@ impl ProductBuilder {
             display_on_site: true, // Default to true
             tax_code: None,
             related_products: Vec::new(),
+            relations: Vec::new(),
             reviews: None,
             hierarchical_categories: None,
             list_categories: Vec::new(),
@@ -198,6 +265,11 @@ impl ProductBuilder {
             descriptive_attributes: HashMap::new(),
             default_variant: None,
             variants: Vec::new(),
+            localizations: HashMap::new(),
+            stores: HashMap::new(),
+            publish_at: None,
+            unpublish_at: None,
+            bundle: None,
         }
     }
 
@@ -256,6 +328,11 @@ impl ProductBuilder {
         self
     }
 
+    pub fn relations(&mut self, relations: Vec<ProductRelation>) -> &mut Self {
+        self.relations = relations;
+        self
+    }
+
     pub fn reviews(&mut self, reviews: Reviews) -> &mut Self {
         self.reviews = Some(reviews);
         self
@@ -330,19 +407,40 @@ impl ProductBuilder {
         self
     }
 
+    pub fn localizations(
+        &mut self,
+        localizations: HashMap<String, LocalizedProductContent>,
+    ) -> &mut Self {
+        self.localizations = localizations;
+        self
+    }
+
+    pub fn stores(&mut self, stores: HashMap<String, ProductStoreSettings>) -> &mut Self {
+        self.stores = stores;
+        self
+    }
+
+    pub fn publish_at(&mut self, publish_at: DateTime<Utc>) -> &mut Self {
+        self.publish_at = Some(publish_at);
+        self
+    }
+
+    pub fn unpublish_at(&mut self, unpublish_at: DateTime<Utc>) -> &mut Self {
+        self.unpublish_at = Some(unpublish_at);
+        self
+    }
+
+    pub fn bundle(&mut self, bundle: ProductBundle) -> &mut Self {
+        self.bundle = Some(bundle);
+        self
+    }
+
     pub fn build(&mut self) -> Product {
         // Generate slug from name if not provided
-        let slug = self.slug.clone().or_else(|| {
-            Some(
-                self.name
-                    .to_lowercase()
-                    .replace(" ", "-")
-                    .replace("_", "-")
-                    .chars()
-                    .filter(|c| c.is_alphanumeric() || *c == '-')
-                    .collect::<String>(),
-            )
-        });
+        let slug = self
+            .slug
+            .clone()
+            .or_else(|| Some(super::slug::slugify(&self.name)));
 
         Product {
             id: self.id.clone(),
@@ -358,6 +456,7 @@ impl ProductBuilder {
             display_on_site: self.display_on_site,
             tax_code: self.tax_code.clone(),
             related_products: self.related_products.clone(),
+            relations: self.relations.clone(),
             reviews: self.reviews.clone(),
             hierarchical_categories: self.hierarchical_categories.clone(),
             list_categories: self.list_categories.clone(),
@@ -369,6 +468,12 @@ impl ProductBuilder {
             descriptive_attributes: self.descriptive_attributes.clone(),
             default_variant: self.default_variant.clone(),
             variants: self.variants.clone(),
+            localizations: self.localizations.clone(),
+            stores: self.stores.clone(),
+            publish_at: self.publish_at,
+            unpublish_at: self.unpublish_at,
+            bundle: self.bundle.clone(),
+            deleted_at: None,
         }
     }
 }
@@ -377,6 +482,45 @@ impl ProductVariant {
     pub fn builder() -> ProductVariantBuilder {
         ProductVariantBuilder::default()
     }
+
+    /// Check the variant's and its packaging's measurements: all non-negative,
+    /// and a weight always comes with its unit. Dimensions without a unit are
+    /// accepted for older data but leave the dimensional weight unknown.
+    pub fn validate_measurements(&self) -> Result<(), String> {
+        validate_dimensions(
+            &self.sku,
+            [self.height, self.width, self.length, self.weight],
+            self.weight_unit,
+        )?;
+        if let Some(packaging) = &self.packaging {
+            validate_dimensions(
+                &format!("{} packaging", self.sku),
+                [
+                    packaging.height,
+                    packaging.width,
+                    packaging.length,
+                    packaging.weight,
+                ],
+                packaging.weight_unit,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn validate_dimensions(
+    owner: &str,
+    [height, width, length, weight]: [Option<f64>; 4],
+    weight_unit: Option<MassUnit>,
+) -> Result<(), String> {
+    validate_measurement(&format!("{owner} height"), height)?;
+    validate_measurement(&format!("{owner} width"), width)?;
+    validate_measurement(&format!("{owner} length"), length)?;
+    validate_measurement(&format!("{owner} weight"), weight)?;
+    if weight.is_some() && weight_unit.is_none() {
+        return Err(format!("{owner} weight needs a weight_unit"));
+    }
+    Ok(())
 }
 
 #[derive(Default)]
@@ -388,8 +532,9 @@ pub struct ProductVariantBuilder {
     height: Option<f64>,
     width: Option<f64>,
     length: Option<f64>,
+    dimension_unit: Option<LengthUnit>,
     weight: Option<f64>,
-    weight_unit: Option<String>,
+    weight_unit: Option<MassUnit>,
     packaging: Option<Packaging>,
     image_urls: Vec<String>,
 }
@@ -404,6 +549,7 @@ impl ProductVariantBuilder {
             height: None,
             width: None,
             length: None,
+            dimension_unit: None,
             weight: None,
             weight_unit: None,
             packaging: None,
@@ -461,7 +607,12 @@ impl ProductVariantBuilder {
         self
     }
 
-    pub fn weight(&mut self, weight: f64, unit: String) -> &mut Self {
+    pub fn dimension_unit(&mut self, unit: LengthUnit) -> &mut Self {
+        self.dimension_unit = Some(unit);
+        self
+    }
+
+    pub fn weight(&mut self, weight: f64, unit: MassUnit) -> &mut Self {
         self.weight = Some(weight);
         self.weight_unit = Some(unit);
         self
@@ -491,8 +642,9 @@ impl ProductVariantBuilder {
             height: self.height,
             width: self.width,
             length: self.length,
+            dimension_unit: self.dimension_unit,
             weight: self.weight,
-            weight_unit: self.weight_unit.clone(),
+            weight_unit: self.weight_unit,
             packaging: self.packaging.clone(),
             image_urls: self.image_urls.clone(),
         }
@@ -503,6 +655,18 @@ impl Packaging {
     pub fn builder() -> PackagingBuilder {
         PackagingBuilder::default()
     }
+
+    /// The weight carriers bill for the package's volume, in `unit`. None
+    /// unless all three dimensions and their unit are known.
+    pub fn dimensional_weight(&self, unit: MassUnit) -> Option<f64> {
+        let to_cm = |value: f64| {
+            self.dimension_unit
+                .map(|from| from.convert(value, LengthUnit::Centimeter))
+        };
+        let volume_cm3 = to_cm(self.height?)? * to_cm(self.width?)? * to_cm(self.length?)?;
+        let kilograms = volume_cm3 / DIMENSIONAL_WEIGHT_DIVISOR_CM3_PER_KG;
+        Some(MassUnit::Kilogram.convert(kilograms, unit))
+    }
 }
 
 #[derive(Default)]
@@ -510,8 +674,9 @@ pub struct PackagingBuilder {
     height: Option<f64>,
     width: Option<f64>,
     length: Option<f64>,
+    dimension_unit: Option<LengthUnit>,
     weight: Option<f64>,
-    weight_unit: Option<String>,
+    weight_unit: Option<MassUnit>,
 }
 
 impl PackagingBuilder {
@@ -541,7 +706,12 @@ impl PackagingBuilder {
         self
     }
 
-    pub fn weight(&mut self, weight: f64, unit: String) -> &mut Self {
+    pub fn dimension_unit(&mut self, unit: LengthUnit) -> &mut Self {
+        self.dimension_unit = Some(unit);
+        self
+    }
+
+    pub fn weight(&mut self, weight: f64, unit: MassUnit) -> &mut Self {
         self.weight = Some(weight);
         self.weight_unit = Some(unit);
         self
@@ -552,8 +722,9 @@ impl PackagingBuilder {
             height: self.height,
             width: self.width,
             length: self.length,
+            dimension_unit: self.dimension_unit,
             weight: self.weight,
-            weight_unit: self.weight_unit.clone(),
+            weight_unit: self.weight_unit,
         }
     }
 }
@@ -642,6 +813,7 @@ impl HierarchicalCategoriesBuilder {
             lvl0: self.lvl0.clone(),
             lvl1: self.lvl1.clone(),
             lvl2: self.lvl2.clone(),
+            levels: Vec::new(),
         }
     }
 }
@@ -758,7 +930,7 @@ mod tests {
             .abbreviated_color("RED".to_string())
             .abbreviated_size("M".to_string())
             .dimensions(10.0, 5.0, 2.0)
-            .weight(0.5, "kg".to_string())
+            .weight(0.5, MassUnit::Kilogram)
             .add_defining_attribute("size".to_string(), "Medium".to_string())
             .add_image_url("https://example.com/image1.jpg".to_string())
             .add_image_url("https://example.com/image2.jpg".to_string())
@@ -771,7 +943,7 @@ mod tests {
         assert_eq!(variant.width, Some(5.0));
         assert_eq!(variant.length, Some(2.0));
         assert_eq!(variant.weight, Some(0.5));
-        assert_eq!(variant.weight_unit, Some("kg".to_string()));
+        assert_eq!(variant.weight_unit, Some(MassUnit::Kilogram));
         assert_eq!(variant.image_urls.len(), 2);
 
         if let Some(defining_attrs) = &variant.defining_attributes {
@@ -779,19 +951,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_packaging_dimensional_weight() {
+        let packaging = PackagingBuilder::new()
+            .dimensions(10.0, 20.0, 50.0)
+            .dimension_unit(LengthUnit::Centimeter)
+            .weight(0.5, MassUnit::Kilogram)
+            .build();
+        assert!((packaging.dimensional_weight(MassUnit::Kilogram).unwrap() - 2.0).abs() < 1e-9);
+
+        let packaging = PackagingBuilder::new()
+            .dimensions(12.0, 7.0, 17.0)
+            .weight(2.0, MassUnit::Pound)
+            .build();
+        assert_eq!(packaging.dimensional_weight(MassUnit::Pound), None);
+    }
+
+    #[test]
+    fn test_variant_weight_needs_a_unit() {
+        let mut variant = ProductVariantBuilder::new("SKU789".to_string())
+            .dimensions(10.0, 5.0, 2.0)
+            .weight(1.5, MassUnit::Pound)
+            .build();
+        assert!(variant.validate_measurements().is_ok());
+
+        variant.weight_unit = None;
+        assert!(variant.validate_measurements().is_err());
+
+        variant.weight_unit = Some(MassUnit::Pound);
+        variant.height = Some(-1.0);
+        assert!(variant.validate_measurements().is_err());
+    }
+
     #[test]
     fn test_complete_product_with_variants() {
         // Test creating a complete product with variants using builders
         let packaging = PackagingBuilder::new()
             .dimensions(15.0, 10.0, 5.0)
-            .weight(0.8, "kg".to_string())
+            .weight(0.8, MassUnit::Kilogram)
             .build();
 
         let variant = ProductVariantBuilder::new("SKU456".to_string())
             .abbreviated_color("BLUE".to_string())
             .abbreviated_size("L".to_string())
             .dimensions(12.0, 8.0, 3.0)
-            .weight(0.6, "kg".to_string())
+            .weight(0.6, MassUnit::Kilogram)
             .packaging(packaging)
             .add_image_url("https://example.com/blue-large.jpg".to_string())
             .build();
@@ -963,6 +1167,28 @@ mod tests {
             serde_json::from_str(&json3).expect("Failed to deserialize");
         assert_eq!(deserialized3.as_f32(), 3.0);
     }
+
+    #[test]
+    fn test_publish_window() {
+        let now = Utc::now();
+        let hour = chrono::Duration::hours(1);
+
+        let open = ProductBuilder::new("Open".to_string(), "WIN001".to_string()).build();
+        assert!(open.is_within_publish_window(now));
+
+        let scheduled = ProductBuilder::new("Scheduled".to_string(), "WIN002".to_string())
+            .publish_at(now + hour)
+            .build();
+        assert!(!scheduled.is_within_publish_window(now));
+        assert!(scheduled.is_within_publish_window(now + hour));
+
+        let expiring = ProductBuilder::new("Expiring".to_string(), "WIN003".to_string())
+            .publish_at(now - hour)
+            .unpublish_at(now + hour)
+            .build();
+        assert!(expiring.is_within_publish_window(now));
+        assert!(!expiring.is_within_publish_window(now + hour));
+    }
 }
 
 // ==================== CATEGORY MODELS ====================
@@ -981,12 +1207,23 @@ pub struct Category {
     pub parent_id: Option<String>, // UUID of parent category
     pub level: i32,
     pub children_count: i32,
-    pub product_count: i32,
+    pub product_count: i32, // Displayed products listed directly in this category
+    #[serde(default)]
+    pub subtree_product_count: i32, // Displayed products in this category or any descendant
     pub is_active: bool,
     pub display_order: i32,
     pub seo: CategorySeo,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub localizations: HashMap<String, LocalizedCategoryContent>, // Keyed by normalized locale, e.g. "fr-CA"
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub stores: HashMap<String, CategoryStoreSettings>, // Keyed by store id; empty means every store
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[serde(
+        default,
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub deleted_at: Option<DateTime<Utc>>, // Tombstone; set when soft deleted
 }
 
 /// SEO metadata for categories
@@ -1006,9 +1243,21 @@ pub struct CategoryTreeNode {
     pub path: String,
     pub level: i32,
     pub product_count: i32,
+    #[serde(default)]
+    pub subtree_product_count: i32,
+    #[serde(default = "default_tree_node_active")]
+    pub is_active: bool, // Trees cached before inactive categories were included hold only active ones
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub localizations: HashMap<String, LocalizedCategoryContent>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub stores: HashMap<String, CategoryStoreSettings>,
     pub children: HashMap<String, CategoryTreeNode>,
 }
 
+fn default_tree_node_active() -> bool {
+    true
+}
+
 /// Tree cache document
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CategoryTreeCache {
@@ -1043,6 +1292,7 @@ impl Category {
             level: 0, // Will be calculated when saved
             children_count: 0,
             product_count: 0,
+            subtree_product_count: 0,
             is_active: true,
             display_order,
             seo: CategorySeo {
@@ -1050,8 +1300,11 @@ impl Category {
                 meta_description: None,
                 keywords: Vec::new(),
             },
+            localizations: HashMap::new(),
+            stores: HashMap::new(),
             created_at: now,
             updated_at: now,
+            deleted_at: None,
         }
     }
 
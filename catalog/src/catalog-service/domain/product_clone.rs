@@ -0,0 +1,157 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::Product;
+
+/// Replaces every occurrence of `find` in a variant SKU with `replace`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkuRewrite {
+    pub find: String,
+    pub replace: String,
+}
+
+/// What changes between a product and its clone
+#[derive(Debug, Clone)]
+pub struct CloneSpec {
+    pub product_ref: String,
+    pub slug: String,
+    pub name: Option<String>,
+    pub sku_rewrites: Vec<SkuRewrite>, // Empty: swap the source product_ref for the new one
+    pub created_by: Option<String>,
+}
+
+/// Apply `rules` in order to `sku`
+pub fn rewrite_sku(sku: &str, rules: &[SkuRewrite]) -> String {
+    rules
+        .iter()
+        .filter(|rule| !rule.find.is_empty())
+        .fold(sku.to_string(), |sku, rule| {
+            sku.replace(&rule.find, &rule.replace)
+        })
+}
+
+/// A deep copy of `source` under a new id, product_ref and slug, hidden from
+/// the site. Reviews, history and localized slugs stay with the source. Fails
+/// when a rewritten SKU is empty, repeated or still one of the source's.
+pub fn clone_product(source: &Product, spec: CloneSpec) -> Result<Product, String> {
+    let rules = if spec.sku_rewrites.is_empty() {
+        vec![SkuRewrite {
+            find: source.product_ref.clone(),
+            replace: spec.product_ref.clone(),
+        }]
+    } else {
+        spec.sku_rewrites
+    };
+
+    let source_skus: Vec<&str> = source.variants.iter().map(|v| v.sku.as_str()).collect();
+    let mut product = source.clone();
+    let mut seen: Vec<String> = Vec::new();
+    for variant in &mut product.variants {
+        let sku = rewrite_sku(&variant.sku, &rules);
+        if sku.trim().is_empty() {
+            return Err(format!("SKU {} would be rewritten to nothing", variant.sku));
+        }
+        if source_skus.contains(&sku.as_str()) {
+            return Err(format!(
+                "SKU {sku} would be shared with the source product; add a SKU rewrite"
+            ));
+        }
+        if seen.contains(&sku) {
+            return Err(format!("More than one variant would get SKU {sku}"));
+        }
+        seen.push(sku.clone());
+        variant.sku = sku;
+    }
+    product.default_variant = source
+        .default_variant
+        .as_deref()
+        .map(|sku| rewrite_sku(sku, &rules));
+
+    let now = Utc::now();
+    product.id = Some(Uuid::new_v4().to_string());
+    product.product_ref = spec.product_ref;
+    product.slug = Some(spec.slug);
+    if let Some(name) = spec.name {
+        product.name = name;
+    }
+    product.display_on_site = false;
+    product.reviews = None;
+    for content in product.localizations.values_mut() {
+        content.slug = None;
+    }
+    for settings in product.stores.values_mut() {
+        settings.slug = None;
+        settings.display_on_site = false;
+    }
+    product.created_at = Some(now);
+    product.updated_at = Some(now);
+    product.created_by = spec.created_by;
+    product.updated_by = None;
+    product.deleted_at = None;
+
+    Ok(product)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ProductBuilder, ProductVariantBuilder};
+
+    fn source() -> Product {
+        ProductBuilder::new("Parka".to_string(), "PK24".to_string())
+            .slug("parka".to_string())
+            .display_on_site(true)
+            .default_variant("PK24-BLK-M".to_string())
+            .variants(vec![
+                ProductVariantBuilder::new("PK24-BLK-M".to_string()).build(),
+                ProductVariantBuilder::new("PK24-BLK-L".to_string()).build(),
+            ])
+            .build()
+    }
+
+    fn spec(rewrites: Vec<SkuRewrite>) -> CloneSpec {
+        CloneSpec {
+            product_ref: "PK25".to_string(),
+            slug: "parka-2025".to_string(),
+            name: None,
+            sku_rewrites: rewrites,
+            created_by: Some("merch".to_string()),
+        }
+    }
+
+    #[test]
+    fn clone_swaps_the_product_ref_in_skus_by_default() {
+        let source = source();
+        let clone = clone_product(&source, spec(vec![])).unwrap();
+
+        assert_ne!(clone.id, source.id);
+        assert_eq!(clone.product_ref, "PK25");
+        assert_eq!(clone.slug.as_deref(), Some("parka-2025"));
+        assert!(!clone.display_on_site);
+        assert_eq!(clone.variants[1].sku, "PK25-BLK-L");
+        assert_eq!(clone.default_variant.as_deref(), Some("PK25-BLK-M"));
+    }
+
+    #[test]
+    fn rewrites_apply_in_order_and_must_change_every_sku() {
+        let rules = vec![
+            SkuRewrite {
+                find: "PK24".to_string(),
+                replace: "PK25".to_string(),
+            },
+            SkuRewrite {
+                find: "-BLK-".to_string(),
+                replace: "-OLV-".to_string(),
+            },
+        ];
+        let clone = clone_product(&source(), spec(rules)).unwrap();
+        assert_eq!(clone.variants[0].sku, "PK25-OLV-M");
+
+        let unchanged = vec![SkuRewrite {
+            find: "-M".to_string(),
+            replace: "-S".to_string(),
+        }];
+        let err = clone_product(&source(), spec(unchanged)).unwrap_err();
+        assert!(err.contains("PK24-BLK-L"));
+    }
+}
@@ -0,0 +1,363 @@
+use std::collections::BTreeMap;
+
+use super::{LengthUnit, MassUnit, Packaging, Product, ProductVariant};
+
+/// Most products one comparison can hold side by side
+pub const MAX_COMPARED_PRODUCTS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ComparisonRowKind {
+    DefiningAttribute,
+    DescriptiveAttribute,
+    Measurement,
+}
+
+/// One line of the comparison matrix, with a value per product in column order
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparisonRow {
+    pub key: String,
+    pub kind: ComparisonRowKind,
+    pub values: Vec<Option<String>>,
+    pub differs: bool, // Some product's value, or lack of one, sets it apart
+}
+
+/// A variant's measurements in the comparison's units. Values given without
+/// a unit cannot be converted and are left out.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VariantMeasurements {
+    pub sku: String,
+    pub height: Option<f64>,
+    pub width: Option<f64>,
+    pub length: Option<f64>,
+    pub weight: Option<f64>,
+    pub package_height: Option<f64>,
+    pub package_width: Option<f64>,
+    pub package_length: Option<f64>,
+    pub package_weight: Option<f64>,
+    pub dimensional_weight: Option<f64>,
+}
+
+type MeasurementField = fn(&VariantMeasurements) -> Option<f64>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductComparison {
+    pub rows: Vec<ComparisonRow>,
+    pub variants: Vec<Vec<VariantMeasurements>>, // Per product, in column order
+    pub length_unit: LengthUnit,
+    pub mass_unit: MassUnit,
+}
+
+/// Attribute keys match across products regardless of case, spacing and
+/// separators: `Heel Height` and `heel-height` both become `heel_height`
+pub fn normalize_attribute_key(key: &str) -> String {
+    key.split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+        .filter(|part| !part.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Align the products' attributes and variant measurements into one matrix.
+/// A product without a product-level value for a defining attribute shows the
+/// distinct values its variants have, e.g. `S, M, L`. Attribute rows come
+/// first, each kind sorted by key, then a measurement row for every
+/// measurement some product has, showing the range across its variants.
+pub fn compare_products(
+    products: &[Product],
+    length_unit: LengthUnit,
+    mass_unit: MassUnit,
+) -> ProductComparison {
+    let mut attributes: BTreeMap<(ComparisonRowKind, String), Vec<Option<String>>> =
+        BTreeMap::new();
+    let mut kinds: BTreeMap<String, ComparisonRowKind> = BTreeMap::new();
+    for product in products {
+        let keys = product
+            .defining_attributes
+            .keys()
+            .map(|key| (key, ComparisonRowKind::DefiningAttribute))
+            .chain(
+                product
+                    .descriptive_attributes
+                    .keys()
+                    .map(|key| (key, ComparisonRowKind::DescriptiveAttribute)),
+            )
+            .chain(
+                product
+                    .variants
+                    .iter()
+                    .filter_map(|variant| variant.defining_attributes.as_ref())
+                    .flat_map(|attributes| attributes.keys())
+                    .map(|key| (key, ComparisonRowKind::DefiningAttribute)),
+            );
+        for (key, kind) in keys {
+            // An attribute defining for any product is defining for all
+            let entry = kinds.entry(normalize_attribute_key(key)).or_insert(kind);
+            *entry = (*entry).min(kind);
+        }
+    }
+    for (key, kind) in kinds {
+        let values = products
+            .iter()
+            .map(|product| attribute_value(product, &key))
+            .collect();
+        attributes.insert((kind, key), values);
+    }
+
+    let mut rows: Vec<ComparisonRow> = attributes
+        .into_iter()
+        .map(|((kind, key), values)| ComparisonRow::new(key, kind, values))
+        .collect();
+
+    let variants: Vec<Vec<VariantMeasurements>> = products
+        .iter()
+        .map(|product| {
+            product
+                .variants
+                .iter()
+                .map(|variant| measure(variant, length_unit, mass_unit))
+                .collect()
+        })
+        .collect();
+
+    let measurements: [(&str, &str, MeasurementField); 6] = [
+        ("height", length_unit.code(), |m| m.height),
+        ("width", length_unit.code(), |m| m.width),
+        ("length", length_unit.code(), |m| m.length),
+        ("weight", mass_unit.code(), |m| m.weight),
+        ("package_weight", mass_unit.code(), |m| m.package_weight),
+        ("dimensional_weight", mass_unit.code(), |m| {
+            m.dimensional_weight
+        }),
+    ];
+    for (key, unit, pick) in measurements {
+        let values: Vec<Option<String>> = variants
+            .iter()
+            .map(|measured| measurement_range(measured.iter().filter_map(pick), unit))
+            .collect();
+        if values.iter().any(Option::is_some) {
+            rows.push(ComparisonRow::new(
+                key.to_string(),
+                ComparisonRowKind::Measurement,
+                values,
+            ));
+        }
+    }
+
+    ProductComparison {
+        rows,
+        variants,
+        length_unit,
+        mass_unit,
+    }
+}
+
+impl ComparisonRow {
+    fn new(key: String, kind: ComparisonRowKind, values: Vec<Option<String>>) -> Self {
+        let comparable = |value: &Option<String>| value.as_deref().map(str::to_lowercase);
+        let differs = values
+            .windows(2)
+            .any(|pair| comparable(&pair[0]) != comparable(&pair[1]));
+        Self {
+            key,
+            kind,
+            values,
+            differs,
+        }
+    }
+}
+
+fn attribute_value(product: &Product, key: &str) -> Option<String> {
+    let own = product
+        .defining_attributes
+        .iter()
+        .chain(&product.descriptive_attributes)
+        .find(|(k, value)| normalize_attribute_key(k) == key && !value.trim().is_empty())
+        .map(|(_, value)| value.trim().to_string());
+    if own.is_some() {
+        return own;
+    }
+
+    let mut variant_values: Vec<String> = Vec::new();
+    for attributes in product
+        .variants
+        .iter()
+        .filter_map(|variant| variant.defining_attributes.as_ref())
+    {
+        for (k, value) in attributes {
+            let value = value.trim();
+            if normalize_attribute_key(k) == key
+                && !value.is_empty()
+                && !variant_values.iter().any(|v| v.eq_ignore_ascii_case(value))
+            {
+                variant_values.push(value.to_string());
+            }
+        }
+    }
+    (!variant_values.is_empty()).then(|| variant_values.join(", "))
+}
+
+fn measure(
+    variant: &ProductVariant,
+    length_unit: LengthUnit,
+    mass_unit: MassUnit,
+) -> VariantMeasurements {
+    let length =
+        |value: Option<f64>, unit: Option<LengthUnit>| Some(unit?.convert(value?, length_unit));
+    let mass = |value: Option<f64>, unit: Option<MassUnit>| Some(unit?.convert(value?, mass_unit));
+    let packaging = variant.packaging.as_ref();
+    let package_length = |pick: fn(&Packaging) -> Option<f64>| {
+        packaging.and_then(|p| length(pick(p), p.dimension_unit))
+    };
+
+    VariantMeasurements {
+        sku: variant.sku.clone(),
+        height: length(variant.height, variant.dimension_unit),
+        width: length(variant.width, variant.dimension_unit),
+        length: length(variant.length, variant.dimension_unit),
+        weight: mass(variant.weight, variant.weight_unit),
+        package_height: package_length(|p| p.height),
+        package_width: package_length(|p| p.width),
+        package_length: package_length(|p| p.length),
+        package_weight: packaging.and_then(|p| mass(p.weight, p.weight_unit)),
+        dimensional_weight: packaging.and_then(|p| p.dimensional_weight(mass_unit)),
+    }
+}
+
+/// `12 cm`, or `10-12 cm` when the variants differ
+fn measurement_range(values: impl Iterator<Item = f64>, unit: &str) -> Option<String> {
+    let (min, max) = values.fold(None, |range: Option<(f64, f64)>, value| {
+        Some(match range {
+            Some((min, max)) => (min.min(value), max.max(value)),
+            None => (value, value),
+        })
+    })?;
+    let (min, max) = (format_amount(min), format_amount(max));
+    Some(if min == max {
+        format!("{min} {unit}")
+    } else {
+        format!("{min}-{max} {unit}")
+    })
+}
+
+/// Two decimals at most, without trailing zeros
+fn format_amount(value: f64) -> String {
+    let formatted = format!("{value:.2}");
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ProductBuilder, ProductVariantBuilder};
+    use std::collections::HashMap;
+
+    fn product(
+        name: &str,
+        defining: &[(&str, &str)],
+        descriptive: &[(&str, &str)],
+        variants: Vec<ProductVariant>,
+    ) -> Product {
+        let to_map = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+        ProductBuilder::new(name.to_string(), name.to_uppercase())
+            .defining_attributes(to_map(defining))
+            .descriptive_attributes(to_map(descriptive))
+            .variants(variants)
+            .build()
+    }
+
+    fn variant(sku: &str, size: &str, weight: f64, unit: MassUnit) -> ProductVariant {
+        let mut variant = ProductVariantBuilder::new(sku.to_string())
+            .defining_attributes(HashMap::from([("size".to_string(), size.to_string())]))
+            .build();
+        variant.weight = Some(weight);
+        variant.weight_unit = Some(unit);
+        variant
+    }
+
+    #[test]
+    fn attribute_keys_are_normalized() {
+        assert_eq!(normalize_attribute_key(" Heel Height "), "heel_height");
+        assert_eq!(normalize_attribute_key("heel-height"), "heel_height");
+        assert_eq!(normalize_attribute_key("Colour"), "colour");
+    }
+
+    #[test]
+    fn rows_align_attributes_and_flag_differences() {
+        let boots = product(
+            "boots",
+            &[("Colour", "Black")],
+            &[("Material", "Leather"), ("Heel Height", "3 cm")],
+            vec![],
+        );
+        let shoes = product(
+            "shoes",
+            &[("colour", "black")],
+            &[("material", "Suede")],
+            vec![],
+        );
+        let comparison =
+            compare_products(&[boots, shoes], LengthUnit::Centimeter, MassUnit::Kilogram);
+
+        let keys: Vec<(&str, ComparisonRowKind)> = comparison
+            .rows
+            .iter()
+            .map(|row| (row.key.as_str(), row.kind))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                ("colour", ComparisonRowKind::DefiningAttribute),
+                ("heel_height", ComparisonRowKind::DescriptiveAttribute),
+                ("material", ComparisonRowKind::DescriptiveAttribute),
+            ]
+        );
+        assert!(!comparison.rows[0].differs, "Case alone is no difference");
+        assert_eq!(
+            comparison.rows[1].values,
+            vec![Some("3 cm".to_string()), None]
+        );
+        assert!(comparison.rows[1].differs);
+        assert!(comparison.rows[2].differs);
+    }
+
+    #[test]
+    fn variants_fill_in_values_and_measurements() {
+        let jacket = product(
+            "jacket",
+            &[],
+            &[],
+            vec![
+                variant("J-S", "S", 800.0, MassUnit::Gram),
+                variant("J-M", "M", 1.2, MassUnit::Kilogram),
+                variant("J-M2", "m", 1.2, MassUnit::Kilogram),
+            ],
+        );
+        let mut vest = product("vest", &[("size", "One size")], &[], vec![]);
+        vest.variants = vec![variant("V-1", "One size", 1.0, MassUnit::Pound)];
+
+        let comparison =
+            compare_products(&[jacket, vest], LengthUnit::Centimeter, MassUnit::Kilogram);
+
+        let size = &comparison.rows[0];
+        assert_eq!(size.key, "size");
+        assert_eq!(size.values[0].as_deref(), Some("S, M"));
+        assert_eq!(size.values[1].as_deref(), Some("One size"));
+
+        let weight = comparison.rows.iter().find(|r| r.key == "weight").unwrap();
+        assert_eq!(weight.kind, ComparisonRowKind::Measurement);
+        assert_eq!(weight.values[0].as_deref(), Some("0.8-1.2 kg"));
+        assert_eq!(weight.values[1].as_deref(), Some("0.45 kg"));
+        assert!(comparison.rows.iter().all(|r| r.key != "height"));
+
+        assert_eq!(comparison.variants[0].len(), 3);
+        assert_eq!(comparison.variants[0][0].weight, Some(0.8));
+    }
+}
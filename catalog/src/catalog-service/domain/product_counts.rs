@@ -0,0 +1,175 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+
+use super::{Category, Product};
+
+impl Product {
+    /// Whether the product counts towards its categories' product counts at
+    /// `at`: it is shown on site, inside its publishing window and not soft deleted
+    pub fn counts_towards_categories(&self, at: DateTime<Utc>) -> bool {
+        self.display_on_site && self.deleted_at.is_none() && self.is_within_publish_window(at)
+    }
+}
+
+/// Changes to category product counts caused by a single product write, keyed
+/// by category id. `direct` adjusts `product_count` on the categories the
+/// product is listed in; `subtree` adjusts `subtree_product_count` on those
+/// categories and all of their ancestors.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ProductCountDeltas {
+    pub direct: HashMap<String, i32>,
+    pub subtree: HashMap<String, i32>,
+}
+
+impl ProductCountDeltas {
+    /// Deltas for a product that counted towards `before` and now counts
+    /// towards `after`. A product listed in both a category and one of its
+    /// descendants is counted once in each subtree.
+    pub fn between(before: &[Category], after: &[Category]) -> Self {
+        Self {
+            direct: diff(&direct_ids(before), &direct_ids(after)),
+            subtree: diff(&subtree_ids(before), &subtree_ids(after)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.direct.is_empty() && self.subtree.is_empty()
+    }
+
+    /// Add another write's deltas to these, so several products can be
+    /// applied as one adjustment. Categories whose changes cancel out are dropped.
+    pub fn absorb(&mut self, other: ProductCountDeltas) {
+        for (totals, deltas) in [
+            (&mut self.direct, other.direct),
+            (&mut self.subtree, other.subtree),
+        ] {
+            for (category_id, delta) in deltas {
+                let total = totals.entry(category_id).or_insert(0);
+                *total += delta;
+            }
+            totals.retain(|_, total| *total != 0);
+        }
+    }
+}
+
+fn direct_ids(categories: &[Category]) -> HashSet<&str> {
+    categories.iter().filter_map(|c| c.id.as_deref()).collect()
+}
+
+fn subtree_ids(categories: &[Category]) -> HashSet<&str> {
+    categories
+        .iter()
+        .flat_map(|c| {
+            c.id.as_deref()
+                .into_iter()
+                .chain(c.ancestors.iter().map(String::as_str))
+        })
+        .collect()
+}
+
+fn diff(before: &HashSet<&str>, after: &HashSet<&str>) -> HashMap<String, i32> {
+    let added = after.difference(before).map(|id| (id.to_string(), 1));
+    let removed = before.difference(after).map(|id| (id.to_string(), -1));
+    added.chain(removed).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ProductBuilder;
+
+    fn category(id: &str, ancestors: &[&str]) -> Category {
+        let mut category = Category::new(
+            id.to_string(),
+            id.to_string(),
+            String::new(),
+            ancestors.last().map(|a| a.to_string()),
+            1,
+        );
+        category.id = Some(id.to_string());
+        category.ancestors = ancestors.iter().map(|a| a.to_string()).collect();
+        category
+    }
+
+    #[test]
+    fn new_product_counts_once_per_subtree() {
+        let shirts = category("shirts", &["clothing"]);
+        let clothing = category("clothing", &[]);
+
+        let deltas = ProductCountDeltas::between(&[], &[shirts, clothing]);
+        assert_eq!(deltas.direct.len(), 2);
+        assert_eq!(deltas.direct["shirts"], 1);
+        assert_eq!(deltas.direct["clothing"], 1);
+        assert_eq!(deltas.subtree.len(), 2);
+        assert_eq!(deltas.subtree["clothing"], 1);
+    }
+
+    #[test]
+    fn moving_between_siblings_leaves_shared_ancestors_alone() {
+        let shirts = category("shirts", &["clothing"]);
+        let pants = category("pants", &["clothing"]);
+
+        let deltas = ProductCountDeltas::between(&[shirts], &[pants]);
+        assert_eq!(deltas.direct["shirts"], -1);
+        assert_eq!(deltas.direct["pants"], 1);
+        assert_eq!(deltas.subtree["shirts"], -1);
+        assert_eq!(deltas.subtree["pants"], 1);
+        assert!(!deltas.subtree.contains_key("clothing"));
+
+        let unchanged = ProductCountDeltas::between(
+            &[category("pants", &["clothing"])],
+            &[category("pants", &["clothing"])],
+        );
+        assert!(unchanged.is_empty());
+    }
+
+    #[test]
+    fn absorbed_deltas_sum_and_cancel() {
+        let shirts = category("shirts", &["clothing"]);
+        let pants = category("pants", &["clothing"]);
+
+        let mut total = ProductCountDeltas::between(&[], std::slice::from_ref(&shirts));
+        total.absorb(ProductCountDeltas::between(
+            &[],
+            std::slice::from_ref(&shirts),
+        ));
+        assert_eq!(total.direct["shirts"], 2);
+        assert_eq!(total.subtree["clothing"], 2);
+
+        total.absorb(ProductCountDeltas::between(&[shirts], &[pants]));
+        assert_eq!(total.direct["shirts"], 1);
+        assert_eq!(total.direct["pants"], 1);
+        assert_eq!(total.subtree["clothing"], 2);
+
+        let mut cancelled = ProductCountDeltas::between(&[], &[category("hats", &[])]);
+        cancelled.absorb(ProductCountDeltas::between(&[category("hats", &[])], &[]));
+        assert!(cancelled.is_empty());
+    }
+
+    #[test]
+    fn hidden_and_deleted_products_do_not_count() {
+        let now = Utc::now();
+        let mut product = ProductBuilder::new("Shirt".to_string(), "CNT001".to_string()).build();
+        assert!(product.counts_towards_categories(now));
+
+        product.display_on_site = false;
+        assert!(!product.counts_towards_categories(now));
+
+        product.display_on_site = true;
+        product.deleted_at = Some(now);
+        assert!(!product.counts_towards_categories(now));
+    }
+
+    #[test]
+    fn products_only_count_inside_their_publishing_window() {
+        let now = Utc::now();
+        let mut product = ProductBuilder::new("Shirt".to_string(), "CNT002".to_string()).build();
+        product.publish_at = Some(now + chrono::Duration::hours(1));
+        product.unpublish_at = Some(now + chrono::Duration::hours(2));
+
+        assert!(!product.counts_towards_categories(now));
+        assert!(product.counts_towards_categories(now + chrono::Duration::minutes(90)));
+        assert!(!product.counts_towards_categories(now + chrono::Duration::hours(2)));
+    }
+}
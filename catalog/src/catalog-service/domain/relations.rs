@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+
+use super::Product;
+
+/// Why a product is shown next to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationKind {
+    #[default]
+    Related, // Also what every id in related_products is
+    Accessory,
+    Upsell,
+    Replacement,
+}
+
+/// A typed link to another product. Bidirectional links are mirrored on the
+/// other product with the same kind, and removed from both sides together.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProductRelation {
+    pub product_id: String,
+    #[serde(default)]
+    pub kind: RelationKind,
+    #[serde(default)]
+    pub bidirectional: bool,
+}
+
+impl Product {
+    /// Every product this one links to, with how, in listing order:
+    /// related_products first, then the typed relations
+    pub fn related_links(&self) -> Vec<(String, RelationKind)> {
+        let mut links: Vec<(String, RelationKind)> = Vec::new();
+        let all = self
+            .related_products
+            .iter()
+            .map(|id| (id, RelationKind::Related))
+            .chain(self.relations.iter().map(|r| (&r.product_id, r.kind)));
+        for (id, kind) in all {
+            if !links.iter().any(|(i, k)| i == id && *k == kind) {
+                links.push((id.clone(), kind));
+            }
+        }
+        links
+    }
+
+    /// Distinct ids of the products this one links to
+    pub fn related_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        for (id, _) in self.related_links() {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        ids
+    }
+
+    /// Drop blank and repeated ids from related_products and repeated
+    /// (product, kind) pairs from relations; the first occurrence wins
+    pub fn normalize_relations(&mut self) {
+        let mut ids: Vec<String> = Vec::new();
+        for id in self.related_products.drain(..) {
+            let id = id.trim().to_string();
+            if !id.is_empty() && !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        self.related_products = ids;
+
+        let mut typed: Vec<ProductRelation> = Vec::new();
+        for mut relation in self.relations.drain(..) {
+            relation.product_id = relation.product_id.trim().to_string();
+            let repeated = typed
+                .iter()
+                .any(|r| r.product_id == relation.product_id && r.kind == relation.kind);
+            if !relation.product_id.is_empty() && !repeated {
+                typed.push(relation);
+            }
+        }
+        self.relations = typed;
+    }
+}
+
+/// Bidirectional relations `after` gained and lost compared with `before`,
+/// as (added, removed)
+pub fn bidirectional_changes(
+    before: &[ProductRelation],
+    after: &[ProductRelation],
+) -> (Vec<ProductRelation>, Vec<ProductRelation>) {
+    let same = |a: &ProductRelation, b: &ProductRelation| {
+        a.product_id == b.product_id && a.kind == b.kind && b.bidirectional
+    };
+    let added = after
+        .iter()
+        .filter(|r| r.bidirectional && !before.iter().any(|b| same(r, b)))
+        .cloned()
+        .collect();
+    let removed = before
+        .iter()
+        .filter(|r| r.bidirectional && !after.iter().any(|a| same(r, a)))
+        .cloned()
+        .collect();
+    (added, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ProductBuilder;
+
+    fn relation(id: &str, kind: RelationKind, bidirectional: bool) -> ProductRelation {
+        ProductRelation {
+            product_id: id.to_string(),
+            kind,
+            bidirectional,
+        }
+    }
+
+    #[test]
+    fn normalize_drops_blank_and_repeated_links() {
+        let mut product = ProductBuilder::new("Phone".to_string(), "PH-1".to_string()).build();
+        product.related_products = vec!["a".to_string(), " a ".to_string(), "".to_string()];
+        product.relations = vec![
+            relation("b", RelationKind::Accessory, false),
+            relation("b", RelationKind::Accessory, true),
+            relation("b", RelationKind::Upsell, false),
+        ];
+        product.normalize_relations();
+
+        assert_eq!(product.related_products, vec!["a".to_string()]);
+        assert_eq!(product.relations.len(), 2);
+        assert!(!product.relations[0].bidirectional);
+    }
+
+    #[test]
+    fn links_list_plain_then_typed_relations() {
+        let mut product = ProductBuilder::new("Phone".to_string(), "PH-1".to_string()).build();
+        product.related_products = vec!["a".to_string()];
+        product.relations = vec![
+            relation("a", RelationKind::Related, false),
+            relation("b", RelationKind::Accessory, false),
+            relation("a", RelationKind::Upsell, false),
+        ];
+
+        assert_eq!(
+            product.related_links(),
+            vec![
+                ("a".to_string(), RelationKind::Related),
+                ("b".to_string(), RelationKind::Accessory),
+                ("a".to_string(), RelationKind::Upsell),
+            ]
+        );
+        assert_eq!(
+            product.related_ids(),
+            vec!["a".to_string(), "b".to_string()]
+        );
+    }
+
+    #[test]
+    fn bidirectional_changes_track_mirrored_links_only() {
+        let before = vec![
+            relation("a", RelationKind::Accessory, true),
+            relation("b", RelationKind::Upsell, false),
+        ];
+        let after = vec![
+            relation("b", RelationKind::Upsell, false),
+            relation("c", RelationKind::Replacement, true),
+            relation("d", RelationKind::Related, false),
+        ];
+
+        let (added, removed) = bidirectional_changes(&before, &after);
+        assert_eq!(added, vec![relation("c", RelationKind::Replacement, true)]);
+        assert_eq!(removed, vec![relation("a", RelationKind::Accessory, true)]);
+    }
+}
@@ -0,0 +1,176 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{BayesianAverage, Reviews};
+
+pub const MIN_RATING: i32 = 1;
+pub const MAX_RATING: i32 = 5;
+
+/// Prior mean used before the catalog has any approved reviews: the middle of the scale
+const DEFAULT_PRIOR_MEAN: f64 = (MIN_RATING + MAX_RATING) as f64 / 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    Pending, // Awaiting moderation; not counted in the product's summary
+    Approved,
+    Rejected,
+}
+
+/// A customer review of a product. Only approved reviews count towards the
+/// product's `reviews` summary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Review {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub product_id: String,
+    pub rating: i32, // MIN_RATING..=MAX_RATING stars
+    pub title: Option<String>,
+    pub body: Option<String>,
+    pub author: Option<String>,
+    pub status: ReviewStatus,
+    pub moderated_by: Option<String>,
+    pub moderation_note: Option<String>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(
+        default,
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub moderated_at: Option<DateTime<Utc>>,
+}
+
+impl Review {
+    /// A new pending review. Fails when the rating is outside the star scale.
+    pub fn new(
+        product_id: String,
+        rating: i32,
+        title: Option<String>,
+        body: Option<String>,
+        author: Option<String>,
+    ) -> Result<Self, String> {
+        if !(MIN_RATING..=MAX_RATING).contains(&rating) {
+            return Err(format!(
+                "rating must be between {MIN_RATING} and {MAX_RATING}, got {rating}"
+            ));
+        }
+
+        Ok(Self {
+            id: Uuid::new_v4().to_string(),
+            product_id,
+            rating,
+            title,
+            body,
+            author,
+            status: ReviewStatus::Pending,
+            moderated_by: None,
+            moderation_note: None,
+            created_at: Utc::now(),
+            moderated_at: None,
+        })
+    }
+}
+
+/// Count and sum of approved ratings, for one product or the whole catalog
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RatingTotals {
+    pub count: i64,
+    pub rating_sum: i64,
+}
+
+/// The catalog-wide belief about a product's rating before its own reviews are
+/// seen. Products with few reviews are pulled towards `mean`; `weight` is how
+/// many reviews it takes for a product's own ratings to count as much as the prior.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReviewPrior {
+    pub mean: f64,
+    pub weight: f64,
+}
+
+impl ReviewPrior {
+    /// Prior from every approved review in the catalog: the mean rating, weighted
+    /// by the average number of approved reviews per reviewed product
+    pub fn from_catalog(totals: RatingTotals, reviewed_products: i64) -> Self {
+        if totals.count == 0 || reviewed_products == 0 {
+            return Self {
+                mean: DEFAULT_PRIOR_MEAN,
+                weight: 1.0,
+            };
+        }
+
+        Self {
+            mean: totals.rating_sum as f64 / totals.count as f64,
+            weight: (totals.count as f64 / reviewed_products as f64).max(1.0),
+        }
+    }
+
+    /// The product's review summary: its Bayesian average, approved review
+    /// count, and mean star rating rounded to a whole star (0 without reviews)
+    pub fn summarize(&self, totals: RatingTotals) -> Reviews {
+        let bayesian_avg = (self.weight * self.mean + totals.rating_sum as f64)
+            / (self.weight + totals.count as f64);
+        let rating = if totals.count > 0 {
+            (totals.rating_sum as f64 / totals.count as f64).round() as i32
+        } else {
+            0
+        };
+
+        Reviews {
+            bayesian_avg: BayesianAverage::new(bayesian_avg as f32),
+            count: totals.count as i32,
+            rating,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rating_must_be_on_the_star_scale() {
+        let review = |rating| Review::new("p1".to_string(), rating, None, None, None);
+        assert!(review(0).is_err());
+        assert!(review(6).is_err());
+        assert_eq!(review(4).unwrap().status, ReviewStatus::Pending);
+    }
+
+    #[test]
+    fn few_reviews_are_pulled_towards_the_catalog_mean() {
+        // 40 approved reviews over 10 products averaging 3.5 stars
+        let prior = ReviewPrior::from_catalog(
+            RatingTotals {
+                count: 40,
+                rating_sum: 140,
+            },
+            10,
+        );
+        assert_eq!(prior.weight, 4.0);
+
+        // One five-star review: (4 * 3.5 + 5) / 5 = 3.8
+        let single = prior.summarize(RatingTotals {
+            count: 1,
+            rating_sum: 5,
+        });
+        assert_eq!(single.bayesian_avg.as_f32(), 3.8);
+        assert_eq!(single.rating, 5);
+        assert_eq!(single.count, 1);
+
+        // Many five-star reviews approach five
+        let many = prior.summarize(RatingTotals {
+            count: 396,
+            rating_sum: 1980,
+        });
+        assert_eq!(many.bayesian_avg.as_f32(), 5.0);
+    }
+
+    #[test]
+    fn empty_catalog_uses_the_middle_of_the_scale() {
+        let prior = ReviewPrior::from_catalog(RatingTotals::default(), 0);
+        let summary = prior.summarize(RatingTotals::default());
+        assert_eq!(summary.bayesian_avg.as_f32(), 3.0);
+        assert_eq!(summary.count, 0);
+        assert_eq!(summary.rating, 0);
+    }
+}
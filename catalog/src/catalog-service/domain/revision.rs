@@ -0,0 +1,137 @@
+use super::Product;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Bookkeeping fields that change on every save and would drown out real edits in a diff
+const DIFF_IGNORED_FIELDS: &[&str] = &["_id", "updated_at", "updated_by", "deleted_at"];
+
+/// Full snapshot of a product as it was saved by a create, update or revert
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductRevision {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub product_id: String,
+    pub revision: i64, // 1-based, increasing per product
+    pub snapshot: Product,
+    pub changed_by: Option<String>,
+    pub reverted_from: Option<i64>, // Revision this one restored, if it came from a revert
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl ProductRevision {
+    pub fn new(
+        product_id: String,
+        revision: i64,
+        snapshot: Product,
+        changed_by: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            product_id,
+            revision,
+            snapshot,
+            changed_by,
+            reverted_from: None,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// A single top-level product field that differs between two revisions
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+/// Compare two product snapshots field by field, ignoring save bookkeeping.
+/// Changes are returned sorted by field name.
+pub fn diff_products(before: &Product, after: &Product) -> Vec<FieldChange> {
+    let before = to_fields(before);
+    let mut after = to_fields(after);
+
+    let mut changes: Vec<FieldChange> = before
+        .into_iter()
+        .filter_map(|(field, before_value)| {
+            let after_value = after.remove(&field).unwrap_or(Value::Null);
+            (before_value != after_value).then(|| FieldChange {
+                field,
+                before: before_value,
+                after: after_value,
+            })
+        })
+        .collect();
+
+    // Fields only present on the newer snapshot
+    changes.extend(after.into_iter().filter(|(_, value)| !value.is_null()).map(
+        |(field, after_value)| FieldChange {
+            field,
+            before: Value::Null,
+            after: after_value,
+        },
+    ));
+
+    changes.sort_by(|a, b| a.field.cmp(&b.field));
+    changes
+}
+
+fn to_fields(product: &Product) -> serde_json::Map<String, Value> {
+    let mut fields = match serde_json::to_value(product) {
+        Ok(Value::Object(fields)) => fields,
+        _ => serde_json::Map::new(),
+    };
+    for ignored in DIFF_IGNORED_FIELDS {
+        fields.remove(*ignored);
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ProductBuilder;
+
+    fn product() -> Product {
+        ProductBuilder::new("Trail Runner".to_string(), "TR-001".to_string())
+            .brand("Acme".to_string())
+            .build()
+    }
+
+    #[test]
+    fn identical_products_have_no_changes() {
+        let product = product();
+        assert!(diff_products(&product, &product.clone()).is_empty());
+    }
+
+    #[test]
+    fn changed_fields_are_reported_in_order() {
+        let before = product();
+        let mut after = before.clone();
+        after.name = "Trail Runner 2".to_string();
+        after.brand = None;
+
+        let changes = diff_products(&before, &after);
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].field, "brand");
+        assert_eq!(changes[0].before, Value::from("Acme"));
+        assert_eq!(changes[0].after, Value::Null);
+        assert_eq!(changes[1].field, "name");
+        assert_eq!(changes[1].after, Value::from("Trail Runner 2"));
+    }
+
+    #[test]
+    fn bookkeeping_fields_are_ignored() {
+        let before = product();
+        let mut after = before.clone();
+        after.id = Some("another-id".to_string());
+        after.updated_at = Some(Utc::now());
+        after.updated_by = Some("editor".to_string());
+
+        assert!(diff_products(&before, &after).is_empty());
+    }
+}
@@ -0,0 +1,187 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+
+/// Most URLs a single urlset file may hold under the sitemap protocol
+pub const MAX_SITEMAP_URLS: usize = 50_000;
+
+const SITEMAP_NAMESPACE: &str = "http://www.sitemaps.org/schemas/sitemap/0.9";
+
+/// What a urlset file lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SitemapKind {
+    Products,
+    Categories,
+}
+
+impl SitemapKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SitemapKind::Products => "products",
+            SitemapKind::Categories => "categories",
+        }
+    }
+}
+
+/// One `<url>` of a urlset, or one `<sitemap>` of an index
+#[derive(Debug, Clone, PartialEq)]
+pub struct SitemapUrl {
+    pub loc: String,
+    pub lastmod: Option<DateTime<Utc>>,
+}
+
+/// A urlset file within the index: which kind, locale and page of URLs it covers
+#[derive(Debug, Clone, PartialEq)]
+pub struct SitemapFile {
+    pub name: String,
+    pub kind: SitemapKind,
+    pub locale: Option<String>, // None for the default site
+    pub offset: usize,
+    pub url_count: usize,
+}
+
+/// Split `total` URLs of one kind and locale into files of at most `urls_per_file`,
+/// named e.g. "sitemap-products-2.xml.gz" or "sitemap-products-fr-ca-2.xml.gz"
+pub fn plan_sitemap_files(
+    kind: SitemapKind,
+    locale: Option<&str>,
+    total: usize,
+    urls_per_file: usize,
+) -> Vec<SitemapFile> {
+    let urls_per_file = urls_per_file.clamp(1, MAX_SITEMAP_URLS);
+    let infix = match locale {
+        Some(locale) => format!("{}-{}", kind.as_str(), locale.to_lowercase()),
+        None => kind.as_str().to_string(),
+    };
+
+    (0..total.div_ceil(urls_per_file))
+        .map(|page| {
+            let offset = page * urls_per_file;
+            SitemapFile {
+                name: format!("sitemap-{infix}-{}.xml.gz", page + 1),
+                kind,
+                locale: locale.map(str::to_string),
+                offset,
+                url_count: urls_per_file.min(total - offset),
+            }
+        })
+        .collect()
+}
+
+/// Absolute URL for a slug under `root`, e.g. ("https://shop.example.com/", "products", "blue-shirt")
+/// gives "https://shop.example.com/products/blue-shirt". Path-style slugs keep their segments.
+pub fn sitemap_url(root: &str, path: &str, slug: &str) -> String {
+    let mut url = root.trim_end_matches('/').to_string();
+    for segment in [path, slug] {
+        let segment = segment.trim_matches('/');
+        if !segment.is_empty() {
+            url.push('/');
+            url.push_str(segment);
+        }
+    }
+    url
+}
+
+/// Render a `<urlset>` document
+pub fn render_urlset(urls: &[SitemapUrl]) -> String {
+    render("urlset", "url", urls)
+}
+
+/// Render a `<sitemapindex>` document pointing at urlset files
+pub fn render_sitemap_index(sitemaps: &[SitemapUrl]) -> String {
+    render("sitemapindex", "sitemap", sitemaps)
+}
+
+fn render(root: &str, element: &str, urls: &[SitemapUrl]) -> String {
+    let mut xml = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<{root} xmlns=\"{SITEMAP_NAMESPACE}\">\n"
+    );
+    for url in urls {
+        xml.push_str(&format!(
+            "  <{element}>\n    <loc>{}</loc>\n",
+            escape_xml(&url.loc)
+        ));
+        if let Some(lastmod) = url.lastmod {
+            xml.push_str(&format!(
+                "    <lastmod>{}</lastmod>\n",
+                lastmod.to_rfc3339_opts(SecondsFormat::Secs, true)
+            ));
+        }
+        xml.push_str(&format!("  </{element}>\n"));
+    }
+    xml.push_str(&format!("</{root}>\n"));
+    xml
+}
+
+pub(crate) fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn files_are_chunked_and_named_per_locale() {
+        let files = plan_sitemap_files(SitemapKind::Products, Some("fr-CA"), 5, 2);
+
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].name, "sitemap-products-fr-ca-1.xml.gz");
+        assert_eq!(files[2].offset, 4);
+        assert_eq!(files[2].url_count, 1);
+        assert!(plan_sitemap_files(SitemapKind::Categories, None, 0, 10).is_empty());
+
+        // The protocol limit wins over a larger request
+        let files = plan_sitemap_files(
+            SitemapKind::Categories,
+            None,
+            MAX_SITEMAP_URLS + 1,
+            usize::MAX,
+        );
+        assert_eq!(files[0].name, "sitemap-categories-1.xml.gz");
+        assert_eq!(files[0].url_count, MAX_SITEMAP_URLS);
+        assert_eq!(files[1].url_count, 1);
+    }
+
+    #[test]
+    fn urls_join_root_path_and_slug() {
+        assert_eq!(
+            sitemap_url("https://shop.example.com/", "/products/", "blue-shirt"),
+            "https://shop.example.com/products/blue-shirt"
+        );
+        assert_eq!(
+            sitemap_url("https://shop.example.com/fr", "", "/apparel/shirts"),
+            "https://shop.example.com/fr/apparel/shirts"
+        );
+    }
+
+    #[test]
+    fn urlset_escapes_locations_and_formats_lastmod() {
+        let xml = render_urlset(&[
+            SitemapUrl {
+                loc: "https://shop.example.com/p?a=1&b=2".to_string(),
+                lastmod: Some(Utc.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap()),
+            },
+            SitemapUrl {
+                loc: "https://shop.example.com/q".to_string(),
+                lastmod: None,
+            },
+        ]);
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset"));
+        assert!(xml.contains("<loc>https://shop.example.com/p?a=1&amp;b=2</loc>"));
+        assert!(xml.contains("<lastmod>2024-03-01T12:30:00Z</lastmod>"));
+        assert_eq!(xml.matches("<lastmod>").count(), 1);
+        assert!(render_sitemap_index(&[]).contains("<sitemapindex"));
+    }
+}
@@ -0,0 +1,141 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Upper bound on numeric suffixes tried before falling back to a random one
+pub const MAX_SLUG_SUFFIX: u32 = 100;
+
+/// Converts a display name into a lowercase, hyphen-separated URL slug.
+///
+/// Runs of anything that is not a letter or digit collapse into a single hyphen,
+/// and leading/trailing hyphens are trimmed, so "Men's  T-Shirts & Tops" becomes
+/// "men-s-t-shirts-tops".
+pub fn slugify(value: &str) -> String {
+    let mut slug = String::with_capacity(value.len());
+    let mut pending_hyphen = false;
+
+    for c in value.chars() {
+        if c.is_alphanumeric() {
+            if pending_hyphen && !slug.is_empty() {
+                slug.push('-');
+            }
+            pending_hyphen = false;
+            slug.extend(c.to_lowercase());
+        } else {
+            pending_hyphen = true;
+        }
+    }
+
+    slug
+}
+
+/// Appends a numeric disambiguator to a slug ("shorts" -> "shorts-2")
+pub fn slug_with_suffix(base: &str, suffix: u32) -> String {
+    if suffix <= 1 {
+        base.to_string()
+    } else {
+        format!("{base}-{suffix}")
+    }
+}
+
+/// The kind of entity a historical slug belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SlugEntityType {
+    Product,
+    Category,
+}
+
+impl SlugEntityType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SlugEntityType::Product => "product",
+            SlugEntityType::Category => "category",
+        }
+    }
+}
+
+/// A slug that an entity used to be published under.
+///
+/// Only the owning entity id is stored, not the slug it moved to, so a product
+/// renamed several times resolves every old slug straight to its current one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlugRedirect {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub entity_type: SlugEntityType,
+    pub slug: String,
+    pub entity_id: String,
+    pub retired_at: DateTime<Utc>,
+}
+
+impl SlugRedirect {
+    pub fn new(entity_type: SlugEntityType, slug: String, entity_id: String) -> Self {
+        Self {
+            id: Self::key(entity_type, &slug),
+            entity_type,
+            slug,
+            entity_id,
+            retired_at: Utc::now(),
+        }
+    }
+
+    /// Document id for a historical slug; one entry per entity type and slug
+    pub fn key(entity_type: SlugEntityType, slug: &str) -> String {
+        format!("{}:{slug}", entity_type.as_str())
+    }
+}
+
+/// Result of resolving a slug that may have been retired
+#[derive(Debug, Clone)]
+pub enum SlugLookup<T> {
+    /// The slug is the entity's current slug
+    Current(T),
+    /// The slug is historical; callers should redirect to `current_slug`
+    MovedPermanently { current_slug: String, entity: T },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugify_lowercases_and_hyphenates() {
+        assert_eq!(slugify("Cargo Shorts"), "cargo-shorts");
+        assert_eq!(slugify("Basic_T_Shirt"), "basic-t-shirt");
+    }
+
+    #[test]
+    fn slugify_collapses_punctuation_and_whitespace() {
+        assert_eq!(slugify("Men's  T-Shirts & Tops"), "men-s-t-shirts-tops");
+        assert_eq!(slugify("  --Sale!--  "), "sale");
+    }
+
+    #[test]
+    fn slugify_keeps_unicode_letters() {
+        assert_eq!(slugify("Café Crème"), "café-crème");
+    }
+
+    #[test]
+    fn slugify_of_symbols_only_is_empty() {
+        assert_eq!(slugify("!!! ???"), "");
+    }
+
+    #[test]
+    fn suffix_is_only_added_after_the_first_candidate() {
+        assert_eq!(slug_with_suffix("shorts", 1), "shorts");
+        assert_eq!(slug_with_suffix("shorts", 2), "shorts-2");
+        assert_eq!(slug_with_suffix("/apparel/shorts", 3), "/apparel/shorts-3");
+    }
+
+    #[test]
+    fn redirect_key_is_scoped_by_entity_type() {
+        assert_eq!(
+            SlugRedirect::key(SlugEntityType::Product, "shorts"),
+            "product:shorts"
+        );
+        assert_eq!(
+            SlugRedirect::key(SlugEntityType::Category, "/apparel"),
+            "category:/apparel"
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::{Category, CategoryTreeNode, Product};
+
+/// How a product shows in one store. A product without store entries is in
+/// every store's assortment and uses its own fields everywhere; once it has
+/// entries it is only in those stores.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProductStoreSettings {
+    pub display_on_site: bool, // Replaces the product's own flag in this store
+    pub slug: Option<String>,  // Unique among the store's slugs
+}
+
+/// How a category shows in one store, with the same all-stores default as products
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CategoryStoreSettings {
+    pub is_active: bool, // Replaces the category's own flag in this store
+    pub slug: Option<String>,
+}
+
+/// Canonical form of a store id: trimmed and lowercased. Returns None unless
+/// it is 1 to 64 ASCII letters, digits, `-` or `_`, which also keeps it safe
+/// to use as a document key.
+pub fn normalize_store_id(store_id: &str) -> Option<String> {
+    let store_id = store_id.trim().to_ascii_lowercase();
+    let valid = (1..=64).contains(&store_id.len())
+        && store_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then_some(store_id)
+}
+
+/// Normalize the keys of a stores map, rejecting invalid store ids
+pub fn normalize_stores<T>(stores: HashMap<String, T>) -> Result<HashMap<String, T>, String> {
+    stores
+        .into_iter()
+        .map(|(store_id, settings)| {
+            normalize_store_id(&store_id)
+                .map(|normalized| (normalized, settings))
+                .ok_or_else(|| format!("'{store_id}' is not a valid store id"))
+        })
+        .collect()
+}
+
+fn in_store<T>(stores: &HashMap<String, T>, store_id: &str) -> bool {
+    stores.is_empty() || stores.contains_key(store_id)
+}
+
+impl Product {
+    pub fn in_store(&self, store_id: &str) -> bool {
+        in_store(&self.stores, store_id)
+    }
+
+    /// This product with its display flag and slug resolved for `store_id`
+    pub fn for_store(mut self, store_id: &str) -> Product {
+        if let Some(settings) = self.stores.get(store_id) {
+            self.display_on_site = settings.display_on_site;
+            if let Some(slug) = &settings.slug {
+                self.slug = Some(slug.clone());
+            }
+        }
+        self
+    }
+}
+
+impl Category {
+    pub fn in_store(&self, store_id: &str) -> bool {
+        in_store(&self.stores, store_id)
+    }
+
+    /// This category with its active flag and slug resolved for `store_id`
+    pub fn for_store(mut self, store_id: &str) -> Category {
+        if let Some(settings) = self.stores.get(store_id) {
+            self.is_active = settings.is_active;
+            if let Some(slug) = &settings.slug {
+                self.slug = slug.clone();
+            }
+        }
+        self
+    }
+}
+
+impl CategoryTreeNode {
+    /// The part of this subtree that `store_id` carries, resolved for the
+    /// store. None when this node is not in the store; a child left out of
+    /// the store takes its descendants with it.
+    pub fn for_store(&self, store_id: &str) -> Option<Self> {
+        if !in_store(&self.stores, store_id) {
+            return None;
+        }
+
+        let mut node = Self {
+            children: self
+                .children
+                .iter()
+                .filter_map(|(id, child)| child.for_store(store_id).map(|c| (id.clone(), c)))
+                .collect(),
+            ..self.clone()
+        };
+        if let Some(settings) = self.stores.get(store_id) {
+            node.is_active = settings.is_active;
+            if let Some(slug) = &settings.slug {
+                node.slug = slug.clone();
+            }
+        }
+        Some(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ProductBuilder;
+
+    fn node(id: &str, stores: &[&str], children: Vec<CategoryTreeNode>) -> CategoryTreeNode {
+        CategoryTreeNode {
+            id: id.to_string(),
+            name: id.to_string(),
+            slug: id.to_string(),
+            path: id.to_string(),
+            level: 0,
+            product_count: 0,
+            subtree_product_count: 0,
+            is_active: true,
+            localizations: HashMap::new(),
+            stores: stores
+                .iter()
+                .map(|store| {
+                    (
+                        store.to_string(),
+                        CategoryStoreSettings {
+                            is_active: true,
+                            slug: Some(format!("{id}-{store}")),
+                        },
+                    )
+                })
+                .collect(),
+            children: children.into_iter().map(|c| (c.id.clone(), c)).collect(),
+        }
+    }
+
+    #[test]
+    fn store_ids_are_normalized() {
+        assert_eq!(normalize_store_id(" EU-Main ").as_deref(), Some("eu-main"));
+        assert_eq!(normalize_store_id("outlet_2").as_deref(), Some("outlet_2"));
+        assert_eq!(normalize_store_id(""), None);
+        assert_eq!(normalize_store_id("eu.main"), None);
+        assert_eq!(normalize_store_id("$store"), None);
+
+        let invalid = HashMap::from([("eu main".to_string(), ())]);
+        assert!(normalize_stores(invalid).is_err());
+    }
+
+    #[test]
+    fn unassigned_products_are_in_every_store() {
+        let mut product = ProductBuilder::new("Tent".to_string(), "TN-1".to_string())
+            .slug("tent".to_string())
+            .display_on_site(true)
+            .build();
+        assert!(product.in_store("eu"));
+
+        product.stores = HashMap::from([(
+            "us".to_string(),
+            ProductStoreSettings {
+                display_on_site: false,
+                slug: Some("camping-tent".to_string()),
+            },
+        )]);
+        assert!(!product.in_store("eu"));
+        assert!(product.in_store("us"));
+
+        let us = product.clone().for_store("us");
+        assert!(!us.display_on_site);
+        assert_eq!(us.slug.as_deref(), Some("camping-tent"));
+        assert_eq!(product.for_store("eu").slug.as_deref(), Some("tent"));
+    }
+
+    #[test]
+    fn tree_keeps_only_the_store_branches() {
+        let tree = node(
+            "outdoor",
+            &[],
+            vec![
+                node("tents", &["eu", "us"], vec![node("domes", &["us"], vec![])]),
+                node("skis", &["eu"], vec![node("boots", &[], vec![])]),
+            ],
+        );
+
+        let us = tree.for_store("us").unwrap();
+        assert_eq!(us.slug, "outdoor");
+        assert_eq!(us.children.len(), 1);
+        let tents = &us.children["tents"];
+        assert_eq!(tents.slug, "tents-us");
+        assert!(tents.children.contains_key("domes"));
+
+        let eu = tree.for_store("eu").unwrap();
+        assert!(eu.children["skis"].children.contains_key("boots"));
+        assert!(eu.children["tents"].children.is_empty());
+
+        assert!(node("tents", &["eu"], vec![]).for_store("us").is_none());
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+
+use super::review::{MAX_RATING, MIN_RATING};
+use super::{Product, RatingTotals};
+
+const SCHEMA_CONTEXT: &str = "https://schema.org";
+
+/// One step of a breadcrumb trail
+#[derive(Debug, Clone, PartialEq)]
+pub struct BreadcrumbItem {
+    pub name: String,
+    pub url: String,
+}
+
+/// What price-service and inventory-service know about a product's SKUs
+#[derive(Debug, Default)]
+pub struct ProductOffers<'a> {
+    pub currency: &'a str,
+    pub prices: HashMap<String, String>, // Best single-unit price by SKU
+    pub available: Option<HashMap<String, i32>>, // None when stock could not be looked up
+}
+
+/// schema.org BreadcrumbList for a trail given root first
+pub fn breadcrumb_list_json_ld(items: &[BreadcrumbItem]) -> Value {
+    let elements: Vec<Value> = items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            json!({
+                "@type": "ListItem",
+                "position": index + 1,
+                "name": item.name,
+                "item": item.url,
+            })
+        })
+        .collect();
+
+    json!({
+        "@context": SCHEMA_CONTEXT,
+        "@type": "BreadcrumbList",
+        "itemListElement": elements,
+    })
+}
+
+/// schema.org Product for a product published at `url`, with one Offer per SKU
+/// that has a price and an AggregateRating once it has approved reviews
+pub fn product_json_ld(
+    product: &Product,
+    url: &str,
+    offers: &ProductOffers,
+    ratings: RatingTotals,
+) -> Value {
+    let mut data = Map::new();
+    data.insert("@context".to_string(), json!(SCHEMA_CONTEXT));
+    data.insert("@type".to_string(), json!("Product"));
+    data.insert("name".to_string(), json!(product.name));
+    data.insert("url".to_string(), json!(url));
+    data.insert("productID".to_string(), json!(product.product_ref));
+
+    let description = product
+        .long_description
+        .as_ref()
+        .or(product.seo_description.as_ref());
+    if let Some(description) = description {
+        data.insert("description".to_string(), json!(description));
+    }
+    if let Some(brand) = &product.brand {
+        data.insert(
+            "brand".to_string(),
+            json!({ "@type": "Brand", "name": brand }),
+        );
+    }
+    if let Some(sku) = product
+        .default_variant
+        .as_ref()
+        .or(product.variants.first().map(|variant| &variant.sku))
+    {
+        data.insert("sku".to_string(), json!(sku));
+    }
+
+    let mut images: Vec<&String> = Vec::new();
+    for image in product.variants.iter().flat_map(|v| &v.image_urls) {
+        if !images.contains(&image) {
+            images.push(image);
+        }
+    }
+    if !images.is_empty() {
+        data.insert("image".to_string(), json!(images));
+    }
+
+    let product_offers: Vec<Value> = product
+        .variants
+        .iter()
+        .filter_map(|variant| {
+            let price = offers.prices.get(&variant.sku)?;
+            let mut offer = json!({
+                "@type": "Offer",
+                "sku": variant.sku,
+                "price": price,
+                "priceCurrency": offers.currency,
+                "url": url,
+            });
+            if let Some(available) = &offers.available {
+                let in_stock = available.get(&variant.sku).is_some_and(|&q| q > 0);
+                offer["availability"] = json!(if in_stock {
+                    "https://schema.org/InStock"
+                } else {
+                    "https://schema.org/OutOfStock"
+                });
+            }
+            Some(offer)
+        })
+        .collect();
+    if !product_offers.is_empty() {
+        data.insert("offers".to_string(), json!(product_offers));
+    }
+
+    if ratings.count > 0 {
+        let mean = ratings.rating_sum as f64 / ratings.count as f64;
+        data.insert(
+            "aggregateRating".to_string(),
+            json!({
+                "@type": "AggregateRating",
+                "ratingValue": (mean * 10.0).round() / 10.0,
+                "reviewCount": ratings.count,
+                "bestRating": MAX_RATING,
+                "worstRating": MIN_RATING,
+            }),
+        );
+    }
+
+    Value::Object(data)
+}
+
+/// Serialize JSON-LD for a `<script type="application/ld+json">` element. Characters
+/// that could end the script or open markup are escaped, which JSON parsers undo.
+pub fn json_ld_script_content(value: &Value) -> String {
+    let mut escaped = String::new();
+    for c in value.to_string().chars() {
+        match c {
+            '<' => escaped.push_str("\\u003c"),
+            '>' => escaped.push_str("\\u003e"),
+            '&' => escaped.push_str("\\u0026"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ProductBuilder, ProductVariantBuilder};
+
+    fn variant(sku: &str, images: &[&str]) -> crate::domain::ProductVariant {
+        ProductVariantBuilder::new(sku.to_string())
+            .image_urls(images.iter().map(|i| i.to_string()).collect())
+            .build()
+    }
+
+    fn product() -> Product {
+        ProductBuilder::new("Trail Runner".to_string(), "TR-1".to_string())
+            .brand("Acme".to_string())
+            .long_description("Grippy & light".to_string())
+            .variants(vec![
+                variant("TR-1-9", &["https://cdn.example.com/a.jpg"]),
+                variant(
+                    "TR-1-10",
+                    &[
+                        "https://cdn.example.com/a.jpg",
+                        "https://cdn.example.com/b.jpg",
+                    ],
+                ),
+            ])
+            .build()
+    }
+
+    #[test]
+    fn product_has_offers_for_priced_skus_and_a_rating() {
+        let offers = ProductOffers {
+            currency: "USD",
+            prices: HashMap::from([("TR-1-10".to_string(), "89.99".to_string())]),
+            available: Some(HashMap::from([("TR-1-10".to_string(), 3)])),
+        };
+        let ratings = RatingTotals {
+            count: 3,
+            rating_sum: 13,
+        };
+        let data = product_json_ld(
+            &product(),
+            "https://shop.example.com/p/tr",
+            &offers,
+            ratings,
+        );
+
+        assert_eq!(data["@type"], "Product");
+        assert_eq!(data["brand"]["name"], "Acme");
+        assert_eq!(data["sku"], "TR-1-9");
+        assert_eq!(data["image"].as_array().unwrap().len(), 2);
+
+        let offers = data["offers"].as_array().unwrap();
+        assert_eq!(offers.len(), 1);
+        assert_eq!(offers[0]["sku"], "TR-1-10");
+        assert_eq!(offers[0]["price"], "89.99");
+        assert_eq!(offers[0]["priceCurrency"], "USD");
+        assert_eq!(offers[0]["availability"], "https://schema.org/InStock");
+
+        assert_eq!(data["aggregateRating"]["ratingValue"], 4.3);
+        assert_eq!(data["aggregateRating"]["reviewCount"], 3);
+    }
+
+    #[test]
+    fn unreviewed_unpriced_products_leave_out_offers_and_rating() {
+        let data = product_json_ld(
+            &product(),
+            "https://shop.example.com/p/tr",
+            &ProductOffers::default(),
+            RatingTotals {
+                count: 0,
+                rating_sum: 0,
+            },
+        );
+
+        assert!(data.get("offers").is_none());
+        assert!(data.get("aggregateRating").is_none());
+    }
+
+    #[test]
+    fn breadcrumbs_are_numbered_from_one() {
+        let data = breadcrumb_list_json_ld(&[
+            BreadcrumbItem {
+                name: "Apparel".to_string(),
+                url: "https://shop.example.com/c/apparel".to_string(),
+            },
+            BreadcrumbItem {
+                name: "Shirts".to_string(),
+                url: "https://shop.example.com/c/apparel/shirts".to_string(),
+            },
+        ]);
+
+        assert_eq!(data["@type"], "BreadcrumbList");
+        assert_eq!(data["itemListElement"][1]["position"], 2);
+        assert_eq!(data["itemListElement"][1]["name"], "Shirts");
+    }
+
+    #[test]
+    fn script_content_cannot_close_the_script_element() {
+        let content = json_ld_script_content(&json!({ "name": "</script><b>&" }));
+
+        assert!(!content.contains('<'));
+        assert!(!content.contains('&'));
+        let parsed: Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(parsed["name"], "</script><b>&");
+    }
+}
@@ -0,0 +1,253 @@
+use std::fmt;
+use std::str::FromStr;
+
+use log::warn;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Cubic centimetres per kilogram carriers divide package volume by to get a
+/// dimensional weight; 5000 is the common international divisor
+pub const DIMENSIONAL_WEIGHT_DIVISOR_CM3_PER_KG: f64 = 5000.0;
+
+/// Units variant and package dimensions are given in. Stored and sent as the
+/// short code, e.g. "cm".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum LengthUnit {
+    Millimeter,
+    Centimeter,
+    Meter,
+    Inch,
+    Foot,
+}
+
+/// Units variant and package weights are given in. Stored and sent as the
+/// short code, e.g. "lb".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum MassUnit {
+    Gram,
+    Kilogram,
+    Ounce,
+    Pound,
+}
+
+impl LengthUnit {
+    pub fn code(self) -> &'static str {
+        match self {
+            LengthUnit::Millimeter => "mm",
+            LengthUnit::Centimeter => "cm",
+            LengthUnit::Meter => "m",
+            LengthUnit::Inch => "in",
+            LengthUnit::Foot => "ft",
+        }
+    }
+
+    fn centimeters(self) -> f64 {
+        match self {
+            LengthUnit::Millimeter => 0.1,
+            LengthUnit::Centimeter => 1.0,
+            LengthUnit::Meter => 100.0,
+            LengthUnit::Inch => 2.54,
+            LengthUnit::Foot => 30.48,
+        }
+    }
+
+    /// `value` in this unit, expressed in `to`
+    pub fn convert(self, value: f64, to: LengthUnit) -> f64 {
+        value * self.centimeters() / to.centimeters()
+    }
+}
+
+impl MassUnit {
+    pub fn code(self) -> &'static str {
+        match self {
+            MassUnit::Gram => "g",
+            MassUnit::Kilogram => "kg",
+            MassUnit::Ounce => "oz",
+            MassUnit::Pound => "lb",
+        }
+    }
+
+    fn kilograms(self) -> f64 {
+        match self {
+            MassUnit::Gram => 0.001,
+            MassUnit::Kilogram => 1.0,
+            MassUnit::Ounce => 0.028_349_523_125,
+            MassUnit::Pound => 0.453_592_37,
+        }
+    }
+
+    /// `value` in this unit, expressed in `to`
+    pub fn convert(self, value: f64, to: MassUnit) -> f64 {
+        value * self.kilograms() / to.kilograms()
+    }
+}
+
+impl FromStr for LengthUnit {
+    type Err = String;
+
+    /// Accepts the short code or common spellings, in any case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "mm" | "millimeter" | "millimeters" | "millimetre" | "millimetres" => {
+                Ok(LengthUnit::Millimeter)
+            }
+            "cm" | "centimeter" | "centimeters" | "centimetre" | "centimetres" => {
+                Ok(LengthUnit::Centimeter)
+            }
+            "m" | "meter" | "meters" | "metre" | "metres" => Ok(LengthUnit::Meter),
+            "in" | "inch" | "inches" | "\"" => Ok(LengthUnit::Inch),
+            "ft" | "foot" | "feet" | "'" => Ok(LengthUnit::Foot),
+            _ => Err(format!(
+                "Unknown length unit '{s}', expected one of mm, cm, m, in, ft"
+            )),
+        }
+    }
+}
+
+impl FromStr for MassUnit {
+    type Err = String;
+
+    /// Accepts the short code or common spellings, in any case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "g" | "gram" | "grams" => Ok(MassUnit::Gram),
+            "kg" | "kgs" | "kilogram" | "kilograms" => Ok(MassUnit::Kilogram),
+            "oz" | "ounce" | "ounces" => Ok(MassUnit::Ounce),
+            "lb" | "lbs" | "pound" | "pounds" => Ok(MassUnit::Pound),
+            _ => Err(format!(
+                "Unknown weight unit '{s}', expected one of g, kg, oz, lb"
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for LengthUnit {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl TryFrom<String> for MassUnit {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<LengthUnit> for String {
+    fn from(unit: LengthUnit) -> Self {
+        unit.code().to_string()
+    }
+}
+
+impl From<MassUnit> for String {
+    fn from(unit: MassUnit) -> Self {
+        unit.code().to_string()
+    }
+}
+
+impl fmt::Display for LengthUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl fmt::Display for MassUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// Read a stored unit, for `#[serde(deserialize_with)]` on `Option` unit
+/// fields. Units were free text before they were parsed, so an empty value is
+/// None and an unrecognised one is logged and dropped rather than failing the
+/// whole document.
+pub fn deserialize_unit_lenient<'de, D, U>(deserializer: D) -> Result<Option<U>, D::Error>
+where
+    D: Deserializer<'de>,
+    U: FromStr<Err = String>,
+{
+    let Some(value) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+    match value.parse() {
+        Ok(unit) => Ok(Some(unit)),
+        Err(e) => {
+            warn!("Ignoring stored unit: {e}");
+            Ok(None)
+        }
+    }
+}
+
+/// A measurement must be a finite, non-negative number
+pub fn validate_measurement(field: &str, value: Option<f64>) -> Result<(), String> {
+    match value {
+        Some(value) if !value.is_finite() || value < 0.0 => Err(format!(
+            "{field} must be a non-negative number, got {value}"
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn units_parse_from_codes_and_spellings() {
+        assert_eq!("LBS".parse::<MassUnit>(), Ok(MassUnit::Pound));
+        assert_eq!(" kilograms ".parse::<MassUnit>(), Ok(MassUnit::Kilogram));
+        assert_eq!("Inches".parse::<LengthUnit>(), Ok(LengthUnit::Inch));
+        assert!("stone".parse::<MassUnit>().is_err());
+        assert!("furlong".parse::<LengthUnit>().is_err());
+    }
+
+    #[test]
+    fn units_serialize_as_codes() {
+        assert_eq!(
+            serde_json::to_string(&MassUnit::Pound).unwrap(),
+            "\"lb\"".to_string()
+        );
+        let unit: LengthUnit = serde_json::from_str("\"CM\"").unwrap();
+        assert_eq!(unit, LengthUnit::Centimeter);
+    }
+
+    #[test]
+    fn stored_units_that_do_not_parse_are_dropped() {
+        #[derive(Deserialize)]
+        struct Stored {
+            #[serde(default, deserialize_with = "deserialize_unit_lenient")]
+            unit: Option<MassUnit>,
+        }
+        let parse = |json: &str| serde_json::from_str::<Stored>(json).unwrap().unit;
+
+        assert_eq!(parse(r#"{"unit": "lbs"}"#), Some(MassUnit::Pound));
+        assert_eq!(parse(r#"{"unit": ""}"#), None);
+        assert_eq!(parse(r#"{"unit": "mg"}"#), None);
+        assert_eq!(parse(r#"{"unit": null}"#), None);
+        assert_eq!(parse("{}"), None);
+    }
+
+    #[test]
+    fn conversions_round_trip() {
+        assert!((LengthUnit::Foot.convert(1.0, LengthUnit::Inch) - 12.0).abs() < 1e-9);
+        assert!((MassUnit::Pound.convert(1.0, MassUnit::Ounce) - 16.0).abs() < 1e-9);
+        let kg = MassUnit::Pound.convert(2.5, MassUnit::Kilogram);
+        assert!((MassUnit::Kilogram.convert(kg, MassUnit::Pound) - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn measurements_must_be_finite_and_non_negative() {
+        assert!(validate_measurement("weight", Some(1.5)).is_ok());
+        assert!(validate_measurement("weight", None).is_ok());
+        assert!(validate_measurement("weight", Some(-1.0)).is_err());
+        assert!(validate_measurement("weight", Some(f64::NAN)).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{ProductVariant, ProductVariantBuilder};
+
+/// Upper bound on the variants one GenerateVariants call may produce
+pub const MAX_GENERATED_VARIANTS: usize = 1000;
+
+/// Placeholder in a SKU pattern that is replaced with the product's product_ref
+pub const PRODUCT_REF_PLACEHOLDER: &str = "{product_ref}";
+
+/// One defining attribute to expand, e.g. `color` with values `[Black, White]`.
+/// `abbreviations` maps a value to the short code used in SKUs and in the
+/// variant's abbreviated_color/abbreviated_size; values without one are used as-is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariantAxis {
+    pub key: String,
+    pub values: Vec<String>,
+    pub abbreviations: HashMap<String, String>,
+}
+
+impl VariantAxis {
+    fn code_for<'a>(&'a self, value: &'a str) -> &'a str {
+        self.abbreviations
+            .get(value)
+            .map(String::as_str)
+            .unwrap_or(value)
+    }
+}
+
+/// Outcome of expanding a set of axes against a product's existing variants
+#[derive(Debug, Clone, Default)]
+pub struct VariantMatrix {
+    pub created: Vec<ProductVariant>,
+    pub skipped_skus: Vec<String>, // Combinations the product already had
+}
+
+/// Build the cartesian product of `axes` as variants. SKUs come from
+/// `sku_pattern`, where `{product_ref}` and `{<axis key>}` are substituted.
+/// Combinations already present in `existing` (same SKU or same defining
+/// attribute values) are skipped rather than duplicated.
+pub fn generate_variant_matrix(
+    product_ref: &str,
+    axes: &[VariantAxis],
+    sku_pattern: &str,
+    existing: &[ProductVariant],
+) -> Result<VariantMatrix, String> {
+    check_axes(axes, sku_pattern)?;
+
+    let combinations = axes.iter().try_fold(1usize, |total, axis| {
+        total
+            .checked_mul(axis.values.len())
+            .filter(|total| *total <= MAX_GENERATED_VARIANTS)
+    });
+    if combinations.is_none() {
+        return Err(format!(
+            "axes expand to more than {MAX_GENERATED_VARIANTS} variants"
+        ));
+    }
+
+    let existing_skus: HashSet<&str> = existing.iter().map(|v| v.sku.as_str()).collect();
+    let mut matrix = VariantMatrix::default();
+
+    for combination in cartesian_product(axes) {
+        let mut sku = sku_pattern.replace(PRODUCT_REF_PLACEHOLDER, product_ref);
+        for (axis, value) in axes.iter().zip(&combination) {
+            sku = sku.replace(&format!("{{{}}}", axis.key), axis.code_for(value));
+        }
+
+        let attributes: HashMap<String, String> = axes
+            .iter()
+            .zip(&combination)
+            .map(|(axis, value)| (axis.key.clone(), value.to_string()))
+            .collect();
+
+        let already_exists = existing_skus.contains(sku.as_str())
+            || existing
+                .iter()
+                .any(|variant| has_attributes(variant, &attributes));
+        if already_exists {
+            matrix.skipped_skus.push(sku);
+            continue;
+        }
+
+        let mut builder = ProductVariantBuilder::new(sku);
+        for (axis, value) in axes.iter().zip(&combination) {
+            match axis.key.to_lowercase().as_str() {
+                "color" | "colour" => {
+                    builder.abbreviated_color(axis.code_for(value).to_string());
+                }
+                "size" => {
+                    builder.abbreviated_size(axis.code_for(value).to_string());
+                }
+                _ => {}
+            }
+        }
+        matrix
+            .created
+            .push(builder.defining_attributes(attributes).build());
+    }
+
+    Ok(matrix)
+}
+
+fn check_axes(axes: &[VariantAxis], sku_pattern: &str) -> Result<(), String> {
+    if axes.is_empty() {
+        return Err("at least one axis is required".to_string());
+    }
+
+    let mut keys = HashSet::new();
+    for axis in axes {
+        if axis.key.trim().is_empty() {
+            return Err("axis keys must not be empty".to_string());
+        }
+        if !keys.insert(axis.key.as_str()) {
+            return Err(format!("axis '{}' is given more than once", axis.key));
+        }
+        if axis.values.is_empty() {
+            return Err(format!("axis '{}' has no values", axis.key));
+        }
+        let unique_values: HashSet<&String> = axis.values.iter().collect();
+        if unique_values.len() != axis.values.len() {
+            return Err(format!("axis '{}' has duplicate values", axis.key));
+        }
+        // Without the placeholder every value of this axis would share a SKU
+        if !sku_pattern.contains(&format!("{{{}}}", axis.key)) {
+            return Err(format!(
+                "sku_pattern must contain {{{}}} for axis '{}'",
+                axis.key, axis.key
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn has_attributes(variant: &ProductVariant, attributes: &HashMap<String, String>) -> bool {
+    variant
+        .defining_attributes
+        .as_ref()
+        .is_some_and(|existing| {
+            attributes
+                .iter()
+                .all(|(key, value)| existing.get(key) == Some(value))
+        })
+}
+
+/// Every combination of axis values, in axis order, first axis varying slowest
+fn cartesian_product(axes: &[VariantAxis]) -> Vec<Vec<&str>> {
+    axes.iter().fold(vec![vec![]], |combinations, axis| {
+        combinations
+            .into_iter()
+            .flat_map(|prefix| {
+                axis.values.iter().map(move |value| {
+                    let mut combination = prefix.clone();
+                    combination.push(value.as_str());
+                    combination
+                })
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn axis(key: &str, values: &[&str]) -> VariantAxis {
+        VariantAxis {
+            key: key.to_string(),
+            values: values.iter().map(|v| v.to_string()).collect(),
+            abbreviations: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn generates_every_combination() {
+        let mut color = axis("color", &["Black", "White"]);
+        color
+            .abbreviations
+            .insert("Black".to_string(), "BLK".to_string());
+        let size = axis("size", &["S", "M", "L"]);
+
+        let matrix =
+            generate_variant_matrix("TEE", &[color, size], "{product_ref}-{color}-{size}", &[])
+                .unwrap();
+
+        let skus: Vec<&str> = matrix.created.iter().map(|v| v.sku.as_str()).collect();
+        assert_eq!(
+            skus,
+            vec![
+                "TEE-BLK-S",
+                "TEE-BLK-M",
+                "TEE-BLK-L",
+                "TEE-White-S",
+                "TEE-White-M",
+                "TEE-White-L"
+            ]
+        );
+        let first = &matrix.created[0];
+        assert_eq!(first.abbreviated_color.as_deref(), Some("BLK"));
+        assert_eq!(first.abbreviated_size.as_deref(), Some("S"));
+        assert_eq!(
+            first.defining_attributes.as_ref().unwrap().get("color"),
+            Some(&"Black".to_string())
+        );
+    }
+
+    #[test]
+    fn existing_combinations_are_skipped() {
+        let existing = ProductVariantBuilder::new("LEGACY-1".to_string())
+            .defining_attributes(HashMap::from([
+                ("color".to_string(), "Black".to_string()),
+                ("size".to_string(), "S".to_string()),
+            ]))
+            .build();
+
+        let matrix = generate_variant_matrix(
+            "TEE",
+            &[axis("color", &["Black"]), axis("size", &["S", "M"])],
+            "{product_ref}-{color}-{size}",
+            &[existing],
+        )
+        .unwrap();
+
+        assert_eq!(matrix.skipped_skus, vec!["TEE-Black-S"]);
+        assert_eq!(matrix.created.len(), 1);
+        assert_eq!(matrix.created[0].sku, "TEE-Black-M");
+    }
+
+    #[test]
+    fn pattern_must_reference_every_axis() {
+        let result = generate_variant_matrix(
+            "TEE",
+            &[axis("color", &["Black"]), axis("size", &["S"])],
+            "{product_ref}-{color}",
+            &[],
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn oversized_matrices_are_rejected() {
+        let values: Vec<String> = (0..100).map(|i| i.to_string()).collect();
+        let values: Vec<&str> = values.iter().map(String::as_str).collect();
+
+        let result = generate_variant_matrix(
+            "TEE",
+            &[axis("a", &values), axis("b", &values)],
+            "{a}-{b}",
+            &[],
+        );
+        assert!(result.is_err());
+    }
+}
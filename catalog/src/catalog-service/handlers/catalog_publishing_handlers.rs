@@ -0,0 +1,345 @@
+use std::sync::Arc;
+
+use async_nats::{Client, Message};
+use chrono::{DateTime, Utc};
+use log::{debug, error, warn};
+use prost::Message as ProstMessage;
+
+use crate::{
+    catalog_messages::{
+        self, CatalogChangeSummary, DiscardStagedChangesRequest, DiscardStagedChangesResponse,
+        ListCatalogVersionsRequest, ListCatalogVersionsResponse, ListStagedChangesRequest,
+        ListStagedChangesResponse, PublishCatalogRequest, PublishCatalogResponse,
+        RollbackCatalogRequest, RollbackCatalogResponse, StageCatalogChangeRequest,
+        StageCatalogChangeResponse, StagedCatalogChange,
+    },
+    domain::{
+        self, CatalogChangeAction, CatalogEntityType, CatalogVersion, CatalogVersionStatus,
+        StagedChange, StagedEdit,
+    },
+    services::product_service::HandlerError,
+    AppState,
+};
+
+pub async fn stage_catalog_change(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing stage_catalog_change request");
+
+    let mut response = StageCatalogChangeResponse::default();
+    let status = match StageCatalogChangeRequest::decode(&*msg.payload) {
+        Ok(request) => {
+            match app_state
+                .catalog_publishing_service
+                .stage_change(request)
+                .await
+            {
+                Ok(change) => {
+                    response.change = Some(map_staged_change(change));
+                    ok_status("Change staged successfully")
+                }
+                Err(e) => error_status(e),
+            }
+        }
+        Err(err) => {
+            warn!("Invalid stage catalog change request format: {err:?}");
+            invalid_format_status()
+        }
+    };
+    response.status = Some(status);
+
+    reply(&client, msg, response.encode_to_vec()).await;
+    Ok(())
+}
+
+pub async fn list_staged_changes(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing list_staged_changes request");
+
+    let mut response = ListStagedChangesResponse::default();
+    let status = match ListStagedChangesRequest::decode(&*msg.payload) {
+        Ok(request) => {
+            match app_state
+                .catalog_publishing_service
+                .list_staged_changes(request.change_set.as_deref())
+                .await
+            {
+                Ok(changes) => {
+                    response.changes = changes.into_iter().map(map_staged_change).collect();
+                    ok_status("Staged changes retrieved successfully")
+                }
+                Err(e) => error_status(e),
+            }
+        }
+        Err(err) => {
+            warn!("Invalid list staged changes request format: {err:?}");
+            invalid_format_status()
+        }
+    };
+    response.status = Some(status);
+
+    reply(&client, msg, response.encode_to_vec()).await;
+    Ok(())
+}
+
+pub async fn discard_staged_changes(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing discard_staged_changes request");
+
+    let mut response = DiscardStagedChangesResponse::default();
+    let status = match DiscardStagedChangesRequest::decode(&*msg.payload) {
+        Ok(request) => {
+            match app_state
+                .catalog_publishing_service
+                .discard_staged_changes(request.change_set.as_deref(), request.change_ids)
+                .await
+            {
+                Ok(discarded) => {
+                    response.discarded_count = discarded;
+                    ok_status("Staged changes discarded successfully")
+                }
+                Err(e) => error_status(e),
+            }
+        }
+        Err(err) => {
+            warn!("Invalid discard staged changes request format: {err:?}");
+            invalid_format_status()
+        }
+    };
+    response.status = Some(status);
+
+    reply(&client, msg, response.encode_to_vec()).await;
+    Ok(())
+}
+
+pub async fn publish_catalog(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing publish_catalog request");
+
+    let mut response = PublishCatalogResponse::default();
+    let status = match PublishCatalogRequest::decode(&*msg.payload) {
+        Ok(request) => {
+            match app_state
+                .catalog_publishing_service
+                .publish(request.change_set, request.published_by)
+                .await
+            {
+                Ok(version) => {
+                    response.version = Some(map_catalog_version(version));
+                    ok_status("Catalog published successfully")
+                }
+                Err(e) => error_status(e),
+            }
+        }
+        Err(err) => {
+            warn!("Invalid publish catalog request format: {err:?}");
+            invalid_format_status()
+        }
+    };
+    response.status = Some(status);
+
+    reply(&client, msg, response.encode_to_vec()).await;
+    Ok(())
+}
+
+pub async fn list_catalog_versions(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing list_catalog_versions request");
+
+    let mut response = ListCatalogVersionsResponse::default();
+    let status = match ListCatalogVersionsRequest::decode(&*msg.payload) {
+        Ok(request) => {
+            match app_state
+                .catalog_publishing_service
+                .list_versions(
+                    request.limit.map(i64::from),
+                    request.offset.map(|offset| offset.max(0) as u64),
+                )
+                .await
+            {
+                Ok(versions) => {
+                    response.versions = versions.into_iter().map(map_catalog_version).collect();
+                    ok_status("Catalog versions retrieved successfully")
+                }
+                Err(e) => error_status(e),
+            }
+        }
+        Err(err) => {
+            warn!("Invalid list catalog versions request format: {err:?}");
+            invalid_format_status()
+        }
+    };
+    response.status = Some(status);
+
+    reply(&client, msg, response.encode_to_vec()).await;
+    Ok(())
+}
+
+pub async fn rollback_catalog(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing rollback_catalog request");
+
+    let mut response = RollbackCatalogResponse::default();
+    let status = match RollbackCatalogRequest::decode(&*msg.payload) {
+        Ok(request) => {
+            match app_state
+                .catalog_publishing_service
+                .rollback(request.version, request.published_by)
+                .await
+            {
+                Ok(version) => {
+                    response.version = Some(map_catalog_version(version));
+                    ok_status("Catalog rolled back successfully")
+                }
+                Err(e) => error_status(e),
+            }
+        }
+        Err(err) => {
+            warn!("Invalid rollback catalog request format: {err:?}");
+            invalid_format_status()
+        }
+    };
+    response.status = Some(status);
+
+    reply(&client, msg, response.encode_to_vec()).await;
+    Ok(())
+}
+
+async fn reply(client: &Client, msg: Message, response_bytes: Vec<u8>) {
+    if let Some(reply) = msg.reply {
+        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+            error!("Failed to send response: {e}");
+        }
+    }
+}
+
+fn map_staged_change(change: StagedChange) -> StagedCatalogChange {
+    let name = match &change.edit {
+        StagedEdit::CreateProduct { product } | StagedEdit::UpdateProduct { product, .. } => {
+            product.name.clone()
+        }
+        StagedEdit::DeleteProduct { .. } => String::new(),
+        StagedEdit::UpdateCategory { category, .. } => category.name.clone(),
+    };
+    StagedCatalogChange {
+        change: Some(CatalogChangeSummary {
+            entity_type: map_entity_type(change.edit.entity_type()).into(),
+            entity_id: change.edit.entity_id(),
+            action: map_action(change.edit.action()).into(),
+            name,
+        }),
+        id: change.id,
+        change_set: change.change_set,
+        staged_by: change.staged_by,
+        staged_at: Some(timestamp(change.staged_at)),
+    }
+}
+
+fn map_catalog_version(version: CatalogVersion) -> catalog_messages::CatalogVersion {
+    catalog_messages::CatalogVersion {
+        version: version.version,
+        status: map_version_status(version.status).into(),
+        change_set: version.change_set,
+        changes: version.changes.iter().map(map_change_summary).collect(),
+        rolled_back_to: version.rolled_back_to,
+        published_by: version.published_by,
+        started_at: Some(timestamp(version.started_at)),
+        published_at: version.published_at.map(timestamp),
+        error: version.error,
+    }
+}
+
+fn map_change_summary(change: &domain::CatalogChangeSummary) -> CatalogChangeSummary {
+    CatalogChangeSummary {
+        entity_type: map_entity_type(change.entity_type).into(),
+        entity_id: change.entity_id.clone(),
+        action: map_action(change.action).into(),
+        name: change.name.clone(),
+    }
+}
+
+fn map_entity_type(entity_type: CatalogEntityType) -> catalog_messages::CatalogEntityType {
+    match entity_type {
+        CatalogEntityType::Product => catalog_messages::CatalogEntityType::Product,
+        CatalogEntityType::Category => catalog_messages::CatalogEntityType::Category,
+    }
+}
+
+fn map_action(action: CatalogChangeAction) -> catalog_messages::CatalogChangeAction {
+    match action {
+        CatalogChangeAction::Create => catalog_messages::CatalogChangeAction::Create,
+        CatalogChangeAction::Update => catalog_messages::CatalogChangeAction::Update,
+        CatalogChangeAction::Delete => catalog_messages::CatalogChangeAction::Delete,
+    }
+}
+
+fn map_version_status(status: CatalogVersionStatus) -> catalog_messages::CatalogVersionStatus {
+    match status {
+        CatalogVersionStatus::Publishing => catalog_messages::CatalogVersionStatus::Publishing,
+        CatalogVersionStatus::Published => catalog_messages::CatalogVersionStatus::Published,
+        CatalogVersionStatus::Failed => catalog_messages::CatalogVersionStatus::Failed,
+    }
+}
+
+fn timestamp(at: DateTime<Utc>) -> prost_types::Timestamp {
+    prost_types::Timestamp {
+        seconds: at.timestamp(),
+        nanos: at.timestamp_subsec_nanos() as i32,
+    }
+}
+
+fn ok_status(message: &str) -> catalog_messages::Status {
+    status(catalog_messages::Code::Ok, message.to_string())
+}
+
+fn invalid_format_status() -> catalog_messages::Status {
+    status(
+        catalog_messages::Code::InvalidArgument,
+        "Invalid request format".to_string(),
+    )
+}
+
+fn error_status(e: HandlerError) -> catalog_messages::Status {
+    match e {
+        HandlerError::ValidationError(message) => {
+            warn!("Invalid catalog publishing request: {message}");
+            status(catalog_messages::Code::InvalidArgument, message)
+        }
+        HandlerError::NotFound(message) => status(catalog_messages::Code::NotFound, message),
+        HandlerError::AlreadyExists(message) => {
+            status(catalog_messages::Code::AlreadyExists, message)
+        }
+        HandlerError::InternalError(message) => {
+            error!("Error in catalog publishing: {message}");
+            status(
+                catalog_messages::Code::Internal,
+                "Internal server error".to_string(),
+            )
+        }
+    }
+}
+
+fn status(code: catalog_messages::Code, message: String) -> catalog_messages::Status {
+    catalog_messages::Status {
+        code: code.into(),
+        message,
+        details: vec![],
+    }
+}
@@ -6,14 +6,19 @@ use prost::Message as ProstMessage;
 
 use crate::{
     catalog_messages::{
-        CategoryExportRequest, CategoryExportResponse, CategoryImportRequest,
+        CategoryCsvImportRequest, CategoryCsvImportResponse, CategoryExportRequest,
+        CategoryExportResponse, CategoryImportAction as ProtoCategoryImportAction,
+        CategoryImportChange as ProtoCategoryImportChange, CategoryImportRequest,
         CategoryImportResponse, CategoryTreeRequest, CategoryTreeResponse, CreateCategoryRequest,
         CreateCategoryResponse, DeleteCategoryRequest, DeleteCategoryResponse,
         GetCategoryBySlugRequest, GetCategoryBySlugResponse, GetCategoryRequest,
-        GetCategoryResponse, UpdateCategoryRequest, UpdateCategoryResponse,
+        GetCategoryResponse, MergeCategoriesRequest, MergeCategoriesResponse, MoveCategoryRequest,
+        MoveCategoryResponse, RestoreCategoryRequest, RestoreCategoryResponse,
+        UpdateCategoryRequest, UpdateCategoryResponse,
     },
     common::Code,
-    services::category_service::CategoryError,
+    domain::{CategoryImportAction, SlugLookup},
+    services::{category_service::CategoryError, product_service::HandlerError},
     AppState,
 };
 
@@ -155,7 +160,20 @@ pub async fn get_category(
     let request = GetCategoryRequest::decode(&*msg.payload);
     match request {
         Ok(request) => {
-            let result = app_state.category_service.get_category(&request.id).await;
+            // Previews of staged or earlier catalog versions go through publishing
+            let result = match request.catalog_version.as_deref() {
+                Some(catalog_version) => {
+                    app_state
+                        .catalog_publishing_service
+                        .get_category(&request.id, catalog_version, request.locale.as_deref())
+                        .await
+                }
+                None => app_state
+                    .category_service
+                    .get_category(&request.id, request.locale.as_deref())
+                    .await
+                    .map_err(|e| HandlerError::InternalError(e.to_string())),
+            };
 
             match result {
                 Ok(Some(category)) => {
@@ -193,8 +211,26 @@ pub async fn get_category(
                         }
                     }
                 }
+                Err(HandlerError::ValidationError(error_msg)) => {
+                    warn!("Validation error getting category: {error_msg}");
+                    let response = GetCategoryResponse {
+                        category: None,
+                        status: Some(crate::common::Status {
+                            code: Code::InvalidArgument as i32,
+                            message: error_msg,
+                            details: vec![],
+                        }),
+                    };
+                    let response_bytes = response.encode_to_vec();
+
+                    if let Some(reply) = msg.reply {
+                        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                            error!("Failed to send error response: {e}");
+                        }
+                    }
+                }
                 Err(e) => {
-                    error!("Error getting category: {e}");
+                    error!("Error getting category: {e:?}");
                     let response = GetCategoryResponse {
                         category: None,
                         status: Some(crate::common::Status {
@@ -248,18 +284,28 @@ pub async fn get_category_by_slug(
         Ok(request) => {
             let result = app_state
                 .category_service
-                .get_category_by_slug(&request.slug)
+                .get_category_by_slug(&request.slug, request.locale.as_deref())
                 .await;
 
             match result {
-                Ok(Some(category)) => {
+                Ok(Some(lookup)) => {
+                    let (category, redirect_slug, message) = match lookup {
+                        SlugLookup::Current(category) => {
+                            (category, None, "Category retrieved successfully")
+                        }
+                        SlugLookup::MovedPermanently {
+                            current_slug,
+                            entity,
+                        } => (entity, Some(current_slug), "Category moved permanently"),
+                    };
                     let response = GetCategoryBySlugResponse {
                         category: Some(category),
                         status: Some(crate::common::Status {
                             code: Code::Ok as i32,
-                            message: "Category retrieved successfully".to_string(),
+                            message: message.to_string(),
                             details: vec![],
                         }),
+                        redirect_slug,
                     };
 
                     let response_bytes = response.encode_to_vec();
@@ -278,6 +324,7 @@ pub async fn get_category_by_slug(
                             message: "Category not found".to_string(),
                             details: vec![],
                         }),
+                        redirect_slug: None,
                     };
                     let response_bytes = response.encode_to_vec();
 
@@ -296,6 +343,7 @@ pub async fn get_category_by_slug(
                             message: "Internal server error".to_string(),
                             details: vec![],
                         }),
+                        redirect_slug: None,
                     };
                     let response_bytes = response.encode_to_vec();
 
@@ -316,6 +364,7 @@ pub async fn get_category_by_slug(
                     message: "Invalid request format".to_string(),
                     details: vec![],
                 }),
+                redirect_slug: None,
             };
             let response_bytes = response.encode_to_vec();
 
@@ -477,6 +526,229 @@ pub async fn delete_category(
     Ok(())
 }
 
+pub async fn restore_category(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing restore_category request");
+
+    let request = RestoreCategoryRequest::decode(&*msg.payload);
+    match request {
+        Ok(request) => {
+            let result = app_state
+                .category_service
+                .restore_category(&request.id)
+                .await;
+
+            match result {
+                Ok(Some(category)) => {
+                    let response = RestoreCategoryResponse {
+                        category: Some(category),
+                        status: Some(crate::common::Status {
+                            code: Code::Ok as i32,
+                            message: "Category restored successfully".to_string(),
+                            details: vec![],
+                        }),
+                    };
+
+                    let response_bytes = response.encode_to_vec();
+
+                    if let Some(reply) = msg.reply {
+                        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                            error!("Failed to send response: {e}");
+                        }
+                    }
+                }
+                Ok(None) => {
+                    let response = RestoreCategoryResponse {
+                        category: None,
+                        status: Some(crate::common::Status {
+                            code: Code::NotFound as i32,
+                            message: "Deleted category not found".to_string(),
+                            details: vec![],
+                        }),
+                    };
+                    let response_bytes = response.encode_to_vec();
+
+                    if let Some(reply) = msg.reply {
+                        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                            error!("Failed to send response: {e}");
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("Error restoring category: {e}");
+                    let response = RestoreCategoryResponse {
+                        category: None,
+                        status: Some(crate::common::Status {
+                            code: Code::Internal as i32,
+                            message: format!("Failed to restore category: {e}"),
+                            details: vec![],
+                        }),
+                    };
+                    let response_bytes = response.encode_to_vec();
+
+                    if let Some(reply) = msg.reply {
+                        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                            error!("Failed to send error response: {e}");
+                        }
+                    }
+                }
+            }
+        }
+        Err(err) => {
+            warn!("Invalid category restore request format: {err:?}");
+            let response = RestoreCategoryResponse {
+                category: None,
+                status: Some(crate::common::Status {
+                    code: Code::InvalidArgument as i32,
+                    message: "Invalid request format".to_string(),
+                    details: vec![],
+                }),
+            };
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send error response: {e}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn move_category(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing move_category request");
+
+    let request = MoveCategoryRequest::decode(&*msg.payload);
+    let (code, message) = match request {
+        Ok(request) => {
+            let result = app_state
+                .category_service
+                .move_category(&request.category_id, request.new_parent_id.as_deref())
+                .await;
+
+            match result {
+                Ok(true) => (Code::Ok, "Category moved successfully".to_string()),
+                Ok(false) => (Code::NotFound, "Category not found".to_string()),
+                Err(CategoryError::ValidationError(error_msg)) => {
+                    warn!("Validation error moving category: {error_msg}");
+                    (Code::InvalidArgument, error_msg)
+                }
+                Err(CategoryError::AlreadyExists(error_msg)) => {
+                    warn!("Conflict moving category: {error_msg}");
+                    (Code::AlreadyExists, error_msg)
+                }
+                Err(CategoryError::NotFound(error_msg)) => {
+                    warn!("Not found error moving category: {error_msg}");
+                    (Code::NotFound, error_msg)
+                }
+                Err(CategoryError::InternalError(error_msg)) => {
+                    error!("Internal error moving category: {error_msg}");
+                    (Code::Internal, "Internal server error".to_string())
+                }
+            }
+        }
+        Err(err) => {
+            warn!("Invalid category move request format: {err:?}");
+            (Code::InvalidArgument, "Invalid request format".to_string())
+        }
+    };
+
+    let response = MoveCategoryResponse {
+        status: Some(crate::common::Status {
+            code: code as i32,
+            message,
+            details: vec![],
+        }),
+    };
+    let response_bytes = response.encode_to_vec();
+
+    if let Some(reply) = msg.reply {
+        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+            error!("Failed to send response: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn merge_categories(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing merge_categories request");
+
+    let mut response = MergeCategoriesResponse::default();
+    let request = MergeCategoriesRequest::decode(&*msg.payload);
+    let (code, message) = match request {
+        Ok(request) => {
+            response.dry_run = request.dry_run;
+            let result = app_state
+                .category_service
+                .merge_categories(&request.source_id, &request.target_id, request.dry_run)
+                .await;
+
+            match result {
+                Ok(report) => {
+                    response.moved_subcategory_ids = report.moved_subcategory_ids;
+                    response.affected_product_ids = report.affected_product_ids;
+                    response.redirected_slug = report.redirected_slug;
+                    let message = if request.dry_run {
+                        "Merge validated (dry run)"
+                    } else {
+                        "Categories merged successfully"
+                    };
+                    (Code::Ok, message.to_string())
+                }
+                Err(CategoryError::ValidationError(error_msg)) => {
+                    warn!("Validation error merging categories: {error_msg}");
+                    (Code::InvalidArgument, error_msg)
+                }
+                Err(CategoryError::AlreadyExists(error_msg)) => {
+                    warn!("Conflict merging categories: {error_msg}");
+                    (Code::AlreadyExists, error_msg)
+                }
+                Err(CategoryError::NotFound(error_msg)) => {
+                    warn!("Not found error merging categories: {error_msg}");
+                    (Code::NotFound, error_msg)
+                }
+                Err(CategoryError::InternalError(error_msg)) => {
+                    error!("Internal error merging categories: {error_msg}");
+                    (Code::Internal, "Internal server error".to_string())
+                }
+            }
+        }
+        Err(err) => {
+            warn!("Invalid category merge request format: {err:?}");
+            (Code::InvalidArgument, "Invalid request format".to_string())
+        }
+    };
+
+    response.status = Some(crate::common::Status {
+        code: code as i32,
+        message,
+        details: vec![],
+    });
+    let response_bytes = response.encode_to_vec();
+
+    if let Some(reply) = msg.reply {
+        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+            error!("Failed to send response: {e}");
+        }
+    }
+
+    Ok(())
+}
+
 pub async fn export_categories(
     app_state: Arc<AppState>,
     client: Client,
@@ -649,6 +921,110 @@ pub async fn import_categories(
     Ok(())
 }
 
+pub async fn import_categories_csv(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing import_categories_csv request");
+
+    let mut response = CategoryCsvImportResponse::default();
+    let request = CategoryCsvImportRequest::decode(&*msg.payload);
+    let (code, message) = match request {
+        Ok(request) => {
+            let result = app_state
+                .category_service
+                .import_categories_csv(
+                    &request.csv,
+                    request.path_separator.as_deref(),
+                    request.dry_run,
+                )
+                .await;
+
+            match result {
+                Ok(report) => {
+                    let count = |action: CategoryImportAction| {
+                        report
+                            .changes
+                            .iter()
+                            .filter(|change| change.action == action)
+                            .count()
+                    };
+                    let message = format!(
+                        "{}: {} to create, {} to update, {} unchanged, {} errors",
+                        if report.applied {
+                            "Import completed"
+                        } else {
+                            "Import not applied"
+                        },
+                        count(CategoryImportAction::Create),
+                        count(CategoryImportAction::Update),
+                        count(CategoryImportAction::Unchanged),
+                        report.errors.len()
+                    );
+
+                    response.changes = report
+                        .changes
+                        .into_iter()
+                        .map(|change| ProtoCategoryImportChange {
+                            action: match change.action {
+                                CategoryImportAction::Create => ProtoCategoryImportAction::Create,
+                                CategoryImportAction::Update => ProtoCategoryImportAction::Update,
+                                CategoryImportAction::Unchanged => {
+                                    ProtoCategoryImportAction::Unchanged
+                                }
+                            } as i32,
+                            slug: change.slug,
+                            path: change.path,
+                            category_id: change.category_id,
+                            changed_fields: change.changed_fields,
+                            line: change.line,
+                        })
+                        .collect();
+                    response.errors = report.errors;
+                    response.applied = report.applied;
+                    (Code::Ok, message)
+                }
+                Err(CategoryError::ValidationError(error_msg)) => {
+                    warn!("Validation error importing categories: {error_msg}");
+                    (Code::InvalidArgument, error_msg)
+                }
+                Err(CategoryError::AlreadyExists(error_msg)) => {
+                    warn!("Conflict importing categories: {error_msg}");
+                    (Code::AlreadyExists, error_msg)
+                }
+                Err(CategoryError::NotFound(error_msg)) => {
+                    warn!("Not found error importing categories: {error_msg}");
+                    (Code::NotFound, error_msg)
+                }
+                Err(CategoryError::InternalError(error_msg)) => {
+                    error!("Internal error importing categories: {error_msg}");
+                    (Code::Internal, "Internal server error".to_string())
+                }
+            }
+        }
+        Err(err) => {
+            warn!("Invalid category CSV import request format: {err:?}");
+            (Code::InvalidArgument, "Invalid request format".to_string())
+        }
+    };
+
+    response.status = Some(crate::common::Status {
+        code: code as i32,
+        message,
+        details: vec![],
+    });
+    let response_bytes = response.encode_to_vec();
+
+    if let Some(reply) = msg.reply {
+        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+            error!("Failed to send response: {e}");
+        }
+    }
+
+    Ok(())
+}
+
 pub async fn get_category_tree(
     app_state: Arc<AppState>,
     client: Client,
@@ -666,6 +1042,8 @@ pub async fn get_category_tree(
                     request.max_depth,
                     request.include_inactive,
                     request.rebuild_cache,
+                    request.locale.as_deref(),
+                    request.store_id.as_deref(),
                 )
                 .await;
 
@@ -689,12 +1067,21 @@ pub async fn get_category_tree(
                     }
                 }
                 Err(e) => {
-                    error!("Error getting category tree: {e}");
+                    let (code, message) = match e.downcast_ref::<CategoryError>() {
+                        Some(CategoryError::ValidationError(error_msg)) => {
+                            warn!("Validation error getting category tree: {error_msg}");
+                            (Code::InvalidArgument, error_msg.clone())
+                        }
+                        _ => {
+                            error!("Error getting category tree: {e}");
+                            (Code::Internal, "Internal server error".to_string())
+                        }
+                    };
                     let response = CategoryTreeResponse {
                         tree: vec![],
                         status: Some(crate::common::Status {
-                            code: Code::Internal as i32,
-                            message: "Internal server error".to_string(),
+                            code: code as i32,
+                            message,
                             details: vec![],
                         }),
                     };
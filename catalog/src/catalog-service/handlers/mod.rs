@@ -2,8 +2,11 @@ use async_nats::{Client, Message};
 use std::collections::HashMap;
 
 use crate::AppState;
+pub mod catalog_publishing_handlers;
 pub mod category_handlers;
 pub mod product_handlers;
+pub mod sitemap_handlers;
+pub mod structured_data_handlers;
 
 pub type RouteHandler = Box<
     dyn Fn(
@@ -6,17 +6,32 @@ use prost::Message as ProstMessage;
 
 use crate::{
     catalog_messages::{
-        self, GetProductSlugsRequest, GetProductSlugsResponse, ProductCreateRequest,
-        ProductCreateResponse, ProductDeleteRequest, ProductDeleteResponse, ProductExportRequest,
-        ProductExportResponse, ProductGetBySlugRequest, ProductGetBySlugResponse,
-        ProductGetRequest, ProductGetResponse, ProductSearchRequest, ProductSearchResponse,
-        ProductUpdateRequest, ProductUpdateResponse,
+        self, BundleComponentQuote, CloneProductRequest, CloneProductResponse,
+        CompareProductsRequest, CompareProductsResponse, DeleteAttributeSchemaRequest,
+        DeleteAttributeSchemaResponse, GenerateVariantsRequest, GenerateVariantsResponse,
+        GetAttributeSchemaRequest, GetAttributeSchemaResponse, GetBundleQuoteRequest,
+        GetBundleQuoteResponse, GetProductSlugsRequest, GetProductSlugsResponse,
+        GetRelatedProductsRequest, GetRelatedProductsResponse, ListAttributeSchemasRequest,
+        ListAttributeSchemasResponse, ListProductRevisionsRequest, ListProductRevisionsResponse,
+        ListReviewsRequest, ListReviewsResponse, ModerateReviewRequest, ModerateReviewResponse,
+        ProductCreateRequest, ProductCreateResponse, ProductDeleteRequest, ProductDeleteResponse,
+        ProductExportRequest, ProductExportResponse, ProductGetBySlugRequest,
+        ProductGetBySlugResponse, ProductGetRequest, ProductGetResponse, ProductRestoreRequest,
+        ProductRestoreResponse, ProductSearchRequest, ProductSearchResponse, ProductUpdateRequest,
+        ProductUpdateResponse, ProductUpdatedEvent, RelatedProduct, RevertProductRequest,
+        RevertProductResponse, SubmitReviewRequest, SubmitReviewResponse,
+        UpsertAttributeSchemaRequest, UpsertAttributeSchemaResponse,
+    },
+    domain::{
+        AttributeDataType, AttributeDefinition, AttributeSchema, AttributeScope, BundlePricing,
+        ComparisonRowKind, MassUnit, Product, ProductBundle, ProductComparison, ProductRevision,
+        RelationKind, Review, ReviewStatus, Reviews, SlugLookup, VariantAxis,
     },
-    domain::Product,
     AppState,
 };
 
-use crate::services::product_service::HandlerError;
+use crate::services::inventory_client::{seed_zero_inventory, DEFAULT_INVENTORY_LOCATION};
+use crate::services::product_service::{relation_kind_from_proto, HandlerError};
 
 pub async fn create_product(
     app_state: Arc<AppState>,
@@ -182,7 +197,21 @@ pub async fn get_product(
                 return Ok(());
             }
 
-            let result = app_state.product_service.get_product(request.id).await;
+            // Previews of staged or earlier catalog versions go through publishing
+            let result = match request.catalog_version.as_deref() {
+                Some(catalog_version) => {
+                    app_state
+                        .catalog_publishing_service
+                        .get_product(&request.id, catalog_version, request.locale.as_deref())
+                        .await
+                }
+                None => {
+                    app_state
+                        .product_service
+                        .get_product(request.id, request.locale.as_deref())
+                        .await
+                }
+            };
 
             match result {
                 Ok(Some(product)) => {
@@ -326,18 +355,28 @@ pub async fn get_product_by_slug(
         Ok(request) => {
             let result = app_state
                 .product_service
-                .get_product_by_slug(request.slug)
+                .get_product_by_slug(request.slug, request.locale)
                 .await;
 
             match result {
-                Ok(Some(product)) => {
+                Ok(Some(lookup)) => {
+                    let (product, redirect_slug, message) = match lookup {
+                        SlugLookup::Current(product) => {
+                            (product, None, "Product retrieved successfully")
+                        }
+                        SlugLookup::MovedPermanently {
+                            current_slug,
+                            entity,
+                        } => (entity, Some(current_slug), "Product moved permanently"),
+                    };
                     let response = ProductGetBySlugResponse {
                         product: Some(map_model_product_to_proto_product(product)),
                         status: Some(catalog_messages::Status {
                             code: catalog_messages::Code::Ok.into(),
-                            message: "Product retrieved successfully".to_string(),
+                            message: message.to_string(),
                             details: vec![],
                         }),
+                        redirect_slug,
                     };
 
                     let response_bytes = response.encode_to_vec();
@@ -356,6 +395,7 @@ pub async fn get_product_by_slug(
                             message: "Product not found".to_string(),
                             details: vec![],
                         }),
+                        redirect_slug: None,
                     };
 
                     let response_bytes = response.encode_to_vec();
@@ -375,6 +415,7 @@ pub async fn get_product_by_slug(
                             message: error_msg,
                             details: vec![],
                         }),
+                        redirect_slug: None,
                     };
                     let response_bytes = response.encode_to_vec();
                     if let Some(reply) = msg.reply {
@@ -394,6 +435,7 @@ pub async fn get_product_by_slug(
                             message: "Internal server error".to_string(),
                             details: vec![],
                         }),
+                        redirect_slug: None,
                     };
 
                     let response_bytes = response.encode_to_vec();
@@ -415,6 +457,7 @@ pub async fn get_product_by_slug(
                     message: "Invalid request format".to_string(),
                     details: vec![],
                 }),
+                redirect_slug: None,
             };
 
             let response_bytes = response.encode_to_vec();
@@ -654,6 +697,119 @@ pub async fn delete_product(
     Ok(())
 }
 
+pub async fn restore_product(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing restore_product request");
+
+    let request = ProductRestoreRequest::decode(&*msg.payload);
+    match request {
+        Ok(request) => {
+            let result = app_state.product_service.restore_product(request.id).await;
+
+            match result {
+                Ok(Some(product)) => {
+                    let response = ProductRestoreResponse {
+                        product: Some(map_model_product_to_proto_product(product)),
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::Ok.into(),
+                            message: "Product restored successfully".to_string(),
+                            details: vec![],
+                        }),
+                    };
+
+                    let response_bytes = response.encode_to_vec();
+
+                    if let Some(reply) = msg.reply {
+                        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                            error!("Failed to send response: {e}");
+                        }
+                    }
+                }
+                Ok(None) => {
+                    let response = ProductRestoreResponse {
+                        product: None,
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::NotFound.into(),
+                            message: "Deleted product not found".to_string(),
+                            details: vec![],
+                        }),
+                    };
+
+                    let response_bytes = response.encode_to_vec();
+
+                    if let Some(reply) = msg.reply {
+                        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                            error!("Failed to send response: {e}");
+                        }
+                    }
+                }
+                Err(HandlerError::ValidationError(error_msg)) => {
+                    warn!("Validation error restoring product: {error_msg}");
+                    let response = ProductRestoreResponse {
+                        product: None,
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::InvalidArgument.into(),
+                            message: error_msg,
+                            details: vec![],
+                        }),
+                    };
+                    let response_bytes = response.encode_to_vec();
+                    if let Some(reply) = msg.reply {
+                        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                            error!("Failed to send error response: {e}");
+                        }
+                    }
+                }
+                Err(HandlerError::AlreadyExists(error_msg))
+                | Err(HandlerError::NotFound(error_msg))
+                | Err(HandlerError::InternalError(error_msg)) => {
+                    error!("Error restoring product: {error_msg}");
+                    let response = ProductRestoreResponse {
+                        product: None,
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::Internal.into(),
+                            message: "Internal server error".to_string(),
+                            details: vec![],
+                        }),
+                    };
+
+                    let response_bytes = response.encode_to_vec();
+
+                    if let Some(reply) = msg.reply {
+                        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                            error!("Failed to send error response: {e}");
+                        }
+                    }
+                }
+            }
+        }
+        Err(err) => {
+            warn!("Invalid product restore request format: {err:?}");
+            let response = ProductRestoreResponse {
+                product: None,
+                status: Some(catalog_messages::Status {
+                    code: catalog_messages::Code::InvalidArgument.into(),
+                    message: "Invalid request format".to_string(),
+                    details: vec![],
+                }),
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send error response: {e}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 pub async fn search_products(
     app_state: Arc<AppState>,
     client: Client,
@@ -664,16 +820,7 @@ pub async fn search_products(
     let request = ProductSearchRequest::decode(&*msg.payload);
     match request {
         Ok(request) => {
-            let result = app_state
-                .product_service
-                .search_products(
-                    request.query,
-                    request.categories,
-                    request.brand,
-                    request.limit.map(|l| l as i64),
-                    request.offset.map(|o| o as u64),
-                )
-                .await;
+            let result = app_state.product_service.search_products(request).await;
 
             match result {
                 Ok(products) => {
@@ -885,7 +1032,12 @@ pub async fn get_product_slugs(
         Ok(request) => {
             let result = app_state
                 .product_service
-                .get_product_slugs(request.batch_size, request.cursor, request.include_inactive)
+                .get_product_slugs(
+                    request.batch_size,
+                    request.cursor,
+                    request.include_inactive,
+                    request.store_id,
+                )
                 .await;
 
             match result {
@@ -910,6 +1062,28 @@ pub async fn get_product_slugs(
                         }
                     }
                 }
+                Err(HandlerError::ValidationError(error_msg)) => {
+                    warn!("Validation error getting product slugs: {error_msg}");
+                    let response = GetProductSlugsResponse {
+                        slugs: vec![],
+                        next_cursor: None,
+                        total_count: 0,
+                        has_more: false,
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::InvalidArgument.into(),
+                            message: error_msg,
+                            details: vec![],
+                        }),
+                    };
+
+                    let response_bytes = response.encode_to_vec();
+
+                    if let Some(reply) = msg.reply {
+                        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                            error!("Failed to send error response: {e}");
+                        }
+                    }
+                }
                 Err(err) => {
                     error!("Error in get_product_slugs handler: {err:?}");
                     let response = GetProductSlugsResponse {
@@ -961,70 +1135,1610 @@ pub async fn get_product_slugs(
     Ok(())
 }
 
-// Mapping functions to convert between domain models and protobuf messages
-fn map_model_product_to_proto_product(product: Product) -> catalog_messages::Product {
-    catalog_messages::Product {
-        id: product.id,
-        name: product.name,
-        long_description: product.long_description,
-        brand: product.brand,
-        slug: product.slug,
-        product_ref: product.product_ref,
-        product_type: product.product_type,
-        seo_title: product.seo_title,
-        seo_description: product.seo_description,
-        seo_keywords: product.seo_keywords,
-        display_on_site: product.display_on_site,
-        tax_code: product.tax_code,
-        related_products: product.related_products,
-        reviews: product.reviews.map(|r| catalog_messages::Reviews {
-            bayesian_avg: r.bayesian_avg.into(),
-            count: r.count,
-            rating: r.rating,
-        }),
-        hierarchical_categories: product.hierarchical_categories.map(|hc| {
-            catalog_messages::HierarchicalCategories {
-                lvl0: hc.lvl0,
-                lvl1: hc.lvl1,
-                lvl2: hc.lvl2,
+pub async fn list_product_revisions(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing list_product_revisions request");
+
+    let request = ListProductRevisionsRequest::decode(&*msg.payload);
+    match request {
+        Ok(request) => {
+            let result = app_state
+                .product_service
+                .list_product_revisions(
+                    request.product_id,
+                    request.revisions,
+                    request.limit.map(|l| l as i64),
+                    request.offset.map(|o| o as u64),
+                )
+                .await;
+
+            match result {
+                Ok(revisions) => {
+                    let response = ListProductRevisionsResponse {
+                        revisions: revisions
+                            .into_iter()
+                            .map(map_model_revision_to_proto_revision)
+                            .collect(),
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::Ok.into(),
+                            message: "Product revisions retrieved successfully".to_string(),
+                            details: vec![],
+                        }),
+                    };
+
+                    let response_bytes = response.encode_to_vec();
+
+                    if let Some(reply) = msg.reply {
+                        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                            error!("Failed to send response: {e}");
+                        }
+                    }
+                }
+                Err(err) => {
+                    error!("Error listing product revisions: {err:?}");
+                    let response = ListProductRevisionsResponse {
+                        revisions: vec![],
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::Internal.into(),
+                            message: "Internal server error".to_string(),
+                            details: vec![],
+                        }),
+                    };
+
+                    let response_bytes = response.encode_to_vec();
+
+                    if let Some(reply) = msg.reply {
+                        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                            error!("Failed to send error response: {e}");
+                        }
+                    }
+                }
             }
-        }),
-        list_categories: product.list_categories,
-        created_at: product.created_at.map(|dt| prost_types::Timestamp {
-            seconds: dt.timestamp(),
-            nanos: dt.timestamp_subsec_nanos() as i32,
-        }),
-        updated_at: product.updated_at.map(|dt| prost_types::Timestamp {
-            seconds: dt.timestamp(),
-            nanos: dt.timestamp_subsec_nanos() as i32,
-        }),
-        created_by: product.created_by,
-        updated_by: product.updated_by,
-        defining_attributes: product.defining_attributes,
-        descriptive_attributes: product.descriptive_attributes,
-        default_variant: product.default_variant,
-        variants: product
-            .variants
-            .into_iter()
-            .map(|v| catalog_messages::ProductVariant {
-                sku: v.sku,
-                defining_attributes: v.defining_attributes.unwrap_or_default(),
-                abbreviated_color: v.abbreviated_color,
-                abbreviated_size: v.abbreviated_size,
-                height: v.height,
-                width: v.width,
-                length: v.length,
-                weight: v.weight,
-                weight_unit: v.weight_unit,
-                packaging: v.packaging.map(|p| catalog_messages::Packaging {
-                    height: p.height,
-                    width: p.width,
-                    length: p.length,
-                    weight: p.weight,
-                    weight_unit: p.weight_unit,
+        }
+        Err(err) => {
+            warn!("Invalid list product revisions request format: {err:?}");
+            let response = ListProductRevisionsResponse {
+                revisions: vec![],
+                status: Some(catalog_messages::Status {
+                    code: catalog_messages::Code::InvalidArgument.into(),
+                    message: "Invalid request format".to_string(),
+                    details: vec![],
                 }),
-                image_urls: v.image_urls,
-            })
-            .collect(),
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send error response: {e}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn revert_product(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing revert_product request");
+
+    let request = RevertProductRequest::decode(&*msg.payload);
+    match request {
+        Ok(request) => {
+            let result = app_state
+                .product_service
+                .revert_product(request.product_id, request.revision, request.reverted_by)
+                .await;
+
+            let (product, code, message) = match result {
+                Ok(Some(product)) => (
+                    Some(map_model_product_to_proto_product(product)),
+                    catalog_messages::Code::Ok,
+                    "Product reverted successfully".to_string(),
+                ),
+                Ok(None) => (
+                    None,
+                    catalog_messages::Code::NotFound,
+                    "Product not found".to_string(),
+                ),
+                Err(HandlerError::NotFound(error_msg)) => {
+                    warn!("Revision not found reverting product: {error_msg}");
+                    (None, catalog_messages::Code::NotFound, error_msg)
+                }
+                Err(HandlerError::ValidationError(error_msg)) => {
+                    warn!("Validation error reverting product: {error_msg}");
+                    (None, catalog_messages::Code::InvalidArgument, error_msg)
+                }
+                Err(HandlerError::AlreadyExists(error_msg))
+                | Err(HandlerError::InternalError(error_msg)) => {
+                    error!("Error reverting product: {error_msg}");
+                    (
+                        None,
+                        catalog_messages::Code::Internal,
+                        "Internal server error".to_string(),
+                    )
+                }
+            };
+
+            let response = RevertProductResponse {
+                product,
+                status: Some(catalog_messages::Status {
+                    code: code.into(),
+                    message,
+                    details: vec![],
+                }),
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send response: {e}");
+                }
+            }
+        }
+        Err(err) => {
+            warn!("Invalid revert product request format: {err:?}");
+            let response = RevertProductResponse {
+                product: None,
+                status: Some(catalog_messages::Status {
+                    code: catalog_messages::Code::InvalidArgument.into(),
+                    message: "Invalid request format".to_string(),
+                    details: vec![],
+                }),
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send error response: {e}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn generate_variants(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing generate_variants request");
+
+    let request = GenerateVariantsRequest::decode(&*msg.payload);
+    match request {
+        Ok(request) => {
+            let axes = request
+                .axes
+                .into_iter()
+                .map(|axis| VariantAxis {
+                    key: axis.key,
+                    values: axis.values,
+                    abbreviations: axis.abbreviations,
+                })
+                .collect();
+
+            let result = app_state
+                .product_service
+                .generate_variants(
+                    request.product_id,
+                    axes,
+                    request.sku_pattern,
+                    request.updated_by,
+                )
+                .await;
+
+            let response = match result {
+                Ok(Some((product, matrix))) => {
+                    let created_skus: Vec<String> =
+                        matrix.created.into_iter().map(|v| v.sku).collect();
+
+                    let inventory_failed_skus = if request.seed_inventory {
+                        let location = request
+                            .inventory_location
+                            .as_deref()
+                            .unwrap_or(DEFAULT_INVENTORY_LOCATION);
+                        seed_zero_inventory(&client, &created_skus, location).await
+                    } else {
+                        vec![]
+                    };
+
+                    GenerateVariantsResponse {
+                        product: Some(map_model_product_to_proto_product(product)),
+                        created_skus,
+                        skipped_skus: matrix.skipped_skus,
+                        inventory_failed_skus,
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::Ok.into(),
+                            message: "Variants generated successfully".to_string(),
+                            details: vec![],
+                        }),
+                    }
+                }
+                Ok(None) => GenerateVariantsResponse {
+                    status: Some(catalog_messages::Status {
+                        code: catalog_messages::Code::NotFound.into(),
+                        message: "Product not found".to_string(),
+                        details: vec![],
+                    }),
+                    ..Default::default()
+                },
+                Err(HandlerError::ValidationError(error_msg)) => {
+                    warn!("Validation error generating variants: {error_msg}");
+                    GenerateVariantsResponse {
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::InvalidArgument.into(),
+                            message: error_msg,
+                            details: vec![],
+                        }),
+                        ..Default::default()
+                    }
+                }
+                Err(HandlerError::AlreadyExists(error_msg)) => {
+                    warn!("Duplicate SKU generating variants: {error_msg}");
+                    GenerateVariantsResponse {
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::AlreadyExists.into(),
+                            message: error_msg,
+                            details: vec![],
+                        }),
+                        ..Default::default()
+                    }
+                }
+                Err(err) => {
+                    error!("Error generating variants: {err:?}");
+                    GenerateVariantsResponse {
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::Internal.into(),
+                            message: "Internal server error".to_string(),
+                            details: vec![],
+                        }),
+                        ..Default::default()
+                    }
+                }
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send response: {e}");
+                }
+            }
+        }
+        Err(err) => {
+            warn!("Invalid generate variants request format: {err:?}");
+            let response = GenerateVariantsResponse {
+                status: Some(catalog_messages::Status {
+                    code: catalog_messages::Code::InvalidArgument.into(),
+                    message: "Invalid request format".to_string(),
+                    details: vec![],
+                }),
+                ..Default::default()
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send error response: {e}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn clone_product(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing clone_product request");
+
+    let request = CloneProductRequest::decode(&*msg.payload);
+    match request {
+        Ok(request) => {
+            let result = app_state.product_service.clone_product(request).await;
+
+            let response = match result {
+                Ok(Some(product)) => CloneProductResponse {
+                    product: Some(map_model_product_to_proto_product(product)),
+                    status: Some(catalog_messages::Status {
+                        code: catalog_messages::Code::Ok.into(),
+                        message: "Product cloned successfully".to_string(),
+                        details: vec![],
+                    }),
+                },
+                Ok(None) => CloneProductResponse {
+                    product: None,
+                    status: Some(catalog_messages::Status {
+                        code: catalog_messages::Code::NotFound.into(),
+                        message: "Product not found".to_string(),
+                        details: vec![],
+                    }),
+                },
+                Err(HandlerError::ValidationError(error_msg)) => {
+                    warn!("Validation error cloning product: {error_msg}");
+                    CloneProductResponse {
+                        product: None,
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::InvalidArgument.into(),
+                            message: error_msg,
+                            details: vec![],
+                        }),
+                    }
+                }
+                Err(HandlerError::AlreadyExists(error_msg)) => {
+                    warn!("Duplicate product cloning product: {error_msg}");
+                    CloneProductResponse {
+                        product: None,
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::AlreadyExists.into(),
+                            message: error_msg,
+                            details: vec![],
+                        }),
+                    }
+                }
+                Err(err) => {
+                    error!("Error cloning product: {err:?}");
+                    CloneProductResponse {
+                        product: None,
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::Internal.into(),
+                            message: "Internal server error".to_string(),
+                            details: vec![],
+                        }),
+                    }
+                }
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send response: {e}");
+                }
+            }
+        }
+        Err(err) => {
+            warn!("Invalid clone product request format: {err:?}");
+            let response = CloneProductResponse {
+                product: None,
+                status: Some(catalog_messages::Status {
+                    code: catalog_messages::Code::InvalidArgument.into(),
+                    message: "Invalid request format".to_string(),
+                    details: vec![],
+                }),
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send error response: {e}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn get_related_products(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing get_related_products request");
+
+    let request = GetRelatedProductsRequest::decode(&*msg.payload);
+    match request {
+        Ok(request) => {
+            let kinds: Vec<RelationKind> = request.kinds().map(relation_kind_from_proto).collect();
+            let result = app_state
+                .product_service
+                .get_related_products(request.product_id, &kinds, request.locale.as_deref())
+                .await;
+
+            let response = match result {
+                Ok(Some(related)) => GetRelatedProductsResponse {
+                    related: related
+                        .into_iter()
+                        .map(|(kind, product)| RelatedProduct {
+                            kind: map_model_relation_kind(kind).into(),
+                            product: Some(map_model_product_to_proto_product(product)),
+                        })
+                        .collect(),
+                    status: Some(catalog_messages::Status {
+                        code: catalog_messages::Code::Ok.into(),
+                        message: "Related products retrieved successfully".to_string(),
+                        details: vec![],
+                    }),
+                },
+                Ok(None) => GetRelatedProductsResponse {
+                    status: Some(catalog_messages::Status {
+                        code: catalog_messages::Code::NotFound.into(),
+                        message: "Product not found".to_string(),
+                        details: vec![],
+                    }),
+                    ..Default::default()
+                },
+                Err(err) => {
+                    error!("Error getting related products: {err:?}");
+                    GetRelatedProductsResponse {
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::Internal.into(),
+                            message: "Internal server error".to_string(),
+                            details: vec![],
+                        }),
+                        ..Default::default()
+                    }
+                }
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send response: {e}");
+                }
+            }
+        }
+        Err(err) => {
+            warn!("Invalid get related products request format: {err:?}");
+            let response = GetRelatedProductsResponse {
+                status: Some(catalog_messages::Status {
+                    code: catalog_messages::Code::InvalidArgument.into(),
+                    message: "Invalid request format".to_string(),
+                    details: vec![],
+                }),
+                ..Default::default()
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send error response: {e}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn compare_products(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing compare_products request");
+
+    let response = match CompareProductsRequest::decode(&*msg.payload) {
+        Ok(request) => match app_state.product_service.compare_products(request).await {
+            Ok((products, comparison)) => CompareProductsResponse {
+                status: Some(catalog_messages::Status {
+                    code: catalog_messages::Code::Ok.into(),
+                    message: "Products compared successfully".to_string(),
+                    details: vec![],
+                }),
+                ..map_comparison_to_proto(products, comparison)
+            },
+            Err(HandlerError::ValidationError(error_msg)) => {
+                warn!("Validation error comparing products: {error_msg}");
+                CompareProductsResponse {
+                    status: Some(catalog_messages::Status {
+                        code: catalog_messages::Code::InvalidArgument.into(),
+                        message: error_msg,
+                        details: vec![],
+                    }),
+                    ..Default::default()
+                }
+            }
+            Err(HandlerError::NotFound(error_msg)) => CompareProductsResponse {
+                status: Some(catalog_messages::Status {
+                    code: catalog_messages::Code::NotFound.into(),
+                    message: error_msg,
+                    details: vec![],
+                }),
+                ..Default::default()
+            },
+            Err(err) => {
+                error!("Error comparing products: {err:?}");
+                CompareProductsResponse {
+                    status: Some(catalog_messages::Status {
+                        code: catalog_messages::Code::Internal.into(),
+                        message: "Internal server error".to_string(),
+                        details: vec![],
+                    }),
+                    ..Default::default()
+                }
+            }
+        },
+        Err(err) => {
+            warn!("Invalid compare products request format: {err:?}");
+            CompareProductsResponse {
+                status: Some(catalog_messages::Status {
+                    code: catalog_messages::Code::InvalidArgument.into(),
+                    message: "Invalid request format".to_string(),
+                    details: vec![],
+                }),
+                ..Default::default()
+            }
+        }
+    };
+
+    if let Some(reply) = msg.reply {
+        if let Err(e) = client.publish(reply, response.encode_to_vec().into()).await {
+            error!("Failed to send response: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn get_bundle_quote(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing get_bundle_quote request");
+
+    let request = GetBundleQuoteRequest::decode(&*msg.payload);
+    match request {
+        Ok(request) => {
+            let result = app_state
+                .product_service
+                .quote_bundle(&client, request.product_id, request.currency)
+                .await;
+
+            let response = match result {
+                Ok(quote) => GetBundleQuoteResponse {
+                    available_quantity: quote.available_quantity,
+                    price: quote.price.map(|price| price.to_string()),
+                    pricing: map_model_bundle_pricing(quote.pricing).into(),
+                    components: quote
+                        .components
+                        .into_iter()
+                        .map(|component| BundleComponentQuote {
+                            sku: component.sku,
+                            quantity: component.quantity,
+                            available: component.available,
+                            unit_price: component.unit_price,
+                        })
+                        .collect(),
+                    status: Some(catalog_messages::Status {
+                        code: catalog_messages::Code::Ok.into(),
+                        message: "Bundle quoted successfully".to_string(),
+                        details: vec![],
+                    }),
+                },
+                Err(HandlerError::NotFound(error_msg)) => GetBundleQuoteResponse {
+                    status: Some(catalog_messages::Status {
+                        code: catalog_messages::Code::NotFound.into(),
+                        message: error_msg,
+                        details: vec![],
+                    }),
+                    ..Default::default()
+                },
+                Err(HandlerError::ValidationError(error_msg)) => {
+                    warn!("Validation error quoting bundle: {error_msg}");
+                    GetBundleQuoteResponse {
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::InvalidArgument.into(),
+                            message: error_msg,
+                            details: vec![],
+                        }),
+                        ..Default::default()
+                    }
+                }
+                Err(err) => {
+                    error!("Error quoting bundle: {err:?}");
+                    GetBundleQuoteResponse {
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::Internal.into(),
+                            message: "Internal server error".to_string(),
+                            details: vec![],
+                        }),
+                        ..Default::default()
+                    }
+                }
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send response: {e}");
+                }
+            }
+        }
+        Err(err) => {
+            warn!("Invalid get bundle quote request format: {err:?}");
+            let response = GetBundleQuoteResponse {
+                status: Some(catalog_messages::Status {
+                    code: catalog_messages::Code::InvalidArgument.into(),
+                    message: "Invalid request format".to_string(),
+                    details: vec![],
+                }),
+                ..Default::default()
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send error response: {e}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn submit_review(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing submit_review request");
+
+    let request = SubmitReviewRequest::decode(&*msg.payload);
+    match request {
+        Ok(request) => {
+            let result = app_state
+                .product_service
+                .submit_review(
+                    request.product_id,
+                    request.rating,
+                    request.title,
+                    request.body,
+                    request.author,
+                )
+                .await;
+
+            let (review, code, message) = match result {
+                Ok(Some(review)) => (
+                    Some(map_model_review_to_proto_review(review)),
+                    catalog_messages::Code::Ok,
+                    "Review submitted for moderation".to_string(),
+                ),
+                Ok(None) => (
+                    None,
+                    catalog_messages::Code::NotFound,
+                    "Product not found".to_string(),
+                ),
+                Err(HandlerError::ValidationError(error_msg)) => {
+                    warn!("Validation error submitting review: {error_msg}");
+                    (None, catalog_messages::Code::InvalidArgument, error_msg)
+                }
+                Err(err) => {
+                    error!("Error submitting review: {err:?}");
+                    (
+                        None,
+                        catalog_messages::Code::Internal,
+                        "Internal server error".to_string(),
+                    )
+                }
+            };
+
+            let response = SubmitReviewResponse {
+                review,
+                status: Some(catalog_messages::Status {
+                    code: code.into(),
+                    message,
+                    details: vec![],
+                }),
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send response: {e}");
+                }
+            }
+        }
+        Err(err) => {
+            warn!("Invalid submit review request format: {err:?}");
+            let response = SubmitReviewResponse {
+                review: None,
+                status: Some(catalog_messages::Status {
+                    code: catalog_messages::Code::InvalidArgument.into(),
+                    message: "Invalid request format".to_string(),
+                    details: vec![],
+                }),
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send error response: {e}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn list_reviews(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing list_reviews request");
+
+    let request = ListReviewsRequest::decode(&*msg.payload);
+    match request {
+        Ok(request) => {
+            let status = request
+                .status
+                .map(|_| map_proto_review_status_to_model(request.status()));
+            let result = app_state
+                .product_service
+                .list_reviews(
+                    request.product_id,
+                    status,
+                    request.limit.map(|l| l as i64),
+                    request.offset.map(|o| o as u64),
+                )
+                .await;
+
+            let response = match result {
+                Ok(reviews) => ListReviewsResponse {
+                    reviews: reviews
+                        .into_iter()
+                        .map(map_model_review_to_proto_review)
+                        .collect(),
+                    status: Some(catalog_messages::Status {
+                        code: catalog_messages::Code::Ok.into(),
+                        message: "Reviews retrieved successfully".to_string(),
+                        details: vec![],
+                    }),
+                },
+                Err(err) => {
+                    error!("Error listing reviews: {err:?}");
+                    ListReviewsResponse {
+                        reviews: vec![],
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::Internal.into(),
+                            message: "Internal server error".to_string(),
+                            details: vec![],
+                        }),
+                    }
+                }
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send response: {e}");
+                }
+            }
+        }
+        Err(err) => {
+            warn!("Invalid list reviews request format: {err:?}");
+            let response = ListReviewsResponse {
+                reviews: vec![],
+                status: Some(catalog_messages::Status {
+                    code: catalog_messages::Code::InvalidArgument.into(),
+                    message: "Invalid request format".to_string(),
+                    details: vec![],
+                }),
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send error response: {e}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn moderate_review(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing moderate_review request");
+
+    let request = ModerateReviewRequest::decode(&*msg.payload);
+    match request {
+        Ok(request) => {
+            let status = map_proto_review_status_to_model(request.status());
+            let updated_by = request.moderated_by.clone().unwrap_or_default();
+            let result = app_state
+                .product_service
+                .moderate_review(
+                    request.review_id,
+                    status,
+                    request.moderated_by,
+                    request.note,
+                )
+                .await;
+
+            let response = match result {
+                Ok(Some((review, product))) => {
+                    let reviews = match product {
+                        Some(product) => {
+                            let reviews = product.reviews.clone();
+                            publish_reviews_updated(&client, product, updated_by).await;
+                            reviews.map(map_model_reviews_to_proto_reviews)
+                        }
+                        None => None,
+                    };
+
+                    ModerateReviewResponse {
+                        review: Some(map_model_review_to_proto_review(review)),
+                        reviews,
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::Ok.into(),
+                            message: "Review moderated successfully".to_string(),
+                            details: vec![],
+                        }),
+                    }
+                }
+                Ok(None) => ModerateReviewResponse {
+                    status: Some(catalog_messages::Status {
+                        code: catalog_messages::Code::NotFound.into(),
+                        message: "Review not found".to_string(),
+                        details: vec![],
+                    }),
+                    ..Default::default()
+                },
+                Err(HandlerError::ValidationError(error_msg)) => {
+                    warn!("Validation error moderating review: {error_msg}");
+                    ModerateReviewResponse {
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::InvalidArgument.into(),
+                            message: error_msg,
+                            details: vec![],
+                        }),
+                        ..Default::default()
+                    }
+                }
+                Err(HandlerError::NotFound(error_msg)) => ModerateReviewResponse {
+                    status: Some(catalog_messages::Status {
+                        code: catalog_messages::Code::NotFound.into(),
+                        message: error_msg,
+                        details: vec![],
+                    }),
+                    ..Default::default()
+                },
+                Err(err) => {
+                    error!("Error moderating review: {err:?}");
+                    ModerateReviewResponse {
+                        status: Some(catalog_messages::Status {
+                            code: catalog_messages::Code::Internal.into(),
+                            message: "Internal server error".to_string(),
+                            details: vec![],
+                        }),
+                        ..Default::default()
+                    }
+                }
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send response: {e}");
+                }
+            }
+        }
+        Err(err) => {
+            warn!("Invalid moderate review request format: {err:?}");
+            let response = ModerateReviewResponse {
+                status: Some(catalog_messages::Status {
+                    code: catalog_messages::Code::InvalidArgument.into(),
+                    message: "Invalid request format".to_string(),
+                    details: vec![],
+                }),
+                ..Default::default()
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send error response: {e}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Let search indexes and caches know the product's review summary changed
+async fn publish_reviews_updated(client: &Client, product: Product, updated_by: String) {
+    let Some(product_id) = product.id else {
+        return;
+    };
+    let now = chrono::Utc::now();
+
+    let event = ProductUpdatedEvent {
+        product_id: product_id.clone(),
+        name: product.name,
+        product_ref: product.product_ref,
+        brand: product.brand,
+        slug: product.slug,
+        changed_fields: vec!["reviews".to_string()],
+        updated_at: Some(prost_types::Timestamp {
+            seconds: now.timestamp(),
+            nanos: now.timestamp_subsec_nanos() as i32,
+        }),
+        updated_by,
+    };
+
+    if let Err(e) = client
+        .publish(
+            crate::nats_config::events::published::PRODUCT_UPDATED,
+            event.encode_to_vec().into(),
+        )
+        .await
+    {
+        error!("Failed to publish review update for product {product_id}: {e}");
+    }
+}
+
+pub async fn upsert_attribute_schema(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing upsert_attribute_schema request");
+
+    let request = UpsertAttributeSchemaRequest::decode(&*msg.payload);
+    match request {
+        Ok(request) => {
+            let result = match request.schema {
+                Some(schema) => {
+                    app_state
+                        .product_service
+                        .upsert_attribute_schema(map_proto_schema_to_model_schema(
+                            schema,
+                            request.updated_by,
+                        ))
+                        .await
+                }
+                None => Err(HandlerError::ValidationError(
+                    "schema is required".to_string(),
+                )),
+            };
+
+            let (schema, code, message) = match result {
+                Ok(schema) => (
+                    Some(map_model_schema_to_proto_schema(schema)),
+                    catalog_messages::Code::Ok,
+                    "Attribute schema saved successfully".to_string(),
+                ),
+                Err(HandlerError::ValidationError(error_msg)) => {
+                    warn!("Validation error saving attribute schema: {error_msg}");
+                    (None, catalog_messages::Code::InvalidArgument, error_msg)
+                }
+                Err(HandlerError::AlreadyExists(error_msg))
+                | Err(HandlerError::NotFound(error_msg))
+                | Err(HandlerError::InternalError(error_msg)) => {
+                    error!("Error saving attribute schema: {error_msg}");
+                    (
+                        None,
+                        catalog_messages::Code::Internal,
+                        "Internal server error".to_string(),
+                    )
+                }
+            };
+
+            let response = UpsertAttributeSchemaResponse {
+                schema,
+                status: Some(catalog_messages::Status {
+                    code: code.into(),
+                    message,
+                    details: vec![],
+                }),
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send response: {e}");
+                }
+            }
+        }
+        Err(err) => {
+            warn!("Invalid upsert attribute schema request format: {err:?}");
+            let response = UpsertAttributeSchemaResponse {
+                schema: None,
+                status: Some(catalog_messages::Status {
+                    code: catalog_messages::Code::InvalidArgument.into(),
+                    message: "Invalid request format".to_string(),
+                    details: vec![],
+                }),
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send error response: {e}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn get_attribute_schema(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing get_attribute_schema request");
+
+    let request = GetAttributeSchemaRequest::decode(&*msg.payload);
+    match request {
+        Ok(request) => {
+            let result = app_state
+                .product_service
+                .get_attribute_schema(request.product_type)
+                .await;
+
+            let (schema, code, message) = match result {
+                Ok(Some(schema)) => (
+                    Some(map_model_schema_to_proto_schema(schema)),
+                    catalog_messages::Code::Ok,
+                    "Attribute schema retrieved successfully".to_string(),
+                ),
+                Ok(None) => (
+                    None,
+                    catalog_messages::Code::NotFound,
+                    "Attribute schema not found".to_string(),
+                ),
+                Err(err) => {
+                    error!("Error getting attribute schema: {err:?}");
+                    (
+                        None,
+                        catalog_messages::Code::Internal,
+                        "Internal server error".to_string(),
+                    )
+                }
+            };
+
+            let response = GetAttributeSchemaResponse {
+                schema,
+                status: Some(catalog_messages::Status {
+                    code: code.into(),
+                    message,
+                    details: vec![],
+                }),
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send response: {e}");
+                }
+            }
+        }
+        Err(err) => {
+            warn!("Invalid get attribute schema request format: {err:?}");
+            let response = GetAttributeSchemaResponse {
+                schema: None,
+                status: Some(catalog_messages::Status {
+                    code: catalog_messages::Code::InvalidArgument.into(),
+                    message: "Invalid request format".to_string(),
+                    details: vec![],
+                }),
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send error response: {e}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn list_attribute_schemas(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing list_attribute_schemas request");
+
+    let request = ListAttributeSchemasRequest::decode(&*msg.payload);
+    match request {
+        Ok(_) => {
+            let (schemas, code, message) =
+                match app_state.product_service.list_attribute_schemas().await {
+                    Ok(schemas) => (
+                        schemas
+                            .into_iter()
+                            .map(map_model_schema_to_proto_schema)
+                            .collect(),
+                        catalog_messages::Code::Ok,
+                        "Attribute schemas retrieved successfully".to_string(),
+                    ),
+                    Err(err) => {
+                        error!("Error listing attribute schemas: {err:?}");
+                        (
+                            vec![],
+                            catalog_messages::Code::Internal,
+                            "Internal server error".to_string(),
+                        )
+                    }
+                };
+
+            let response = ListAttributeSchemasResponse {
+                schemas,
+                status: Some(catalog_messages::Status {
+                    code: code.into(),
+                    message,
+                    details: vec![],
+                }),
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send response: {e}");
+                }
+            }
+        }
+        Err(err) => {
+            warn!("Invalid list attribute schemas request format: {err:?}");
+            let response = ListAttributeSchemasResponse {
+                schemas: vec![],
+                status: Some(catalog_messages::Status {
+                    code: catalog_messages::Code::InvalidArgument.into(),
+                    message: "Invalid request format".to_string(),
+                    details: vec![],
+                }),
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send error response: {e}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn delete_attribute_schema(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing delete_attribute_schema request");
+
+    let request = DeleteAttributeSchemaRequest::decode(&*msg.payload);
+    match request {
+        Ok(request) => {
+            let result = app_state
+                .product_service
+                .delete_attribute_schema(request.product_type)
+                .await;
+
+            let (code, message) = match result {
+                Ok(true) => (
+                    catalog_messages::Code::Ok,
+                    "Attribute schema deleted successfully".to_string(),
+                ),
+                Ok(false) => (
+                    catalog_messages::Code::NotFound,
+                    "Attribute schema not found".to_string(),
+                ),
+                Err(err) => {
+                    error!("Error deleting attribute schema: {err:?}");
+                    (
+                        catalog_messages::Code::Internal,
+                        "Internal server error".to_string(),
+                    )
+                }
+            };
+
+            let response = DeleteAttributeSchemaResponse {
+                status: Some(catalog_messages::Status {
+                    code: code.into(),
+                    message,
+                    details: vec![],
+                }),
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send response: {e}");
+                }
+            }
+        }
+        Err(err) => {
+            warn!("Invalid delete attribute schema request format: {err:?}");
+            let response = DeleteAttributeSchemaResponse {
+                status: Some(catalog_messages::Status {
+                    code: catalog_messages::Code::InvalidArgument.into(),
+                    message: "Invalid request format".to_string(),
+                    details: vec![],
+                }),
+            };
+
+            let response_bytes = response.encode_to_vec();
+
+            if let Some(reply) = msg.reply {
+                if let Err(e) = client.publish(reply, response_bytes.into()).await {
+                    error!("Failed to send error response: {e}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Mapping functions to convert between domain models and protobuf messages
+fn map_proto_schema_to_model_schema(
+    schema: catalog_messages::AttributeSchema,
+    updated_by: Option<String>,
+) -> AttributeSchema {
+    let attributes = schema
+        .attributes
+        .into_iter()
+        .map(|definition| AttributeDefinition {
+            scope: match definition.scope() {
+                catalog_messages::AttributeScope::Defining => AttributeScope::Defining,
+                catalog_messages::AttributeScope::Descriptive => AttributeScope::Descriptive,
+            },
+            data_type: match definition.data_type() {
+                catalog_messages::AttributeDataType::String => AttributeDataType::String,
+                catalog_messages::AttributeDataType::Integer => AttributeDataType::Integer,
+                catalog_messages::AttributeDataType::Decimal => AttributeDataType::Decimal,
+                catalog_messages::AttributeDataType::Boolean => AttributeDataType::Boolean,
+                catalog_messages::AttributeDataType::Enum => AttributeDataType::Enum,
+            },
+            key: definition.key,
+            allowed_values: definition.allowed_values,
+            required: definition.required,
+        })
+        .collect();
+
+    AttributeSchema::new(schema.product_type, attributes, updated_by)
+}
+
+fn map_model_schema_to_proto_schema(schema: AttributeSchema) -> catalog_messages::AttributeSchema {
+    catalog_messages::AttributeSchema {
+        product_type: schema.product_type,
+        attributes: schema
+            .attributes
+            .into_iter()
+            .map(|definition| catalog_messages::AttributeDefinition {
+                key: definition.key,
+                scope: match definition.scope {
+                    AttributeScope::Defining => catalog_messages::AttributeScope::Defining,
+                    AttributeScope::Descriptive => catalog_messages::AttributeScope::Descriptive,
+                }
+                .into(),
+                data_type: match definition.data_type {
+                    AttributeDataType::String => catalog_messages::AttributeDataType::String,
+                    AttributeDataType::Integer => catalog_messages::AttributeDataType::Integer,
+                    AttributeDataType::Decimal => catalog_messages::AttributeDataType::Decimal,
+                    AttributeDataType::Boolean => catalog_messages::AttributeDataType::Boolean,
+                    AttributeDataType::Enum => catalog_messages::AttributeDataType::Enum,
+                }
+                .into(),
+                allowed_values: definition.allowed_values,
+                required: definition.required,
+            })
+            .collect(),
+        updated_by: schema.updated_by,
+        updated_at: Some(prost_types::Timestamp {
+            seconds: schema.updated_at.timestamp(),
+            nanos: schema.updated_at.timestamp_subsec_nanos() as i32,
+        }),
+    }
+}
+
+fn map_proto_review_status_to_model(status: catalog_messages::ReviewStatus) -> ReviewStatus {
+    match status {
+        catalog_messages::ReviewStatus::Pending => ReviewStatus::Pending,
+        catalog_messages::ReviewStatus::Approved => ReviewStatus::Approved,
+        catalog_messages::ReviewStatus::Rejected => ReviewStatus::Rejected,
+    }
+}
+
+fn map_model_review_to_proto_review(review: Review) -> catalog_messages::Review {
+    catalog_messages::Review {
+        id: review.id,
+        product_id: review.product_id,
+        rating: review.rating,
+        title: review.title,
+        body: review.body,
+        author: review.author,
+        status: match review.status {
+            ReviewStatus::Pending => catalog_messages::ReviewStatus::Pending,
+            ReviewStatus::Approved => catalog_messages::ReviewStatus::Approved,
+            ReviewStatus::Rejected => catalog_messages::ReviewStatus::Rejected,
+        }
+        .into(),
+        moderated_by: review.moderated_by,
+        moderation_note: review.moderation_note,
+        created_at: Some(prost_types::Timestamp {
+            seconds: review.created_at.timestamp(),
+            nanos: review.created_at.timestamp_subsec_nanos() as i32,
+        }),
+        moderated_at: review.moderated_at.map(|dt| prost_types::Timestamp {
+            seconds: dt.timestamp(),
+            nanos: dt.timestamp_subsec_nanos() as i32,
+        }),
+    }
+}
+
+fn map_model_reviews_to_proto_reviews(reviews: Reviews) -> catalog_messages::Reviews {
+    catalog_messages::Reviews {
+        bayesian_avg: reviews.bayesian_avg.into(),
+        count: reviews.count,
+        rating: reviews.rating,
+    }
+}
+
+fn map_model_revision_to_proto_revision(
+    revision: ProductRevision,
+) -> catalog_messages::ProductRevision {
+    catalog_messages::ProductRevision {
+        revision: revision.revision,
+        snapshot: Some(map_model_product_to_proto_product(revision.snapshot)),
+        changed_by: revision.changed_by,
+        reverted_from: revision.reverted_from,
+        created_at: Some(prost_types::Timestamp {
+            seconds: revision.created_at.timestamp(),
+            nanos: revision.created_at.timestamp_subsec_nanos() as i32,
+        }),
+    }
+}
+
+fn map_model_product_to_proto_product(product: Product) -> catalog_messages::Product {
+    catalog_messages::Product {
+        id: product.id,
+        name: product.name,
+        long_description: product.long_description,
+        brand: product.brand,
+        slug: product.slug,
+        product_ref: product.product_ref,
+        product_type: product.product_type,
+        seo_title: product.seo_title,
+        seo_description: product.seo_description,
+        seo_keywords: product.seo_keywords,
+        display_on_site: product.display_on_site,
+        tax_code: product.tax_code,
+        related_products: product.related_products,
+        reviews: product.reviews.map(map_model_reviews_to_proto_reviews),
+        hierarchical_categories: product.hierarchical_categories.map(|hc| {
+            catalog_messages::HierarchicalCategories {
+                lvl0: hc.lvl0,
+                lvl1: hc.lvl1,
+                lvl2: hc.lvl2,
+                levels: hc
+                    .levels
+                    .into_iter()
+                    .map(|paths| catalog_messages::HierarchicalCategoryLevel { paths })
+                    .collect(),
+            }
+        }),
+        list_categories: product.list_categories,
+        created_at: product.created_at.map(|dt| prost_types::Timestamp {
+            seconds: dt.timestamp(),
+            nanos: dt.timestamp_subsec_nanos() as i32,
+        }),
+        updated_at: product.updated_at.map(|dt| prost_types::Timestamp {
+            seconds: dt.timestamp(),
+            nanos: dt.timestamp_subsec_nanos() as i32,
+        }),
+        created_by: product.created_by,
+        updated_by: product.updated_by,
+        defining_attributes: product.defining_attributes,
+        descriptive_attributes: product.descriptive_attributes,
+        default_variant: product.default_variant,
+        variants: product
+            .variants
+            .into_iter()
+            .map(|v| catalog_messages::ProductVariant {
+                sku: v.sku,
+                defining_attributes: v.defining_attributes.unwrap_or_default(),
+                abbreviated_color: v.abbreviated_color,
+                abbreviated_size: v.abbreviated_size,
+                height: v.height,
+                width: v.width,
+                length: v.length,
+                dimension_unit: v.dimension_unit.map(String::from),
+                weight: v.weight,
+                weight_unit: v.weight_unit.map(String::from),
+                packaging: v.packaging.map(|p| catalog_messages::Packaging {
+                    dimensional_weight: p
+                        .dimensional_weight(p.weight_unit.unwrap_or(MassUnit::Kilogram)),
+                    height: p.height,
+                    width: p.width,
+                    length: p.length,
+                    dimension_unit: p.dimension_unit.map(String::from),
+                    weight: p.weight,
+                    weight_unit: p.weight_unit.map(String::from),
+                }),
+                image_urls: v.image_urls,
+            })
+            .collect(),
+        publish_at: product.publish_at.map(|dt| prost_types::Timestamp {
+            seconds: dt.timestamp(),
+            nanos: dt.timestamp_subsec_nanos() as i32,
+        }),
+        unpublish_at: product.unpublish_at.map(|dt| prost_types::Timestamp {
+            seconds: dt.timestamp(),
+            nanos: dt.timestamp_subsec_nanos() as i32,
+        }),
+        localizations: product
+            .localizations
+            .into_iter()
+            .map(|(locale, content)| {
+                (
+                    locale,
+                    catalog_messages::LocalizedProductContent {
+                        name: content.name,
+                        long_description: content.long_description,
+                        seo_title: content.seo_title,
+                        seo_description: content.seo_description,
+                        seo_keywords: content.seo_keywords,
+                        slug: content.slug,
+                    },
+                )
+            })
+            .collect(),
+        stores: product
+            .stores
+            .into_iter()
+            .map(|(store_id, settings)| {
+                (
+                    store_id,
+                    catalog_messages::ProductStoreSettings {
+                        display_on_site: settings.display_on_site,
+                        slug: settings.slug,
+                    },
+                )
+            })
+            .collect(),
+        bundle: product.bundle.map(map_model_bundle_to_proto_bundle),
+        relations: product
+            .relations
+            .into_iter()
+            .map(|relation| catalog_messages::ProductRelation {
+                product_id: relation.product_id,
+                kind: map_model_relation_kind(relation.kind).into(),
+                bidirectional: relation.bidirectional,
+            })
+            .collect(),
+    }
+}
+
+fn map_model_relation_kind(kind: RelationKind) -> catalog_messages::RelationKind {
+    match kind {
+        RelationKind::Related => catalog_messages::RelationKind::Related,
+        RelationKind::Accessory => catalog_messages::RelationKind::Accessory,
+        RelationKind::Upsell => catalog_messages::RelationKind::Upsell,
+        RelationKind::Replacement => catalog_messages::RelationKind::Replacement,
+    }
+}
+
+fn map_comparison_to_proto(
+    products: Vec<Product>,
+    comparison: ProductComparison,
+) -> CompareProductsResponse {
+    CompareProductsResponse {
+        products: products
+            .into_iter()
+            .zip(comparison.variants)
+            .map(|(product, variants)| catalog_messages::ComparedProduct {
+                id: product.id.unwrap_or_default(),
+                name: product.name,
+                slug: product.slug,
+                brand: product.brand,
+                variants: variants
+                    .into_iter()
+                    .map(|v| catalog_messages::ComparedVariant {
+                        sku: v.sku,
+                        height: v.height,
+                        width: v.width,
+                        length: v.length,
+                        weight: v.weight,
+                        package_height: v.package_height,
+                        package_width: v.package_width,
+                        package_length: v.package_length,
+                        package_weight: v.package_weight,
+                        dimensional_weight: v.dimensional_weight,
+                    })
+                    .collect(),
+            })
+            .collect(),
+        rows: comparison
+            .rows
+            .into_iter()
+            .map(|row| catalog_messages::ComparisonRow {
+                key: row.key,
+                kind: match row.kind {
+                    ComparisonRowKind::DefiningAttribute => {
+                        catalog_messages::ComparisonRowKind::DefiningAttribute
+                    }
+                    ComparisonRowKind::DescriptiveAttribute => {
+                        catalog_messages::ComparisonRowKind::DescriptiveAttribute
+                    }
+                    ComparisonRowKind::Measurement => {
+                        catalog_messages::ComparisonRowKind::Measurement
+                    }
+                }
+                .into(),
+                values: row
+                    .values
+                    .into_iter()
+                    .map(Option::unwrap_or_default)
+                    .collect(),
+                differs: row.differs,
+            })
+            .collect(),
+        length_unit: comparison.length_unit.code().to_string(),
+        weight_unit: comparison.mass_unit.code().to_string(),
+        status: None,
+    }
+}
+
+fn map_model_bundle_to_proto_bundle(bundle: ProductBundle) -> catalog_messages::ProductBundle {
+    catalog_messages::ProductBundle {
+        components: bundle
+            .components
+            .into_iter()
+            .map(|component| catalog_messages::BundleComponent {
+                sku: component.sku,
+                quantity: component.quantity,
+            })
+            .collect(),
+        pricing: map_model_bundle_pricing(bundle.pricing).into(),
+    }
+}
+
+fn map_model_bundle_pricing(pricing: BundlePricing) -> catalog_messages::BundlePricing {
+    match pricing {
+        BundlePricing::OwnOffer => catalog_messages::BundlePricing::OwnOffer,
+        BundlePricing::ComponentSum => catalog_messages::BundlePricing::ComponentSum,
     }
 }
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use async_nats::{Client, Message};
+use log::{debug, error, warn};
+use prost::Message as ProstMessage;
+
+use crate::{
+    catalog_messages::{self, GenerateSitemapRequest, GenerateSitemapResponse, SitemapFileInfo},
+    services::{product_service::HandlerError, sitemap_service::SitemapOptions},
+    AppState,
+};
+
+pub async fn generate_sitemap(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing generate_sitemap request");
+
+    let response = match GenerateSitemapRequest::decode(&*msg.payload) {
+        Ok(request) => render_sitemap(&app_state, request).await,
+        Err(err) => {
+            warn!("Invalid generate sitemap request format: {err:?}");
+            sitemap_error(
+                catalog_messages::Code::InvalidArgument,
+                "Invalid request format".to_string(),
+            )
+        }
+    };
+
+    let response_bytes = response.encode_to_vec();
+
+    if let Some(reply) = msg.reply {
+        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+            error!("Failed to send response: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+async fn render_sitemap(
+    app_state: &AppState,
+    request: GenerateSitemapRequest,
+) -> GenerateSitemapResponse {
+    let options = match SitemapOptions::new(
+        request.base_url,
+        request.locale_base_urls,
+        request.urls_per_file,
+        request.product_path,
+        request.category_path,
+    ) {
+        Ok(options) => options,
+        Err(e) => return handler_error_response(e),
+    };
+
+    let service = &app_state.sitemap_service;
+    let result = match request.file {
+        None => service
+            .render_index(&options)
+            .await
+            .map(|(index, files)| Some((index, files))),
+        Some(name) => service
+            .render_file(&options, &name)
+            .await
+            .map(|file| file.map(|file| (file, vec![]))),
+    };
+
+    match result {
+        Ok(Some((rendered, files))) => GenerateSitemapResponse {
+            name: rendered.name,
+            content: rendered.content,
+            files: files
+                .into_iter()
+                .map(|file| SitemapFileInfo {
+                    name: file.name,
+                    url_count: file.url_count as i32,
+                })
+                .collect(),
+            status: Some(catalog_messages::Status {
+                code: catalog_messages::Code::Ok.into(),
+                message: "Sitemap generated successfully".to_string(),
+                details: vec![],
+            }),
+        },
+        Ok(None) => sitemap_error(
+            catalog_messages::Code::NotFound,
+            "Sitemap file not found".to_string(),
+        ),
+        Err(e) => handler_error_response(e),
+    }
+}
+
+fn handler_error_response(e: HandlerError) -> GenerateSitemapResponse {
+    match e {
+        HandlerError::ValidationError(message) => {
+            warn!("Invalid sitemap request: {message}");
+            sitemap_error(catalog_messages::Code::InvalidArgument, message)
+        }
+        HandlerError::NotFound(message) => sitemap_error(catalog_messages::Code::NotFound, message),
+        HandlerError::AlreadyExists(message) => {
+            sitemap_error(catalog_messages::Code::AlreadyExists, message)
+        }
+        HandlerError::InternalError(message) => {
+            error!("Error generating sitemap: {message}");
+            sitemap_error(
+                catalog_messages::Code::Internal,
+                "Internal server error".to_string(),
+            )
+        }
+    }
+}
+
+fn sitemap_error(code: catalog_messages::Code, message: String) -> GenerateSitemapResponse {
+    GenerateSitemapResponse {
+        name: String::new(),
+        content: vec![],
+        files: vec![],
+        status: Some(catalog_messages::Status {
+            code: code.into(),
+            message,
+            details: vec![],
+        }),
+    }
+}
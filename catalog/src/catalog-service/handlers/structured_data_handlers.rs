@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use async_nats::{Client, Message};
+use log::{debug, error, warn};
+use prost::Message as ProstMessage;
+
+use crate::{
+    catalog_messages::{
+        self, Breadcrumb, GetBreadcrumbsRequest, GetBreadcrumbsResponse,
+        GetProductStructuredDataRequest, GetProductStructuredDataResponse,
+    },
+    services::{product_service::HandlerError, structured_data_service::StructuredDataOptions},
+    AppState,
+};
+
+pub async fn get_product_structured_data(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing get_product_structured_data request");
+
+    let mut response = GetProductStructuredDataResponse::default();
+    let status = match GetProductStructuredDataRequest::decode(&*msg.payload) {
+        Ok(request) => {
+            let result = match StructuredDataOptions::new(
+                request.base_url,
+                request.product_path,
+                None,
+                request.locale,
+            ) {
+                Ok(options) => {
+                    app_state
+                        .structured_data_service
+                        .product_json_ld(&client, &request.product_id, &request.currency, &options)
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(data) => {
+                    response.json_ld = data.json_ld;
+                    response.unpriced_skus = data.unpriced_skus;
+                    ok_status("Structured data generated successfully")
+                }
+                Err(e) => error_status(e),
+            }
+        }
+        Err(err) => {
+            warn!("Invalid product structured data request format: {err:?}");
+            status(
+                catalog_messages::Code::InvalidArgument,
+                "Invalid request format".to_string(),
+            )
+        }
+    };
+    response.status = Some(status);
+
+    let response_bytes = response.encode_to_vec();
+
+    if let Some(reply) = msg.reply {
+        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+            error!("Failed to send response: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn get_breadcrumbs(
+    app_state: Arc<AppState>,
+    client: Client,
+    msg: Message,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    debug!("Processing get_breadcrumbs request");
+
+    let mut response = GetBreadcrumbsResponse::default();
+    let status = match GetBreadcrumbsRequest::decode(&*msg.payload) {
+        Ok(request) => {
+            let result = match StructuredDataOptions::new(
+                request.base_url,
+                request.product_path,
+                request.category_path,
+                request.locale,
+            ) {
+                Ok(options) => {
+                    let service = &app_state.structured_data_service;
+                    match (request.category_id, request.product_id) {
+                        (Some(category_id), None) => {
+                            service.category_breadcrumbs(&category_id, &options).await
+                        }
+                        (None, Some(product_id)) => {
+                            service.product_breadcrumbs(&product_id, &options).await
+                        }
+                        _ => Err(HandlerError::ValidationError(
+                            "Exactly one of category_id and product_id is required".to_string(),
+                        )),
+                    }
+                }
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(breadcrumbs) => {
+                    response.breadcrumbs = breadcrumbs
+                        .items
+                        .into_iter()
+                        .map(|item| Breadcrumb {
+                            name: item.name,
+                            url: item.url,
+                        })
+                        .collect();
+                    response.json_ld = breadcrumbs.json_ld;
+                    ok_status("Breadcrumbs generated successfully")
+                }
+                Err(e) => error_status(e),
+            }
+        }
+        Err(err) => {
+            warn!("Invalid breadcrumbs request format: {err:?}");
+            status(
+                catalog_messages::Code::InvalidArgument,
+                "Invalid request format".to_string(),
+            )
+        }
+    };
+    response.status = Some(status);
+
+    let response_bytes = response.encode_to_vec();
+
+    if let Some(reply) = msg.reply {
+        if let Err(e) = client.publish(reply, response_bytes.into()).await {
+            error!("Failed to send response: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+fn ok_status(message: &str) -> catalog_messages::Status {
+    status(catalog_messages::Code::Ok, message.to_string())
+}
+
+fn error_status(e: HandlerError) -> catalog_messages::Status {
+    match e {
+        HandlerError::ValidationError(message) => {
+            warn!("Invalid structured data request: {message}");
+            status(catalog_messages::Code::InvalidArgument, message)
+        }
+        HandlerError::NotFound(message) => status(catalog_messages::Code::NotFound, message),
+        HandlerError::AlreadyExists(message) => {
+            status(catalog_messages::Code::AlreadyExists, message)
+        }
+        HandlerError::InternalError(message) => {
+            error!("Error generating structured data: {message}");
+            status(
+                catalog_messages::Code::Internal,
+                "Internal server error".to_string(),
+            )
+        }
+    }
+}
+
+fn status(code: catalog_messages::Code, message: String) -> catalog_messages::Status {
+    catalog_messages::Status {
+        code: code.into(),
+        message,
+        details: vec![],
+    }
+}
@@ -0,0 +1,78 @@
+use crate::domain::AttributeSchema;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use mongodb::{bson::doc, Collection};
+use std::error::Error;
+
+#[async_trait]
+pub trait AttributeSchemaDao {
+    /// Create or replace the schema for its product_type
+    async fn upsert_schema(
+        &self,
+        schema: AttributeSchema,
+    ) -> Result<AttributeSchema, Box<dyn Error + Send + Sync>>;
+    async fn get_schema(
+        &self,
+        product_type: &str,
+    ) -> Result<Option<AttributeSchema>, Box<dyn Error + Send + Sync>>;
+    async fn list_schemas(&self) -> Result<Vec<AttributeSchema>, Box<dyn Error + Send + Sync>>;
+    async fn delete_schema(&self, product_type: &str)
+        -> Result<bool, Box<dyn Error + Send + Sync>>;
+}
+
+pub struct AttributeSchemaDaoImpl {
+    collection: Collection<AttributeSchema>,
+}
+
+impl AttributeSchemaDaoImpl {
+    pub fn new(collection: Collection<AttributeSchema>) -> Self {
+        Self { collection }
+    }
+}
+
+#[async_trait]
+impl AttributeSchemaDao for AttributeSchemaDaoImpl {
+    async fn upsert_schema(
+        &self,
+        schema: AttributeSchema,
+    ) -> Result<AttributeSchema, Box<dyn Error + Send + Sync>> {
+        self.collection
+            .replace_one(doc! { "_id": &schema.product_type }, &schema)
+            .upsert(true)
+            .await?;
+        Ok(schema)
+    }
+
+    async fn get_schema(
+        &self,
+        product_type: &str,
+    ) -> Result<Option<AttributeSchema>, Box<dyn Error + Send + Sync>> {
+        let schema = self
+            .collection
+            .find_one(doc! { "_id": product_type })
+            .await?;
+        Ok(schema)
+    }
+
+    async fn list_schemas(&self) -> Result<Vec<AttributeSchema>, Box<dyn Error + Send + Sync>> {
+        let schemas: Vec<AttributeSchema> = self
+            .collection
+            .find(doc! {})
+            .sort(doc! { "_id": 1 })
+            .await?
+            .try_collect()
+            .await?;
+        Ok(schemas)
+    }
+
+    async fn delete_schema(
+        &self,
+        product_type: &str,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let result = self
+            .collection
+            .delete_one(doc! { "_id": product_type })
+            .await?;
+        Ok(result.deleted_count > 0)
+    }
+}
@@ -0,0 +1,398 @@
+use crate::domain::{
+    staged_change_id, CatalogChange, CatalogEntityType, CatalogSnapshotEntry, CatalogVersion,
+    CatalogVersionStatus, Category, Product, StagedChange, PUBLISH_LEASE,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::{
+    bson::{self, doc},
+    options::ReplaceOptions,
+    Collection,
+};
+use std::error::Error;
+
+/// Snapshot entries written per insert
+const SNAPSHOT_BATCH_SIZE: usize = 500;
+
+#[async_trait]
+pub trait CatalogVersionDao {
+    // Staging workspace
+    /// Insert the staged change, replacing any staged earlier for the same entity
+    async fn stage_change(
+        &self,
+        change: StagedChange,
+    ) -> Result<StagedChange, Box<dyn Error + Send + Sync>>;
+    async fn get_staged_change(
+        &self,
+        id: &str,
+    ) -> Result<Option<StagedChange>, Box<dyn Error + Send + Sync>>;
+    /// Staged changes oldest first, across every change set when `change_set` is None
+    async fn list_staged_changes(
+        &self,
+        change_set: Option<&str>,
+    ) -> Result<Vec<StagedChange>, Box<dyn Error + Send + Sync>>;
+    async fn delete_staged_changes(
+        &self,
+        ids: &[String],
+    ) -> Result<u64, Box<dyn Error + Send + Sync>>;
+
+    // Published versions
+    /// Highest version number handed out, or 0 before the first publish
+    async fn latest_version_number(&self) -> Result<i64, Box<dyn Error + Send + Sync>>;
+    /// Whether a publish holding its lease is under way
+    async fn publish_in_progress(&self) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    /// Versions still publishing past their lease
+    async fn list_abandoned_publishes(
+        &self,
+    ) -> Result<Vec<CatalogVersion>, Box<dyn Error + Send + Sync>>;
+    /// Fails with a duplicate key error when the version number is taken
+    async fn create_version(
+        &self,
+        version: CatalogVersion,
+    ) -> Result<CatalogVersion, Box<dyn Error + Send + Sync>>;
+    /// Mark the version published. Returns false when it is no longer
+    /// publishing because its lease ran out.
+    async fn mark_published(
+        &self,
+        version: i64,
+        published_at: DateTime<Utc>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    async fn finish_version(
+        &self,
+        version: i64,
+        status: CatalogVersionStatus,
+        error: Option<String>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn get_version(
+        &self,
+        version: i64,
+    ) -> Result<Option<CatalogVersion>, Box<dyn Error + Send + Sync>>;
+    /// Versions newest first, failed ones included
+    async fn list_versions(
+        &self,
+        limit: Option<i64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<CatalogVersion>, Box<dyn Error + Send + Sync>>;
+
+    // Documents a version writes, outside the DAOs' follow-up of derived data
+    /// Replace the product, creating it when it does not exist
+    async fn write_product(&self, product: &Product) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn write_category(&self, category: &Category)
+        -> Result<(), Box<dyn Error + Send + Sync>>;
+
+    // Images of what each version changed
+    /// Record the version's changes, in batches
+    async fn save_snapshot(
+        &self,
+        version: i64,
+        changes: &[CatalogChange],
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn list_snapshot(
+        &self,
+        version: i64,
+    ) -> Result<Vec<CatalogChange>, Box<dyn Error + Send + Sync>>;
+    async fn delete_snapshot(&self, version: i64) -> Result<u64, Box<dyn Error + Send + Sync>>;
+    /// Drop the images of every version up to and including `version`
+    async fn prune_snapshots(&self, version: i64) -> Result<u64, Box<dyn Error + Send + Sync>>;
+    /// The entity's change in the earliest version after `version` to change it
+    async fn first_change_after(
+        &self,
+        version: i64,
+        entity_type: CatalogEntityType,
+        entity_id: &str,
+    ) -> Result<Option<CatalogChange>, Box<dyn Error + Send + Sync>>;
+    /// For each entity changed after `version`, its change in the earliest
+    /// version to change it
+    async fn first_changes_after(
+        &self,
+        version: i64,
+    ) -> Result<Vec<CatalogChange>, Box<dyn Error + Send + Sync>>;
+}
+
+pub struct CatalogVersionDaoImpl {
+    staged_collection: Collection<StagedChange>,
+    version_collection: Collection<CatalogVersion>,
+    snapshot_collection: Collection<CatalogSnapshotEntry>,
+    product_collection: Collection<Product>,
+    category_collection: Collection<Category>,
+}
+
+impl CatalogVersionDaoImpl {
+    pub fn new(
+        staged_collection: Collection<StagedChange>,
+        version_collection: Collection<CatalogVersion>,
+        snapshot_collection: Collection<CatalogSnapshotEntry>,
+        product_collection: Collection<Product>,
+        category_collection: Collection<Category>,
+    ) -> Self {
+        Self {
+            staged_collection,
+            version_collection,
+            snapshot_collection,
+            product_collection,
+            category_collection,
+        }
+    }
+}
+
+#[async_trait]
+impl CatalogVersionDao for CatalogVersionDaoImpl {
+    async fn stage_change(
+        &self,
+        change: StagedChange,
+    ) -> Result<StagedChange, Box<dyn Error + Send + Sync>> {
+        self.staged_collection
+            .replace_one(doc! { "_id": &change.id }, &change)
+            .with_options(ReplaceOptions::builder().upsert(true).build())
+            .await?;
+        Ok(change)
+    }
+
+    async fn get_staged_change(
+        &self,
+        id: &str,
+    ) -> Result<Option<StagedChange>, Box<dyn Error + Send + Sync>> {
+        let change = self.staged_collection.find_one(doc! { "_id": id }).await?;
+        Ok(change)
+    }
+
+    async fn list_staged_changes(
+        &self,
+        change_set: Option<&str>,
+    ) -> Result<Vec<StagedChange>, Box<dyn Error + Send + Sync>> {
+        let filter = match change_set {
+            Some(change_set) => doc! { "change_set": change_set },
+            None => doc! {},
+        };
+        let changes: Vec<StagedChange> = self
+            .staged_collection
+            .find(filter)
+            .sort(doc! { "staged_at": 1 })
+            .await?
+            .try_collect()
+            .await?;
+        Ok(changes)
+    }
+
+    async fn delete_staged_changes(
+        &self,
+        ids: &[String],
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let result = self
+            .staged_collection
+            .delete_many(doc! { "_id": { "$in": ids } })
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    async fn latest_version_number(&self) -> Result<i64, Box<dyn Error + Send + Sync>> {
+        let latest = self
+            .version_collection
+            .find_one(doc! {})
+            .sort(doc! { "version": -1 })
+            .await?;
+        Ok(latest.map(|v| v.version).unwrap_or(0))
+    }
+
+    async fn publish_in_progress(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let lease_start = bson::DateTime::from_chrono(Utc::now() - PUBLISH_LEASE);
+        let count = self
+            .version_collection
+            .count_documents(doc! { "status": "publishing", "started_at": { "$gt": lease_start } })
+            .await?;
+        Ok(count > 0)
+    }
+
+    async fn list_abandoned_publishes(
+        &self,
+    ) -> Result<Vec<CatalogVersion>, Box<dyn Error + Send + Sync>> {
+        let lease_start = bson::DateTime::from_chrono(Utc::now() - PUBLISH_LEASE);
+        let versions: Vec<CatalogVersion> = self
+            .version_collection
+            .find(doc! { "status": "publishing", "started_at": { "$lte": lease_start } })
+            .await?
+            .try_collect()
+            .await?;
+        Ok(versions)
+    }
+
+    async fn create_version(
+        &self,
+        version: CatalogVersion,
+    ) -> Result<CatalogVersion, Box<dyn Error + Send + Sync>> {
+        self.version_collection.insert_one(&version).await?;
+        Ok(version)
+    }
+
+    async fn mark_published(
+        &self,
+        version: i64,
+        published_at: DateTime<Utc>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let result = self
+            .version_collection
+            .update_one(
+                doc! { "version": version, "status": "publishing" },
+                doc! { "$set": {
+                    "status": bson::to_bson(&CatalogVersionStatus::Published)?,
+                    "published_at": bson::DateTime::from_chrono(published_at),
+                } },
+            )
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+
+    async fn finish_version(
+        &self,
+        version: i64,
+        status: CatalogVersionStatus,
+        error: Option<String>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let now = bson::DateTime::from_chrono(Utc::now());
+        let published_at = (status == CatalogVersionStatus::Published).then_some(now);
+        self.version_collection
+            .update_one(
+                doc! { "version": version },
+                doc! { "$set": {
+                    "status": bson::to_bson(&status)?,
+                    "published_at": published_at,
+                    "error": error,
+                } },
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_version(
+        &self,
+        version: i64,
+    ) -> Result<Option<CatalogVersion>, Box<dyn Error + Send + Sync>> {
+        let version = self
+            .version_collection
+            .find_one(doc! { "version": version })
+            .await?;
+        Ok(version)
+    }
+
+    async fn list_versions(
+        &self,
+        limit: Option<i64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<CatalogVersion>, Box<dyn Error + Send + Sync>> {
+        let mut find = self
+            .version_collection
+            .find(doc! {})
+            .sort(doc! { "version": -1 });
+        if let Some(l) = limit {
+            find = find.limit(l);
+        }
+        if let Some(o) = offset {
+            find = find.skip(o);
+        }
+
+        let versions: Vec<CatalogVersion> = find.await?.try_collect().await?;
+        Ok(versions)
+    }
+
+    async fn write_product(&self, product: &Product) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.product_collection
+            .replace_one(doc! { "_id": &product.id }, product)
+            .upsert(true)
+            .await?;
+        Ok(())
+    }
+
+    async fn write_category(
+        &self,
+        category: &Category,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.category_collection
+            .replace_one(doc! { "_id": &category.id }, category)
+            .await?;
+        Ok(())
+    }
+
+    async fn save_snapshot(
+        &self,
+        version: i64,
+        changes: &[CatalogChange],
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        for batch in changes.chunks(SNAPSHOT_BATCH_SIZE) {
+            let entries: Vec<CatalogSnapshotEntry> = batch
+                .iter()
+                .map(|change| CatalogSnapshotEntry::new(version, change.clone()))
+                .collect();
+            self.snapshot_collection.insert_many(entries).await?;
+        }
+        Ok(())
+    }
+
+    async fn list_snapshot(
+        &self,
+        version: i64,
+    ) -> Result<Vec<CatalogChange>, Box<dyn Error + Send + Sync>> {
+        let entries: Vec<CatalogSnapshotEntry> = self
+            .snapshot_collection
+            .find(doc! { "version": version })
+            .await?
+            .try_collect()
+            .await?;
+        Ok(entries.into_iter().map(|entry| entry.change).collect())
+    }
+
+    async fn delete_snapshot(&self, version: i64) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        let result = self
+            .snapshot_collection
+            .delete_many(doc! { "version": version })
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    async fn prune_snapshots(&self, version: i64) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        let result = self
+            .snapshot_collection
+            .delete_many(doc! { "version": { "$lte": version } })
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    async fn first_change_after(
+        &self,
+        version: i64,
+        entity_type: CatalogEntityType,
+        entity_id: &str,
+    ) -> Result<Option<CatalogChange>, Box<dyn Error + Send + Sync>> {
+        let entry = self
+            .snapshot_collection
+            .find_one(doc! {
+                "entity": staged_change_id(entity_type, entity_id),
+                "version": { "$gt": version },
+            })
+            .sort(doc! { "version": 1 })
+            .await?;
+        Ok(entry.map(|entry| entry.change))
+    }
+
+    async fn first_changes_after(
+        &self,
+        version: i64,
+    ) -> Result<Vec<CatalogChange>, Box<dyn Error + Send + Sync>> {
+        let pipeline = vec![
+            doc! { "$match": { "version": { "$gt": version } } },
+            doc! { "$sort": { "entity": 1, "version": 1 } },
+            doc! { "$group": { "_id": "$entity", "change": { "$first": "$change" } } },
+        ];
+        let mut cursor = self.snapshot_collection.aggregate(pipeline).await?;
+        let mut changes = Vec::new();
+        while let Some(document) = cursor.try_next().await? {
+            let change = document.get_document("change")?.clone();
+            changes.push(bson::from_document(change)?);
+        }
+        Ok(changes)
+    }
+}
@@ -1,10 +1,15 @@
-use crate::domain::{Category, CategoryTreeCache, CategoryTreeNode};
+use super::category_tree_memory::CategoryTreeMemory;
+use crate::domain::{Category, CategoryTreeCache, CategoryTreeNode, ProductCountDeltas};
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use futures::TryStreamExt;
-use mongodb::{bson::doc, Collection};
+use mongodb::{
+    bson::{self, doc, Document},
+    Collection,
+};
 use std::collections::HashMap;
 use std::error::Error;
+use std::sync::Arc;
 use uuid::Uuid;
 
 #[async_trait]
@@ -22,12 +27,43 @@ pub trait CategoryDao {
         &self,
         slug: &str,
     ) -> Result<Option<Category>, Box<dyn Error + Send + Sync>>;
+    /// Find a category by a localized slug, trying `locales` in order
+    async fn get_category_by_localized_slug(
+        &self,
+        slug: &str,
+        locales: &[String],
+    ) -> Result<Option<Category>, Box<dyn Error + Send + Sync>>;
     async fn update_category(
         &self,
         id: &str,
         category: Category,
     ) -> Result<Option<Category>, Box<dyn Error + Send + Sync>>;
+    /// Soft delete: tombstones the category so it can be restored until purged
     async fn delete_category(&self, id: &str) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    async fn restore_category(&self, id: &str) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    /// Permanently delete categories tombstoned before `deleted_before`, returning their IDs
+    async fn purge_deleted_categories(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<Vec<String>, Box<dyn Error + Send + Sync>>;
+    /// Whether any category, including tombstoned ones, holds the slug
+    async fn slug_exists(&self, slug: &str) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    /// Whether a category other than `exclude_id` uses `slug`, either as its
+    /// slug or as its localized slug for `locale`
+    async fn localized_slug_taken(
+        &self,
+        slug: &str,
+        locale: &str,
+        exclude_id: Option<&str>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    /// Whether a category other than `exclude_id` uses `slug`, either as its
+    /// slug or as its slug in `store_id`
+    async fn store_slug_taken(
+        &self,
+        slug: &str,
+        store_id: &str,
+        exclude_id: Option<&str>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>>;
 
     // Hierarchy Operations
     async fn get_children(
@@ -52,15 +88,40 @@ pub trait CategoryDao {
         new_parent_id: Option<&str>,
     ) -> Result<bool, Box<dyn Error + Send + Sync>>;
 
+    /// Active, live categories ordered by level then display order
+    async fn get_active_categories(&self) -> Result<Vec<Category>, Box<dyn Error + Send + Sync>>;
+
     // Tree Cache Operations
     async fn get_full_tree(
         &self,
     ) -> Result<Option<CategoryTreeCache>, Box<dyn Error + Send + Sync>>;
+    /// Rebuild the cached tree from every live category, active or not
     async fn rebuild_tree_cache(&self) -> Result<CategoryTreeCache, Box<dyn Error + Send + Sync>>;
     async fn invalidate_tree_cache(&self) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    /// The cached tree, served from memory when this instance holds a current
+    /// copy and rebuilding the cache first when it has been invalidated
+    async fn get_or_rebuild_tree(
+        &self,
+    ) -> Result<Arc<CategoryTreeCache>, Box<dyn Error + Send + Sync>>;
 
     // Utility Operations
     async fn update_product_counts(&self) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    /// Live categories whose id or slug appears in `refs`, as used by a product's list_categories
+    async fn get_categories_by_refs(
+        &self,
+        refs: &[String],
+    ) -> Result<Vec<Category>, Box<dyn Error + Send + Sync>>;
+    /// Apply the count changes from one product write and invalidate the tree cache
+    async fn adjust_product_counts(
+        &self,
+        deltas: &ProductCountDeltas,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    /// Overwrite recounted (product_count, subtree_product_count) pairs, keyed
+    /// by category id, and invalidate the tree cache
+    async fn set_product_counts(
+        &self,
+        counts: &HashMap<String, (i32, i32)>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>>;
     async fn reorder_children(
         &self,
         parent_id: &str,
@@ -82,6 +143,7 @@ pub trait CategoryDao {
 pub struct CategoryDaoImpl {
     collection: Collection<Category>,
     cache_collection: Collection<CategoryTreeCache>,
+    tree_memory: Arc<CategoryTreeMemory>,
 }
 
 impl CategoryDaoImpl {
@@ -92,9 +154,15 @@ impl CategoryDaoImpl {
         Self {
             collection,
             cache_collection,
+            tree_memory: Arc::new(CategoryTreeMemory::default()),
         }
     }
 
+    /// This instance's in-memory tree, for relaying invalidations between instances
+    pub fn tree_memory(&self) -> Arc<CategoryTreeMemory> {
+        self.tree_memory.clone()
+    }
+
     /// Helper method to calculate hierarchy data for a category
     async fn calculate_hierarchy_data(
         &self,
@@ -136,7 +204,7 @@ impl CategoryDaoImpl {
             return Ok(Vec::new());
         }
 
-        let filter = doc! { "_id": { "$in": ancestor_ids } };
+        let filter = doc! { "_id": { "$in": ancestor_ids }, "deleted_at": null };
         let cursor = self.collection.find(filter).await?;
         let ancestors: Vec<Category> = cursor.try_collect().await?;
 
@@ -154,7 +222,7 @@ impl CategoryDaoImpl {
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
         let children_count = self
             .collection
-            .count_documents(doc! { "parent_id": parent_id })
+            .count_documents(doc! { "parent_id": parent_id, "deleted_at": null })
             .await? as i32;
 
         self.collection
@@ -205,7 +273,10 @@ impl CategoryDao for CategoryDaoImpl {
         &self,
         id: &str,
     ) -> Result<Option<Category>, Box<dyn Error + Send + Sync>> {
-        let category = self.collection.find_one(doc! { "_id": id }).await?;
+        let category = self
+            .collection
+            .find_one(doc! { "_id": id, "deleted_at": null })
+            .await?;
         Ok(category)
     }
 
@@ -213,10 +284,30 @@ impl CategoryDao for CategoryDaoImpl {
         &self,
         slug: &str,
     ) -> Result<Option<Category>, Box<dyn Error + Send + Sync>> {
-        let category = self.collection.find_one(doc! { "slug": slug }).await?;
+        let category = self
+            .collection
+            .find_one(doc! { "slug": slug, "deleted_at": null })
+            .await?;
         Ok(category)
     }
 
+    async fn get_category_by_localized_slug(
+        &self,
+        slug: &str,
+        locales: &[String],
+    ) -> Result<Option<Category>, Box<dyn Error + Send + Sync>> {
+        for locale in locales {
+            let filter = doc! {
+                format!("localizations.{locale}.slug"): slug,
+                "deleted_at": null,
+            };
+            if let Some(category) = self.collection.find_one(filter).await? {
+                return Ok(Some(category));
+            }
+        }
+        Ok(None)
+    }
+
     async fn update_category(
         &self,
         id: &str,
@@ -241,7 +332,7 @@ impl CategoryDao for CategoryDaoImpl {
         // Update the category
         let result = self
             .collection
-            .replace_one(doc! { "_id": id }, &category)
+            .replace_one(doc! { "_id": id, "deleted_at": null }, &category)
             .await?;
 
         if result.modified_count > 0 {
@@ -270,7 +361,7 @@ impl CategoryDao for CategoryDaoImpl {
         // Check if category has children
         let children_count = self
             .collection
-            .count_documents(doc! { "parent_id": id })
+            .count_documents(doc! { "parent_id": id, "deleted_at": null })
             .await?;
 
         if children_count > 0 {
@@ -283,9 +374,15 @@ impl CategoryDao for CategoryDaoImpl {
         // Get category to update parent's children count
         let category = self.get_category(id).await?;
 
-        let result = self.collection.delete_one(doc! { "_id": id }).await?;
+        let result = self
+            .collection
+            .update_one(
+                doc! { "_id": id, "deleted_at": null },
+                doc! { "$set": { "deleted_at": bson::DateTime::now() } },
+            )
+            .await?;
 
-        if result.deleted_count > 0 {
+        if result.modified_count > 0 {
             // Update parent's children count if this wasn't a root category
             if let Some(category) = category {
                 if let Some(parent_id) = &category.parent_id {
@@ -302,13 +399,124 @@ impl CategoryDao for CategoryDaoImpl {
         }
     }
 
+    async fn restore_category(&self, id: &str) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let tombstoned = self
+            .collection
+            .find_one(doc! { "_id": id, "deleted_at": { "$ne": null } })
+            .await?;
+        let Some(category) = tombstoned else {
+            return Ok(false);
+        };
+
+        // A category cannot come back underneath a parent that is still deleted
+        if let Some(parent_id) = &category.parent_id {
+            if self.get_category(parent_id).await?.is_none() {
+                return Err(format!(
+                    "Cannot restore category under deleted parent {parent_id}. Restore the parent first."
+                )
+                .into());
+            }
+        }
+
+        let result = self
+            .collection
+            .update_one(doc! { "_id": id }, doc! { "$set": { "deleted_at": null } })
+            .await?;
+
+        if let Some(parent_id) = &category.parent_id {
+            self.update_children_count(parent_id).await?;
+        }
+
+        // Invalidate tree cache
+        self.invalidate_tree_cache().await?;
+
+        Ok(result.modified_count > 0)
+    }
+
+    async fn purge_deleted_categories(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let filter = doc! { "deleted_at": { "$lt": bson::DateTime::from_chrono(deleted_before) } };
+
+        // Collect the IDs first so callers can clean up data keyed by category
+        let id_docs: Vec<Document> = self
+            .collection
+            .clone_with_type::<Document>()
+            .find(filter)
+            .projection(doc! { "_id": 1 })
+            .await?
+            .try_collect()
+            .await?;
+        let ids: Vec<String> = id_docs
+            .iter()
+            .filter_map(|d| d.get_str("_id").ok().map(str::to_string))
+            .collect();
+
+        if !ids.is_empty() {
+            self.collection
+                .delete_many(doc! { "_id": { "$in": &ids } })
+                .await?;
+        }
+
+        Ok(ids)
+    }
+
+    async fn slug_exists(&self, slug: &str) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let count = self
+            .collection
+            .count_documents(doc! { "slug": slug })
+            .await?;
+        Ok(count > 0)
+    }
+
+    async fn localized_slug_taken(
+        &self,
+        slug: &str,
+        locale: &str,
+        exclude_id: Option<&str>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let mut filter = doc! {
+            "$or": [
+                { "slug": slug },
+                { format!("localizations.{locale}.slug"): slug },
+            ]
+        };
+        if let Some(id) = exclude_id {
+            filter.insert("_id", doc! { "$ne": id });
+        }
+
+        let count = self.collection.count_documents(filter).await?;
+        Ok(count > 0)
+    }
+
+    async fn store_slug_taken(
+        &self,
+        slug: &str,
+        store_id: &str,
+        exclude_id: Option<&str>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let mut filter = doc! {
+            "$or": [
+                { "slug": slug },
+                { format!("stores.{store_id}.slug"): slug },
+            ]
+        };
+        if let Some(id) = exclude_id {
+            filter.insert("_id", doc! { "$ne": id });
+        }
+
+        let count = self.collection.count_documents(filter).await?;
+        Ok(count > 0)
+    }
+
     async fn get_children(
         &self,
         parent_id: &str,
     ) -> Result<Vec<Category>, Box<dyn Error + Send + Sync>> {
         let cursor = self
             .collection
-            .find(doc! { "parent_id": parent_id })
+            .find(doc! { "parent_id": parent_id, "deleted_at": null })
             .sort(doc! { "display_order": 1, "name": 1 })
             .await?;
 
@@ -322,7 +530,7 @@ impl CategoryDao for CategoryDaoImpl {
     ) -> Result<Vec<Category>, Box<dyn Error + Send + Sync>> {
         let cursor = self
             .collection
-            .find(doc! { "ancestors": ancestor_id })
+            .find(doc! { "ancestors": ancestor_id, "deleted_at": null })
             .sort(doc! { "level": 1, "display_order": 1, "name": 1 })
             .await?;
 
@@ -386,8 +594,28 @@ impl CategoryDao for CategoryDaoImpl {
             self.update_children_count(new_parent).await?;
         }
 
-        // TODO: Update all descendants' paths and ancestor arrays
-        // This is a complex operation that would require recursive updates
+        // Carry the new position down to every descendant. Descendants come
+        // ordered by level, so each parent is updated before its children.
+        for descendant in self.get_descendants(category_id).await? {
+            let Some(descendant_id) = descendant.id.clone() else {
+                continue;
+            };
+            let descendant = self.calculate_hierarchy_data(descendant).await?;
+            self.collection
+                .update_one(
+                    doc! { "_id": &descendant_id },
+                    doc! { "$set": {
+                        "ancestors": descendant.ancestors,
+                        "level": descendant.level,
+                        "path": descendant.path,
+                        "updated_at": bson::to_bson(&Utc::now())?,
+                    } },
+                )
+                .await?;
+        }
+
+        // Invalidate tree cache
+        self.invalidate_tree_cache().await?;
 
         Ok(true)
     }
@@ -404,16 +632,31 @@ impl CategoryDao for CategoryDaoImpl {
         Ok(tree_cache)
     }
 
-    async fn rebuild_tree_cache(&self) -> Result<CategoryTreeCache, Box<dyn Error + Send + Sync>> {
-        // Get all categories
+    async fn get_active_categories(&self) -> Result<Vec<Category>, Box<dyn Error + Send + Sync>> {
         let cursor = self
             .collection
-            .find(doc! { "is_active": true })
-            .sort(doc! { "level": 1, "display_order": 1 })
+            .find(doc! { "is_active": true, "deleted_at": null })
+            .sort(doc! { "level": 1, "display_order": 1, "_id": 1 })
             .await?;
 
         let categories: Vec<Category> = cursor.try_collect().await?;
 
+        Ok(categories)
+    }
+
+    async fn rebuild_tree_cache(&self) -> Result<CategoryTreeCache, Box<dyn Error + Send + Sync>> {
+        let generation = self.tree_memory.generation();
+
+        // Get all live categories; inactive ones stay in the tree, flagged, so
+        // readers can choose whether to show them
+        let categories: Vec<Category> = self
+            .collection
+            .find(doc! { "deleted_at": null })
+            .sort(doc! { "level": 1, "display_order": 1, "_id": 1 })
+            .await?
+            .try_collect()
+            .await?;
+
         // Build tree structure using recursive algorithm
         let tree = self.build_category_tree(categories);
 
@@ -430,13 +673,37 @@ impl CategoryDao for CategoryDaoImpl {
             .upsert(true)
             .await?;
 
+        self.tree_memory.store(Arc::new(cache.clone()), generation);
+
         Ok(cache)
     }
 
     async fn invalidate_tree_cache(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
-        let result = self.cache_collection.delete_many(doc! {}).await?;
+        let result = self.cache_collection.delete_many(doc! {}).await;
+
+        // Only after the document is gone, so a concurrent read cannot reload it
+        // into memory under the new generation
+        self.tree_memory.invalidate();
+
+        Ok(result?.deleted_count > 0)
+    }
+
+    async fn get_or_rebuild_tree(
+        &self,
+    ) -> Result<Arc<CategoryTreeCache>, Box<dyn Error + Send + Sync>> {
+        if let Some(tree) = self.tree_memory.get() {
+            return Ok(tree);
+        }
 
-        Ok(result.deleted_count > 0)
+        let generation = self.tree_memory.generation();
+        match self.get_full_tree().await? {
+            Some(cache) => {
+                let cache = Arc::new(cache);
+                self.tree_memory.store(cache.clone(), generation);
+                Ok(cache)
+            }
+            None => Ok(Arc::new(self.rebuild_tree_cache().await?)),
+        }
     }
 
     async fn update_product_counts(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
@@ -445,6 +712,88 @@ impl CategoryDao for CategoryDaoImpl {
         Ok(true)
     }
 
+    async fn get_categories_by_refs(
+        &self,
+        refs: &[String],
+    ) -> Result<Vec<Category>, Box<dyn Error + Send + Sync>> {
+        if refs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let cursor = self
+            .collection
+            .find(doc! {
+                "$or": [ { "_id": { "$in": refs } }, { "slug": { "$in": refs } } ],
+                "deleted_at": null,
+            })
+            .await?;
+
+        let categories: Vec<Category> = cursor.try_collect().await?;
+
+        Ok(categories)
+    }
+
+    async fn adjust_product_counts(
+        &self,
+        deltas: &ProductCountDeltas,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        if deltas.is_empty() {
+            return Ok(false);
+        }
+
+        // One $inc per category, covering both counts where both changed
+        let mut increments: HashMap<&str, Document> = HashMap::new();
+        for (category_id, delta) in &deltas.direct {
+            increments
+                .entry(category_id)
+                .or_default()
+                .insert("product_count", delta);
+        }
+        for (category_id, delta) in &deltas.subtree {
+            increments
+                .entry(category_id)
+                .or_default()
+                .insert("subtree_product_count", delta);
+        }
+
+        for (category_id, inc) in increments {
+            self.collection
+                .update_one(doc! { "_id": category_id }, doc! { "$inc": inc })
+                .await?;
+        }
+
+        // Invalidate tree cache
+        self.invalidate_tree_cache().await?;
+
+        Ok(true)
+    }
+
+    async fn set_product_counts(
+        &self,
+        counts: &HashMap<String, (i32, i32)>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        if counts.is_empty() {
+            return Ok(false);
+        }
+
+        for (category_id, (product_count, subtree_product_count)) in counts {
+            self.collection
+                .update_one(
+                    doc! { "_id": category_id },
+                    doc! { "$set": {
+                        "product_count": product_count,
+                        "subtree_product_count": subtree_product_count,
+                    } },
+                )
+                .await?;
+        }
+
+        // Invalidate tree cache
+        self.invalidate_tree_cache().await?;
+
+        Ok(true)
+    }
+
     async fn reorder_children(
         &self,
         parent_id: &str,
@@ -453,7 +802,7 @@ impl CategoryDao for CategoryDaoImpl {
         for (index, category_id) in ordered_ids.iter().enumerate() {
             self.collection
                 .update_one(
-                    doc! { "_id": category_id, "parent_id": parent_id },
+                    doc! { "_id": category_id, "parent_id": parent_id, "deleted_at": null },
                     doc! { "$set": { "display_order": index as i32 + 1 } },
                 )
                 .await?;
@@ -473,7 +822,7 @@ impl CategoryDao for CategoryDaoImpl {
 
         let cursor = self
             .collection
-            .find(doc! {})
+            .find(doc! { "deleted_at": null })
             .sort(doc! { "level": 1, "display_order": 1 })
             .limit(batch_size)
             .await?;
@@ -493,7 +842,7 @@ impl CategoryDao for CategoryDaoImpl {
 
         let cursor = self
             .collection
-            .find(doc! {})
+            .find(doc! { "deleted_at": null })
             .sort(doc! { "level": 1, "display_order": 1 })
             .limit(batch_size)
             .skip(offset)
@@ -533,6 +882,10 @@ impl CategoryDaoImpl {
                     path: category.path.clone(),
                     level: category.level,
                     product_count: category.product_count,
+                    subtree_product_count: category.subtree_product_count,
+                    is_active: category.is_active,
+                    localizations: category.localizations.clone(),
+                    stores: category.stores.clone(),
                     children: HashMap::new(),
                 };
             }
@@ -555,6 +908,10 @@ impl CategoryDaoImpl {
                 path: category.path.clone(),
                 level: category.level,
                 product_count: category.product_count,
+                subtree_product_count: category.subtree_product_count,
+                is_active: category.is_active,
+                localizations: category.localizations.clone(),
+                stores: category.stores.clone(),
                 children,
             }
         }
@@ -0,0 +1,83 @@
+use crate::domain::CategoryMerge;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::{bson::doc, Collection};
+use std::error::Error;
+
+#[async_trait]
+pub trait CategoryMergeDao {
+    /// Record that a merge has started. When one is already recorded for the
+    /// source, that record is returned unchanged instead.
+    async fn start_merge(
+        &self,
+        merge: CategoryMerge,
+    ) -> Result<CategoryMerge, Box<dyn Error + Send + Sync>>;
+    async fn get_merge(
+        &self,
+        source_id: &str,
+    ) -> Result<Option<CategoryMerge>, Box<dyn Error + Send + Sync>>;
+    /// Merges that started before `started_before` and never finished
+    async fn list_unfinished_merges(
+        &self,
+        started_before: DateTime<Utc>,
+    ) -> Result<Vec<CategoryMerge>, Box<dyn Error + Send + Sync>>;
+    async fn finish_merge(&self, source_id: &str) -> Result<bool, Box<dyn Error + Send + Sync>>;
+}
+
+pub struct CategoryMergeDaoImpl {
+    collection: Collection<CategoryMerge>,
+}
+
+impl CategoryMergeDaoImpl {
+    pub fn new(collection: Collection<CategoryMerge>) -> Self {
+        Self { collection }
+    }
+}
+
+#[async_trait]
+impl CategoryMergeDao for CategoryMergeDaoImpl {
+    async fn start_merge(
+        &self,
+        merge: CategoryMerge,
+    ) -> Result<CategoryMerge, Box<dyn Error + Send + Sync>> {
+        match self.collection.insert_one(&merge).await {
+            Ok(_) => Ok(merge),
+            Err(e) if e.to_string().contains("E11000") => self
+                .get_merge(&merge.source_id)
+                .await?
+                .ok_or_else(|| "Category merge record disappeared".into()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn get_merge(
+        &self,
+        source_id: &str,
+    ) -> Result<Option<CategoryMerge>, Box<dyn Error + Send + Sync>> {
+        let merge = self.collection.find_one(doc! { "_id": source_id }).await?;
+        Ok(merge)
+    }
+
+    async fn list_unfinished_merges(
+        &self,
+        started_before: DateTime<Utc>,
+    ) -> Result<Vec<CategoryMerge>, Box<dyn Error + Send + Sync>> {
+        let merges: Vec<CategoryMerge> = self
+            .collection
+            .find(doc! { "started_at": { "$lt": bson::DateTime::from_chrono(started_before) } })
+            .sort(doc! { "started_at": 1 })
+            .await?
+            .try_collect()
+            .await?;
+        Ok(merges)
+    }
+
+    async fn finish_merge(&self, source_id: &str) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let result = self
+            .collection
+            .delete_one(doc! { "_id": source_id })
+            .await?;
+        Ok(result.deleted_count > 0)
+    }
+}
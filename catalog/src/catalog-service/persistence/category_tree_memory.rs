@@ -0,0 +1,145 @@
+use crate::domain::CategoryTreeCache;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+/// How long an instance serves its in-memory tree without hearing of a change.
+/// Invalidations from other instances arrive over core NATS, which does not
+/// guarantee delivery, so this bounds how long a missed one can leave a stale tree.
+pub const CATEGORY_TREE_MEMORY_TTL: Duration = Duration::from_secs(300);
+
+/// Hot per-instance copy of the category tree cache document, so tree reads do
+/// not go to MongoDB. Every invalidation bumps a generation counter; a tree
+/// loaded before the latest invalidation is not stored, so a slow read cannot
+/// put back a tree that a concurrent write has already retired.
+pub struct CategoryTreeMemory {
+    state: RwLock<MemoryState>,
+    ttl: Duration,
+    local_invalidations: broadcast::Sender<()>,
+}
+
+struct MemoryState {
+    generation: u64,
+    tree: Option<(Arc<CategoryTreeCache>, Instant)>,
+}
+
+impl CategoryTreeMemory {
+    pub fn new(ttl: Duration) -> Self {
+        let (local_invalidations, _) = broadcast::channel(16);
+        Self {
+            state: RwLock::new(MemoryState {
+                generation: 0,
+                tree: None,
+            }),
+            ttl,
+            local_invalidations,
+        }
+    }
+
+    /// The held tree, unless there is none or it has outlived the TTL
+    pub fn get(&self) -> Option<Arc<CategoryTreeCache>> {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        state
+            .tree
+            .as_ref()
+            .filter(|(_, stored_at)| stored_at.elapsed() < self.ttl)
+            .map(|(tree, _)| tree.clone())
+    }
+
+    /// Generation to pass to `store` for a tree about to be loaded
+    pub fn generation(&self) -> u64 {
+        self.state
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .generation
+    }
+
+    /// Hold a tree loaded at `generation`. Returns false, holding nothing new,
+    /// when the memory was invalidated while the tree was being loaded.
+    pub fn store(&self, tree: Arc<CategoryTreeCache>, generation: u64) -> bool {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        if state.generation != generation {
+            return false;
+        }
+        state.tree = Some((tree, Instant::now()));
+        true
+    }
+
+    /// Drop the held tree after categories changed through this instance and
+    /// notify `subscribe` listeners, which pass the change on to other instances
+    pub fn invalidate(&self) {
+        self.discard();
+        // No receivers just means nothing relays invalidations, as in tests
+        let _ = self.local_invalidations.send(());
+    }
+
+    /// Drop the held tree after another instance announced a change
+    pub fn discard(&self) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        state.generation += 1;
+        state.tree = None;
+    }
+
+    /// Notified on every `invalidate`
+    pub fn subscribe(&self) -> broadcast::Receiver<()> {
+        self.local_invalidations.subscribe()
+    }
+}
+
+impl Default for CategoryTreeMemory {
+    fn default() -> Self {
+        Self::new(CATEGORY_TREE_MEMORY_TTL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::collections::HashMap;
+
+    fn tree() -> Arc<CategoryTreeCache> {
+        Arc::new(CategoryTreeCache {
+            id: "category_tree_v1".to_string(),
+            version: 1,
+            last_updated: Utc::now(),
+            tree: HashMap::new(),
+        })
+    }
+
+    #[test]
+    fn trees_loaded_before_an_invalidation_are_not_stored() {
+        let memory = CategoryTreeMemory::default();
+        let generation = memory.generation();
+        assert!(memory.store(tree(), generation));
+        assert!(memory.get().is_some());
+
+        let stale = memory.generation();
+        memory.discard();
+        assert!(memory.get().is_none());
+        assert!(!memory.store(tree(), stale));
+        assert!(memory.get().is_none());
+
+        assert!(memory.store(tree(), memory.generation()));
+        assert!(memory.get().is_some());
+    }
+
+    #[test]
+    fn expired_trees_are_not_served() {
+        let memory = CategoryTreeMemory::new(Duration::ZERO);
+        assert!(memory.store(tree(), memory.generation()));
+        assert!(memory.get().is_none());
+    }
+
+    #[test]
+    fn only_local_invalidations_are_announced() {
+        let memory = CategoryTreeMemory::default();
+        let mut invalidations = memory.subscribe();
+
+        memory.discard();
+        assert!(invalidations.try_recv().is_err());
+
+        memory.invalidate();
+        assert!(invalidations.try_recv().is_ok());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::domain::JobLease;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use mongodb::{bson::doc, Collection};
+use std::error::Error;
+
+#[async_trait]
+pub trait JobLeaseDao {
+    async fn get_lease(&self, job: &str) -> Result<Option<JobLease>, Box<dyn Error + Send + Sync>>;
+    /// Take or renew the lease on `job` for `ttl`. Returns None while another
+    /// owner holds an unexpired lease.
+    async fn acquire_lease(
+        &self,
+        job: &str,
+        owner: &str,
+        now: DateTime<Utc>,
+        ttl: Duration,
+    ) -> Result<Option<JobLease>, Box<dyn Error + Send + Sync>>;
+    /// Record how far the job has got. Returns false when `owner` no longer
+    /// holds the lease, in which case nothing is written.
+    async fn advance_watermark(
+        &self,
+        job: &str,
+        owner: &str,
+        watermark: DateTime<Utc>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    /// Give up the lease on `job` so another owner can take it at once.
+    /// Returns false when `owner` did not hold it.
+    async fn release_lease(
+        &self,
+        job: &str,
+        owner: &str,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>>;
+}
+
+pub struct JobLeaseDaoImpl {
+    collection: Collection<JobLease>,
+}
+
+impl JobLeaseDaoImpl {
+    pub fn new(collection: Collection<JobLease>) -> Self {
+        Self { collection }
+    }
+}
+
+#[async_trait]
+impl JobLeaseDao for JobLeaseDaoImpl {
+    async fn get_lease(&self, job: &str) -> Result<Option<JobLease>, Box<dyn Error + Send + Sync>> {
+        let lease = self.collection.find_one(doc! { "_id": job }).await?;
+        Ok(lease)
+    }
+
+    async fn acquire_lease(
+        &self,
+        job: &str,
+        owner: &str,
+        now: DateTime<Utc>,
+        ttl: Duration,
+    ) -> Result<Option<JobLease>, Box<dyn Error + Send + Sync>> {
+        let expires_at = now + ttl;
+
+        let Some(mut lease) = self.collection.find_one(doc! { "_id": job }).await? else {
+            let lease = JobLease {
+                job: job.to_string(),
+                owner: owner.to_string(),
+                expires_at,
+                watermark: None,
+            };
+            return match self.collection.insert_one(&lease).await {
+                Ok(_) => Ok(Some(lease)),
+                // Another instance created it first and holds it now
+                Err(e) if e.to_string().contains("E11000") => Ok(None),
+                Err(e) => Err(e.into()),
+            };
+        };
+        if !lease.is_available_to(owner, now) {
+            return Ok(None);
+        }
+
+        // Compare-and-set on the lease as read, so two instances seeing the same
+        // expired lease cannot both take it
+        let result = self
+            .collection
+            .update_one(
+                doc! {
+                    "_id": job,
+                    "owner": &lease.owner,
+                    "expires_at": bson::DateTime::from_chrono(lease.expires_at),
+                },
+                doc! { "$set": {
+                    "owner": owner,
+                    "expires_at": bson::DateTime::from_chrono(expires_at),
+                } },
+            )
+            .await?;
+        if result.matched_count == 0 {
+            return Ok(None);
+        }
+
+        lease.owner = owner.to_string();
+        lease.expires_at = expires_at;
+        Ok(Some(lease))
+    }
+
+    async fn advance_watermark(
+        &self,
+        job: &str,
+        owner: &str,
+        watermark: DateTime<Utc>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let result = self
+            .collection
+            .update_one(
+                doc! { "_id": job, "owner": owner },
+                doc! { "$set": { "watermark": bson::DateTime::from_chrono(watermark) } },
+            )
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+    async fn release_lease(
+        &self,
+        job: &str,
+        owner: &str,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let result = self
+            .collection
+            .delete_one(doc! { "_id": job, "owner": owner })
+            .await?;
+        Ok(result.deleted_count > 0)
+    }
+}
@@ -1,2 +1,10 @@
+pub mod attribute_schema_dao;
+pub mod catalog_version_dao;
 pub mod category_dao;
+pub mod category_merge_dao;
+pub mod category_tree_memory;
+pub mod job_lease_dao;
 pub mod product_dao;
+pub mod product_revision_dao;
+pub mod review_dao;
+pub mod slug_history_dao;
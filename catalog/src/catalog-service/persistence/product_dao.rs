@@ -1,10 +1,26 @@
-use crate::domain::{Product, ProductSlug};
+use crate::domain::{
+    HierarchicalCategories, Product, ProductRelation, ProductSlug, RelationKind, Reviews,
+};
 use async_trait::async_trait;
 use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
 use futures::TryStreamExt;
-use mongodb::{bson::doc, Collection, Database};
+use mongodb::{
+    bson::{self, doc, Document},
+    Collection, Database,
+};
 use std::error::Error;
 
+/// What search_products matches on; unset criteria match every product
+#[derive(Debug, Default)]
+pub struct ProductSearchFilter<'a> {
+    pub query: Option<&'a str>,
+    pub categories: &'a [String],
+    pub brand: Option<&'a str>,
+    pub locales: &'a [String], // Extends the text query to the localized content for these
+    pub store_id: Option<&'a str>, // Limits the results to the store's assortment
+}
+
 #[async_trait]
 pub trait ProductDao {
     async fn create_product(
@@ -16,17 +32,64 @@ pub trait ProductDao {
         &self,
         slug: &str,
     ) -> Result<Option<Product>, Box<dyn Error + Send + Sync>>;
+    /// Find a product by a localized slug, trying `locales` in order
+    async fn get_product_by_localized_slug(
+        &self,
+        slug: &str,
+        locales: &[String],
+    ) -> Result<Option<Product>, Box<dyn Error + Send + Sync>>;
+    /// Whether a product other than `exclude_id` uses `slug`, either as its
+    /// slug or as its localized slug for `locale`. Includes tombstoned products.
+    async fn localized_slug_taken(
+        &self,
+        slug: &str,
+        locale: &str,
+        exclude_id: Option<&str>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    /// Whether a product other than `exclude_id` uses `slug`, either as its
+    /// slug or as its slug in `store_id`. Includes tombstoned products.
+    async fn store_slug_taken(
+        &self,
+        slug: &str,
+        store_id: &str,
+        exclude_id: Option<&str>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>>;
     async fn update_product(
         &self,
         id: &str,
         product: Product,
     ) -> Result<Option<Product>, Box<dyn Error + Send + Sync>>;
+    /// Overwrite only the review summary, leaving the rest of the product untouched
+    async fn set_product_reviews(
+        &self,
+        id: &str,
+        reviews: &Reviews,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    /// Overwrite only the derived category paths; applies to soft-deleted products too
+    async fn set_hierarchical_categories(
+        &self,
+        id: &str,
+        hierarchical_categories: Option<&HierarchicalCategories>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    /// Replace list_categories without touching any other field
+    async fn set_list_categories(
+        &self,
+        id: &str,
+        list_categories: &[String],
+    ) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    /// Soft delete: tombstones the product so it can be restored until purged
     async fn delete_product(&self, id: &str) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    async fn restore_product(&self, id: &str) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    /// Permanently delete products tombstoned before `deleted_before`, returning their IDs
+    async fn purge_deleted_products(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<Vec<String>, Box<dyn Error + Send + Sync>>;
+    /// Whether any product, including tombstoned ones, holds the slug
+    async fn slug_exists(&self, slug: &str) -> Result<bool, Box<dyn Error + Send + Sync>>;
     async fn search_products(
         &self,
-        query: Option<&str>,
-        categories: &[String],
-        brand: Option<&str>,
+        filter: ProductSearchFilter<'_>,
         limit: Option<i64>,
         offset: Option<u64>,
     ) -> Result<Vec<Product>, Box<dyn Error + Send + Sync>>;
@@ -44,7 +107,111 @@ pub trait ProductDao {
         batch_size: i32,
         cursor: Option<String>,
         include_inactive: bool,
+        store_id: Option<&str>,
     ) -> Result<(Vec<String>, Option<String>, bool), Box<dyn Error + Send + Sync>>;
+    /// Products whose publish_at or unpublish_at falls in `(after, up_to]`
+    async fn find_products_crossing_publish_window(
+        &self,
+        after: DateTime<Utc>,
+        up_to: DateTime<Utc>,
+    ) -> Result<Vec<Product>, Box<dyn Error + Send + Sync>>;
+    /// Number of products a visitor can reach: displayed, published and with a slug
+    async fn count_live_products(&self) -> Result<u64, Box<dyn Error + Send + Sync>>;
+    /// A page of the live products, ordered by slug
+    async fn list_live_products(
+        &self,
+        offset: u64,
+        limit: i64,
+    ) -> Result<Vec<Product>, Box<dyn Error + Send + Sync>>;
+    /// Products counting towards category counts at `at` that list any of
+    /// `category_refs`: shown on site, inside their publishing window and not deleted
+    async fn count_products_counted_in(
+        &self,
+        category_refs: &[String],
+        at: DateTime<Utc>,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>>;
+    /// Products, including soft-deleted ones, listing any of `category_refs`
+    async fn find_products_in_categories(
+        &self,
+        category_refs: &[String],
+    ) -> Result<Vec<Product>, Box<dyn Error + Send + Sync>>;
+    /// Products that are not deleted and have a variant with any of `skus`
+    async fn find_products_by_skus(
+        &self,
+        skus: &[String],
+    ) -> Result<Vec<Product>, Box<dyn Error + Send + Sync>>;
+    /// Bundles that are not deleted and have any of `skus` as a component
+    async fn find_bundles_with_components(
+        &self,
+        skus: &[String],
+    ) -> Result<Vec<Product>, Box<dyn Error + Send + Sync>>;
+    /// Products that are not deleted, among `ids`
+    async fn find_products_by_ids(
+        &self,
+        ids: &[String],
+    ) -> Result<Vec<Product>, Box<dyn Error + Send + Sync>>;
+    /// Add `relation` to a product unless it already links there with that kind
+    async fn add_relation(
+        &self,
+        id: &str,
+        relation: &ProductRelation,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    /// Remove a product's `kind` relation to `related_id`
+    async fn remove_relation(
+        &self,
+        id: &str,
+        related_id: &str,
+        kind: RelationKind,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>>;
+    /// Remove every product's links to `related_id`. Returns how many products changed.
+    async fn remove_references_to(
+        &self,
+        related_id: &str,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>>;
+}
+
+/// Matches products whose publishing window contains `now`; unset bounds are open
+fn publish_window_filter(now: DateTime<Utc>) -> Document {
+    let now = bson::DateTime::from_chrono(now);
+    doc! {
+        "$and": [
+            { "$or": [ { "publish_at": null }, { "publish_at": { "$lte": now } } ] },
+            { "$or": [ { "unpublish_at": null }, { "unpublish_at": { "$gt": now } } ] },
+        ]
+    }
+}
+
+/// Matches products in the store's assortment: those assigned to it and
+/// those not assigned to any store
+fn in_store_filter(store_id: &str) -> Document {
+    doc! {
+        "$or": [
+            { "stores": { "$exists": false } },
+            { format!("stores.{store_id}"): { "$exists": true } },
+        ]
+    }
+}
+
+/// Matches products shown in the store: its own display flag replaces the
+/// product's, which only applies to products not assigned to any store
+fn store_display_filter(store_id: &str) -> Document {
+    doc! {
+        "$or": [
+            { format!("stores.{store_id}.display_on_site"): true },
+            { "stores": { "$exists": false }, "display_on_site": true },
+        ]
+    }
+}
+
+/// Matches products a visitor can reach right now
+fn live_product_filter(now: DateTime<Utc>) -> Document {
+    let mut filter = doc! {
+        "deleted_at": null,
+        "display_on_site": true,
+        "slug": { "$type": "string" },
+    };
+    filter.extend(publish_window_filter(now));
+    filter
 }
 
 pub struct ProductDaoImpl {
@@ -73,7 +240,10 @@ impl ProductDao for ProductDaoImpl {
     }
 
     async fn get_product(&self, id: &str) -> Result<Option<Product>, Box<dyn Error + Send + Sync>> {
-        let product = self.collection.find_one(doc! { "_id": &id }).await?;
+        let product = self
+            .collection
+            .find_one(doc! { "_id": &id, "deleted_at": null })
+            .await?;
         Ok(product)
     }
 
@@ -81,10 +251,72 @@ impl ProductDao for ProductDaoImpl {
         &self,
         slug: &str,
     ) -> Result<Option<Product>, Box<dyn Error + Send + Sync>> {
-        let product = self.collection.find_one(doc! { "slug": &slug }).await?;
+        let mut filter = doc! { "slug": &slug, "deleted_at": null };
+        filter.extend(publish_window_filter(Utc::now()));
+
+        let product = self.collection.find_one(filter).await?;
         Ok(product)
     }
 
+    async fn get_product_by_localized_slug(
+        &self,
+        slug: &str,
+        locales: &[String],
+    ) -> Result<Option<Product>, Box<dyn Error + Send + Sync>> {
+        for locale in locales {
+            let mut filter = doc! {
+                format!("localizations.{locale}.slug"): slug,
+                "deleted_at": null,
+            };
+            filter.extend(publish_window_filter(Utc::now()));
+
+            if let Some(product) = self.collection.find_one(filter).await? {
+                return Ok(Some(product));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn localized_slug_taken(
+        &self,
+        slug: &str,
+        locale: &str,
+        exclude_id: Option<&str>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let mut filter = doc! {
+            "$or": [
+                { "slug": slug },
+                { format!("localizations.{locale}.slug"): slug },
+            ]
+        };
+        if let Some(id) = exclude_id {
+            filter.insert("_id", doc! { "$ne": id });
+        }
+
+        let count = self.collection.count_documents(filter).await?;
+        Ok(count > 0)
+    }
+
+    async fn store_slug_taken(
+        &self,
+        slug: &str,
+        store_id: &str,
+        exclude_id: Option<&str>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let mut filter = doc! {
+            "$or": [
+                { "slug": slug },
+                { format!("stores.{store_id}.slug"): slug },
+            ]
+        };
+        if let Some(id) = exclude_id {
+            filter.insert("_id", doc! { "$ne": id });
+        }
+
+        let count = self.collection.count_documents(filter).await?;
+        Ok(count > 0)
+    }
+
     async fn update_product(
         &self,
         id: &str,
@@ -92,7 +324,7 @@ impl ProductDao for ProductDaoImpl {
     ) -> Result<Option<Product>, Box<dyn Error + Send + Sync>> {
         let result = self
             .collection
-            .replace_one(doc! { "_id": &id }, &product)
+            .replace_one(doc! { "_id": &id, "deleted_at": null }, &product)
             .await?;
 
         if result.modified_count > 0 {
@@ -102,40 +334,159 @@ impl ProductDao for ProductDaoImpl {
         }
     }
 
+    async fn set_product_reviews(
+        &self,
+        id: &str,
+        reviews: &Reviews,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let result = self
+            .collection
+            .update_one(
+                doc! { "_id": &id, "deleted_at": null },
+                doc! { "$set": { "reviews": bson::to_bson(reviews)? } },
+            )
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+
+    async fn set_hierarchical_categories(
+        &self,
+        id: &str,
+        hierarchical_categories: Option<&HierarchicalCategories>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let result = self
+            .collection
+            .update_one(
+                doc! { "_id": &id },
+                doc! { "$set": {
+                    "hierarchical_categories": bson::to_bson(&hierarchical_categories)?,
+                } },
+            )
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+
+    async fn set_list_categories(
+        &self,
+        id: &str,
+        list_categories: &[String],
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let result = self
+            .collection
+            .update_one(
+                doc! { "_id": &id },
+                doc! { "$set": {
+                    "list_categories": list_categories,
+                    "updated_at": bson::to_bson(&Utc::now())?,
+                } },
+            )
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+
     async fn delete_product(&self, id: &str) -> Result<bool, Box<dyn Error + Send + Sync>> {
-        let result = self.collection.delete_one(doc! { "_id": &id }).await?;
-        Ok(result.deleted_count > 0)
+        let result = self
+            .collection
+            .update_one(
+                doc! { "_id": &id, "deleted_at": null },
+                doc! { "$set": { "deleted_at": bson::DateTime::now() } },
+            )
+            .await?;
+        Ok(result.modified_count > 0)
+    }
+
+    async fn restore_product(&self, id: &str) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let result = self
+            .collection
+            .update_one(
+                doc! { "_id": &id, "deleted_at": { "$ne": null } },
+                doc! { "$set": { "deleted_at": null } },
+            )
+            .await?;
+        Ok(result.modified_count > 0)
+    }
+
+    async fn purge_deleted_products(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let filter = doc! { "deleted_at": { "$lt": bson::DateTime::from_chrono(deleted_before) } };
+
+        // Collect the IDs first so callers can clean up data keyed by product
+        let id_docs: Vec<Document> = self
+            .collection
+            .clone_with_type::<Document>()
+            .find(filter)
+            .projection(doc! { "_id": 1 })
+            .await?
+            .try_collect()
+            .await?;
+        let ids: Vec<String> = id_docs
+            .iter()
+            .filter_map(|d| d.get_str("_id").ok().map(str::to_string))
+            .collect();
+
+        if !ids.is_empty() {
+            self.collection
+                .delete_many(doc! { "_id": { "$in": &ids } })
+                .await?;
+        }
+
+        Ok(ids)
+    }
+
+    async fn slug_exists(&self, slug: &str) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let count = self
+            .collection
+            .count_documents(doc! { "slug": slug })
+            .await?;
+        Ok(count > 0)
     }
 
     async fn search_products(
         &self,
-        query: Option<&str>,
-        categories: &[String],
-        brand: Option<&str>,
+        search: ProductSearchFilter<'_>,
         limit: Option<i64>,
         offset: Option<u64>,
     ) -> Result<Vec<Product>, Box<dyn Error + Send + Sync>> {
-        let mut filter = doc! {};
-
-        if let Some(q) = query {
-            filter.insert(
-                "$or",
-                vec![
-                    doc! { "name": { "$regex": q, "$options": "i" } },
-                    doc! { "long_description": { "$regex": q, "$options": "i" } },
-                    doc! { "seo_keywords": { "$regex": q, "$options": "i" } },
-                ],
-            );
+        let mut filter = doc! { "deleted_at": null };
+        filter.extend(publish_window_filter(Utc::now()));
+
+        if let Some(q) = search.query {
+            let mut text_fields = vec![
+                "name".to_string(),
+                "long_description".to_string(),
+                "seo_keywords".to_string(),
+            ];
+            for locale in search.locales {
+                for field in ["name", "long_description", "seo_keywords"] {
+                    text_fields.push(format!("localizations.{locale}.{field}"));
+                }
+            }
+
+            let clauses: Vec<Document> = text_fields
+                .into_iter()
+                .map(|field| doc! { field: { "$regex": q, "$options": "i" } })
+                .collect();
+            filter.insert("$or", clauses);
         }
 
-        if !categories.is_empty() {
-            filter.insert("list_categories", doc! { "$in": categories });
+        if !search.categories.is_empty() {
+            filter.insert("list_categories", doc! { "$in": search.categories });
         }
 
-        if let Some(b) = brand {
+        if let Some(b) = search.brand {
             filter.insert("brand", b);
         }
 
+        if let Some(store_id) = search.store_id {
+            filter = doc! { "$and": [
+                filter,
+                in_store_filter(store_id),
+                store_display_filter(store_id),
+            ] };
+        }
+
         let mut find_options = self.collection.find(filter);
 
         if let Some(l) = limit {
@@ -159,7 +510,11 @@ impl ProductDao for ProductDaoImpl {
         // NATS has a default max payload of 1MB, so we need to be conservative
         let batch_size = batch_size.unwrap_or(50); // Reduced default batch size to 50
 
-        let cursor = self.collection.find(doc! {}).limit(batch_size).await?;
+        let cursor = self
+            .collection
+            .find(doc! { "deleted_at": null })
+            .limit(batch_size)
+            .await?;
 
         let products: Vec<Product> = cursor.try_collect().await?;
 
@@ -176,7 +531,7 @@ impl ProductDao for ProductDaoImpl {
 
         let cursor = self
             .collection
-            .find(doc! {})
+            .find(doc! { "deleted_at": null })
             .limit(batch_size)
             .skip(offset)
             .await?;
@@ -191,12 +546,13 @@ impl ProductDao for ProductDaoImpl {
         batch_size: i32,
         cursor: Option<String>,
         include_inactive: bool,
+        store_id: Option<&str>,
     ) -> Result<(Vec<String>, Option<String>, bool), Box<dyn Error + Send + Sync>> {
         // Validate and clamp batch_size
         let batch_size = batch_size.clamp(10, 1000);
 
         // Build the query filter
-        let mut query = doc! {};
+        let mut query = doc! { "deleted_at": null };
 
         // Add cursor condition if provided
         if let Some(cursor_slug) = &cursor {
@@ -211,9 +567,26 @@ impl ProductDao for ProductDaoImpl {
             query.insert("slug", doc! { "$gt": decoded_cursor });
         }
 
-        // Add active filter if needed
+        // Add active filter if needed; scheduled or expired products count as inactive
         if !include_inactive {
-            query.insert("display_on_site", true);
+            query.extend(publish_window_filter(Utc::now()));
+        }
+
+        let mut projection = doc! { "slug": 1, "_id": 0 };
+        match store_id {
+            Some(store_id) => {
+                // A store's own display flag replaces the product's
+                let mut clauses = vec![in_store_filter(store_id)];
+                if !include_inactive {
+                    clauses.push(store_display_filter(store_id));
+                }
+                query = doc! { "$and": [query, { "$and": clauses }] };
+                projection.insert(format!("stores.{store_id}"), 1);
+            }
+            None if !include_inactive => {
+                query.insert("display_on_site", true);
+            }
+            None => {}
         }
 
         // Create a ProductSlug collection for efficient querying
@@ -222,7 +595,7 @@ impl ProductDao for ProductDaoImpl {
         // Execute query with typed ProductSlug collection
         let mut cursor = slug_collection
             .find(query)
-            .projection(doc! { "slug": 1, "_id": 0 })
+            .projection(projection)
             .sort(doc! { "slug": 1 })
             .limit(batch_size as i64 + 1) // Fetch one extra to check if more results exist
             .await?;
@@ -236,21 +609,221 @@ impl ProductDao for ProductDaoImpl {
         // Extract slugs
         let total_count = product_slugs.len();
         let items_to_return = std::cmp::min(batch_size as usize, total_count);
-        let slugs: Vec<String> = product_slugs
-            .iter()
-            .take(items_to_return)
-            .map(|ps| ps.slug.clone())
-            .collect();
+        product_slugs.truncate(items_to_return);
 
-        // Check if there are more results
+        // Check if there are more results. The cursor stays on the default
+        // slug, which is what the results are sorted by.
         let has_more = total_count > batch_size as usize;
-        let next_cursor = if has_more && !slugs.is_empty() {
-            let last_slug = slugs.last().unwrap();
-            Some(general_purpose::STANDARD.encode(last_slug))
-        } else {
-            None
+        let next_cursor = match product_slugs.last() {
+            Some(last) if has_more => Some(general_purpose::STANDARD.encode(&last.slug)),
+            _ => None,
         };
 
+        let slugs: Vec<String> = product_slugs
+            .into_iter()
+            .map(|ps| {
+                store_id
+                    .and_then(|store_id| ps.stores.get(store_id))
+                    .and_then(|settings| settings.slug.clone())
+                    .unwrap_or(ps.slug)
+            })
+            .collect();
+
         Ok((slugs, next_cursor, has_more))
     }
+
+    async fn find_products_crossing_publish_window(
+        &self,
+        after: DateTime<Utc>,
+        up_to: DateTime<Utc>,
+    ) -> Result<Vec<Product>, Box<dyn Error + Send + Sync>> {
+        let window = doc! {
+            "$gt": bson::DateTime::from_chrono(after),
+            "$lte": bson::DateTime::from_chrono(up_to),
+        };
+
+        let cursor = self
+            .collection
+            .find(doc! {
+                "deleted_at": null,
+                "$or": [ { "publish_at": window.clone() }, { "unpublish_at": window } ],
+            })
+            .await?;
+
+        let products: Vec<Product> = cursor.try_collect().await?;
+        Ok(products)
+    }
+
+    async fn count_live_products(&self) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        let count = self
+            .collection
+            .count_documents(live_product_filter(Utc::now()))
+            .await?;
+        Ok(count)
+    }
+
+    async fn list_live_products(
+        &self,
+        offset: u64,
+        limit: i64,
+    ) -> Result<Vec<Product>, Box<dyn Error + Send + Sync>> {
+        let cursor = self
+            .collection
+            .find(live_product_filter(Utc::now()))
+            .sort(doc! { "slug": 1 })
+            .skip(offset)
+            .limit(limit)
+            .await?;
+
+        let products: Vec<Product> = cursor.try_collect().await?;
+        Ok(products)
+    }
+
+    async fn count_products_counted_in(
+        &self,
+        category_refs: &[String],
+        at: DateTime<Utc>,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        if category_refs.is_empty() {
+            return Ok(0);
+        }
+
+        let mut filter = doc! {
+            "list_categories": { "$in": category_refs },
+            "deleted_at": null,
+            "display_on_site": true,
+        };
+        filter.extend(publish_window_filter(at));
+        let count = self.collection.count_documents(filter).await?;
+        Ok(count)
+    }
+
+    async fn find_products_in_categories(
+        &self,
+        category_refs: &[String],
+    ) -> Result<Vec<Product>, Box<dyn Error + Send + Sync>> {
+        if category_refs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let cursor = self
+            .collection
+            .find(doc! { "list_categories": { "$in": category_refs } })
+            .await?;
+
+        let products: Vec<Product> = cursor.try_collect().await?;
+        Ok(products)
+    }
+
+    async fn find_products_by_skus(
+        &self,
+        skus: &[String],
+    ) -> Result<Vec<Product>, Box<dyn Error + Send + Sync>> {
+        if skus.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let cursor = self
+            .collection
+            .find(doc! { "variants.sku": { "$in": skus }, "deleted_at": null })
+            .await?;
+
+        let products: Vec<Product> = cursor.try_collect().await?;
+        Ok(products)
+    }
+
+    async fn find_bundles_with_components(
+        &self,
+        skus: &[String],
+    ) -> Result<Vec<Product>, Box<dyn Error + Send + Sync>> {
+        if skus.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let cursor = self
+            .collection
+            .find(doc! { "bundle.components.sku": { "$in": skus }, "deleted_at": null })
+            .await?;
+
+        let products: Vec<Product> = cursor.try_collect().await?;
+        Ok(products)
+    }
+
+    async fn find_products_by_ids(
+        &self,
+        ids: &[String],
+    ) -> Result<Vec<Product>, Box<dyn Error + Send + Sync>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let cursor = self
+            .collection
+            .find(doc! { "_id": { "$in": ids }, "deleted_at": null })
+            .await?;
+
+        let products: Vec<Product> = cursor.try_collect().await?;
+        Ok(products)
+    }
+
+    async fn add_relation(
+        &self,
+        id: &str,
+        relation: &ProductRelation,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let result = self
+            .collection
+            .update_one(
+                doc! {
+                    "_id": &id,
+                    "deleted_at": null,
+                    "relations": { "$not": { "$elemMatch": {
+                        "product_id": &relation.product_id,
+                        "kind": bson::to_bson(&relation.kind)?,
+                    } } },
+                },
+                doc! { "$push": { "relations": bson::to_bson(relation)? } },
+            )
+            .await?;
+        Ok(result.modified_count > 0)
+    }
+
+    async fn remove_relation(
+        &self,
+        id: &str,
+        related_id: &str,
+        kind: RelationKind,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let result = self
+            .collection
+            .update_one(
+                doc! { "_id": &id, "deleted_at": null },
+                doc! { "$pull": { "relations": {
+                    "product_id": related_id,
+                    "kind": bson::to_bson(&kind)?,
+                } } },
+            )
+            .await?;
+        Ok(result.modified_count > 0)
+    }
+
+    async fn remove_references_to(
+        &self,
+        related_id: &str,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        let result = self
+            .collection
+            .update_many(
+                doc! { "$or": [
+                    { "related_products": related_id },
+                    { "relations.product_id": related_id },
+                ] },
+                doc! { "$pull": {
+                    "related_products": related_id,
+                    "relations": { "product_id": related_id },
+                } },
+            )
+            .await?;
+        Ok(result.modified_count)
+    }
 }
@@ -0,0 +1,119 @@
+use crate::domain::ProductRevision;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use mongodb::{bson::doc, Collection};
+use std::error::Error;
+
+#[async_trait]
+pub trait ProductRevisionDao {
+    async fn create_revision(
+        &self,
+        revision: ProductRevision,
+    ) -> Result<ProductRevision, Box<dyn Error + Send + Sync>>;
+    /// Highest revision number stored for the product, or 0 when it has no history
+    async fn latest_revision_number(
+        &self,
+        product_id: &str,
+    ) -> Result<i64, Box<dyn Error + Send + Sync>>;
+    /// Revisions newest first; `revisions` narrows the result to specific revision numbers
+    async fn list_revisions(
+        &self,
+        product_id: &str,
+        revisions: &[i64],
+        limit: Option<i64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<ProductRevision>, Box<dyn Error + Send + Sync>>;
+    async fn get_revision(
+        &self,
+        product_id: &str,
+        revision: i64,
+    ) -> Result<Option<ProductRevision>, Box<dyn Error + Send + Sync>>;
+    async fn delete_revisions_for_products(
+        &self,
+        product_ids: &[String],
+    ) -> Result<u64, Box<dyn Error + Send + Sync>>;
+}
+
+pub struct ProductRevisionDaoImpl {
+    collection: Collection<ProductRevision>,
+}
+
+impl ProductRevisionDaoImpl {
+    pub fn new(collection: Collection<ProductRevision>) -> Self {
+        Self { collection }
+    }
+}
+
+#[async_trait]
+impl ProductRevisionDao for ProductRevisionDaoImpl {
+    async fn create_revision(
+        &self,
+        revision: ProductRevision,
+    ) -> Result<ProductRevision, Box<dyn Error + Send + Sync>> {
+        self.collection.insert_one(&revision).await?;
+        Ok(revision)
+    }
+
+    async fn latest_revision_number(
+        &self,
+        product_id: &str,
+    ) -> Result<i64, Box<dyn Error + Send + Sync>> {
+        let latest = self
+            .collection
+            .find_one(doc! { "product_id": product_id })
+            .sort(doc! { "revision": -1 })
+            .await?;
+        Ok(latest.map(|r| r.revision).unwrap_or(0))
+    }
+
+    async fn list_revisions(
+        &self,
+        product_id: &str,
+        revisions: &[i64],
+        limit: Option<i64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<ProductRevision>, Box<dyn Error + Send + Sync>> {
+        let mut filter = doc! { "product_id": product_id };
+        if !revisions.is_empty() {
+            filter.insert("revision", doc! { "$in": revisions });
+        }
+
+        let mut find = self.collection.find(filter).sort(doc! { "revision": -1 });
+        if let Some(l) = limit {
+            find = find.limit(l);
+        }
+        if let Some(o) = offset {
+            find = find.skip(o);
+        }
+
+        let revisions: Vec<ProductRevision> = find.await?.try_collect().await?;
+        Ok(revisions)
+    }
+
+    async fn get_revision(
+        &self,
+        product_id: &str,
+        revision: i64,
+    ) -> Result<Option<ProductRevision>, Box<dyn Error + Send + Sync>> {
+        let revision = self
+            .collection
+            .find_one(doc! { "product_id": product_id, "revision": revision })
+            .await?;
+        Ok(revision)
+    }
+
+    async fn delete_revisions_for_products(
+        &self,
+        product_ids: &[String],
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        if product_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let result = self
+            .collection
+            .delete_many(doc! { "product_id": { "$in": product_ids } })
+            .await?;
+        Ok(result.deleted_count)
+    }
+}
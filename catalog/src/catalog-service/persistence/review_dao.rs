@@ -0,0 +1,224 @@
+use crate::domain::{RatingTotals, Review, ReviewStatus};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use mongodb::{
+    bson::{self, doc, Bson, Document},
+    Collection,
+};
+use std::error::Error;
+
+#[async_trait]
+pub trait ReviewDao {
+    async fn create_review(&self, review: Review) -> Result<Review, Box<dyn Error + Send + Sync>>;
+    async fn get_review(&self, id: &str) -> Result<Option<Review>, Box<dyn Error + Send + Sync>>;
+    /// Reviews newest first, optionally narrowed to one product and/or status
+    async fn list_reviews(
+        &self,
+        product_id: Option<&str>,
+        status: Option<ReviewStatus>,
+        limit: Option<i64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<Review>, Box<dyn Error + Send + Sync>>;
+    async fn update_review(
+        &self,
+        review: Review,
+    ) -> Result<Option<Review>, Box<dyn Error + Send + Sync>>;
+    /// Totals over the product's approved reviews
+    async fn product_rating_totals(
+        &self,
+        product_id: &str,
+    ) -> Result<RatingTotals, Box<dyn Error + Send + Sync>>;
+    /// Totals over every approved review, with the number of products they cover
+    async fn catalog_rating_totals(
+        &self,
+    ) -> Result<(RatingTotals, i64), Box<dyn Error + Send + Sync>>;
+    /// Approved totals for every product with at least one review, including
+    /// products none of whose reviews are approved
+    async fn rating_totals_by_product(
+        &self,
+    ) -> Result<Vec<(String, RatingTotals)>, Box<dyn Error + Send + Sync>>;
+    async fn delete_reviews_for_products(
+        &self,
+        product_ids: &[String],
+    ) -> Result<u64, Box<dyn Error + Send + Sync>>;
+}
+
+pub struct ReviewDaoImpl {
+    collection: Collection<Review>,
+}
+
+impl ReviewDaoImpl {
+    pub fn new(collection: Collection<Review>) -> Self {
+        Self { collection }
+    }
+}
+
+fn status_value(status: ReviewStatus) -> Result<Bson, Box<dyn Error + Send + Sync>> {
+    Ok(bson::to_bson(&status)?)
+}
+
+#[async_trait]
+impl ReviewDao for ReviewDaoImpl {
+    async fn create_review(&self, review: Review) -> Result<Review, Box<dyn Error + Send + Sync>> {
+        self.collection.insert_one(&review).await?;
+        Ok(review)
+    }
+
+    async fn get_review(&self, id: &str) -> Result<Option<Review>, Box<dyn Error + Send + Sync>> {
+        let review = self.collection.find_one(doc! { "_id": id }).await?;
+        Ok(review)
+    }
+
+    async fn list_reviews(
+        &self,
+        product_id: Option<&str>,
+        status: Option<ReviewStatus>,
+        limit: Option<i64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<Review>, Box<dyn Error + Send + Sync>> {
+        let mut filter = doc! {};
+        if let Some(product_id) = product_id {
+            filter.insert("product_id", product_id);
+        }
+        if let Some(status) = status {
+            filter.insert("status", status_value(status)?);
+        }
+
+        let mut find = self.collection.find(filter).sort(doc! { "created_at": -1 });
+        if let Some(l) = limit {
+            find = find.limit(l);
+        }
+        if let Some(o) = offset {
+            find = find.skip(o);
+        }
+
+        let reviews: Vec<Review> = find.await?.try_collect().await?;
+        Ok(reviews)
+    }
+
+    async fn update_review(
+        &self,
+        review: Review,
+    ) -> Result<Option<Review>, Box<dyn Error + Send + Sync>> {
+        let result = self
+            .collection
+            .replace_one(doc! { "_id": &review.id }, &review)
+            .await?;
+
+        if result.matched_count > 0 {
+            Ok(Some(review))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn product_rating_totals(
+        &self,
+        product_id: &str,
+    ) -> Result<RatingTotals, Box<dyn Error + Send + Sync>> {
+        let pipeline = vec![
+            doc! { "$match": {
+                "product_id": product_id,
+                "status": status_value(ReviewStatus::Approved)?,
+            } },
+            doc! { "$group": {
+                "_id": null,
+                "count": { "$sum": 1 },
+                "rating_sum": { "$sum": "$rating" },
+            } },
+        ];
+
+        let groups: Vec<Document> = self
+            .collection
+            .aggregate(pipeline)
+            .await?
+            .try_collect()
+            .await?;
+        Ok(groups.first().map(rating_totals).unwrap_or_default())
+    }
+
+    async fn catalog_rating_totals(
+        &self,
+    ) -> Result<(RatingTotals, i64), Box<dyn Error + Send + Sync>> {
+        let pipeline = vec![
+            doc! { "$match": { "status": status_value(ReviewStatus::Approved)? } },
+            doc! { "$group": {
+                "_id": "$product_id",
+                "count": { "$sum": 1 },
+                "rating_sum": { "$sum": "$rating" },
+            } },
+            doc! { "$group": {
+                "_id": null,
+                "count": { "$sum": "$count" },
+                "rating_sum": { "$sum": "$rating_sum" },
+                "products": { "$sum": 1 },
+            } },
+        ];
+
+        let groups: Vec<Document> = self
+            .collection
+            .aggregate(pipeline)
+            .await?
+            .try_collect()
+            .await?;
+        Ok(groups
+            .first()
+            .map(|group| (rating_totals(group), read_count(group, "products")))
+            .unwrap_or_default())
+    }
+
+    async fn rating_totals_by_product(
+        &self,
+    ) -> Result<Vec<(String, RatingTotals)>, Box<dyn Error + Send + Sync>> {
+        let approved = doc! { "$eq": ["$status", status_value(ReviewStatus::Approved)?] };
+        let pipeline = vec![doc! { "$group": {
+            "_id": "$product_id",
+            "count": { "$sum": { "$cond": [approved.clone(), 1, 0] } },
+            "rating_sum": { "$sum": { "$cond": [approved, "$rating", 0] } },
+        } }];
+
+        let groups: Vec<Document> = self
+            .collection
+            .aggregate(pipeline)
+            .await?
+            .try_collect()
+            .await?;
+        Ok(groups
+            .iter()
+            .filter_map(|group| {
+                let product_id = group.get_str("_id").ok()?;
+                Some((product_id.to_string(), rating_totals(group)))
+            })
+            .collect())
+    }
+
+    async fn delete_reviews_for_products(
+        &self,
+        product_ids: &[String],
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        if product_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let result = self
+            .collection
+            .delete_many(doc! { "product_id": { "$in": product_ids } })
+            .await?;
+        Ok(result.deleted_count)
+    }
+}
+
+fn rating_totals(group: &Document) -> RatingTotals {
+    RatingTotals {
+        count: read_count(group, "count"),
+        rating_sum: read_count(group, "rating_sum"),
+    }
+}
+
+/// `$sum` yields an int32 or int64 depending on the magnitude of the result
+fn read_count(group: &Document, key: &str) -> i64 {
+    group
+        .get_i64(key)
+        .or_else(|_| group.get_i32(key).map(i64::from))
+        .unwrap_or(0)
+}
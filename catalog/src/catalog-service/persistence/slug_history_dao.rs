@@ -0,0 +1,111 @@
+use crate::domain::{SlugEntityType, SlugRedirect};
+use async_trait::async_trait;
+use mongodb::{bson::doc, Collection};
+use std::error::Error;
+
+#[async_trait]
+pub trait SlugHistoryDao {
+    /// Remember that `entity_id` used to be published under `old_slug`
+    async fn record_retired_slug(
+        &self,
+        entity_type: SlugEntityType,
+        entity_id: &str,
+        old_slug: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn find_redirect(
+        &self,
+        entity_type: SlugEntityType,
+        slug: &str,
+    ) -> Result<Option<SlugRedirect>, Box<dyn Error + Send + Sync>>;
+    /// Point every redirect for `from_entity_id` at `to_entity_id`, e.g. when one
+    /// category is merged into another
+    async fn reassign_redirects(
+        &self,
+        entity_type: SlugEntityType,
+        from_entity_id: &str,
+        to_entity_id: &str,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>>;
+    /// Drop every redirect pointing at the given entities, e.g. once they are purged
+    async fn delete_redirects_for_entities(
+        &self,
+        entity_type: SlugEntityType,
+        entity_ids: &[String],
+    ) -> Result<u64, Box<dyn Error + Send + Sync>>;
+}
+
+pub struct SlugHistoryDaoImpl {
+    collection: Collection<SlugRedirect>,
+}
+
+impl SlugHistoryDaoImpl {
+    pub fn new(collection: Collection<SlugRedirect>) -> Self {
+        Self { collection }
+    }
+}
+
+#[async_trait]
+impl SlugHistoryDao for SlugHistoryDaoImpl {
+    async fn record_retired_slug(
+        &self,
+        entity_type: SlugEntityType,
+        entity_id: &str,
+        old_slug: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let redirect = SlugRedirect::new(entity_type, old_slug.to_string(), entity_id.to_string());
+
+        // Upsert so a slug that changes hands always points at its latest owner
+        self.collection
+            .replace_one(doc! { "_id": &redirect.id }, &redirect)
+            .upsert(true)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn find_redirect(
+        &self,
+        entity_type: SlugEntityType,
+        slug: &str,
+    ) -> Result<Option<SlugRedirect>, Box<dyn Error + Send + Sync>> {
+        let redirect = self
+            .collection
+            .find_one(doc! { "_id": SlugRedirect::key(entity_type, slug) })
+            .await?;
+        Ok(redirect)
+    }
+
+    async fn reassign_redirects(
+        &self,
+        entity_type: SlugEntityType,
+        from_entity_id: &str,
+        to_entity_id: &str,
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        let result = self
+            .collection
+            .update_many(
+                doc! { "entity_type": entity_type.as_str(), "entity_id": from_entity_id },
+                doc! { "$set": { "entity_id": to_entity_id } },
+            )
+            .await?;
+        Ok(result.modified_count)
+    }
+
+    async fn delete_redirects_for_entities(
+        &self,
+        entity_type: SlugEntityType,
+        entity_ids: &[String],
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        if entity_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let result = self
+            .collection
+            .delete_many(doc! {
+                "entity_type": entity_type.as_str(),
+                "entity_id": { "$in": entity_ids },
+            })
+            .await?;
+        Ok(result.deleted_count)
+    }
+}
@@ -0,0 +1,994 @@
+use crate::catalog_messages::CategoryResponse;
+use crate::catalog_messages::{stage_catalog_change_request::Edit, StageCatalogChangeRequest};
+use crate::domain::{
+    category_content_onto, changes_back_to, same_category_content, same_product_content,
+    staged_change_id, CatalogChange, CatalogEntityType, CatalogVersion, CatalogVersionRef,
+    CatalogVersionStatus, Category, Product, StagedChange, StagedEdit, DEFAULT_CHANGE_SET,
+};
+use crate::persistence::{
+    catalog_version_dao::CatalogVersionDao, category_dao::CategoryDao, product_dao::ProductDao,
+};
+use crate::services::category_service::CategoryService;
+use crate::services::product_service::{HandlerError, ProductService};
+use chrono::{DateTime, Utc};
+use log::{error, info, warn};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Versions returned by list_versions when the caller sets no limit
+const DEFAULT_VERSION_PAGE_SIZE: i64 = 50;
+
+/// Stages product and category edits and publishes them as numbered catalog
+/// versions. A version records the before and after images of what it
+/// changes, then writes the changes one document at a time; when a write
+/// fails, or the publish is abandoned, the writes before it are undone from
+/// those images. Reads at a version and rollbacks use the images too, so only
+/// the most recent `version_retention` versions keep them. Slug redirects,
+/// revisions and derived counts follow once every change is written, as they
+/// do after direct edits.
+pub struct CatalogPublishingService {
+    version_dao: Arc<dyn CatalogVersionDao + Send + Sync>,
+    product_dao: Arc<dyn ProductDao + Send + Sync>,
+    category_dao: Arc<dyn CategoryDao + Send + Sync>,
+    product_service: Arc<ProductService>,
+    category_service: Arc<CategoryService>,
+    version_retention: i64,
+}
+
+impl CatalogPublishingService {
+    pub fn new(
+        version_dao: Arc<dyn CatalogVersionDao + Send + Sync>,
+        product_dao: Arc<dyn ProductDao + Send + Sync>,
+        category_dao: Arc<dyn CategoryDao + Send + Sync>,
+        product_service: Arc<ProductService>,
+        category_service: Arc<CategoryService>,
+        version_retention: i64,
+    ) -> Self {
+        Self {
+            version_dao,
+            product_dao,
+            category_dao,
+            product_service,
+            category_service,
+            version_retention,
+        }
+    }
+
+    async fn live_product(&self, product_id: &str) -> Result<Option<Product>, HandlerError> {
+        self.product_dao.get_product(product_id).await.map_err(|e| {
+            error!("Error getting product {product_id}: {e}");
+            HandlerError::InternalError(format!("Failed to get product: {e}"))
+        })
+    }
+
+    async fn live_category(&self, category_id: &str) -> Result<Option<Category>, HandlerError> {
+        self.category_dao
+            .get_category(category_id)
+            .await
+            .map_err(|e| {
+                error!("Error getting category {category_id}: {e}");
+                HandlerError::InternalError(format!("Failed to get category: {e}"))
+            })
+    }
+
+    async fn staged_edit(
+        &self,
+        entity_type: CatalogEntityType,
+        entity_id: &str,
+    ) -> Result<Option<StagedEdit>, HandlerError> {
+        let staged = self
+            .version_dao
+            .get_staged_change(&staged_change_id(entity_type, entity_id))
+            .await
+            .map_err(|e| {
+                error!("Error getting staged change for {entity_id}: {e}");
+                HandlerError::InternalError(format!("Failed to get staged change: {e}"))
+            })?;
+        Ok(staged.map(|staged| staged.edit))
+    }
+
+    /// Validate an edit against the live catalog, or against what is already
+    /// staged for the same entity, and put it in the staging workspace
+    pub async fn stage_change(
+        &self,
+        request: StageCatalogChangeRequest,
+    ) -> Result<StagedChange, HandlerError> {
+        let change_set = request
+            .change_set
+            .map(|change_set| change_set.trim().to_string())
+            .filter(|change_set| !change_set.is_empty())
+            .unwrap_or_else(|| DEFAULT_CHANGE_SET.to_string());
+
+        let edit = match request.edit {
+            Some(Edit::CreateProduct(create)) => StagedEdit::CreateProduct {
+                product: self
+                    .product_service
+                    .prepare_product_create(create, request.staged_by.clone())
+                    .await?,
+            },
+            Some(Edit::UpdateProduct(update)) => {
+                let product = update.product.ok_or_else(|| {
+                    HandlerError::ValidationError(
+                        "Product is required in update request".to_string(),
+                    )
+                })?;
+                // A product only staged for creation is edited in the workspace
+                let (existing, staged_creation) = match self.live_product(&update.id).await? {
+                    Some(live) => (live, false),
+                    None => match self
+                        .staged_edit(CatalogEntityType::Product, &update.id)
+                        .await?
+                    {
+                        Some(StagedEdit::CreateProduct { product }) => (product, true),
+                        _ => {
+                            return Err(HandlerError::NotFound(format!(
+                                "Product with ID {} not found",
+                                update.id
+                            )))
+                        }
+                    },
+                };
+
+                let base_updated_at = existing.updated_at;
+                let product = self
+                    .product_service
+                    .prepare_product_update(&update.id, &existing, product)
+                    .await?;
+                if staged_creation {
+                    StagedEdit::CreateProduct { product }
+                } else {
+                    StagedEdit::UpdateProduct {
+                        product,
+                        base_updated_at,
+                    }
+                }
+            }
+            Some(Edit::DeleteProductId(product_id)) => {
+                if self.live_product(&product_id).await?.is_none() {
+                    return Err(
+                        match self
+                            .staged_edit(CatalogEntityType::Product, &product_id)
+                            .await?
+                        {
+                            Some(StagedEdit::CreateProduct { .. }) => {
+                                HandlerError::ValidationError(format!(
+                                "Product {product_id} is only staged for creation; discard that change instead"
+                            ))
+                            }
+                            _ => HandlerError::NotFound(format!(
+                                "Product with ID {product_id} not found"
+                            )),
+                        },
+                    );
+                }
+                StagedEdit::DeleteProduct { product_id }
+            }
+            Some(Edit::UpdateCategory(update)) => {
+                // Partial updates build on what is already staged for the
+                // category, against the live category it was first staged on
+                let existing = match self
+                    .staged_edit(CatalogEntityType::Category, &update.id)
+                    .await?
+                {
+                    Some(StagedEdit::UpdateCategory {
+                        category,
+                        base_updated_at,
+                    }) => Some((category, base_updated_at)),
+                    _ => self
+                        .live_category(&update.id)
+                        .await?
+                        .map(|live| (live.clone(), Some(live.updated_at))),
+                };
+                let Some((existing, base_updated_at)) = existing else {
+                    return Err(HandlerError::NotFound(format!(
+                        "Category with ID {} not found",
+                        update.id
+                    )));
+                };
+
+                let category = self
+                    .category_service
+                    .prepare_category_update(update, &existing)
+                    .await
+                    .map_err(|e| HandlerError::ValidationError(e.to_string()))?;
+                StagedEdit::UpdateCategory {
+                    category,
+                    base_updated_at,
+                }
+            }
+            None => {
+                return Err(HandlerError::ValidationError(
+                    "A staged change needs an edit".to_string(),
+                ))
+            }
+        };
+
+        let change = StagedChange::new(edit, change_set, request.staged_by);
+        self.version_dao.stage_change(change).await.map_err(|e| {
+            error!("Error staging catalog change: {e}");
+            HandlerError::InternalError(format!("Failed to stage change: {e}"))
+        })
+    }
+
+    pub async fn list_staged_changes(
+        &self,
+        change_set: Option<&str>,
+    ) -> Result<Vec<StagedChange>, HandlerError> {
+        self.version_dao
+            .list_staged_changes(change_set)
+            .await
+            .map_err(|e| {
+                error!("Error listing staged changes: {e}");
+                HandlerError::InternalError(format!("Failed to list staged changes: {e}"))
+            })
+    }
+
+    /// Drop staged changes: the listed ones, or every one in the change set
+    pub async fn discard_staged_changes(
+        &self,
+        change_set: Option<&str>,
+        change_ids: Vec<String>,
+    ) -> Result<u64, HandlerError> {
+        let ids: Vec<String> = if change_ids.is_empty() || change_set.is_some() {
+            self.list_staged_changes(change_set)
+                .await?
+                .into_iter()
+                .map(|change| change.id)
+                .filter(|id| change_ids.is_empty() || change_ids.contains(id))
+                .collect()
+        } else {
+            change_ids
+        };
+
+        self.version_dao
+            .delete_staged_changes(&ids)
+            .await
+            .map_err(|e| {
+                error!("Error discarding staged changes: {e}");
+                HandlerError::InternalError(format!("Failed to discard staged changes: {e}"))
+            })
+    }
+
+    /// Write the staged changes of `change_set`, or everything staged, to
+    /// live as the next catalog version
+    pub async fn publish(
+        &self,
+        change_set: Option<String>,
+        published_by: Option<String>,
+    ) -> Result<CatalogVersion, HandlerError> {
+        self.check_no_publish_in_progress().await?;
+
+        let staged = self.list_staged_changes(change_set.as_deref()).await?;
+        if staged.is_empty() {
+            return Err(HandlerError::ValidationError(match &change_set {
+                Some(change_set) => format!("Nothing is staged in change set '{change_set}'"),
+                None => "Nothing is staged".to_string(),
+            }));
+        }
+
+        let mut changes = Vec::new();
+        for staged in &staged {
+            changes.push(self.change_from_staged(&staged.edit).await?);
+        }
+
+        let version = self
+            .promote(changes, change_set, None, published_by)
+            .await?;
+
+        let ids: Vec<String> = staged.into_iter().map(|staged| staged.id).collect();
+        if let Err(e) = self.version_dao.delete_staged_changes(&ids).await {
+            error!(
+                "Published catalog version {} but failed to clear its staged changes: {e}",
+                version.version
+            );
+        }
+        Ok(version)
+    }
+
+    /// Publish, as a new version, the changes that return what later versions
+    /// changed to how `version` left it. Direct edits to anything no later
+    /// version changed stay as they are.
+    pub async fn rollback(
+        &self,
+        version: i64,
+        published_by: Option<String>,
+    ) -> Result<CatalogVersion, HandlerError> {
+        self.check_retained(version).await?;
+        self.check_no_publish_in_progress().await?;
+
+        let first_after = self
+            .version_dao
+            .first_changes_after(version)
+            .await
+            .map_err(|e| {
+                error!("Error reading the changes after catalog version {version}: {e}");
+                HandlerError::InternalError(format!("Failed to read catalog versions: {e}"))
+            })?;
+        let mut live_products = HashMap::new();
+        let mut live_categories = HashMap::new();
+        for change in &first_after {
+            match change {
+                CatalogChange::Product { product_id, .. } => {
+                    if let Some(product) = self.live_product(product_id).await? {
+                        live_products.insert(product_id.clone(), product);
+                    }
+                }
+                CatalogChange::Category { category_id, .. } => {
+                    if let Some(category) = self.live_category(category_id).await? {
+                        live_categories.insert(category_id.clone(), category);
+                    }
+                }
+            }
+        }
+
+        let changes = changes_back_to(first_after, &live_products, &live_categories);
+        if changes.is_empty() {
+            return Err(HandlerError::ValidationError(format!(
+                "The catalog already matches version {version}"
+            )));
+        }
+
+        self.promote(changes, None, Some(version), published_by)
+            .await
+    }
+
+    pub async fn list_versions(
+        &self,
+        limit: Option<i64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<CatalogVersion>, HandlerError> {
+        self.version_dao
+            .list_versions(Some(limit.unwrap_or(DEFAULT_VERSION_PAGE_SIZE)), offset)
+            .await
+            .map_err(|e| {
+                error!("Error listing catalog versions: {e}");
+                HandlerError::InternalError(format!("Failed to list catalog versions: {e}"))
+            })
+    }
+
+    /// A product as `catalog_version` has it, with its content resolved for
+    /// `locale` when one is given. Only reads by id are versioned.
+    pub async fn get_product(
+        &self,
+        product_id: &str,
+        catalog_version: &str,
+        locale: Option<&str>,
+    ) -> Result<Option<Product>, HandlerError> {
+        let product = match parse_version_ref(catalog_version)? {
+            CatalogVersionRef::Live => self.live_product(product_id).await?,
+            CatalogVersionRef::Draft => {
+                match self
+                    .staged_edit(CatalogEntityType::Product, product_id)
+                    .await?
+                {
+                    Some(StagedEdit::CreateProduct { product })
+                    | Some(StagedEdit::UpdateProduct { product, .. }) => Some(product),
+                    Some(StagedEdit::DeleteProduct { .. }) => None,
+                    _ => self.live_product(product_id).await?,
+                }
+            }
+            CatalogVersionRef::Published(version) => {
+                self.check_retained(version).await?;
+                let next_change = self
+                    .version_dao
+                    .first_change_after(version, CatalogEntityType::Product, product_id)
+                    .await
+                    .map_err(|e| {
+                        error!("Error getting product {product_id} at version {version}: {e}");
+                        HandlerError::InternalError(format!("Failed to get product: {e}"))
+                    })?;
+                // As the version left it: before the next version to change it
+                match next_change {
+                    Some(CatalogChange::Product { before, .. }) => before.map(|product| *product),
+                    _ => self.live_product(product_id).await?,
+                }
+            }
+        };
+
+        Ok(product.map(|product| match locale {
+            Some(locale) => product.localized(locale),
+            None => product,
+        }))
+    }
+
+    /// A category as `catalog_version` has it, with its content resolved for
+    /// `locale` when one is given. Only its content is versioned, not its
+    /// place in the tree.
+    pub async fn get_category(
+        &self,
+        category_id: &str,
+        catalog_version: &str,
+        locale: Option<&str>,
+    ) -> Result<Option<CategoryResponse>, HandlerError> {
+        let category = match parse_version_ref(catalog_version)? {
+            CatalogVersionRef::Live => self.live_category(category_id).await?,
+            CatalogVersionRef::Draft => {
+                match self
+                    .staged_edit(CatalogEntityType::Category, category_id)
+                    .await?
+                {
+                    Some(StagedEdit::UpdateCategory { category, .. }) => Some(category),
+                    _ => self.live_category(category_id).await?,
+                }
+            }
+            CatalogVersionRef::Published(version) => {
+                self.check_retained(version).await?;
+                let next_change = self
+                    .version_dao
+                    .first_change_after(version, CatalogEntityType::Category, category_id)
+                    .await
+                    .map_err(|e| {
+                        error!("Error getting category {category_id} at version {version}: {e}");
+                        HandlerError::InternalError(format!("Failed to get category: {e}"))
+                    })?;
+                match next_change {
+                    Some(CatalogChange::Category { before, .. }) => Some(*before),
+                    _ => self.live_category(category_id).await?,
+                }
+            }
+        };
+
+        Ok(category.map(|category| {
+            let category = match locale {
+                Some(locale) => category.localized(locale),
+                None => category,
+            };
+            self.category_service.category_to_response(category)
+        }))
+    }
+
+    async fn check_published(&self, version: i64) -> Result<(), HandlerError> {
+        let found = self.version_dao.get_version(version).await.map_err(|e| {
+            error!("Error getting catalog version {version}: {e}");
+            HandlerError::InternalError(format!("Failed to get catalog version: {e}"))
+        })?;
+        match found {
+            Some(found) if found.status == CatalogVersionStatus::Published => Ok(()),
+            _ => Err(HandlerError::ValidationError(format!(
+                "Catalog version {version} was never published"
+            ))),
+        }
+    }
+
+    /// Reads and rollbacks at a version need the images of every later
+    /// version, which are only kept for the most recent ones
+    async fn check_retained(&self, version: i64) -> Result<(), HandlerError> {
+        self.check_published(version).await?;
+        let latest = self
+            .version_dao
+            .latest_version_number()
+            .await
+            .map_err(|e| {
+                error!("Error getting latest catalog version: {e}");
+                HandlerError::InternalError(format!("Failed to get latest catalog version: {e}"))
+            })?;
+        if version < latest - self.version_retention {
+            return Err(HandlerError::ValidationError(format!(
+                "Catalog version {version} is older than the last {} versions kept",
+                self.version_retention
+            )));
+        }
+        Ok(())
+    }
+
+    /// Fails when another publish holds its lease. Publishes whose lease ran
+    /// out were abandoned by a crashed instance; what they wrote is undone
+    /// and they are marked failed first.
+    async fn check_no_publish_in_progress(&self) -> Result<(), HandlerError> {
+        let abandoned = self
+            .version_dao
+            .list_abandoned_publishes()
+            .await
+            .map_err(|e| {
+                error!("Error listing abandoned catalog publishes: {e}");
+                HandlerError::InternalError(format!("Failed to check catalog versions: {e}"))
+            })?;
+        for version in abandoned {
+            warn!(
+                "Catalog version {} was abandoned while publishing; undoing it",
+                version.version
+            );
+            self.undo_abandoned(version.version).await?;
+        }
+
+        let in_progress = self.version_dao.publish_in_progress().await.map_err(|e| {
+            error!("Error checking for a catalog publish in progress: {e}");
+            HandlerError::InternalError(format!("Failed to check catalog versions: {e}"))
+        })?;
+        if in_progress {
+            return Err(HandlerError::AlreadyExists(
+                "Another catalog publish is in progress".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// What publishing a staged edit changes, against the live catalog as it is now
+    async fn change_from_staged(&self, edit: &StagedEdit) -> Result<CatalogChange, HandlerError> {
+        Ok(match edit {
+            StagedEdit::CreateProduct { product } => CatalogChange::Product {
+                product_id: product.id.clone().unwrap_or_default(),
+                before: None,
+                after: Some(Box::new(product.clone())),
+            },
+            StagedEdit::UpdateProduct {
+                product,
+                base_updated_at,
+            } => {
+                let product_id = product.id.clone().unwrap_or_default();
+                let Some(before) = self.live_product(&product_id).await? else {
+                    return Err(HandlerError::ValidationError(format!(
+                        "Product {product_id} was deleted after its update was staged"
+                    )));
+                };
+                if before.updated_at != *base_updated_at {
+                    return Err(HandlerError::AlreadyExists(format!(
+                        "Product {product_id} changed after its update was staged; stage it again"
+                    )));
+                }
+                CatalogChange::Product {
+                    product_id,
+                    before: Some(Box::new(before)),
+                    after: Some(Box::new(product.clone())),
+                }
+            }
+            StagedEdit::DeleteProduct { product_id } => {
+                let Some(before) = self.live_product(product_id).await? else {
+                    return Err(HandlerError::ValidationError(format!(
+                        "Product {product_id} was deleted after its deletion was staged"
+                    )));
+                };
+                CatalogChange::Product {
+                    product_id: product_id.clone(),
+                    before: Some(Box::new(before)),
+                    after: None,
+                }
+            }
+            StagedEdit::UpdateCategory {
+                category,
+                base_updated_at,
+            } => {
+                let category_id = category.id.clone().unwrap_or_default();
+                let Some(before) = self.live_category(&category_id).await? else {
+                    return Err(HandlerError::ValidationError(format!(
+                        "Category {category_id} was deleted after its update was staged"
+                    )));
+                };
+                if base_updated_at.is_some_and(|base| base != before.updated_at) {
+                    return Err(HandlerError::AlreadyExists(format!(
+                        "Category {category_id} changed after its update was staged; stage it again"
+                    )));
+                }
+                CatalogChange::Category {
+                    category_id,
+                    before: Box::new(before),
+                    after: Box::new(category.clone()),
+                }
+            }
+        })
+    }
+
+    /// Record `changes` as the next version and write them to live, then bring
+    /// derived data in step with them
+    async fn promote(
+        &self,
+        changes: Vec<CatalogChange>,
+        change_set: Option<String>,
+        rolled_back_to: Option<i64>,
+        published_by: Option<String>,
+    ) -> Result<CatalogVersion, HandlerError> {
+        let latest = self
+            .version_dao
+            .latest_version_number()
+            .await
+            .map_err(|e| {
+                error!("Error getting latest catalog version: {e}");
+                HandlerError::InternalError(format!("Failed to get latest catalog version: {e}"))
+            })?;
+
+        let mut version = CatalogVersion::new(latest + 1, &changes, change_set, published_by);
+        version.rolled_back_to = rolled_back_to;
+        let mut version = self
+            .version_dao
+            .create_version(version)
+            .await
+            .map_err(|e| {
+                let error_str = e.to_string();
+                if error_str.contains("E11000") || error_str.contains("duplicate key") {
+                    HandlerError::AlreadyExists(
+                        "Another catalog publish is in progress".to_string(),
+                    )
+                } else {
+                    error!("Error recording catalog version: {e}");
+                    HandlerError::InternalError(format!("Failed to record catalog version: {e}"))
+                }
+            })?;
+
+        let written = match self.commit(version.version, &changes).await {
+            Ok(written) => written,
+            Err(failure) => {
+                warn!(
+                    "Publishing catalog version {} failed, its writes were undone: {failure:?}",
+                    version.version
+                );
+                self.fail_version(version.version, format!("{failure:?}"))
+                    .await;
+                return Err(failure);
+            }
+        };
+
+        version.status = CatalogVersionStatus::Published;
+        version.published_at = Some(written.published_at);
+        self.after_commit(written).await;
+        self.prune_old_snapshots(version.version).await;
+        info!(
+            "Published catalog version {} with {} change(s)",
+            version.version,
+            version.changes.len()
+        );
+        Ok(version)
+    }
+
+    /// Build the documents the version leaves live, record their images and
+    /// write them one at a time. When a write fails, or the version lost its
+    /// lease meanwhile, the writes before it are undone.
+    async fn commit(
+        &self,
+        version: i64,
+        changes: &[CatalogChange],
+    ) -> Result<WrittenVersion, HandlerError> {
+        let now = Utc::now();
+        let mut products = Vec::new();
+        let mut categories = Vec::new();
+        for change in changes {
+            match change {
+                CatalogChange::Product {
+                    product_id,
+                    after: Some(product),
+                    ..
+                } => {
+                    let live = self.live_product(product_id).await?;
+                    // The review summary is derived, never taken from an image
+                    let mut product = Product::clone(product);
+                    if let Some(live) = &live {
+                        product.reviews = live.reviews.clone();
+                    }
+                    product.deleted_at = None;
+                    products.push(WrittenProduct {
+                        product_id: product_id.clone(),
+                        live,
+                        product,
+                    });
+                }
+                CatalogChange::Product {
+                    product_id,
+                    after: None,
+                    ..
+                } => {
+                    let Some(live) = self.live_product(product_id).await? else {
+                        return Err(HandlerError::ValidationError(format!(
+                            "Product {product_id} no longer exists"
+                        )));
+                    };
+                    let mut product = live.clone();
+                    product.deleted_at = Some(now);
+                    products.push(WrittenProduct {
+                        product_id: product_id.clone(),
+                        live: Some(live),
+                        product,
+                    });
+                }
+                CatalogChange::Category {
+                    category_id, after, ..
+                } => {
+                    let Some(live) = self.live_category(category_id).await? else {
+                        return Err(HandlerError::ValidationError(format!(
+                            "Category {category_id} no longer exists"
+                        )));
+                    };
+                    let category = category_content_onto(&live, after, now);
+                    let refs_before = self
+                        .category_service
+                        .refs_before_update(category_id, &live, &category)
+                        .await
+                        .map_err(|e| {
+                            error!("Error reading the category tree for {category_id}: {e}");
+                            HandlerError::InternalError(format!(
+                                "Failed to read the category tree: {e}"
+                            ))
+                        })?;
+                    categories.push(WrittenCategory {
+                        category_id: category_id.clone(),
+                        live,
+                        category,
+                        refs_before,
+                    });
+                }
+            }
+        }
+
+        let images: Vec<CatalogChange> = products
+            .iter()
+            .map(WrittenProduct::image)
+            .chain(categories.iter().map(WrittenCategory::image))
+            .collect();
+        self.version_dao
+            .save_snapshot(version, &images)
+            .await
+            .map_err(|e| {
+                error!("Error recording the changes of catalog version {version}: {e}");
+                HandlerError::InternalError(format!("Failed to record catalog version: {e}"))
+            })?;
+
+        let writes = products
+            .iter()
+            .map(|written| self.version_dao.write_product(&written.product))
+            .chain(
+                categories
+                    .iter()
+                    .map(|written| self.version_dao.write_category(&written.category)),
+            );
+        for (done, write) in writes.enumerate() {
+            if let Err(e) = write.await {
+                self.undo(&images[..done]).await;
+                let error_str = e.to_string();
+                return Err(
+                    if error_str.contains("E11000") || error_str.contains("duplicate key") {
+                        let field = if error_str.contains("slug_1") {
+                            "slug"
+                        } else {
+                            "product_ref"
+                        };
+                        HandlerError::AlreadyExists(format!(
+                            "Product with this {field} already exists"
+                        ))
+                    } else {
+                        error!("Error writing catalog version {version}: {e}");
+                        HandlerError::InternalError(format!("Failed to write catalog version: {e}"))
+                    },
+                );
+            }
+        }
+
+        let published_at = Utc::now();
+        match self.version_dao.mark_published(version, published_at).await {
+            Ok(true) => {}
+            Ok(false) => {
+                self.undo(&images).await;
+                return Err(HandlerError::InternalError(format!(
+                    "Catalog version {version} is no longer publishing; its lease ran out"
+                )));
+            }
+            Err(e) => {
+                self.undo(&images).await;
+                error!("Error marking catalog version {version} published: {e}");
+                return Err(HandlerError::InternalError(format!(
+                    "Failed to record catalog version: {e}"
+                )));
+            }
+        }
+
+        Ok(WrittenVersion {
+            published_at,
+            products,
+            categories,
+        })
+    }
+
+    /// Put back the before images of changes that were written, last first.
+    /// A product the version created is tombstoned rather than removed, so
+    /// whatever it replaced is not lost. Failures are logged and the rest
+    /// still undone.
+    async fn undo(&self, images: &[CatalogChange]) {
+        let now = Utc::now();
+        for image in images.iter().rev() {
+            let result = match image {
+                CatalogChange::Product {
+                    before: Some(before),
+                    ..
+                } => self.version_dao.write_product(before).await,
+                CatalogChange::Product {
+                    before: None,
+                    after: Some(after),
+                    ..
+                } => {
+                    let mut product = Product::clone(after);
+                    product.deleted_at = Some(now);
+                    self.version_dao.write_product(&product).await
+                }
+                CatalogChange::Product { .. } => Ok(()),
+                CatalogChange::Category {
+                    category_id,
+                    before,
+                    ..
+                } => match self.live_category(category_id).await {
+                    Ok(Some(live)) => {
+                        let category = category_content_onto(&live, before, now);
+                        self.version_dao.write_category(&category).await
+                    }
+                    Ok(None) => Ok(()),
+                    Err(e) => Err(format!("{e:?}").into()),
+                },
+            };
+            if let Err(e) = result {
+                error!("Failed to undo the change to {}: {e}", image.entity_id());
+            }
+        }
+    }
+
+    /// Undo what an abandoned publish got as far as writing, found by the
+    /// live documents still matching its after images, and mark it failed
+    async fn undo_abandoned(&self, version: i64) -> Result<(), HandlerError> {
+        let images = self.version_dao.list_snapshot(version).await.map_err(|e| {
+            error!("Error reading the changes of catalog version {version}: {e}");
+            HandlerError::InternalError(format!("Failed to read catalog version: {e}"))
+        })?;
+
+        let mut written = Vec::new();
+        for image in images {
+            let is_written = match &image {
+                CatalogChange::Product {
+                    product_id, after, ..
+                } => match (after, self.live_product(product_id).await?) {
+                    (Some(after), Some(live)) => same_product_content(after, &live),
+                    (None, None) => true,
+                    _ => false,
+                },
+                CatalogChange::Category {
+                    category_id, after, ..
+                } => self
+                    .live_category(category_id)
+                    .await?
+                    .is_some_and(|live| same_category_content(after, &live)),
+            };
+            if is_written {
+                written.push(image);
+            }
+        }
+        self.undo(&written).await;
+        self.fail_version(
+            version,
+            "Abandoned: still publishing when its lease ran out".to_string(),
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Mark a version whose writes were undone failed and drop its images
+    async fn fail_version(&self, version: i64, failure: String) {
+        if let Err(e) = self
+            .version_dao
+            .finish_version(version, CatalogVersionStatus::Failed, Some(failure))
+            .await
+        {
+            error!("Failed to mark catalog version {version} failed: {e}");
+            return;
+        }
+        if let Err(e) = self.version_dao.delete_snapshot(version).await {
+            error!("Failed to drop the images of failed catalog version {version}: {e}");
+        }
+    }
+
+    /// Drop the images of versions older than the retained ones
+    async fn prune_old_snapshots(&self, latest: i64) {
+        let oldest_kept = latest - self.version_retention;
+        if oldest_kept < 1 {
+            return;
+        }
+        match self.version_dao.prune_snapshots(oldest_kept).await {
+            Ok(0) => {}
+            Ok(count) => info!("Dropped {count} image(s) of catalog versions up to {oldest_kept}"),
+            Err(e) => error!("Failed to drop the images of old catalog versions: {e}"),
+        }
+    }
+
+    /// Slug redirects, revisions, relations, counts and category paths for a
+    /// written version. Derived data, so failures are logged: the version
+    /// stays published.
+    async fn after_commit(&self, written: WrittenVersion) {
+        if !written.categories.is_empty() {
+            if let Err(e) = self.category_dao.invalidate_tree_cache().await {
+                error!("Failed to invalidate the category tree cache: {e}");
+            }
+        }
+        for written in &written.categories {
+            if let Err(e) = self
+                .category_service
+                .after_category_saved(
+                    &written.category_id,
+                    &written.live,
+                    &written.category,
+                    written.refs_before.clone(),
+                )
+                .await
+            {
+                error!(
+                    "Failed to follow up the update to category {}: {e}",
+                    written.category_id
+                );
+            }
+        }
+
+        for written in &written.products {
+            if written.product.deleted_at.is_some() {
+                self.product_service
+                    .after_product_deleted(&written.product_id, written.live.as_ref())
+                    .await;
+                continue;
+            }
+            self.product_service
+                .after_product_saved(
+                    &written.product_id,
+                    written.live.as_ref(),
+                    &written.product,
+                    None,
+                )
+                .await;
+            // A product brought back by a rollback has reviews from before its deletion
+            if written.live.is_none() {
+                if let Err(e) = self
+                    .product_service
+                    .refresh_review_summary(&written.product_id)
+                    .await
+                {
+                    error!(
+                        "Failed to refresh the review summary of product {}: {e:?}",
+                        written.product_id
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// A product as a version writes it, with the live product it replaces
+struct WrittenProduct {
+    product_id: String,
+    live: Option<Product>,
+    product: Product, // Tombstoned when the version deletes it
+}
+
+impl WrittenProduct {
+    fn image(&self) -> CatalogChange {
+        CatalogChange::Product {
+            product_id: self.product_id.clone(),
+            before: self.live.clone().map(Box::new),
+            after: self
+                .product
+                .deleted_at
+                .is_none()
+                .then(|| Box::new(self.product.clone())),
+        }
+    }
+}
+
+/// A category as a version writes it, with the live category it replaces
+struct WrittenCategory {
+    category_id: String,
+    live: Category,
+    category: Category,
+    refs_before: Option<Vec<String>>,
+}
+
+impl WrittenCategory {
+    fn image(&self) -> CatalogChange {
+        CatalogChange::Category {
+            category_id: self.category_id.clone(),
+            before: Box::new(self.live.clone()),
+            after: Box::new(self.category.clone()),
+        }
+    }
+}
+
+/// What a committed version wrote, for the follow-up of derived data
+struct WrittenVersion {
+    published_at: DateTime<Utc>,
+    products: Vec<WrittenProduct>,
+    categories: Vec<WrittenCategory>,
+}
+
+fn parse_version_ref(catalog_version: &str) -> Result<CatalogVersionRef, HandlerError> {
+    catalog_version
+        .parse()
+        .map_err(HandlerError::ValidationError)
+}
@@ -1,9 +1,25 @@
 use crate::{
-    catalog_messages::{CategoryResponse, CreateCategoryRequest, UpdateCategoryRequest},
-    domain::{Category, CategorySeo},
-    persistence::category_dao::CategoryDao,
+    catalog_messages::{self, CategoryResponse, CreateCategoryRequest, UpdateCategoryRequest},
+    domain::{
+        category_path_slug, locale_fallback_chain,
+        localization::normalize_localizations,
+        merged_list_categories, normalize_locale, normalize_store_id, parse_category_csv,
+        plan_category_import,
+        slug::{slug_with_suffix, MAX_SLUG_SUFFIX},
+        slugify,
+        store::normalize_stores,
+        Category, CategoryImportAction, CategoryImportChange, CategoryMerge, CategorySeo,
+        CategoryStoreSettings, CategoryTreeNode, HierarchicalCategories, LocalizedCategoryContent,
+        Product, ProductCountDeltas, SlugEntityType, SlugLookup, DEFAULT_CSV_PATH_SEPARATOR,
+    },
+    persistence::{
+        category_dao::CategoryDao, category_merge_dao::CategoryMergeDao,
+        job_lease_dao::JobLeaseDao, product_dao::ProductDao, slug_history_dao::SlugHistoryDao,
+    },
+    services::publication_scheduler,
 };
-use log::{debug, error};
+use log::{debug, error, info};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 #[derive(Debug)]
@@ -27,20 +43,21 @@ impl std::fmt::Display for CategoryError {
 
 impl std::error::Error for CategoryError {}
 
-type BuildNodeFut<'a> = std::pin::Pin<
-    Box<
-        dyn std::future::Future<
-                Output = Result<
-                    Option<crate::catalog_messages::CategoryTreeNode>,
-                    Box<dyn std::error::Error + Send + Sync>,
-                >,
-            > + Send
-            + 'a,
-    >,
->;
+/// How long a merge that stops renewing its lease keeps others from taking it
+/// over. Each step renews it, so this only has to outlast one step.
+const MERGE_LEASE_TTL: chrono::Duration = chrono::Duration::minutes(5);
+
+/// Lease name for merging `source_id`, held by whichever request or job runs it
+fn merge_lease_job(source_id: &str) -> String {
+    format!("category_merge:{source_id}")
+}
 
 pub struct CategoryService {
     category_dao: Arc<dyn CategoryDao + Send + Sync>,
+    slug_history_dao: Arc<dyn SlugHistoryDao + Send + Sync>,
+    product_dao: Arc<dyn ProductDao + Send + Sync>,
+    job_lease_dao: Arc<dyn JobLeaseDao + Send + Sync>,
+    category_merge_dao: Arc<dyn CategoryMergeDao + Send + Sync>,
 }
 
 #[derive(Debug)]
@@ -51,15 +68,226 @@ pub struct ImportResult {
     pub errors: Vec<String>,
 }
 
+/// Planned changes of a CSV import and whether they were written
+#[derive(Debug)]
+pub struct CsvImportReport {
+    pub changes: Vec<CategoryImportChange>,
+    pub errors: Vec<String>,
+    pub applied: bool,
+}
+
+/// What merging one category into another changes, or would change on a dry run
+#[derive(Debug)]
+pub struct MergeReport {
+    pub moved_subcategory_ids: Vec<String>, // Direct children of the source, now under the target
+    pub affected_product_ids: Vec<String>, // Products that listed the source, soft-deleted ones included
+    pub redirected_slug: String,           // The source slug, now redirecting to the target
+}
+
+/// A counted product's category listings before a hierarchy change
+struct CountSnapshot {
+    product_id: String,
+    list_categories: Vec<String>,
+    categories: Vec<Category>,
+}
+
 impl CategoryService {
-    pub fn new(category_dao: Arc<dyn CategoryDao + Send + Sync>) -> Self {
-        Self { category_dao }
+    pub fn new(
+        category_dao: Arc<dyn CategoryDao + Send + Sync>,
+        slug_history_dao: Arc<dyn SlugHistoryDao + Send + Sync>,
+        product_dao: Arc<dyn ProductDao + Send + Sync>,
+        job_lease_dao: Arc<dyn JobLeaseDao + Send + Sync>,
+        category_merge_dao: Arc<dyn CategoryMergeDao + Send + Sync>,
+    ) -> Self {
+        Self {
+            category_dao,
+            slug_history_dao,
+            product_dao,
+            job_lease_dao,
+            category_merge_dao,
+        }
+    }
+
+    /// list_categories entries whose paths go through the category: its id and
+    /// slug and those of its descendants, as the tree currently has them.
+    async fn affected_category_refs(
+        &self,
+        category_id: &str,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let tree = self.category_dao.get_or_rebuild_tree().await?;
+        Ok(CategoryTreeNode::find(&tree.tree, category_id)
+            .map(CategoryTreeNode::subtree_refs)
+            .unwrap_or_default())
+    }
+
+    /// Re-derive hierarchical_categories for the products affected by a change to
+    /// the category. `refs_before` are the category's affected refs from before
+    /// the change, so products listing a retired slug or a node that left the
+    /// tree are refreshed too. Paths are derived data, so failures are logged
+    /// rather than failing the category change.
+    async fn refresh_product_category_paths(&self, category_id: &str, refs_before: Vec<String>) {
+        let result = async {
+            let mut refs = refs_before;
+            refs.extend(self.affected_category_refs(category_id).await?);
+            refs.sort();
+            refs.dedup();
+            if refs.is_empty() {
+                return Ok(0);
+            }
+
+            let tree = self.category_dao.get_or_rebuild_tree().await?;
+            let products = self.product_dao.find_products_in_categories(&refs).await?;
+
+            let mut updated = 0;
+            for product in products {
+                let derived =
+                    HierarchicalCategories::from_tree(&tree.tree, &product.list_categories);
+                let Some(product_id) = product.id.as_deref() else {
+                    continue;
+                };
+                if derived != product.hierarchical_categories
+                    && self
+                        .product_dao
+                        .set_hierarchical_categories(product_id, derived.as_ref())
+                        .await?
+                {
+                    updated += 1;
+                }
+            }
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(updated)
+        }
+        .await;
+
+        match result {
+            Ok(updated) => {
+                info!("Re-derived category paths for {updated} products after change to category {category_id}")
+            }
+            Err(e) => error!("Failed to re-derive product category paths: {e}"),
+        }
+    }
+
+    /// Generate a path-style slug under the parent's slug ("/apparel/mens-shirts")
+    /// that is neither live nor retired into the redirect history
+    async fn generate_unique_slug(
+        &self,
+        name: &str,
+        parent_id: Option<&str>,
+    ) -> Result<String, CategoryError> {
+        let parent_slug = match parent_id {
+            Some(parent_id) => match self.category_dao.get_category(parent_id).await {
+                Ok(Some(parent)) => parent.slug,
+                Ok(None) => {
+                    return Err(CategoryError::NotFound(format!(
+                        "Parent category with ID {parent_id} not found"
+                    )))
+                }
+                Err(e) => {
+                    return Err(CategoryError::InternalError(format!(
+                        "Failed to look up parent category: {e}"
+                    )))
+                }
+            },
+            None => String::new(),
+        };
+
+        let name_slug = slugify(name);
+        if name_slug.is_empty() {
+            return Err(CategoryError::ValidationError(
+                "Cannot generate a slug from the category name".to_string(),
+            ));
+        }
+        let base = format!("{}/{name_slug}", parent_slug.trim_end_matches('/'));
+
+        for suffix in 1..=MAX_SLUG_SUFFIX {
+            let candidate = slug_with_suffix(&base, suffix);
+            if !self.is_slug_taken(&candidate).await? {
+                return Ok(candidate);
+            }
+        }
+
+        Err(CategoryError::AlreadyExists(format!(
+            "Could not generate a unique slug for category '{name}'"
+        )))
+    }
+
+    async fn is_slug_taken(&self, slug: &str) -> Result<bool, CategoryError> {
+        let exists = self
+            .category_dao
+            .slug_exists(slug)
+            .await
+            .map_err(|e| CategoryError::InternalError(format!("Failed to check slug: {e}")))?;
+        if exists {
+            return Ok(true);
+        }
+
+        let retired = self
+            .slug_history_dao
+            .find_redirect(SlugEntityType::Category, slug)
+            .await
+            .map_err(|e| CategoryError::InternalError(format!("Failed to check slug: {e}")))?;
+        Ok(retired.is_some())
+    }
+
+    /// Reject localized slugs that collide, within their locale, with another
+    /// category's default or localized slug
+    async fn check_localized_slugs(
+        &self,
+        category: &Category,
+        exclude_id: Option<&str>,
+    ) -> Result<(), CategoryError> {
+        for (locale, content) in &category.localizations {
+            let Some(slug) = content.slug.as_deref() else {
+                continue;
+            };
+
+            let taken = self
+                .category_dao
+                .localized_slug_taken(slug, locale, exclude_id)
+                .await
+                .map_err(|e| {
+                    CategoryError::InternalError(format!("Failed to check for duplicate slug: {e}"))
+                })?;
+            if taken {
+                return Err(CategoryError::AlreadyExists(format!(
+                    "Slug '{slug}' is already used for locale '{locale}'"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Reject store slugs that collide, within their store, with another
+    /// category's default or store slug
+    async fn check_store_slugs(
+        &self,
+        category: &Category,
+        exclude_id: Option<&str>,
+    ) -> Result<(), CategoryError> {
+        for (store_id, settings) in &category.stores {
+            let Some(slug) = settings.slug.as_deref() else {
+                continue;
+            };
+
+            let taken = self
+                .category_dao
+                .store_slug_taken(slug, store_id, exclude_id)
+                .await
+                .map_err(|e| {
+                    CategoryError::InternalError(format!("Failed to check for duplicate slug: {e}"))
+                })?;
+            if taken {
+                return Err(CategoryError::AlreadyExists(format!(
+                    "Slug '{slug}' is already used in store '{store_id}'"
+                )));
+            }
+        }
+        Ok(())
     }
 
     /// Create a new category (internal version with cache control)
     async fn create_category_internal(
         &self,
-        request: CreateCategoryRequest,
+        mut request: CreateCategoryRequest,
         invalidate_cache: bool,
     ) -> Result<CategoryResponse, CategoryError> {
         // Validate input
@@ -69,21 +297,26 @@ impl CategoryService {
             ));
         }
 
+        // Derive a slug from the name when the caller leaves it blank
         if request.slug.trim().is_empty() {
-            return Err(CategoryError::ValidationError(
-                "Category slug cannot be empty".to_string(),
-            ));
+            request.slug = self
+                .generate_unique_slug(&request.name, request.parent_id.as_deref())
+                .await?;
+            debug!(
+                "Generated slug '{}' for category '{}'",
+                request.slug, request.name
+            );
         }
 
-        // Check if slug already exists
-        match self.category_dao.get_category_by_slug(&request.slug).await {
-            Ok(Some(_)) => {
+        // Check if slug already exists (deleted categories keep theirs until purged)
+        match self.category_dao.slug_exists(&request.slug).await {
+            Ok(true) => {
                 return Err(CategoryError::AlreadyExists(format!(
                     "Category with slug '{}' already exists",
                     request.slug
                 )));
             }
-            Ok(None) => {
+            Ok(false) => {
                 // Slug doesn't exist, continue
             }
             Err(e) => {
@@ -105,6 +338,12 @@ impl CategoryService {
 
         // Set optional fields
         category.full_description = request.full_description.clone();
+        category.localizations = localizations_from_proto(request.localizations.clone())
+            .map_err(CategoryError::ValidationError)?;
+        self.check_localized_slugs(&category, None).await?;
+        category.stores =
+            stores_from_proto(request.stores.clone()).map_err(CategoryError::ValidationError)?;
+        self.check_store_slugs(&category, None).await?;
 
         // Set SEO data
         if let Some(seo) = request.seo {
@@ -162,25 +401,61 @@ impl CategoryService {
         self.create_category_internal(request, true).await
     }
 
-    /// Get category by ID
+    /// Get category by ID, with its content resolved for `locale` when one is given
     pub async fn get_category(
         &self,
         id: &str,
+        locale: Option<&str>,
     ) -> Result<Option<CategoryResponse>, Box<dyn std::error::Error + Send + Sync>> {
         match self.category_dao.get_category(id).await? {
-            Some(category) => Ok(Some(self.category_to_response(category))),
+            Some(category) => Ok(Some(
+                self.category_to_response(localize_category(category, locale)),
+            )),
             None => Ok(None),
         }
     }
 
-    /// Get category by slug
+    /// Get category by slug, following the slug history for renamed categories.
+    /// With a locale, localized slugs along its fallback chain are also matched.
     pub async fn get_category_by_slug(
         &self,
         slug: &str,
-    ) -> Result<Option<CategoryResponse>, Box<dyn std::error::Error + Send + Sync>> {
-        match self.category_dao.get_category_by_slug(slug).await? {
-            Some(category) => Ok(Some(self.category_to_response(category))),
-            None => Ok(None),
+        locale: Option<&str>,
+    ) -> Result<Option<SlugLookup<CategoryResponse>>, Box<dyn std::error::Error + Send + Sync>>
+    {
+        let mut category = self.category_dao.get_category_by_slug(slug).await?;
+        if category.is_none() {
+            if let Some(locale) = locale {
+                let chain = locale_fallback_chain(locale);
+                category = self
+                    .category_dao
+                    .get_category_by_localized_slug(slug, &chain)
+                    .await?;
+            }
+        }
+        if let Some(category) = category {
+            return Ok(Some(SlugLookup::Current(
+                self.category_to_response(localize_category(category, locale)),
+            )));
+        }
+
+        let Some(redirect) = self
+            .slug_history_dao
+            .find_redirect(SlugEntityType::Category, slug)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        match self.category_dao.get_category(&redirect.entity_id).await? {
+            Some(category) if category.slug != slug => {
+                let category = localize_category(category, locale);
+                Ok(Some(SlugLookup::MovedPermanently {
+                    current_slug: category.slug.clone(),
+                    entity: self.category_to_response(category),
+                }))
+            }
+            _ => Ok(None),
         }
     }
 
@@ -210,6 +485,8 @@ impl CategoryService {
         max_depth: Option<i32>,
         include_inactive: Option<bool>,
         rebuild_cache: Option<bool>,
+        locale: Option<&str>,
+        store_id: Option<&str>,
     ) -> Result<
         Vec<crate::catalog_messages::CategoryTreeNode>,
         Box<dyn std::error::Error + Send + Sync>,
@@ -218,51 +495,38 @@ impl CategoryService {
             "Getting category tree - max_depth: {max_depth:?}, include_inactive: {include_inactive:?}, rebuild_cache: {rebuild_cache:?}"
         );
 
+        let store_id = store_id
+            .map(|store_id| {
+                normalize_store_id(store_id).ok_or_else(|| {
+                    CategoryError::ValidationError(format!("'{store_id}' is not a valid store id"))
+                })
+            })
+            .transpose()?;
+
         // If rebuild_cache is requested, rebuild the cache first
         if rebuild_cache.unwrap_or(false) {
             debug!("Rebuilding tree cache as requested");
             self.category_dao.rebuild_tree_cache().await?;
         }
 
-        // Get the tree cache
-        let tree_cache = match self.category_dao.get_full_tree().await? {
-            Some(cache) => cache,
-            None => {
-                debug!("No tree cache found, rebuilding...");
-                self.category_dao.rebuild_tree_cache().await?
-            }
-        };
+        // Get the tree cache, usually this instance's in-memory copy
+        let tree_cache = self.category_dao.get_or_rebuild_tree().await?;
 
-        // Convert tree cache to CategoryTreeNode format
-        let mut tree_nodes = Vec::new();
         let include_inactive = include_inactive.unwrap_or(false);
-        let max_depth = max_depth.unwrap_or(-1); // -1 means no depth limit
-
-        // The tree cache contains all categories as a flat HashMap
-        // We need to identify root categories and build the tree structure
-        for (category_id, cache_node) in &tree_cache.tree {
-            // Check if this is a root category by checking if any category has this as a child
-            let is_root = !tree_cache
-                .tree
-                .values()
-                .any(|node| node.children.contains_key(category_id));
-
-            if is_root {
-                if let Some(node) = self
-                    .build_tree_node_from_cache(
-                        &tree_cache,
-                        category_id,
-                        cache_node,
-                        0,
-                        max_depth,
-                        include_inactive,
-                    )
-                    .await?
-                {
-                    tree_nodes.push(node);
-                }
-            }
-        }
+        // A negative or missing max_depth means no depth limit
+        let max_depth = max_depth.and_then(|depth| usize::try_from(depth).ok());
+
+        // The store view goes first, so a branch the store deactivates is pruned too
+        let mut tree_nodes: Vec<_> = tree_cache
+            .tree
+            .values()
+            .filter_map(|root| match &store_id {
+                Some(store_id) => root.for_store(store_id),
+                None => Some(root.clone()),
+            })
+            .filter_map(|root| root.pruned(max_depth, include_inactive))
+            .map(|root| tree_node_to_proto(root, locale))
+            .collect();
 
         // Sort root nodes by name
         tree_nodes.sort_by(|a, b| a.name.cmp(&b.name));
@@ -270,67 +534,6 @@ impl CategoryService {
         Ok(tree_nodes)
     }
 
-    /// Helper method to build a CategoryTreeNode recursively from cache
-    fn build_tree_node_from_cache<'a>(
-        &'a self,
-        _tree_cache: &'a crate::domain::CategoryTreeCache,
-        category_id: &'a str,
-        cache_node: &'a crate::domain::CategoryTreeNode,
-        current_depth: i32,
-        max_depth: i32,
-        include_inactive: bool,
-    ) -> BuildNodeFut<'a> {
-        Box::pin(async move {
-            if max_depth >= 0 && current_depth >= max_depth {
-                return Ok(None);
-            }
-
-            // Get the full category details for active/inactive check
-            if let Some(category) = self.category_dao.get_category(category_id).await? {
-                // Skip inactive categories if not requested
-                if !include_inactive && !category.is_active {
-                    return Ok(None);
-                }
-
-                let mut children = Vec::new();
-
-                // Recursively build child nodes
-                for (child_id, child_cache_node) in &cache_node.children {
-                    if let Some(child_node) = self
-                        .build_tree_node_from_cache(
-                            _tree_cache,
-                            child_id,
-                            child_cache_node,
-                            current_depth + 1,
-                            max_depth,
-                            include_inactive,
-                        )
-                        .await?
-                    {
-                        children.push(child_node);
-                    }
-                }
-
-                // Sort children by name
-                children.sort_by(|a, b| a.name.cmp(&b.name));
-
-                let tree_node = crate::catalog_messages::CategoryTreeNode {
-                    id: category.id.unwrap_or_default(),
-                    name: category.name,
-                    slug: category.slug,
-                    path: category.path,
-                    level: category.level,
-                    product_count: category.product_count,
-                    children,
-                };
-
-                return Ok(Some(tree_node));
-            }
-
-            Ok(None)
-        })
-    }
-
     /// Update an existing category
     pub async fn update_category(
         &self,
@@ -350,8 +553,23 @@ impl CategoryService {
             .await?
             .ok_or("Category not found")?;
 
-        // Create updated category from request
-        let mut updated_category = existing_category.clone();
+        let id = request.id.clone();
+        let updated_category = self
+            .prepare_category_update(request, &existing_category)
+            .await?;
+        let category = self
+            .save_category_update(&id, existing_category, updated_category)
+            .await?;
+        Ok(self.category_to_response(category))
+    }
+
+    /// Apply an update request to `existing` and validate the result, without saving it
+    pub async fn prepare_category_update(
+        &self,
+        request: UpdateCategoryRequest,
+        existing: &Category,
+    ) -> Result<Category, Box<dyn std::error::Error + Send + Sync>> {
+        let mut updated_category = existing.clone();
 
         if let Some(name) = request.name {
             if !name.is_empty() {
@@ -372,11 +590,17 @@ impl CategoryService {
         // Handle slug update (regenerate if name changed or explicit slug provided)
         if let Some(new_slug) = request.slug {
             if !new_slug.is_empty() {
-                // Check if slug is already taken by another category
-                if let Some(existing_with_slug) =
-                    self.category_dao.get_category_by_slug(&new_slug).await?
-                {
-                    if existing_with_slug.id != Some(request.id.clone()) {
+                // Taken when held by a category, live or as a redirect; a
+                // category may take back a slug it retired
+                if new_slug != existing.slug && self.is_slug_taken(&new_slug).await? {
+                    let redirect = self
+                        .slug_history_dao
+                        .find_redirect(SlugEntityType::Category, &new_slug)
+                        .await?;
+                    let reclaimed = redirect
+                        .is_some_and(|redirect| redirect.entity_id == request.id)
+                        && !self.category_dao.slug_exists(&new_slug).await?;
+                    if !reclaimed {
                         return Err("Slug already exists".into());
                     }
                 }
@@ -399,17 +623,103 @@ impl CategoryService {
             updated_category.seo.keywords = seo_request.keywords;
         }
 
+        // Localizations are merged per locale; listed locales are dropped
+        for locale in &request.remove_locales {
+            let locale = normalize_locale(locale)
+                .ok_or_else(|| format!("'{locale}' is not a valid locale"))?;
+            updated_category.localizations.remove(&locale);
+        }
+        updated_category
+            .localizations
+            .extend(localizations_from_proto(request.localizations)?);
+        self.check_localized_slugs(&updated_category, Some(&request.id))
+            .await?;
+
+        // Store settings are merged the same way
+        for store_id in &request.remove_stores {
+            let store_id = normalize_store_id(store_id)
+                .ok_or_else(|| format!("'{store_id}' is not a valid store id"))?;
+            updated_category.stores.remove(&store_id);
+        }
+        updated_category
+            .stores
+            .extend(stores_from_proto(request.stores)?);
+        self.check_store_slugs(&updated_category, Some(&request.id))
+            .await?;
+
+        Ok(updated_category)
+    }
+
+    /// Replace a live category, keeping its slug redirects and the category
+    /// paths of listed products in step
+    pub async fn save_category_update(
+        &self,
+        id: &str,
+        existing_category: Category,
+        updated_category: Category,
+    ) -> Result<Category, Box<dyn std::error::Error + Send + Sync>> {
+        let refs_before = self
+            .refs_before_update(id, &existing_category, &updated_category)
+            .await?;
+
         // Update the category
         match self
             .category_dao
-            .update_category(&request.id, updated_category)
+            .update_category(id, updated_category)
             .await?
         {
-            Some(category) => Ok(self.category_to_response(category)),
+            Some(category) => {
+                self.after_category_saved(id, &existing_category, &category, refs_before)
+                    .await?;
+                Ok(category)
+            }
             None => Err("Failed to update category".into()),
         }
     }
 
+    /// The category's affected refs, taken before an update when the update
+    /// changes the paths of listed products: renames, slug changes and
+    /// (de)activation do
+    pub(crate) async fn refs_before_update(
+        &self,
+        id: &str,
+        existing_category: &Category,
+        updated_category: &Category,
+    ) -> Result<Option<Vec<String>>, Box<dyn std::error::Error + Send + Sync>> {
+        if existing_category.name != updated_category.name
+            || existing_category.slug != updated_category.slug
+            || existing_category.is_active != updated_category.is_active
+        {
+            Ok(Some(self.affected_category_refs(id).await?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Record the retired slug and re-derive product paths once an update to
+    /// the category has been written
+    pub(crate) async fn after_category_saved(
+        &self,
+        id: &str,
+        existing_category: &Category,
+        category: &Category,
+        refs_before: Option<Vec<String>>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if existing_category.slug != category.slug {
+            info!(
+                "Category {id} slug changed from '{}', recording redirect",
+                existing_category.slug
+            );
+            self.slug_history_dao
+                .record_retired_slug(SlugEntityType::Category, id, &existing_category.slug)
+                .await?;
+        }
+        if let Some(refs_before) = refs_before {
+            self.refresh_product_category_paths(id, refs_before).await;
+        }
+        Ok(())
+    }
+
     /// Delete a category
     pub async fn delete_category(
         &self,
@@ -437,7 +747,473 @@ impl CategoryService {
         // TODO: Check if category has products assigned
         // This would require integration with product service
 
-        self.category_dao.delete_category(id).await
+        let refs_before = self.affected_category_refs(id).await?;
+        let counts_before = self.snapshot_product_counts(&refs_before).await;
+        let deleted = self.category_dao.delete_category(id).await?;
+        if deleted {
+            self.recount_products(counts_before, &HashMap::new()).await;
+            self.refresh_product_category_paths(id, refs_before).await;
+        }
+        Ok(deleted)
+    }
+
+    /// Restore a soft-deleted category
+    pub async fn restore_category(
+        &self,
+        id: &str,
+    ) -> Result<Option<CategoryResponse>, Box<dyn std::error::Error + Send + Sync>> {
+        debug!("Restoring category with ID: {id}");
+
+        if id.is_empty() {
+            return Err("Category ID is required".into());
+        }
+
+        if !self.category_dao.restore_category(id).await? {
+            return Ok(None);
+        }
+        // Products listing the category counted without it while it was deleted
+        let refs = self.affected_category_refs(id).await?;
+        let mut counts_before = self.snapshot_product_counts(&refs).await;
+        for snapshot in &mut counts_before {
+            snapshot
+                .categories
+                .retain(|category| category.id.as_deref() != Some(id));
+        }
+        self.recount_products(counts_before, &HashMap::new()).await;
+        self.refresh_product_category_paths(id, Vec::new()).await;
+
+        self.get_category(id, None).await
+    }
+
+    /// Move a category under a new parent, or to the root when `new_parent_id`
+    /// is None. Returns false when the category does not exist.
+    pub async fn move_category(
+        &self,
+        category_id: &str,
+        new_parent_id: Option<&str>,
+    ) -> Result<bool, CategoryError> {
+        debug!("Moving category {category_id} under {new_parent_id:?}");
+
+        if category_id.is_empty() {
+            return Err(CategoryError::ValidationError(
+                "Category ID is required".to_string(),
+            ));
+        }
+
+        // Walk up from the new parent; reaching the category itself would create a cycle
+        let mut next_ancestor = new_parent_id.map(str::to_string);
+        while let Some(ancestor_id) = next_ancestor {
+            if ancestor_id == category_id {
+                return Err(CategoryError::ValidationError(
+                    "Cannot move a category under itself or one of its descendants".to_string(),
+                ));
+            }
+            let ancestor = self
+                .category_dao
+                .get_category(&ancestor_id)
+                .await
+                .map_err(|e| CategoryError::InternalError(e.to_string()))?
+                .ok_or_else(|| {
+                    CategoryError::NotFound(format!("Parent category {ancestor_id} not found"))
+                })?;
+            next_ancestor = ancestor.parent_id;
+        }
+
+        let refs_before = self
+            .affected_category_refs(category_id)
+            .await
+            .map_err(|e| CategoryError::InternalError(e.to_string()))?;
+        let counts_before = self.snapshot_product_counts(&refs_before).await;
+        let moved = self
+            .category_dao
+            .move_category(category_id, new_parent_id)
+            .await
+            .map_err(|e| CategoryError::InternalError(e.to_string()))?;
+        if moved {
+            self.recount_products(counts_before, &HashMap::new()).await;
+            self.refresh_product_category_paths(category_id, refs_before)
+                .await;
+        }
+
+        Ok(moved)
+    }
+
+    /// Fold `source_id` into `target_id`: move the source's children under the
+    /// target, rewrite products' list_categories entries for the source to the
+    /// target, redirect the source slug (and any slugs it retired) to the target,
+    /// then delete the source. A dry run only reports what would change.
+    ///
+    /// The merge is recorded before its first write and each step skips what is
+    /// already done, so rerunning a merge that failed part way finishes it; the
+    /// purge job does so for merges left unfinished. Until then the source cannot
+    /// be merged into any other category.
+    ///
+    /// A merge holds a lease on its source while it writes, so two requests,
+    /// or a request and the purge job, never work on the same merge at once.
+    pub async fn merge_categories(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        dry_run: bool,
+    ) -> Result<MergeReport, CategoryError> {
+        debug!("Merging category {source_id} into {target_id}, dry_run: {dry_run}");
+
+        if source_id.is_empty() || target_id.is_empty() {
+            return Err(CategoryError::ValidationError(
+                "Source and target category IDs are required".to_string(),
+            ));
+        }
+        if source_id == target_id {
+            return Err(CategoryError::ValidationError(
+                "Cannot merge a category into itself".to_string(),
+            ));
+        }
+        if dry_run {
+            return self.run_merge(source_id, target_id, None).await;
+        }
+
+        let owner = uuid::Uuid::new_v4().to_string();
+        self.claim_merge_lease(source_id, &owner).await?;
+        let result = self.run_merge(source_id, target_id, Some(&owner)).await;
+        if let Err(e) = self
+            .job_lease_dao
+            .release_lease(&merge_lease_job(source_id), &owner)
+            .await
+        {
+            error!("Failed to release the merge lease for category {source_id}: {e}");
+        }
+        result
+    }
+
+    /// Take, or renew, the lease on merging `source_id` for `owner`
+    async fn claim_merge_lease(&self, source_id: &str, owner: &str) -> Result<(), CategoryError> {
+        let lease = self
+            .job_lease_dao
+            .acquire_lease(
+                &merge_lease_job(source_id),
+                owner,
+                chrono::Utc::now(),
+                MERGE_LEASE_TTL,
+            )
+            .await
+            .map_err(|e| CategoryError::InternalError(e.to_string()))?;
+        match lease {
+            Some(_) => Ok(()),
+            None => Err(CategoryError::AlreadyExists(format!(
+                "Category {source_id} is already being merged"
+            ))),
+        }
+    }
+
+    /// The merge itself. `owner` holds the merge lease, which is renewed
+    /// before each step; a dry run takes no lease and passes None.
+    async fn run_merge(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        owner: Option<&str>,
+    ) -> Result<MergeReport, CategoryError> {
+        let dry_run = owner.is_none();
+        let internal = |e: Box<dyn std::error::Error + Send + Sync>| {
+            CategoryError::InternalError(e.to_string())
+        };
+
+        let unfinished = self
+            .category_merge_dao
+            .get_merge(source_id)
+            .await
+            .map_err(internal)?;
+        if let Some(merge) = &unfinished {
+            merge
+                .check_target(target_id)
+                .map_err(CategoryError::ValidationError)?;
+        }
+
+        let source = self
+            .category_dao
+            .get_category(source_id)
+            .await
+            .map_err(internal)?;
+        let source = match (source, unfinished) {
+            (Some(source), _) => source,
+            // An earlier attempt got as far as deleting the source
+            (None, Some(merge)) if !dry_run => {
+                return self.finish_merge(merge).await;
+            }
+            (None, _) => {
+                return Err(CategoryError::NotFound(format!(
+                    "Category {source_id} not found"
+                )))
+            }
+        };
+        let target = self
+            .category_dao
+            .get_category(target_id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| CategoryError::NotFound(format!("Category {target_id} not found")))?;
+        if target.ancestors.iter().any(|id| id == source_id) {
+            return Err(CategoryError::ValidationError(
+                "Cannot merge a category into one of its descendants".to_string(),
+            ));
+        }
+
+        let children = self
+            .category_dao
+            .get_children(source_id)
+            .await
+            .map_err(internal)?;
+        let source_refs = vec![source_id.to_string(), source.slug.clone()];
+        let products = self
+            .product_dao
+            .find_products_in_categories(&source_refs)
+            .await
+            .map_err(internal)?;
+
+        let report = MergeReport {
+            moved_subcategory_ids: children.iter().filter_map(|c| c.id.clone()).collect(),
+            affected_product_ids: products.iter().filter_map(|p| p.id.clone()).collect(),
+            redirected_slug: source.slug.clone(),
+        };
+        if dry_run {
+            return Ok(report);
+        }
+
+        let merge = self
+            .category_merge_dao
+            .start_merge(CategoryMerge::new(
+                source_id.to_string(),
+                target_id.to_string(),
+                source.slug.clone(),
+            ))
+            .await
+            .map_err(internal)?;
+        merge
+            .check_target(target_id)
+            .map_err(CategoryError::ValidationError)?;
+
+        let mut refs_before = self
+            .affected_category_refs(source_id)
+            .await
+            .map_err(internal)?;
+        refs_before.extend(
+            self.affected_category_refs(target_id)
+                .await
+                .map_err(internal)?,
+        );
+
+        // Each child moves, and each product is rewritten, together with its
+        // own count changes, so a failure between two of them loses no counts
+        for child_id in &report.moved_subcategory_ids {
+            if let Some(owner) = owner {
+                self.claim_merge_lease(source_id, owner).await?;
+            }
+            self.move_category(child_id, Some(target_id)).await?;
+        }
+
+        let at = publication_scheduler::publication_watermark(self.job_lease_dao.as_ref()).await;
+        for product in &products {
+            let Some(product_id) = product.id.clone() else {
+                continue;
+            };
+            if let Some(owner) = owner {
+                self.claim_merge_lease(source_id, owner).await?;
+            }
+            let list_categories =
+                merged_list_categories(&product.list_categories, &source, &target);
+            let counts_before = self.count_snapshot(product, at).await.map_err(internal)?;
+            self.product_dao
+                .set_list_categories(&product_id, &list_categories)
+                .await
+                .map_err(internal)?;
+            self.recount_products(
+                counts_before.into_iter().collect(),
+                &HashMap::from([(product_id, list_categories)]),
+            )
+            .await;
+        }
+
+        self.slug_history_dao
+            .reassign_redirects(SlugEntityType::Category, source_id, target_id)
+            .await
+            .map_err(internal)?;
+        self.slug_history_dao
+            .record_retired_slug(SlugEntityType::Category, target_id, &source.slug)
+            .await
+            .map_err(internal)?;
+
+        self.category_dao
+            .delete_category(source_id)
+            .await
+            .map_err(internal)?;
+        self.refresh_product_category_paths(target_id, refs_before)
+            .await;
+        self.category_merge_dao
+            .finish_merge(source_id)
+            .await
+            .map_err(internal)?;
+
+        info!(
+            "Merged category {source_id} into {target_id}: {} subcategories moved, {} products reassigned",
+            report.moved_subcategory_ids.len(),
+            report.affected_product_ids.len()
+        );
+        Ok(report)
+    }
+
+    /// Last steps of a merge whose source was already deleted by an earlier attempt
+    async fn finish_merge(&self, merge: CategoryMerge) -> Result<MergeReport, CategoryError> {
+        let internal = |e: Box<dyn std::error::Error + Send + Sync>| {
+            CategoryError::InternalError(e.to_string())
+        };
+
+        let refs = self
+            .affected_category_refs(&merge.target_id)
+            .await
+            .map_err(internal)?;
+        self.refresh_product_category_paths(&merge.target_id, refs)
+            .await;
+        self.category_merge_dao
+            .finish_merge(&merge.source_id)
+            .await
+            .map_err(internal)?;
+
+        info!(
+            "Finished merging category {} into {}",
+            merge.source_id, merge.target_id
+        );
+        Ok(MergeReport {
+            moved_subcategory_ids: Vec::new(),
+            affected_product_ids: Vec::new(),
+            redirected_slug: merge.source_slug,
+        })
+    }
+
+    /// Rerun merges that started more than `grace` ago and never finished.
+    /// Returns how many were completed.
+    pub async fn resume_unfinished_merges(
+        &self,
+        grace: chrono::Duration,
+    ) -> Result<usize, CategoryError> {
+        let merges = self
+            .category_merge_dao
+            .list_unfinished_merges(chrono::Utc::now() - grace)
+            .await
+            .map_err(|e| CategoryError::InternalError(e.to_string()))?;
+
+        let mut resumed = 0;
+        for merge in merges {
+            match self
+                .merge_categories(&merge.source_id, &merge.target_id, false)
+                .await
+            {
+                Ok(_) => resumed += 1,
+                // Still running, or being resumed, elsewhere
+                Err(CategoryError::AlreadyExists(e)) => debug!("Skipping merge: {e}"),
+                Err(e) => error!(
+                    "Failed to resume merge of category {} into {}: {e}",
+                    merge.source_id, merge.target_id
+                ),
+            }
+        }
+        Ok(resumed)
+    }
+
+    /// The categories each counted product listing any of `refs` counts towards
+    /// now, to compare against once the hierarchy has changed. Counts are
+    /// derived data, so a failure is logged and leaves them as they are.
+    async fn snapshot_product_counts(&self, refs: &[String]) -> Vec<CountSnapshot> {
+        let result = async {
+            let mut snapshots = Vec::new();
+            if refs.is_empty() {
+                return Ok(snapshots);
+            }
+            let at =
+                publication_scheduler::publication_watermark(self.job_lease_dao.as_ref()).await;
+            let products = self.product_dao.find_products_in_categories(refs).await?;
+            for product in products {
+                snapshots.extend(self.count_snapshot(&product, at).await?);
+            }
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(snapshots)
+        }
+        .await;
+
+        result.unwrap_or_else(|e| {
+            error!("Failed to snapshot category product counts: {e}");
+            Vec::new()
+        })
+    }
+
+    /// The categories one product counts towards at `at`, None when it does not count
+    async fn count_snapshot(
+        &self,
+        product: &Product,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<CountSnapshot>, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(product_id) = product.id.clone() else {
+            return Ok(None);
+        };
+        if !product.counts_towards_categories(at) {
+            return Ok(None);
+        }
+        let categories = self
+            .category_dao
+            .get_categories_by_refs(&product.list_categories)
+            .await?;
+        Ok(Some(CountSnapshot {
+            product_id,
+            list_categories: product.list_categories.clone(),
+            categories,
+        }))
+    }
+
+    /// Adjust category counts for the snapshotted products after a hierarchy
+    /// change. `new_lists` holds the list_categories of products the change
+    /// rewrote; the others keep the list they had.
+    async fn recount_products(
+        &self,
+        snapshots: Vec<CountSnapshot>,
+        new_lists: &HashMap<String, Vec<String>>,
+    ) {
+        let result = async {
+            let mut deltas = ProductCountDeltas::default();
+            for snapshot in snapshots {
+                let list_categories = new_lists
+                    .get(&snapshot.product_id)
+                    .unwrap_or(&snapshot.list_categories);
+                let after = self
+                    .category_dao
+                    .get_categories_by_refs(list_categories)
+                    .await?;
+                deltas.absorb(ProductCountDeltas::between(&snapshot.categories, &after));
+            }
+            if deltas.is_empty() {
+                return Ok(true);
+            }
+            self.category_dao.adjust_product_counts(&deltas).await
+        }
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to update category product counts: {e}");
+        }
+    }
+
+    /// Permanently delete categories that have been soft-deleted for longer than `retention`
+    pub async fn purge_deleted_categories(
+        &self,
+        retention: chrono::Duration,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let purged_ids = self
+            .category_dao
+            .purge_deleted_categories(chrono::Utc::now() - retention)
+            .await?;
+
+        self.slug_history_dao
+            .delete_redirects_for_entities(SlugEntityType::Category, &purged_ids)
+            .await?;
+
+        Ok(purged_ids.len())
     }
 
     /// Import multiple categories with hierarchical slug support and efficient batch processing
@@ -571,6 +1347,167 @@ impl CategoryService {
         })
     }
 
+    /// Import categories from a CSV of breadcrumb paths. Categories missing along
+    /// each path are created and existing ones, matched by slug, get the row's
+    /// non-blank cells. Nothing is written on a dry run or when any row has
+    /// errors; the report lists the planned changes either way.
+    pub async fn import_categories_csv(
+        &self,
+        csv: &str,
+        path_separator: Option<&str>,
+        dry_run: bool,
+    ) -> Result<CsvImportReport, CategoryError> {
+        let parsed = parse_category_csv(csv, path_separator.unwrap_or(DEFAULT_CSV_PATH_SEPARATOR))
+            .map_err(CategoryError::ValidationError)?;
+        let mut errors = parsed.errors;
+        debug!(
+            "Importing {} category rows from CSV, dry_run: {dry_run}",
+            parsed.rows.len()
+        );
+
+        let mut slugs = Vec::new();
+        for row in &parsed.rows {
+            for depth in 1..=row.names.len() {
+                let slug = category_path_slug(&row.names[..depth]);
+                if !slugs.contains(&slug) {
+                    slugs.push(slug);
+                }
+            }
+        }
+        let mut existing = HashMap::new();
+        for slug in slugs {
+            let category = self
+                .category_dao
+                .get_category_by_slug(&slug)
+                .await
+                .map_err(|e| {
+                    CategoryError::InternalError(format!("Failed to look up category: {e}"))
+                })?;
+            if let Some(category) = category {
+                existing.insert(slug, category);
+            }
+        }
+
+        let (mut changes, plan_errors) = plan_category_import(&parsed.rows, &existing);
+        errors.extend(plan_errors);
+        if dry_run || !errors.is_empty() {
+            return Ok(CsvImportReport {
+                changes,
+                errors,
+                applied: false,
+            });
+        }
+
+        let mut id_by_slug: HashMap<String, String> = existing
+            .iter()
+            .filter_map(|(slug, category)| Some((slug.clone(), category.id.clone()?)))
+            .collect();
+        let mut written = false;
+        for change in &mut changes {
+            let fields = &change.fields;
+            match change.action {
+                CategoryImportAction::Unchanged => {}
+                CategoryImportAction::Create => {
+                    let parent_id = match &change.parent_slug {
+                        Some(parent_slug) => match id_by_slug.get(parent_slug) {
+                            Some(parent_id) => Some(parent_id.clone()),
+                            None => {
+                                errors.push(format!(
+                                    "Skipped '{}': its parent was not created",
+                                    change.path
+                                ));
+                                continue;
+                            }
+                        },
+                        None => None,
+                    };
+                    let short_description = fields.short_description.clone().unwrap_or_default();
+                    let seo = fields.has_seo().then(|| {
+                        let defaults =
+                            CategorySeo::default_for_category(&change.name, &short_description);
+                        catalog_messages::CategorySeo {
+                            meta_title: fields.meta_title.clone().or(defaults.meta_title),
+                            meta_description: fields
+                                .meta_description
+                                .clone()
+                                .or(defaults.meta_description),
+                            keywords: fields.keywords.clone().unwrap_or(defaults.keywords),
+                        }
+                    });
+                    let request = CreateCategoryRequest {
+                        name: change.name.clone(),
+                        slug: change.slug.clone(),
+                        short_description,
+                        full_description: fields.full_description.clone(),
+                        parent_id,
+                        display_order: fields.display_order.unwrap_or(0),
+                        seo,
+                        is_active: Some(true),
+                        parent_slug: None,
+                        localizations: HashMap::new(),
+                        stores: HashMap::new(),
+                    };
+
+                    match self.create_category_internal(request, false).await {
+                        Ok(created) => {
+                            id_by_slug.insert(change.slug.clone(), created.id.clone());
+                            change.category_id = Some(created.id);
+                            written = true;
+                        }
+                        Err(e) => {
+                            errors.push(format!("Failed to create '{}': {e}", change.path));
+                        }
+                    }
+                }
+                CategoryImportAction::Update => {
+                    let category = &existing[&change.slug];
+                    let seo = fields.has_seo().then(|| catalog_messages::CategorySeo {
+                        meta_title: fields
+                            .meta_title
+                            .clone()
+                            .or(category.seo.meta_title.clone()),
+                        meta_description: fields
+                            .meta_description
+                            .clone()
+                            .or(category.seo.meta_description.clone()),
+                        keywords: fields
+                            .keywords
+                            .clone()
+                            .unwrap_or(category.seo.keywords.clone()),
+                    });
+                    let request = UpdateCategoryRequest {
+                        id: change.category_id.clone().unwrap_or_default(),
+                        name: Some(change.name.clone()),
+                        short_description: fields.short_description.clone(),
+                        full_description: fields.full_description.clone(),
+                        display_order: fields.display_order,
+                        seo,
+                        ..Default::default()
+                    };
+
+                    match self.update_category(request).await {
+                        Ok(_) => written = true,
+                        Err(e) => {
+                            errors.push(format!("Failed to update '{}': {e}", change.path));
+                        }
+                    }
+                }
+            }
+        }
+
+        if written {
+            if let Err(e) = self.category_dao.invalidate_tree_cache().await {
+                debug!("Warning: Failed to invalidate tree cache after import: {e}",);
+            }
+        }
+
+        Ok(CsvImportReport {
+            changes,
+            errors,
+            applied: true,
+        })
+    }
+
     /// Sort categories by dependency order (parents before children)
     fn sort_categories_by_dependency(
         &self,
@@ -669,7 +1606,7 @@ impl CategoryService {
     }
 
     /// Helper method to convert Category model to CategoryResponse
-    fn category_to_response(&self, category: Category) -> CategoryResponse {
+    pub(crate) fn category_to_response(&self, category: Category) -> CategoryResponse {
         CategoryResponse {
             id: category.id.unwrap_or_default(),
             slug: category.slug,
@@ -682,6 +1619,7 @@ impl CategoryService {
             level: category.level,
             children_count: category.children_count,
             product_count: category.product_count,
+            subtree_product_count: category.subtree_product_count,
             is_active: category.is_active,
             display_order: category.display_order,
             seo: Some(crate::catalog_messages::CategorySeo {
@@ -697,10 +1635,124 @@ impl CategoryService {
                 seconds: category.updated_at.timestamp(),
                 nanos: category.updated_at.timestamp_subsec_nanos() as i32,
             }),
+            localizations: category
+                .localizations
+                .into_iter()
+                .map(|(locale, content)| {
+                    (
+                        locale,
+                        catalog_messages::LocalizedCategoryContent {
+                            name: content.name,
+                            short_description: content.short_description,
+                            full_description: content.full_description,
+                            slug: content.slug,
+                            seo: content.seo.map(|seo| catalog_messages::CategorySeo {
+                                meta_title: seo.meta_title,
+                                meta_description: seo.meta_description,
+                                keywords: seo.keywords,
+                            }),
+                        },
+                    )
+                })
+                .collect(),
+            stores: category
+                .stores
+                .into_iter()
+                .map(|(store_id, settings)| {
+                    (
+                        store_id,
+                        catalog_messages::CategoryStoreSettings {
+                            is_active: settings.is_active,
+                            slug: settings.slug,
+                        },
+                    )
+                })
+                .collect(),
         }
     }
 }
 
+/// Proto tree node for a pruned cache node, localized and with children sorted by name
+fn tree_node_to_proto(
+    node: CategoryTreeNode,
+    locale: Option<&str>,
+) -> catalog_messages::CategoryTreeNode {
+    let node = match locale {
+        Some(locale) => node.localized(locale),
+        None => node,
+    };
+
+    let mut children: Vec<_> = node
+        .children
+        .into_values()
+        .map(|child| tree_node_to_proto(child, locale))
+        .collect();
+    children.sort_by(|a, b| a.name.cmp(&b.name));
+
+    catalog_messages::CategoryTreeNode {
+        id: node.id,
+        name: node.name,
+        slug: node.slug,
+        path: node.path,
+        level: node.level,
+        product_count: node.product_count,
+        subtree_product_count: node.subtree_product_count,
+        children,
+    }
+}
+
+fn localize_category(category: Category, locale: Option<&str>) -> Category {
+    match locale {
+        Some(locale) => category.localized(locale),
+        None => category,
+    }
+}
+
+fn localizations_from_proto(
+    localizations: HashMap<String, catalog_messages::LocalizedCategoryContent>,
+) -> Result<HashMap<String, LocalizedCategoryContent>, String> {
+    let localizations = localizations
+        .into_iter()
+        .map(|(locale, content)| {
+            (
+                locale,
+                LocalizedCategoryContent {
+                    name: content.name,
+                    short_description: content.short_description,
+                    full_description: content.full_description,
+                    slug: content.slug,
+                    seo: content.seo.map(|seo| CategorySeo {
+                        meta_title: seo.meta_title,
+                        meta_description: seo.meta_description,
+                        keywords: seo.keywords,
+                    }),
+                },
+            )
+        })
+        .collect();
+
+    normalize_localizations(localizations).map_err(|e| format!("Invalid localizations: {e}"))
+}
+
+fn stores_from_proto(
+    stores: HashMap<String, catalog_messages::CategoryStoreSettings>,
+) -> Result<HashMap<String, CategoryStoreSettings>, String> {
+    let stores = stores
+        .into_iter()
+        .map(|(store_id, settings)| {
+            (
+                store_id,
+                CategoryStoreSettings {
+                    is_active: settings.is_active,
+                    slug: settings.slug.filter(|slug| !slug.is_empty()),
+                },
+            )
+        })
+        .collect();
+
+    normalize_stores(stores).map_err(|e| format!("Invalid stores: {e}"))
+}
+
 #[cfg(test)]
 mod tests {
     // Note: These tests would require a MongoDB test instance
@@ -0,0 +1,73 @@
+use crate::catalog_messages::CategoryTreeInvalidatedEvent;
+use crate::persistence::category_tree_memory::CategoryTreeMemory;
+use async_nats::Client as NatsClient;
+use chrono::Utc;
+use futures::StreamExt;
+use log::{debug, error, warn};
+use prost::Message;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+/// Keep this instance's in-memory category tree in step with the others.
+/// Invalidations made here are announced as a CategoryTreeInvalidatedEvent and
+/// events from other instances drop the local copy. Every instance subscribes
+/// outside the request queue group, so each one hears every event. Runs until
+/// the service shuts down.
+pub async fn spawn_category_tree_sync(
+    nats_client: NatsClient,
+    memory: Arc<CategoryTreeMemory>,
+) -> Result<(), async_nats::SubscribeError> {
+    let subject = crate::nats_config::events::published::CATEGORY_TREE_INVALIDATED;
+    let instance_id = uuid::Uuid::new_v4().to_string();
+    let mut remote_invalidations = nats_client.subscribe(subject).await?;
+    let mut local_invalidations = memory.subscribe();
+
+    let own_id = instance_id.clone();
+    let remote_memory = memory.clone();
+    tokio::spawn(async move {
+        while let Some(message) = remote_invalidations.next().await {
+            match CategoryTreeInvalidatedEvent::decode(&*message.payload) {
+                Ok(event) if event.instance_id == own_id => {}
+                Ok(event) => {
+                    debug!(
+                        "Dropping in-memory category tree after change on {}",
+                        event.instance_id
+                    );
+                    remote_memory.discard();
+                }
+                Err(e) => {
+                    // Still drop the copy: a change happened somewhere
+                    warn!("Invalid category tree invalidation event: {e}");
+                    remote_memory.discard();
+                }
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        loop {
+            match local_invalidations.recv().await {
+                // Missed notifications all stand for the same thing, one event covers them
+                Ok(()) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+
+            let now = Utc::now();
+            let event = CategoryTreeInvalidatedEvent {
+                instance_id: instance_id.clone(),
+                invalidated_at: Some(prost_types::Timestamp {
+                    seconds: now.timestamp(),
+                    nanos: now.timestamp_subsec_nanos() as i32,
+                }),
+            };
+            if let Err(e) = nats_client
+                .publish(subject, event.encode_to_vec().into())
+                .await
+            {
+                error!("Failed to announce category tree invalidation: {e}");
+            }
+        }
+    });
+
+    Ok(())
+}
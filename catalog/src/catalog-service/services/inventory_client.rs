@@ -0,0 +1,105 @@
+use crate::catalog_messages::Code;
+use crate::inventory_messages::{
+    InventoryCreateRequest, InventoryCreateResponse, InventoryGetAllLocationsBySkuRequest,
+    InventoryGetAllLocationsBySkuResponse,
+};
+use async_nats::Client;
+use log::{error, warn};
+use prost::Message;
+use std::collections::HashMap;
+
+/// inventory-service subject that creates a stock record for a SKU and location
+pub const INVENTORY_CREATE_ITEM_SUBJECT: &str = "inventory.create_item";
+
+/// inventory-service subject that sums stock across all locations for a batch of SKUs
+pub const INVENTORY_GET_ALL_LOCATIONS_BY_SKU_SUBJECT: &str = "inventory.get_all_locations_by_sku";
+
+/// Most SKUs inventory-service accepts in one lookup
+pub const MAX_INVENTORY_LOOKUP_SKUS: usize = 100;
+
+/// Location used for seeded records when the caller does not name one
+pub const DEFAULT_INVENTORY_LOCATION: &str = "default";
+
+/// Create a zero-quantity inventory record for each SKU at `location`.
+/// Seeding is best-effort: returns the SKUs inventory-service did not accept
+/// (including ones that already had a record there) instead of failing.
+pub async fn seed_zero_inventory(client: &Client, skus: &[String], location: &str) -> Vec<String> {
+    let mut failed = Vec::new();
+
+    for sku in skus {
+        let request = InventoryCreateRequest {
+            sku: sku.clone(),
+            quantity: 0,
+            reserved_quantity: 0,
+            min_stock_level: 0,
+            location: location.to_string(),
+        };
+
+        let response = match client
+            .request(
+                INVENTORY_CREATE_ITEM_SUBJECT,
+                request.encode_to_vec().into(),
+            )
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                error!("Failed to seed inventory for {sku}: {e}");
+                failed.push(sku.clone());
+                continue;
+            }
+        };
+
+        match InventoryCreateResponse::decode(&*response.payload) {
+            Ok(InventoryCreateResponse { item: Some(_), .. }) => {}
+            Ok(InventoryCreateResponse { status, .. }) => {
+                let message = status.map(|s| s.message).unwrap_or_default();
+                warn!("Inventory not seeded for {sku}: {message}");
+                failed.push(sku.clone());
+            }
+            Err(e) => {
+                error!("Invalid inventory response for {sku}: {e}");
+                failed.push(sku.clone());
+            }
+        }
+    }
+
+    failed
+}
+
+/// Available quantity across all locations for each SKU, looked up in batches of
+/// MAX_INVENTORY_LOOKUP_SKUS. SKUs without any inventory record are left out of
+/// the map. Fails if inventory-service cannot be reached or rejects a batch.
+pub async fn get_available_quantities(
+    client: &Client,
+    skus: &[String],
+) -> Result<HashMap<String, i32>, Box<dyn std::error::Error>> {
+    let mut available = HashMap::new();
+
+    for batch in skus.chunks(MAX_INVENTORY_LOOKUP_SKUS) {
+        let request = InventoryGetAllLocationsBySkuRequest {
+            skus: batch.to_vec(),
+        };
+        let response = client
+            .request(
+                INVENTORY_GET_ALL_LOCATIONS_BY_SKU_SUBJECT,
+                request.encode_to_vec().into(),
+            )
+            .await?;
+        let response = InventoryGetAllLocationsBySkuResponse::decode(&*response.payload)?;
+
+        if let Some(status) = response.status.filter(|s| s.code != Code::Ok as i32) {
+            return Err(format!("inventory lookup failed: {}", status.message).into());
+        }
+
+        for summary in response.sku_summaries {
+            let quantity = summary
+                .total_inventory
+                .map(|total| total.total_available_quantity)
+                .unwrap_or(0);
+            available.insert(summary.sku, quantity);
+        }
+    }
+
+    Ok(available)
+}
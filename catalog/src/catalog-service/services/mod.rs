@@ -1,2 +1,11 @@
+pub mod catalog_publishing_service;
 pub mod category_service;
+pub mod category_tree_sync;
+pub mod inventory_client;
+pub mod price_client;
 pub mod product_service;
+pub mod publication_scheduler;
+pub mod purge_job;
+pub mod review_summary_job;
+pub mod sitemap_service;
+pub mod structured_data_service;
@@ -0,0 +1,63 @@
+use crate::catalog_messages::Code;
+use crate::offer_messages::{GetBestOfferPricesRequest, GetBestOfferPricesResponse};
+use async_nats::Client;
+use prost::Message;
+use std::collections::HashMap;
+
+/// price-service subject that finds the best current offer for a batch of SKUs
+pub const OFFERS_GET_BEST_OFFER_PRICES_SUBJECT: &str = "offers.get_best_offer_prices";
+
+/// Most SKUs price-service accepts in one lookup
+pub const MAX_PRICE_LOOKUP_SKUS: usize = 100;
+
+/// Best single-unit offer price in `currency` for each SKU, looked up in batches
+/// of MAX_PRICE_LOOKUP_SKUS. Prices are returned as price-service formats them,
+/// e.g. "19.99". SKUs without an offer, or whose offer has no price in
+/// `currency`, are left out of the map. Fails if price-service cannot be
+/// reached or rejects a batch.
+pub async fn get_best_offer_prices(
+    client: &Client,
+    skus: &[String],
+    currency: &str,
+) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+    let mut prices = HashMap::new();
+
+    for batch in skus.chunks(MAX_PRICE_LOOKUP_SKUS) {
+        let request = GetBestOfferPricesRequest {
+            skus: batch.to_vec(),
+            quantity: 1,
+            date: None,
+            currency: currency.to_string(),
+            price_list_context: None,
+        };
+        let response = client
+            .request(
+                OFFERS_GET_BEST_OFFER_PRICES_SUBJECT,
+                request.encode_to_vec().into(),
+            )
+            .await?;
+        let response = GetBestOfferPricesResponse::decode(&*response.payload)?;
+
+        if let Some(status) = response.status.filter(|s| s.code != Code::Ok as i32) {
+            return Err(format!("price lookup failed: {}", status.message).into());
+        }
+
+        for result in response.sku_results {
+            let price = result
+                .offer
+                .filter(|_| result.found)
+                .and_then(|offer| {
+                    offer
+                        .offer_prices
+                        .into_iter()
+                        .find(|price| price.currency == currency)
+                })
+                .map(|price| price.price);
+            if let Some(price) = price {
+                prices.insert(result.sku, price);
+            }
+        }
+    }
+
+    Ok(prices)
+}
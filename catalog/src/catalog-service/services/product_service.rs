@@ -1,12 +1,65 @@
-use crate::catalog_messages::{ProductCreateRequest, ProductUpdateRequest};
+use crate::catalog_messages::{
+    self, CloneProductRequest, CompareProductsRequest, ProductCreateRequest, ProductSearchRequest,
+    ProductUpdateRequest,
+};
 use crate::domain::{
-    HierarchicalCategories, Packaging, Product, ProductBuilder, ProductName, ProductRef,
-    ProductVariant, Reviews,
+    bidirectional_changes, clone_product, compare_products, generate_variant_matrix,
+    locale_fallback_chain,
+    localization::normalize_localizations,
+    normalize_store_id,
+    slug::{slug_with_suffix, MAX_SLUG_SUFFIX},
+    slugify,
+    store::normalize_stores,
+    AttributeSchema, BundleComponent, BundlePricing, Category, CategoryTreeNode, CloneSpec,
+    HierarchicalCategories, LengthUnit, LocalizedProductContent, MassUnit, Packaging, Product,
+    ProductBuilder, ProductBundle, ProductComparison, ProductCountDeltas, ProductName, ProductRef,
+    ProductRelation, ProductRevision, ProductStoreSettings, ProductVariant, RelationKind, Review,
+    ReviewPrior, ReviewStatus, Reviews, SkuRewrite, SlugEntityType, SlugLookup, VariantAxis,
+    VariantMatrix, MAX_COMPARED_PRODUCTS,
+};
+use crate::persistence::{
+    attribute_schema_dao::AttributeSchemaDao,
+    category_dao::CategoryDao,
+    job_lease_dao::JobLeaseDao,
+    product_dao::{ProductDao, ProductSearchFilter},
+    product_revision_dao::ProductRevisionDao,
+    review_dao::ReviewDao,
+    slug_history_dao::SlugHistoryDao,
 };
-use crate::persistence::product_dao::ProductDao;
-use log::{debug, error};
+use crate::services::{inventory_client, price_client, publication_scheduler};
+use async_nats::Client;
+use chrono::{DateTime, Utc};
+use log::{debug, error, info};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::error::Error;
+use std::str::FromStr;
 use std::sync::Arc;
 
+/// Revisions returned by list_product_revisions when the caller sets no limit
+const DEFAULT_REVISION_PAGE_SIZE: i64 = 50;
+
+/// Reviews returned by list_reviews when the caller sets no limit
+const DEFAULT_REVIEW_PAGE_SIZE: i64 = 50;
+
+/// What one bundle component contributes to a bundle quote
+#[derive(Debug)]
+pub struct BundleComponentQuote {
+    pub sku: String,
+    pub quantity: i32,
+    pub available: i32,
+    pub unit_price: Option<String>, // Best offer, None when price-service has none
+}
+
+/// How many of a bundle can be sold right now, and for how much
+#[derive(Debug)]
+pub struct BundleQuote {
+    pub available_quantity: i32,
+    pub price: Option<Decimal>, // None when a price it depends on is missing
+    pub pricing: BundlePricing,
+    pub components: Vec<BundleComponentQuote>,
+}
+
 #[derive(Debug)]
 pub enum HandlerError {
     InternalError(String),
@@ -17,11 +70,601 @@ pub enum HandlerError {
 
 pub struct ProductService {
     product_dao: Arc<dyn ProductDao + Send + Sync>,
+    slug_history_dao: Arc<dyn SlugHistoryDao + Send + Sync>,
+    revision_dao: Arc<dyn ProductRevisionDao + Send + Sync>,
+    attribute_schema_dao: Arc<dyn AttributeSchemaDao + Send + Sync>,
+    review_dao: Arc<dyn ReviewDao + Send + Sync>,
+    category_dao: Arc<dyn CategoryDao + Send + Sync>,
+    job_lease_dao: Arc<dyn JobLeaseDao + Send + Sync>,
 }
 
 impl ProductService {
-    pub fn new(product_dao: Arc<dyn ProductDao + Send + Sync>) -> Self {
-        Self { product_dao }
+    pub fn new(
+        product_dao: Arc<dyn ProductDao + Send + Sync>,
+        slug_history_dao: Arc<dyn SlugHistoryDao + Send + Sync>,
+        revision_dao: Arc<dyn ProductRevisionDao + Send + Sync>,
+        attribute_schema_dao: Arc<dyn AttributeSchemaDao + Send + Sync>,
+        review_dao: Arc<dyn ReviewDao + Send + Sync>,
+        category_dao: Arc<dyn CategoryDao + Send + Sync>,
+        job_lease_dao: Arc<dyn JobLeaseDao + Send + Sync>,
+    ) -> Self {
+        Self {
+            product_dao,
+            slug_history_dao,
+            revision_dao,
+            attribute_schema_dao,
+            review_dao,
+            category_dao,
+            job_lease_dao,
+        }
+    }
+
+    /// Keep category product counts in step with a product write. `before` is
+    /// the product as it was and `after` as it now is, None when it did not or
+    /// no longer exists. Publishing windows are evaluated as of the scheduler's
+    /// watermark; crossings since then are left to the scheduler.
+    async fn update_category_counts(&self, before: Option<&Product>, after: Option<&Product>) {
+        let at = publication_scheduler::publication_watermark(self.job_lease_dao.as_ref()).await;
+        let counted = |product: Option<&Product>| {
+            product
+                .filter(|p| p.counts_towards_categories(at))
+                .map(|p| p.list_categories.clone())
+                .unwrap_or_default()
+        };
+        self.adjust_category_counts(counted(before), counted(after))
+            .await;
+    }
+
+    /// Recount, as of `at`, the categories `products` are listed in and their
+    /// ancestors. Counts are written as totals rather than adjusted, so
+    /// recounting the same products again leaves them as they are. Counts are
+    /// derived data, so failures are logged.
+    pub async fn recount_categories_of(&self, products: &[Product], at: DateTime<Utc>) {
+        let result = async {
+            let mut refs: Vec<String> = products
+                .iter()
+                .flat_map(|product| product.list_categories.iter().cloned())
+                .collect();
+            refs.sort();
+            refs.dedup();
+            let categories = self.category_dao.get_categories_by_refs(&refs).await?;
+            let mut ids: Vec<String> = categories
+                .into_iter()
+                .flat_map(|category| category.id.into_iter().chain(category.ancestors))
+                .collect();
+            ids.sort();
+            ids.dedup();
+
+            let tree = self.category_dao.get_or_rebuild_tree().await?;
+            let mut counts = HashMap::new();
+            for id in ids {
+                let Some(node) = CategoryTreeNode::find(&tree.tree, &id) else {
+                    continue;
+                };
+                let direct = self
+                    .product_dao
+                    .count_products_counted_in(&[node.id.clone(), node.slug.clone()], at)
+                    .await?;
+                let subtree = self
+                    .product_dao
+                    .count_products_counted_in(&node.subtree_refs(), at)
+                    .await?;
+                counts.insert(id, (direct as i32, subtree as i32));
+            }
+            self.category_dao.set_product_counts(&counts).await
+        }
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to recount category product counts: {e}");
+        }
+    }
+
+    /// Move counts from the categories listed in `before` to those in `after`.
+    /// Counts are derived data, so failures are logged rather than failing the
+    /// write that caused them.
+    async fn adjust_category_counts(&self, before: Vec<String>, after: Vec<String>) {
+        if before == after {
+            return;
+        }
+
+        let result = async {
+            let before = self.counted_categories(&before).await?;
+            let after = self.counted_categories(&after).await?;
+            self.category_dao
+                .adjust_product_counts(&ProductCountDeltas::between(&before, &after))
+                .await
+        }
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to update category product counts: {e}");
+        }
+    }
+
+    /// Category paths for `list_categories`, derived from the category tree
+    async fn derive_hierarchical_categories(
+        &self,
+        list_categories: &[String],
+    ) -> Result<Option<HierarchicalCategories>, HandlerError> {
+        if list_categories.is_empty() {
+            return Ok(None);
+        }
+
+        let tree = self.category_dao.get_or_rebuild_tree().await.map_err(|e| {
+            error!("Error getting category tree: {e}");
+            HandlerError::InternalError(format!("Failed to get category tree: {e}"))
+        })?;
+        Ok(HierarchicalCategories::from_tree(
+            &tree.tree,
+            list_categories,
+        ))
+    }
+
+    async fn counted_categories(
+        &self,
+        list_categories: &[String],
+    ) -> Result<Vec<Category>, Box<dyn Error + Send + Sync>> {
+        self.category_dao
+            .get_categories_by_refs(list_categories)
+            .await
+    }
+
+    /// Check the product's attributes against the schema registered for its
+    /// product_type. Product types without a schema accept any attributes.
+    async fn validate_attributes(&self, product: &Product) -> Result<(), HandlerError> {
+        let Some(product_type) = product.product_type.as_deref() else {
+            return Ok(());
+        };
+
+        let schema = self
+            .attribute_schema_dao
+            .get_schema(product_type)
+            .await
+            .map_err(|e| {
+                error!("Error getting attribute schema: {e}");
+                HandlerError::InternalError(format!("Failed to get attribute schema: {e}"))
+            })?;
+        let Some(schema) = schema else {
+            return Ok(());
+        };
+
+        let violations = schema.validate(product);
+        if violations.is_empty() {
+            return Ok(());
+        }
+
+        let details: Vec<String> = violations.iter().map(ToString::to_string).collect();
+        Err(HandlerError::ValidationError(format!(
+            "Invalid attributes for product type '{product_type}': {}",
+            details.join("; ")
+        )))
+    }
+
+    /// Check a bundle's components: each must be a variant SKU of another live
+    /// product that is not itself a bundle. A product whose SKUs are components
+    /// of some other bundle cannot become a bundle, so kits never nest.
+    async fn validate_bundle(
+        &self,
+        product: &Product,
+        product_id: Option<&str>,
+    ) -> Result<(), HandlerError> {
+        let Some(bundle) = &product.bundle else {
+            return Ok(());
+        };
+
+        let own_skus: Vec<String> = product.variants.iter().map(|v| v.sku.clone()).collect();
+        bundle
+            .validate(&own_skus)
+            .map_err(|e| HandlerError::ValidationError(format!("Invalid bundle: {e}")))?;
+
+        let is_other = |p: &Product| p.id.is_none() || p.id.as_deref() != product_id;
+        let component_skus = bundle.component_skus();
+        let owners = self
+            .product_dao
+            .find_products_by_skus(&component_skus)
+            .await
+            .map_err(|e| {
+                error!("Error looking up bundle components: {e}");
+                HandlerError::InternalError(format!("Failed to look up bundle components: {e}"))
+            })?;
+        for sku in &component_skus {
+            let owner = owners
+                .iter()
+                .filter(|owner| is_other(owner))
+                .find(|owner| owner.variants.iter().any(|v| &v.sku == sku));
+            match owner {
+                None => {
+                    return Err(HandlerError::ValidationError(format!(
+                        "Bundle component {sku} is not a SKU of any product"
+                    )))
+                }
+                Some(owner) if owner.bundle.is_some() => {
+                    return Err(HandlerError::ValidationError(format!(
+                        "Bundle component {sku} is itself a bundle"
+                    )))
+                }
+                Some(_) => {}
+            }
+        }
+
+        let containing = self
+            .product_dao
+            .find_bundles_with_components(&own_skus)
+            .await
+            .map_err(|e| {
+                error!("Error looking up bundles containing product SKUs: {e}");
+                HandlerError::InternalError(format!("Failed to look up bundles: {e}"))
+            })?;
+        if let Some(other) = containing.iter().find(|other| is_other(other)) {
+            return Err(HandlerError::ValidationError(format!(
+                "Product is a component of bundle {} and cannot be a bundle itself",
+                other.product_ref
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Every product linked from related_products or relations must exist,
+    /// not be deleted and not be the product itself
+    async fn validate_relations(&self, product: &Product) -> Result<(), HandlerError> {
+        let ids = product.related_ids();
+        if ids.is_empty() {
+            return Ok(());
+        }
+        if let Some(own_id) = product.id.as_ref().filter(|id| ids.contains(id)) {
+            return Err(HandlerError::ValidationError(format!(
+                "Product {own_id} cannot be related to itself"
+            )));
+        }
+
+        let found = self
+            .product_dao
+            .find_products_by_ids(&ids)
+            .await
+            .map_err(|e| {
+                error!("Error looking up related products: {e}");
+                HandlerError::InternalError(format!("Failed to look up related products: {e}"))
+            })?;
+        let missing: Vec<&str> = ids
+            .iter()
+            .filter(|id| !found.iter().any(|p| p.id.as_ref() == Some(*id)))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(HandlerError::ValidationError(format!(
+                "Related products not found: {}",
+                missing.join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    /// Keep the other side of bidirectional relations in step with a write.
+    /// Like category counts this is derived data, so failures are logged
+    /// rather than failing the write.
+    async fn mirror_relations(
+        &self,
+        product_id: &str,
+        before: &[ProductRelation],
+        after: &[ProductRelation],
+    ) {
+        let (added, removed) = bidirectional_changes(before, after);
+        for relation in added {
+            let reverse = ProductRelation {
+                product_id: product_id.to_string(),
+                kind: relation.kind,
+                bidirectional: true,
+            };
+            if let Err(e) = self
+                .product_dao
+                .add_relation(&relation.product_id, &reverse)
+                .await
+            {
+                error!(
+                    "Failed to link product {} back to {product_id}: {e}",
+                    relation.product_id
+                );
+            }
+        }
+        for relation in removed {
+            if let Err(e) = self
+                .product_dao
+                .remove_relation(&relation.product_id, product_id, relation.kind)
+                .await
+            {
+                error!(
+                    "Failed to unlink product {} from {product_id}: {e}",
+                    relation.product_id
+                );
+            }
+        }
+    }
+
+    /// Localized slugs share the namespace of their locale with the default
+    /// slugs, so `/fr/veste` can never resolve to two products
+    async fn check_localized_slugs(
+        &self,
+        product: &Product,
+        exclude_id: Option<&str>,
+    ) -> Result<(), HandlerError> {
+        for (locale, content) in &product.localizations {
+            let Some(slug) = content.slug.as_deref() else {
+                continue;
+            };
+
+            let taken = self
+                .product_dao
+                .localized_slug_taken(slug, locale, exclude_id)
+                .await
+                .map_err(|e| HandlerError::InternalError(format!("Failed to check slug: {e}")))?;
+            if taken {
+                return Err(HandlerError::AlreadyExists(format!(
+                    "Slug '{slug}' is already used for locale '{locale}'"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Store slugs share the namespace of their store with the default slugs
+    async fn check_store_slugs(
+        &self,
+        product: &Product,
+        exclude_id: Option<&str>,
+    ) -> Result<(), HandlerError> {
+        for (store_id, settings) in &product.stores {
+            let Some(slug) = settings.slug.as_deref() else {
+                continue;
+            };
+
+            let taken = self
+                .product_dao
+                .store_slug_taken(slug, store_id, exclude_id)
+                .await
+                .map_err(|e| HandlerError::InternalError(format!("Failed to check slug: {e}")))?;
+            if taken {
+                return Err(HandlerError::AlreadyExists(format!(
+                    "Slug '{slug}' is already used in store '{store_id}'"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Append a snapshot of `saved` to the product's revision history. Products
+    /// created before history was tracked get `previous` stored as their baseline.
+    /// History is best-effort and never fails the write that triggered it.
+    async fn record_revision(
+        &self,
+        product_id: &str,
+        previous: Option<&Product>,
+        saved: &Product,
+        reverted_from: Option<i64>,
+    ) {
+        let result: Result<(), Box<dyn std::error::Error + Send + Sync>> = async {
+            let mut latest = self.revision_dao.latest_revision_number(product_id).await?;
+
+            if let (0, Some(previous)) = (latest, previous) {
+                latest = 1;
+                let changed_by = previous
+                    .updated_by
+                    .clone()
+                    .or_else(|| previous.created_by.clone());
+                self.revision_dao
+                    .create_revision(ProductRevision::new(
+                        product_id.to_string(),
+                        latest,
+                        previous.clone(),
+                        changed_by,
+                    ))
+                    .await?;
+            }
+
+            let changed_by = saved
+                .updated_by
+                .clone()
+                .or_else(|| saved.created_by.clone());
+            let mut revision = ProductRevision::new(
+                product_id.to_string(),
+                latest + 1,
+                saved.clone(),
+                changed_by,
+            );
+            revision.reverted_from = reverted_from;
+            self.revision_dao.create_revision(revision).await?;
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to record revision for product {product_id}: {e}");
+        }
+    }
+
+    /// Replace a live product and keep its slug redirects and revision history in step
+    pub(crate) async fn save_product_update(
+        &self,
+        product_id: &str,
+        existing: Product,
+        product: Product,
+        reverted_from: Option<i64>,
+    ) -> Result<Option<Product>, HandlerError> {
+        let result = self.product_dao.update_product(product_id, product).await;
+
+        match result {
+            Ok(Some(product)) => {
+                self.after_product_saved(product_id, Some(&existing), &product, reverted_from)
+                    .await;
+                Ok(Some(product))
+            }
+            Ok(None) => Ok(None),
+            Err(e) => {
+                error!("Error updating product: {e}");
+                Err(HandlerError::InternalError(format!(
+                    "Failed to update product: {e}"
+                )))
+            }
+        }
+    }
+
+    /// Keep slug redirects, revision history, mirrored relations and category
+    /// counts in step with a product write that has landed. `existing` is the
+    /// live product it replaced, None when the product was not live.
+    pub(crate) async fn after_product_saved(
+        &self,
+        product_id: &str,
+        existing: Option<&Product>,
+        product: &Product,
+        reverted_from: Option<i64>,
+    ) {
+        if let Some(old_slug) = existing
+            .and_then(|existing| existing.slug.as_ref())
+            .filter(|old| Some(*old) != product.slug.as_ref())
+        {
+            info!("Product {product_id} slug changed from '{old_slug}', recording redirect");
+            if let Err(e) = self
+                .slug_history_dao
+                .record_retired_slug(SlugEntityType::Product, product_id, old_slug)
+                .await
+            {
+                error!("Failed to record retired product slug '{old_slug}': {e}");
+            }
+        }
+        self.record_revision(product_id, existing, product, reverted_from)
+            .await;
+        let relations_before = existing
+            .map(|existing| existing.relations.as_slice())
+            .unwrap_or_default();
+        self.mirror_relations(product_id, relations_before, &product.relations)
+            .await;
+        self.update_category_counts(existing, Some(product)).await;
+    }
+
+    /// Take a deleted product out of category counts and drop the links
+    /// other products have to it
+    pub(crate) async fn after_product_deleted(&self, product_id: &str, existing: Option<&Product>) {
+        self.update_category_counts(existing, None).await;
+        // Restoring the product later does not bring these links back
+        match self.product_dao.remove_references_to(product_id).await {
+            Ok(0) => {}
+            Ok(count) => {
+                info!("Removed links to deleted product {product_id} from {count} product(s)")
+            }
+            Err(e) => error!("Failed to remove links to product {product_id}: {e}"),
+        }
+    }
+
+    /// Reject SKUs a variant of another live product already uses. SKUs key
+    /// inventory and price records, so a shared one would mix two products' stock.
+    async fn check_skus_unused(
+        &self,
+        skus: &[String],
+        product_id: Option<&str>,
+    ) -> Result<(), HandlerError> {
+        let holders = self
+            .product_dao
+            .find_products_by_skus(skus)
+            .await
+            .map_err(|e| HandlerError::InternalError(format!("Failed to check SKUs: {e}")))?;
+        for holder in holders {
+            if holder.id.as_deref() == product_id {
+                continue;
+            }
+            if let Some(sku) = holder
+                .variants
+                .iter()
+                .find(|variant| skus.contains(&variant.sku))
+                .map(|variant| &variant.sku)
+            {
+                return Err(HandlerError::AlreadyExists(format!(
+                    "SKU {sku} is already used by product {}",
+                    holder.id.as_deref().unwrap_or_default()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Generate a slug from the product name that is not used by any live
+    /// product and has never been retired into the redirect history
+    async fn generate_unique_slug(
+        &self,
+        name: &str,
+        product_ref: &str,
+    ) -> Result<String, HandlerError> {
+        let mut base = slugify(name);
+        if base.is_empty() {
+            base = slugify(product_ref);
+        }
+
+        for suffix in 1..=MAX_SLUG_SUFFIX {
+            let candidate = slug_with_suffix(&base, suffix);
+            if !self.is_slug_taken(&candidate).await? {
+                return Ok(candidate);
+            }
+        }
+
+        // Pathological collision count; fall back to the product ref which is unique
+        Ok(format!("{base}-{}", slugify(product_ref)))
+    }
+
+    /// Make sure no other product holds a slug the caller chose, live or as a
+    /// redirect. The slug is kept as given. An `existing` product keeps its own
+    /// slug and may reclaim the ones it retired.
+    async fn claim_explicit_slug(
+        &self,
+        slug: &str,
+        existing: Option<&Product>,
+    ) -> Result<String, HandlerError> {
+        if existing.and_then(|p| p.slug.as_deref()) == Some(slug) {
+            return Ok(slug.to_string());
+        }
+
+        let exists = self
+            .product_dao
+            .slug_exists(slug)
+            .await
+            .map_err(|e| HandlerError::InternalError(format!("Failed to check slug: {e}")))?;
+        if exists {
+            return Err(HandlerError::AlreadyExists(format!(
+                "Product with slug '{slug}' already exists"
+            )));
+        }
+
+        let redirect = self
+            .slug_history_dao
+            .find_redirect(SlugEntityType::Product, slug)
+            .await
+            .map_err(|e| HandlerError::InternalError(format!("Failed to check slug: {e}")))?;
+        match redirect {
+            Some(redirect) if existing.and_then(|p| p.id.as_ref()) != Some(&redirect.entity_id) => {
+                Err(HandlerError::AlreadyExists(format!(
+                    "Slug '{slug}' redirects to another product"
+                )))
+            }
+            _ => Ok(slug.to_string()),
+        }
+    }
+
+    async fn is_slug_taken(&self, slug: &str) -> Result<bool, HandlerError> {
+        // Deleted products keep their slug reserved so they can be restored
+        let exists = self
+            .product_dao
+            .slug_exists(slug)
+            .await
+            .map_err(|e| HandlerError::InternalError(format!("Failed to check slug: {e}")))?;
+        if exists {
+            return Ok(true);
+        }
+
+        let retired = self
+            .slug_history_dao
+            .find_redirect(SlugEntityType::Product, slug)
+            .await
+            .map_err(|e| HandlerError::InternalError(format!("Failed to check slug: {e}")))?;
+        Ok(retired.is_some())
     }
 
     pub async fn create_product(
@@ -30,7 +673,16 @@ impl ProductService {
         created_by: Option<String>,
     ) -> Result<Product, HandlerError> {
         debug!("Before call to create_product handler_inner");
+        let product = self.prepare_product_create(request, created_by).await?;
+        self.insert_product(product).await
+    }
 
+    /// Build and validate the product a create request describes, without saving it
+    pub async fn prepare_product_create(
+        &self,
+        request: ProductCreateRequest,
+        created_by: Option<String>,
+    ) -> Result<Product, HandlerError> {
         // Validate product name
         let product_name = ProductName::parse(request.name)
             .map_err(|e| HandlerError::ValidationError(format!("Invalid product name: {e}")))?;
@@ -47,9 +699,15 @@ impl ProductService {
             product_builder.brand(brand);
         }
 
-        if let Some(slug) = request.slug {
-            product_builder.slug(slug);
-        }
+        // Keep an explicit slug; otherwise derive a unique one from the name
+        let slug = match request.slug.filter(|slug| !slug.trim().is_empty()) {
+            Some(slug) => self.claim_explicit_slug(&slug, None).await?,
+            None => {
+                self.generate_unique_slug(product_name.as_ref(), product_ref.as_ref())
+                    .await?
+            }
+        };
+        product_builder.slug(slug);
 
         if let Some(long_description) = request.long_description {
             product_builder.long_description(long_description);
@@ -83,6 +741,7 @@ impl ProductService {
 
         // Map related products
         product_builder.related_products(request.related_products);
+        product_builder.relations(relations_from_proto(request.relations));
 
         // Map reviews if present
         if let Some(proto_reviews) = request.reviews {
@@ -95,13 +754,11 @@ impl ProductService {
             product_builder.reviews(reviews);
         }
 
-        // Map hierarchical categories if present
-        if let Some(proto_hc) = request.hierarchical_categories {
-            let hc = HierarchicalCategories {
-                lvl0: proto_hc.lvl0,
-                lvl1: proto_hc.lvl1,
-                lvl2: proto_hc.lvl2,
-            };
+        // Category paths are derived from list_categories, never taken from the caller
+        if let Some(hc) = self
+            .derive_hierarchical_categories(&request.list_categories)
+            .await?
+        {
             product_builder.hierarchical_categories(hc);
         }
 
@@ -123,40 +780,127 @@ impl ProductService {
         let variants: Vec<ProductVariant> = request
             .variants
             .into_iter()
-            .map(|proto_variant| ProductVariant {
-                sku: proto_variant.sku,
-                defining_attributes: Some(proto_variant.defining_attributes),
-                abbreviated_color: proto_variant.abbreviated_color,
-                abbreviated_size: proto_variant.abbreviated_size,
-                height: proto_variant.height,
-                width: proto_variant.width,
-                length: proto_variant.length,
-                weight: proto_variant.weight,
-                weight_unit: proto_variant.weight_unit,
-                packaging: proto_variant.packaging.map(|proto_packaging| Packaging {
-                    height: proto_packaging.height,
-                    width: proto_packaging.width,
-                    length: proto_packaging.length,
-                    weight: proto_packaging.weight,
-                    weight_unit: proto_packaging.weight_unit,
-                }),
-                image_urls: proto_variant.image_urls,
-            })
-            .collect();
+            .map(variant_from_proto)
+            .collect::<Result<_, _>>()?;
         product_builder.variants(variants);
 
-        let product = product_builder.build();
+        // Map localized content
+        product_builder.localizations(localizations_from_proto(request.localizations)?);
+
+        // Map store assortment
+        product_builder.stores(stores_from_proto(request.stores)?);
+
+        // Map publishing window
+        let publish_at = request.publish_at.map(publish_timestamp).transpose()?;
+        let unpublish_at = request.unpublish_at.map(publish_timestamp).transpose()?;
+        validate_publish_window(publish_at, unpublish_at)?;
+        if let Some(publish_at) = publish_at {
+            product_builder.publish_at(publish_at);
+        }
+        if let Some(unpublish_at) = unpublish_at {
+            product_builder.unpublish_at(unpublish_at);
+        }
+
+        if let Some(bundle) = request.bundle {
+            product_builder.bundle(bundle_from_proto(bundle));
+        }
+
+        let mut product = product_builder.build();
+        product.normalize_relations();
+        self.validate_attributes(&product).await?;
+        self.validate_bundle(&product, None).await?;
+        self.validate_relations(&product).await?;
+        self.check_localized_slugs(&product, None).await?;
+        self.check_store_slugs(&product, None).await?;
+
+        Ok(product)
+    }
+
+    /// Copy a product under a new product_ref and slug, hidden until someone
+    /// reviews it. Returns None when the source product does not exist.
+    pub async fn clone_product(
+        &self,
+        request: CloneProductRequest,
+    ) -> Result<Option<Product>, HandlerError> {
+        debug!("Before call to clone_product handler_inner");
+
+        let product_ref = ProductRef::parse(request.product_ref).map_err(|e| {
+            HandlerError::ValidationError(format!("Invalid product reference: {e}"))
+        })?;
+        let name = match request.name {
+            Some(name) => Some(
+                ProductName::parse(name)
+                    .map_err(|e| {
+                        HandlerError::ValidationError(format!("Invalid product name: {e}"))
+                    })?
+                    .to_string(),
+            ),
+            None => None,
+        };
+
+        let Some(source) = self.get_product(request.product_id, None).await? else {
+            return Ok(None);
+        };
+
+        let slug = match request.slug.filter(|slug| !slug.trim().is_empty()) {
+            Some(slug) => self.claim_explicit_slug(&slug, None).await?,
+            None => {
+                let name = name.as_deref().unwrap_or(&source.name);
+                self.generate_unique_slug(name, product_ref.as_ref())
+                    .await?
+            }
+        };
+
+        let spec = CloneSpec {
+            product_ref: product_ref.to_string(),
+            slug,
+            name,
+            sku_rewrites: request
+                .sku_rewrites
+                .into_iter()
+                .map(|rule| SkuRewrite {
+                    find: rule.find,
+                    replace: rule.replace,
+                })
+                .collect(),
+            created_by: request.created_by,
+        };
+        let product = clone_product(&source, spec)
+            .map_err(|e| HandlerError::ValidationError(format!("Cannot clone product: {e}")))?;
+        let skus: Vec<String> = product.variants.iter().map(|v| v.sku.clone()).collect();
+        self.check_skus_unused(&skus, None).await?;
+
+        self.validate_bundle(&product, None).await?;
+        self.validate_relations(&product).await?;
+
+        self.insert_product(product).await.map(Some)
+    }
+
+    /// Insert a new product and record its first revision and category counts
+    pub(crate) async fn insert_product(&self, product: Product) -> Result<Product, HandlerError> {
         let result = self.product_dao.create_product(product).await;
 
         match result {
-            Ok(product) => Ok(product),
+            Ok(product) => {
+                if let Some(product_id) = &product.id {
+                    self.after_product_saved(product_id, None, &product, None)
+                        .await;
+                }
+                Ok(product)
+            }
             Err(e) => {
                 let error_str = e.to_string();
                 if error_str.contains("E11000") || error_str.contains("duplicate key") {
                     error!("Duplicate product detected: {e}");
-                    Err(HandlerError::AlreadyExists(
-                        "Product with this product_ref already exists".to_string(),
-                    ))
+                    // Two creates racing for the same slug both pass the slug check
+                    let field = if error_str.contains("slug_1") {
+                        "slug"
+                    } else {
+                        "product_ref"
+                    };
+                    Err(HandlerError::AlreadyExists(format!(
+                        "Product with this {field} already exists"
+                    )))
                 } else {
                     error!("Error creating product: {e}");
                     Err(HandlerError::InternalError(format!(
@@ -167,13 +911,21 @@ impl ProductService {
         }
     }
 
-    pub async fn get_product(&self, product_id: String) -> Result<Option<Product>, HandlerError> {
+    /// Get a product, with its content resolved for `locale` when one is given
+    pub async fn get_product(
+        &self,
+        product_id: String,
+        locale: Option<&str>,
+    ) -> Result<Option<Product>, HandlerError> {
         debug!("Before call to get_product handler_inner");
         let result = self.product_dao.get_product(&product_id).await;
         debug!("After call to get_product handler_inner: {result:?}");
 
         match result {
-            Ok(Some(product)) => Ok(Some(product)),
+            Ok(Some(product)) => Ok(Some(match locale {
+                Some(locale) => product.localized(locale),
+                None => product,
+            })),
             Ok(None) => Ok(None),
             Err(e) => {
                 error!("Error getting product: {e}");
@@ -184,19 +936,100 @@ impl ProductService {
         }
     }
 
+    /// Resolve a slug to a product. With a locale, the localized slugs along
+    /// its fallback chain are tried after the default slug, and the product's
+    /// content is resolved for that locale.
     pub async fn get_product_by_slug(
         &self,
         product_slug: String,
-    ) -> Result<Option<Product>, HandlerError> {
+        locale: Option<String>,
+    ) -> Result<Option<SlugLookup<Product>>, HandlerError> {
+        let lookup = self
+            .find_product_by_slug(&product_slug, locale.as_deref())
+            .await?;
+
+        let Some(locale) = locale else {
+            return Ok(lookup);
+        };
+        Ok(lookup.map(|lookup| match lookup {
+            SlugLookup::Current(product) => SlugLookup::Current(product.localized(&locale)),
+            SlugLookup::MovedPermanently {
+                current_slug,
+                entity,
+            } => {
+                let entity = entity.localized(&locale);
+                SlugLookup::MovedPermanently {
+                    current_slug: entity.slug.clone().unwrap_or(current_slug),
+                    entity,
+                }
+            }
+        }))
+    }
+
+    async fn find_product_by_slug(
+        &self,
+        product_slug: &str,
+        locale: Option<&str>,
+    ) -> Result<Option<SlugLookup<Product>>, HandlerError> {
         debug!("Before call to get_product_by_slug handler_inner");
-        let result = self.product_dao.get_product_by_slug(&product_slug).await;
+        let result = self.product_dao.get_product_by_slug(product_slug).await;
         debug!("After call to get_product_by_slug handler_inner: {result:?}");
 
         match result {
-            Ok(Some(product)) => Ok(Some(product)),
-            Ok(None) => Ok(None),
+            Ok(Some(product)) => return Ok(Some(SlugLookup::Current(product))),
+            Ok(None) => {}
             Err(e) => {
                 error!("Error getting product by slug: {e}");
+                return Err(HandlerError::InternalError(format!(
+                    "Failed to get product by slug: {e}"
+                )));
+            }
+        }
+
+        if let Some(locale) = locale {
+            let chain = locale_fallback_chain(locale);
+            let localized = self
+                .product_dao
+                .get_product_by_localized_slug(product_slug, &chain)
+                .await
+                .map_err(|e| {
+                    error!("Error getting product by localized slug: {e}");
+                    HandlerError::InternalError(format!("Failed to get product by slug: {e}"))
+                })?;
+            if let Some(product) = localized {
+                return Ok(Some(SlugLookup::Current(product)));
+            }
+        }
+
+        // Fall back to the slug history so renamed products keep resolving
+        let redirect = self
+            .slug_history_dao
+            .find_redirect(SlugEntityType::Product, product_slug)
+            .await
+            .map_err(|e| {
+                error!("Error looking up slug history: {e}");
+                HandlerError::InternalError(format!("Failed to look up slug history: {e}"))
+            })?;
+
+        let Some(redirect) = redirect else {
+            return Ok(None);
+        };
+
+        match self.product_dao.get_product(&redirect.entity_id).await {
+            Ok(Some(product)) if product.is_within_publish_window(Utc::now()) => {
+                match product.slug.clone() {
+                    Some(current_slug) if current_slug != product_slug => {
+                        Ok(Some(SlugLookup::MovedPermanently {
+                            current_slug,
+                            entity: product,
+                        }))
+                    }
+                    _ => Ok(None),
+                }
+            }
+            Ok(_) => Ok(None),
+            Err(e) => {
+                error!("Error getting redirected product: {e}");
                 Err(HandlerError::InternalError(format!(
                     "Failed to get product by slug: {e}"
                 )))
@@ -216,13 +1049,61 @@ impl ProductService {
             HandlerError::InternalError("Product is required in update request".to_string())
         })?;
 
+        let existing = self
+            .product_dao
+            .get_product(&product_id)
+            .await
+            .map_err(|e| {
+                error!("Error getting product for update: {e}");
+                HandlerError::InternalError(format!("Failed to update product: {e}"))
+            })?;
+
+        let Some(existing) = existing else {
+            return Ok(None);
+        };
+
+        let domain_product = self
+            .prepare_product_update(&product_id, &existing, product)
+            .await?;
+
+        self.save_product_update(&product_id, existing, domain_product, None)
+            .await
+    }
+
+    /// Build and validate the product an update of `existing` describes,
+    /// without saving it
+    pub async fn prepare_product_update(
+        &self,
+        product_id: &str,
+        existing: &Product,
+        product: catalog_messages::Product,
+    ) -> Result<Product, HandlerError> {
+        // An update without a slug keeps the published one rather than dropping it
+        let slug = match product
+            .slug
+            .as_deref()
+            .filter(|slug| !slug.trim().is_empty())
+        {
+            Some(slug) => Some(self.claim_explicit_slug(slug, Some(existing)).await?),
+            None => existing.slug.clone(),
+        };
+
+        let publish_at = product.publish_at.map(publish_timestamp).transpose()?;
+        let unpublish_at = product.unpublish_at.map(publish_timestamp).transpose()?;
+        validate_publish_window(publish_at, unpublish_at)?;
+
+        // Category paths are derived from list_categories, never taken from the caller
+        let hierarchical_categories = self
+            .derive_hierarchical_categories(&product.list_categories)
+            .await?;
+
         // Map the proto product to domain product
-        let domain_product = Product {
-            id: Some(product_id.clone()), // Use the product_id parameter, not the one from request
+        let mut domain_product = Product {
+            id: Some(product_id.to_string()), // Use the product_id parameter, not the one from request
             name: product.name,
             long_description: product.long_description,
             brand: product.brand,
-            slug: product.slug,
+            slug,
             product_ref: product.product_ref,
             product_type: product.product_type,
             seo_title: product.seo_title,
@@ -231,18 +1112,10 @@ impl ProductService {
             display_on_site: product.display_on_site,
             tax_code: product.tax_code,
             related_products: product.related_products,
-            reviews: product.reviews.map(|proto_reviews| Reviews {
-                bayesian_avg: proto_reviews.bayesian_avg.into(),
-                count: proto_reviews.count,
-                rating: proto_reviews.rating,
-            }),
-            hierarchical_categories: product.hierarchical_categories.map(|proto_hc| {
-                HierarchicalCategories {
-                    lvl0: proto_hc.lvl0,
-                    lvl1: proto_hc.lvl1,
-                    lvl2: proto_hc.lvl2,
-                }
-            }),
+            relations: relations_from_proto(product.relations),
+            // The summary is derived from approved reviews, never taken from the caller
+            reviews: existing.reviews.clone(),
+            hierarchical_categories,
             list_categories: product.list_categories,
             created_at: product.created_at.map(|ts| {
                 use chrono::{DateTime, Utc};
@@ -262,51 +1135,342 @@ impl ProductService {
             variants: product
                 .variants
                 .into_iter()
-                .map(|proto_variant| ProductVariant {
-                    sku: proto_variant.sku,
-                    defining_attributes: Some(proto_variant.defining_attributes),
-                    abbreviated_color: proto_variant.abbreviated_color,
-                    abbreviated_size: proto_variant.abbreviated_size,
-                    height: proto_variant.height,
-                    width: proto_variant.width,
-                    length: proto_variant.length,
-                    weight: proto_variant.weight,
-                    weight_unit: proto_variant.weight_unit,
-                    packaging: proto_variant.packaging.map(|proto_packaging| Packaging {
-                        height: proto_packaging.height,
-                        width: proto_packaging.width,
-                        length: proto_packaging.length,
-                        weight: proto_packaging.weight,
-                        weight_unit: proto_packaging.weight_unit,
-                    }),
-                    image_urls: proto_variant.image_urls,
-                })
-                .collect(),
+                .map(variant_from_proto)
+                .collect::<Result<_, _>>()?,
+            localizations: localizations_from_proto(product.localizations)?,
+            stores: stores_from_proto(product.stores)?,
+            publish_at,
+            unpublish_at,
+            bundle: product.bundle.map(bundle_from_proto),
+            deleted_at: None,
         };
+        domain_product.normalize_relations();
+
+        self.validate_attributes(&domain_product).await?;
+        self.validate_bundle(&domain_product, Some(product_id))
+            .await?;
+        self.validate_relations(&domain_product).await?;
+        self.check_localized_slugs(&domain_product, Some(product_id))
+            .await?;
+        self.check_store_slugs(&domain_product, Some(product_id))
+            .await?;
+
+        Ok(domain_product)
+    }
 
+    pub async fn list_product_revisions(
+        &self,
+        product_id: String,
+        revisions: Vec<i64>,
+        limit: Option<i64>,
+        offset: Option<u64>,
+    ) -> Result<Vec<ProductRevision>, HandlerError> {
+        debug!("Before call to list_product_revisions handler_inner");
         let result = self
-            .product_dao
-            .update_product(&product_id, domain_product)
+            .revision_dao
+            .list_revisions(
+                &product_id,
+                &revisions,
+                Some(limit.unwrap_or(DEFAULT_REVISION_PAGE_SIZE)),
+                offset,
+            )
             .await;
 
         match result {
-            Ok(Some(product)) => Ok(Some(product)),
-            Ok(None) => Ok(None),
+            Ok(revisions) => Ok(revisions),
             Err(e) => {
-                error!("Error updating product: {e}");
+                error!("Error listing product revisions: {e}");
                 Err(HandlerError::InternalError(format!(
-                    "Failed to update product: {e}"
+                    "Failed to list product revisions: {e}"
                 )))
             }
         }
     }
 
+    /// Restore a product's content to an earlier revision. The revert itself is
+    /// saved as a new revision, so it can be undone the same way.
+    pub async fn revert_product(
+        &self,
+        product_id: String,
+        revision: i64,
+        reverted_by: Option<String>,
+    ) -> Result<Option<Product>, HandlerError> {
+        debug!("Before call to revert_product handler_inner");
+
+        let existing = self.get_product(product_id.clone(), None).await?;
+        let Some(existing) = existing else {
+            return Ok(None);
+        };
+
+        let target = self
+            .revision_dao
+            .get_revision(&product_id, revision)
+            .await
+            .map_err(|e| {
+                error!("Error getting product revision: {e}");
+                HandlerError::InternalError(format!("Failed to revert product: {e}"))
+            })?
+            .ok_or_else(|| {
+                HandlerError::NotFound(format!(
+                    "Revision {revision} not found for product {product_id}"
+                ))
+            })?;
+
+        let mut product = target.snapshot;
+        product.id = Some(product_id.clone());
+        product.created_at = existing.created_at;
+        product.created_by = existing.created_by.clone();
+        product.updated_at = Some(Utc::now());
+        product.updated_by = reverted_by;
+        product.reviews = existing.reviews.clone();
+        product.deleted_at = None;
+        // The snapshot's paths reflect the tree as it was; derive them from today's tree
+        product.hierarchical_categories = self
+            .derive_hierarchical_categories(&product.list_categories)
+            .await?;
+
+        self.save_product_update(&product_id, existing, product, Some(revision))
+            .await
+    }
+
+    /// Append the cartesian product of `axes` to the product's variants.
+    /// Returns the saved product with the generated and skipped SKUs.
+    pub async fn generate_variants(
+        &self,
+        product_id: String,
+        axes: Vec<VariantAxis>,
+        sku_pattern: String,
+        updated_by: Option<String>,
+    ) -> Result<Option<(Product, VariantMatrix)>, HandlerError> {
+        debug!("Before call to generate_variants handler_inner");
+
+        let existing = self.get_product(product_id.clone(), None).await?;
+        let Some(existing) = existing else {
+            return Ok(None);
+        };
+
+        let matrix = generate_variant_matrix(
+            &existing.product_ref,
+            &axes,
+            &sku_pattern,
+            &existing.variants,
+        )
+        .map_err(|e| HandlerError::ValidationError(format!("Invalid variant axes: {e}")))?;
+
+        if matrix.created.is_empty() {
+            return Ok(Some((existing, matrix)));
+        }
+        let created_skus: Vec<String> = matrix.created.iter().map(|v| v.sku.clone()).collect();
+        self.check_skus_unused(&created_skus, Some(&product_id))
+            .await?;
+
+        let mut product = existing.clone();
+        product.variants.extend(matrix.created.iter().cloned());
+        if product.default_variant.is_none() {
+            product.default_variant = matrix.created.first().map(|v| v.sku.clone());
+        }
+        product.updated_at = Some(Utc::now());
+        product.updated_by = updated_by;
+
+        self.validate_attributes(&product).await?;
+
+        let saved = self
+            .save_product_update(&product_id, existing, product, None)
+            .await?;
+        Ok(saved.map(|product| (product, matrix)))
+    }
+
+    /// Availability and price of a bundle. Availability is the number of whole
+    /// bundles the components' stock makes up; the price is the bundle's own
+    /// best offer or the sum of its components', as the bundle is configured.
+    pub async fn quote_bundle(
+        &self,
+        client: &Client,
+        product_id: String,
+        currency: String,
+    ) -> Result<BundleQuote, HandlerError> {
+        if currency.len() != 3 {
+            return Err(HandlerError::ValidationError(format!(
+                "currency must be a three-letter ISO 4217 code, got '{currency}'"
+            )));
+        }
+
+        let product = self
+            .get_product(product_id.clone(), None)
+            .await?
+            .ok_or_else(|| {
+                HandlerError::NotFound(format!("Product with ID {product_id} not found"))
+            })?;
+        let Some(bundle) = product.bundle.clone() else {
+            return Err(HandlerError::ValidationError(format!(
+                "Product {product_id} is not a bundle"
+            )));
+        };
+
+        let component_skus = bundle.component_skus();
+        let available = inventory_client::get_available_quantities(client, &component_skus)
+            .await
+            .map_err(|e| {
+                error!("Error looking up stock for bundle {product_id}: {e}");
+                HandlerError::InternalError(format!("Failed to look up stock: {e}"))
+            })?;
+
+        let own_sku = product
+            .default_variant
+            .clone()
+            .or_else(|| product.variants.first().map(|v| v.sku.clone()));
+        let mut price_skus = component_skus;
+        price_skus.extend(own_sku.clone());
+        let prices = price_client::get_best_offer_prices(client, &price_skus, &currency)
+            .await
+            .map_err(|e| {
+                error!("Error looking up prices for bundle {product_id}: {e}");
+                HandlerError::InternalError(format!("Failed to look up prices: {e}"))
+            })?;
+
+        let price = match bundle.pricing {
+            BundlePricing::OwnOffer => own_sku
+                .and_then(|sku| prices.get(&sku))
+                .and_then(|price| price.parse::<Decimal>().ok()),
+            BundlePricing::ComponentSum => bundle.component_sum(&prices).ok(),
+        };
+
+        Ok(BundleQuote {
+            available_quantity: bundle.available_quantity(&available),
+            price,
+            pricing: bundle.pricing,
+            components: bundle
+                .components
+                .iter()
+                .map(|component| BundleComponentQuote {
+                    sku: component.sku.clone(),
+                    quantity: component.quantity,
+                    available: available.get(&component.sku).copied().unwrap_or(0),
+                    unit_price: prices.get(&component.sku).cloned(),
+                })
+                .collect(),
+        })
+    }
+
+    /// The live products a product links to, in listing order, limited to
+    /// `kinds` when any are given. Returns None when the product does not exist.
+    pub async fn get_related_products(
+        &self,
+        product_id: String,
+        kinds: &[RelationKind],
+        locale: Option<&str>,
+    ) -> Result<Option<Vec<(RelationKind, Product)>>, HandlerError> {
+        let Some(product) = self.get_product(product_id, None).await? else {
+            return Ok(None);
+        };
+
+        let links: Vec<(String, RelationKind)> = product
+            .related_links()
+            .into_iter()
+            .filter(|(_, kind)| kinds.is_empty() || kinds.contains(kind))
+            .collect();
+        let ids: Vec<String> = links.iter().map(|(id, _)| id.clone()).collect();
+        let now = Utc::now();
+        let found: Vec<Product> = self
+            .product_dao
+            .find_products_by_ids(&ids)
+            .await
+            .map_err(|e| {
+                error!("Error looking up related products: {e}");
+                HandlerError::InternalError(format!("Failed to look up related products: {e}"))
+            })?
+            .into_iter()
+            .filter(|p| p.display_on_site && p.is_within_publish_window(now))
+            .collect();
+
+        let related = links
+            .into_iter()
+            .filter_map(|(id, kind)| {
+                let related = found.iter().find(|p| p.id.as_ref() == Some(&id))?.clone();
+                Some(match locale {
+                    Some(locale) => (kind, related.localized(locale)),
+                    None => (kind, related),
+                })
+            })
+            .collect();
+        Ok(Some(related))
+    }
+
+    /// Compare live products side by side, in the order requested. Fails with
+    /// NotFound when any of them is missing or not live.
+    pub async fn compare_products(
+        &self,
+        request: CompareProductsRequest,
+    ) -> Result<(Vec<Product>, ProductComparison), HandlerError> {
+        let ids: Vec<String> = request
+            .product_ids
+            .iter()
+            .map(|id| id.trim().to_string())
+            .collect();
+        if ids.is_empty() || ids.iter().any(String::is_empty) {
+            return Err(HandlerError::ValidationError(
+                "Product ids are required".to_string(),
+            ));
+        }
+        if ids.len() > MAX_COMPARED_PRODUCTS {
+            return Err(HandlerError::ValidationError(format!(
+                "At most {MAX_COMPARED_PRODUCTS} products can be compared"
+            )));
+        }
+        if let Some(duplicate) = ids
+            .iter()
+            .enumerate()
+            .find_map(|(i, id)| ids[..i].contains(id).then_some(id))
+        {
+            return Err(HandlerError::ValidationError(format!(
+                "Product {duplicate} is listed more than once"
+            )));
+        }
+        let length_unit = parse_unit(request.length_unit)?.unwrap_or(LengthUnit::Centimeter);
+        let mass_unit = parse_unit(request.weight_unit)?.unwrap_or(MassUnit::Kilogram);
+
+        let now = Utc::now();
+        let mut found: Vec<Product> = self
+            .product_dao
+            .find_products_by_ids(&ids)
+            .await
+            .map_err(|e| {
+                error!("Error looking up products to compare: {e}");
+                HandlerError::InternalError(format!("Failed to look up products: {e}"))
+            })?
+            .into_iter()
+            .filter(|p| p.display_on_site && p.is_within_publish_window(now))
+            .collect();
+
+        let mut products = Vec::with_capacity(ids.len());
+        for id in &ids {
+            let Some(index) = found.iter().position(|p| p.id.as_ref() == Some(id)) else {
+                return Err(HandlerError::NotFound(format!("Product {id} not found")));
+            };
+            let product = found.swap_remove(index);
+            products.push(match request.locale.as_deref() {
+                Some(locale) => product.localized(locale),
+                None => product,
+            });
+        }
+
+        let comparison = compare_products(&products, length_unit, mass_unit);
+        Ok((products, comparison))
+    }
+
     pub async fn delete_product(&self, product_id: String) -> Result<bool, HandlerError> {
         debug!("Before call to delete_product handler_inner");
+        let existing = self.get_product(product_id.clone(), None).await?;
         let result = self.product_dao.delete_product(&product_id).await;
 
         match result {
-            Ok(deleted) => Ok(deleted),
+            Ok(deleted) => {
+                if deleted {
+                    self.after_product_deleted(&product_id, existing.as_ref())
+                        .await;
+                }
+                Ok(deleted)
+            }
             Err(e) => {
                 error!("Error deleting product: {e}");
                 Err(HandlerError::InternalError(format!(
@@ -316,28 +1480,364 @@ impl ProductService {
         }
     }
 
-    pub async fn search_products(
+    pub async fn restore_product(
+        &self,
+        product_id: String,
+    ) -> Result<Option<Product>, HandlerError> {
+        debug!("Before call to restore_product handler_inner");
+        let result = self.product_dao.restore_product(&product_id).await;
+
+        match result {
+            Ok(true) => {
+                let restored = self.get_product(product_id, None).await?;
+                self.update_category_counts(None, restored.as_ref()).await;
+                Ok(restored)
+            }
+            Ok(false) => Ok(None),
+            Err(e) => {
+                error!("Error restoring product: {e}");
+                Err(HandlerError::InternalError(format!(
+                    "Failed to restore product: {e}"
+                )))
+            }
+        }
+    }
+
+    /// Permanently delete products that have been soft-deleted for longer than `retention`
+    pub async fn purge_deleted_products(
+        &self,
+        retention: chrono::Duration,
+    ) -> Result<usize, HandlerError> {
+        let purged_ids = self
+            .product_dao
+            .purge_deleted_products(chrono::Utc::now() - retention)
+            .await
+            .map_err(|e| {
+                error!("Error purging deleted products: {e}");
+                HandlerError::InternalError(format!("Failed to purge deleted products: {e}"))
+            })?;
+
+        self.slug_history_dao
+            .delete_redirects_for_entities(SlugEntityType::Product, &purged_ids)
+            .await
+            .map_err(|e| {
+                error!("Error purging slug history for deleted products: {e}");
+                HandlerError::InternalError(format!("Failed to purge slug history: {e}"))
+            })?;
+
+        self.revision_dao
+            .delete_revisions_for_products(&purged_ids)
+            .await
+            .map_err(|e| {
+                error!("Error purging revisions for deleted products: {e}");
+                HandlerError::InternalError(format!("Failed to purge product revisions: {e}"))
+            })?;
+
+        self.review_dao
+            .delete_reviews_for_products(&purged_ids)
+            .await
+            .map_err(|e| {
+                error!("Error purging reviews for deleted products: {e}");
+                HandlerError::InternalError(format!("Failed to purge product reviews: {e}"))
+            })?;
+
+        Ok(purged_ids.len())
+    }
+
+    /// Store a new review as pending moderation. Returns None when the product does not exist.
+    pub async fn submit_review(
         &self,
-        query: Option<String>,
-        categories: Vec<String>,
-        brand: Option<String>,
+        product_id: String,
+        rating: i32,
+        title: Option<String>,
+        body: Option<String>,
+        author: Option<String>,
+    ) -> Result<Option<Review>, HandlerError> {
+        debug!("Before call to submit_review handler_inner");
+
+        let review = Review::new(product_id.clone(), rating, title, body, author)
+            .map_err(|e| HandlerError::ValidationError(format!("Invalid review: {e}")))?;
+
+        if self.get_product(product_id, None).await?.is_none() {
+            return Ok(None);
+        }
+
+        self.review_dao
+            .create_review(review)
+            .await
+            .map(Some)
+            .map_err(|e| {
+                error!("Error creating review: {e}");
+                HandlerError::InternalError(format!("Failed to submit review: {e}"))
+            })
+    }
+
+    pub async fn list_reviews(
+        &self,
+        product_id: Option<String>,
+        status: Option<ReviewStatus>,
         limit: Option<i64>,
         offset: Option<u64>,
+    ) -> Result<Vec<Review>, HandlerError> {
+        debug!("Before call to list_reviews handler_inner");
+        self.review_dao
+            .list_reviews(
+                product_id.as_deref(),
+                status,
+                Some(limit.unwrap_or(DEFAULT_REVIEW_PAGE_SIZE)),
+                offset,
+            )
+            .await
+            .map_err(|e| {
+                error!("Error listing reviews: {e}");
+                HandlerError::InternalError(format!("Failed to list reviews: {e}"))
+            })
+    }
+
+    /// Approve or reject a review. When that moves the review into or out of
+    /// the approved set, the product's summary is recomputed and the updated
+    /// product is returned alongside the review.
+    pub async fn moderate_review(
+        &self,
+        review_id: String,
+        status: ReviewStatus,
+        moderated_by: Option<String>,
+        note: Option<String>,
+    ) -> Result<Option<(Review, Option<Product>)>, HandlerError> {
+        debug!("Before call to moderate_review handler_inner");
+
+        if status == ReviewStatus::Pending {
+            return Err(HandlerError::ValidationError(
+                "Moderation status must be approved or rejected".to_string(),
+            ));
+        }
+
+        let review = self.review_dao.get_review(&review_id).await.map_err(|e| {
+            error!("Error getting review: {e}");
+            HandlerError::InternalError(format!("Failed to moderate review: {e}"))
+        })?;
+        let Some(mut review) = review else {
+            return Ok(None);
+        };
+
+        let previous_status = review.status;
+        review.status = status;
+        review.moderated_by = moderated_by;
+        review.moderation_note = note;
+        review.moderated_at = Some(Utc::now());
+
+        let review = self
+            .review_dao
+            .update_review(review)
+            .await
+            .map_err(|e| {
+                error!("Error updating review: {e}");
+                HandlerError::InternalError(format!("Failed to moderate review: {e}"))
+            })?
+            .ok_or_else(|| HandlerError::NotFound(format!("Review {review_id} not found")))?;
+
+        let approval_changed =
+            (previous_status == ReviewStatus::Approved) != (status == ReviewStatus::Approved);
+        if !approval_changed {
+            return Ok(Some((review, None)));
+        }
+
+        let product = self.refresh_review_summary(&review.product_id).await?;
+        Ok(Some((review, product)))
+    }
+
+    /// Recompute a product's review summary from its approved reviews against
+    /// the current catalog-wide prior. Returns None when the product is gone.
+    /// Other products keep the prior they were last summarized with until
+    /// refresh_all_review_summaries runs.
+    pub async fn refresh_review_summary(
+        &self,
+        product_id: &str,
+    ) -> Result<Option<Product>, HandlerError> {
+        let Some(mut product) = self.get_product(product_id.to_string(), None).await? else {
+            return Ok(None);
+        };
+
+        let internal = |e: Box<dyn std::error::Error + Send + Sync>| {
+            error!("Error recomputing review summary: {e}");
+            HandlerError::InternalError(format!("Failed to recompute review summary: {e}"))
+        };
+        let (catalog_totals, reviewed_products) = self
+            .review_dao
+            .catalog_rating_totals()
+            .await
+            .map_err(internal)?;
+        let product_totals = self
+            .review_dao
+            .product_rating_totals(product_id)
+            .await
+            .map_err(internal)?;
+
+        let summary =
+            ReviewPrior::from_catalog(catalog_totals, reviewed_products).summarize(product_totals);
+        self.product_dao
+            .set_product_reviews(product_id, &summary)
+            .await
+            .map_err(internal)?;
+
+        info!(
+            "Product {product_id} review summary: {} approved, Bayesian average {}",
+            summary.count,
+            summary.bayesian_avg.as_f32()
+        );
+        product.reviews = Some(summary);
+        Ok(Some(product))
+    }
+
+    /// Recompute the review summary of every reviewed product against the
+    /// current catalog-wide prior. Moderation only refreshes the product it
+    /// touched, so without this every other product's Bayesian average would
+    /// keep the prior from its own last moderation. Returns how many products
+    /// were updated.
+    pub async fn refresh_all_review_summaries(&self) -> Result<usize, HandlerError> {
+        let internal = |e: Box<dyn std::error::Error + Send + Sync>| {
+            error!("Error recomputing review summaries: {e}");
+            HandlerError::InternalError(format!("Failed to recompute review summaries: {e}"))
+        };
+        let (catalog_totals, reviewed_products) = self
+            .review_dao
+            .catalog_rating_totals()
+            .await
+            .map_err(internal)?;
+        let prior = ReviewPrior::from_catalog(catalog_totals, reviewed_products);
+        let products = self
+            .review_dao
+            .rating_totals_by_product()
+            .await
+            .map_err(internal)?;
+
+        let mut updated = 0;
+        for (product_id, totals) in products {
+            // Deleted products are skipped by the DAO and count as not updated
+            let summary = prior.summarize(totals);
+            if self
+                .product_dao
+                .set_product_reviews(&product_id, &summary)
+                .await
+                .map_err(internal)?
+            {
+                updated += 1;
+            }
+        }
+        Ok(updated)
+    }
+
+    pub async fn upsert_attribute_schema(
+        &self,
+        schema: AttributeSchema,
+    ) -> Result<AttributeSchema, HandlerError> {
+        schema
+            .check_definitions()
+            .map_err(|e| HandlerError::ValidationError(format!("Invalid attribute schema: {e}")))?;
+
+        self.attribute_schema_dao
+            .upsert_schema(schema)
+            .await
+            .map_err(|e| {
+                error!("Error saving attribute schema: {e}");
+                HandlerError::InternalError(format!("Failed to save attribute schema: {e}"))
+            })
+    }
+
+    pub async fn get_attribute_schema(
+        &self,
+        product_type: String,
+    ) -> Result<Option<AttributeSchema>, HandlerError> {
+        self.attribute_schema_dao
+            .get_schema(&product_type)
+            .await
+            .map_err(|e| {
+                error!("Error getting attribute schema: {e}");
+                HandlerError::InternalError(format!("Failed to get attribute schema: {e}"))
+            })
+    }
+
+    pub async fn list_attribute_schemas(&self) -> Result<Vec<AttributeSchema>, HandlerError> {
+        self.attribute_schema_dao.list_schemas().await.map_err(|e| {
+            error!("Error listing attribute schemas: {e}");
+            HandlerError::InternalError(format!("Failed to list attribute schemas: {e}"))
+        })
+    }
+
+    pub async fn delete_attribute_schema(
+        &self,
+        product_type: String,
+    ) -> Result<bool, HandlerError> {
+        self.attribute_schema_dao
+            .delete_schema(&product_type)
+            .await
+            .map_err(|e| {
+                error!("Error deleting attribute schema: {e}");
+                HandlerError::InternalError(format!("Failed to delete attribute schema: {e}"))
+            })
+    }
+
+    /// Products whose publishing window opened or closed in `(after, up_to]`
+    pub async fn find_publish_window_transitions(
+        &self,
+        after: DateTime<Utc>,
+        up_to: DateTime<Utc>,
+    ) -> Result<Vec<Product>, HandlerError> {
+        self.product_dao
+            .find_products_crossing_publish_window(after, up_to)
+            .await
+            .map_err(|e| {
+                error!("Error finding publish window transitions: {e}");
+                HandlerError::InternalError(format!(
+                    "Failed to find publish window transitions: {e}"
+                ))
+            })
+    }
+
+    pub async fn search_products(
+        &self,
+        request: ProductSearchRequest,
     ) -> Result<Vec<Product>, HandlerError> {
         debug!("Before call to search_products handler_inner");
+        let locales = request
+            .locale
+            .as_deref()
+            .map(locale_fallback_chain)
+            .unwrap_or_default();
+        let store_id = request
+            .store_id
+            .as_deref()
+            .map(parse_store_id)
+            .transpose()?;
+        let filter = ProductSearchFilter {
+            query: request.query.as_deref(),
+            categories: &request.categories,
+            brand: request.brand.as_deref(),
+            locales: &locales,
+            store_id: store_id.as_deref(),
+        };
         let result = self
             .product_dao
             .search_products(
-                query.as_deref(),
-                &categories,
-                brand.as_deref(),
-                limit,
-                offset,
+                filter,
+                request.limit.map(i64::from),
+                request.offset.map(|offset| offset as u64),
             )
             .await;
 
         match result {
-            Ok(products) => Ok(products),
+            // A store slug is more specific than a localized one, so it wins
+            Ok(products) => Ok(products
+                .into_iter()
+                .map(|product| match &request.locale {
+                    Some(locale) => product.localized(locale),
+                    None => product,
+                })
+                .map(|product| match &store_id {
+                    Some(store_id) => product.for_store(store_id),
+                    None => product,
+                })
+                .collect()),
             Err(e) => {
                 error!("Error searching products: {e}");
                 Err(HandlerError::InternalError(format!(
@@ -377,16 +1877,18 @@ impl ProductService {
         batch_size: Option<i32>,
         cursor: Option<String>,
         include_inactive: Option<bool>,
+        store_id: Option<String>,
     ) -> Result<(Vec<String>, Option<String>, bool), HandlerError> {
         debug!("Before call to get_product_slugs handler_inner");
 
         // Set defaults for optional parameters
         let batch_size = batch_size.unwrap_or(100);
         let include_inactive = include_inactive.unwrap_or(false);
+        let store_id = store_id.as_deref().map(parse_store_id).transpose()?;
 
         let result = self
             .product_dao
-            .get_product_slugs_paginated(batch_size, cursor, include_inactive)
+            .get_product_slugs_paginated(batch_size, cursor, include_inactive, store_id.as_deref())
             .await;
 
         match result {
@@ -400,3 +1902,165 @@ impl ProductService {
         }
     }
 }
+
+fn localizations_from_proto(
+    localizations: HashMap<String, catalog_messages::LocalizedProductContent>,
+) -> Result<HashMap<String, LocalizedProductContent>, HandlerError> {
+    let localizations = localizations
+        .into_iter()
+        .map(|(locale, content)| {
+            (
+                locale,
+                LocalizedProductContent {
+                    name: content.name,
+                    long_description: content.long_description,
+                    seo_title: content.seo_title,
+                    seo_description: content.seo_description,
+                    seo_keywords: content.seo_keywords,
+                    slug: content.slug,
+                },
+            )
+        })
+        .collect();
+
+    normalize_localizations(localizations)
+        .map_err(|e| HandlerError::ValidationError(format!("Invalid localizations: {e}")))
+}
+
+fn stores_from_proto(
+    stores: HashMap<String, catalog_messages::ProductStoreSettings>,
+) -> Result<HashMap<String, ProductStoreSettings>, HandlerError> {
+    let stores = stores
+        .into_iter()
+        .map(|(store_id, settings)| {
+            (
+                store_id,
+                ProductStoreSettings {
+                    display_on_site: settings.display_on_site,
+                    slug: settings.slug.filter(|slug| !slug.is_empty()),
+                },
+            )
+        })
+        .collect();
+
+    normalize_stores(stores)
+        .map_err(|e| HandlerError::ValidationError(format!("Invalid stores: {e}")))
+}
+
+fn parse_store_id(store_id: &str) -> Result<String, HandlerError> {
+    normalize_store_id(store_id).ok_or_else(|| {
+        HandlerError::ValidationError(format!("'{store_id}' is not a valid store id"))
+    })
+}
+
+/// Map a proto variant, normalizing its units and checking its measurements
+fn variant_from_proto(
+    proto_variant: catalog_messages::ProductVariant,
+) -> Result<ProductVariant, HandlerError> {
+    let packaging = match proto_variant.packaging {
+        Some(proto_packaging) => Some(Packaging {
+            height: proto_packaging.height,
+            width: proto_packaging.width,
+            length: proto_packaging.length,
+            dimension_unit: parse_unit(proto_packaging.dimension_unit)?,
+            weight: proto_packaging.weight,
+            weight_unit: parse_unit(proto_packaging.weight_unit)?,
+        }),
+        None => None,
+    };
+    let variant = ProductVariant {
+        sku: proto_variant.sku,
+        defining_attributes: Some(proto_variant.defining_attributes),
+        abbreviated_color: proto_variant.abbreviated_color,
+        abbreviated_size: proto_variant.abbreviated_size,
+        height: proto_variant.height,
+        width: proto_variant.width,
+        length: proto_variant.length,
+        dimension_unit: parse_unit(proto_variant.dimension_unit)?,
+        weight: proto_variant.weight,
+        weight_unit: parse_unit(proto_variant.weight_unit)?,
+        packaging,
+        image_urls: proto_variant.image_urls,
+    };
+
+    variant
+        .validate_measurements()
+        .map_err(|e| HandlerError::ValidationError(format!("Invalid measurements: {e}")))?;
+    Ok(variant)
+}
+
+/// An empty unit is treated as unset
+fn parse_unit<U: FromStr<Err = String>>(unit: Option<String>) -> Result<Option<U>, HandlerError> {
+    match unit {
+        Some(unit) if !unit.trim().is_empty() => unit
+            .parse()
+            .map(Some)
+            .map_err(HandlerError::ValidationError),
+        _ => Ok(None),
+    }
+}
+
+fn relations_from_proto(relations: Vec<catalog_messages::ProductRelation>) -> Vec<ProductRelation> {
+    relations
+        .into_iter()
+        .map(|relation| ProductRelation {
+            kind: relation_kind_from_proto(relation.kind()),
+            product_id: relation.product_id,
+            bidirectional: relation.bidirectional,
+        })
+        .collect()
+}
+
+pub(crate) fn relation_kind_from_proto(kind: catalog_messages::RelationKind) -> RelationKind {
+    match kind {
+        catalog_messages::RelationKind::Related => RelationKind::Related,
+        catalog_messages::RelationKind::Accessory => RelationKind::Accessory,
+        catalog_messages::RelationKind::Upsell => RelationKind::Upsell,
+        catalog_messages::RelationKind::Replacement => RelationKind::Replacement,
+    }
+}
+
+fn bundle_from_proto(bundle: catalog_messages::ProductBundle) -> ProductBundle {
+    let pricing = match bundle.pricing() {
+        catalog_messages::BundlePricing::OwnOffer => BundlePricing::OwnOffer,
+        catalog_messages::BundlePricing::ComponentSum => BundlePricing::ComponentSum,
+    };
+    ProductBundle {
+        components: bundle
+            .components
+            .into_iter()
+            .map(|component| BundleComponent {
+                sku: component.sku,
+                quantity: component.quantity,
+            })
+            .collect(),
+        pricing,
+    }
+}
+
+/// A timestamp chrono cannot represent is rejected rather than read as "now",
+/// which would publish the product immediately
+fn publish_timestamp(ts: prost_types::Timestamp) -> Result<DateTime<Utc>, HandlerError> {
+    u32::try_from(ts.nanos)
+        .ok()
+        .and_then(|nanos| DateTime::<Utc>::from_timestamp(ts.seconds, nanos))
+        .ok_or_else(|| {
+            HandlerError::ValidationError(format!(
+                "Publishing timestamp {}s {}ns is out of range",
+                ts.seconds, ts.nanos
+            ))
+        })
+}
+
+/// A window that closes before it opens would hide the product forever
+fn validate_publish_window(
+    publish_at: Option<DateTime<Utc>>,
+    unpublish_at: Option<DateTime<Utc>>,
+) -> Result<(), HandlerError> {
+    match (publish_at, unpublish_at) {
+        (Some(publish_at), Some(unpublish_at)) if unpublish_at <= publish_at => Err(
+            HandlerError::ValidationError("unpublish_at must be later than publish_at".to_string()),
+        ),
+        _ => Ok(()),
+    }
+}
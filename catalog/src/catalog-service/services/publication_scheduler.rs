@@ -0,0 +1,175 @@
+use crate::catalog_messages::ProductUpdatedEvent;
+use crate::domain::Product;
+use crate::persistence::job_lease_dao::JobLeaseDao;
+use crate::AppState;
+use async_nats::Client as NatsClient;
+use chrono::{DateTime, Utc};
+use log::{debug, error, info, warn};
+use prost::Message;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often the scheduler checks for publishing windows that opened or closed
+pub const PUBLICATION_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Lease name shared by every catalog instance
+pub const PUBLICATION_JOB: &str = "publication_scheduler";
+
+/// How long an instance that stops checking keeps the others from taking over
+const PUBLICATION_LEASE_TTL: chrono::Duration = chrono::Duration::minutes(5);
+
+/// Recorded as `updated_by` on the events the scheduler emits
+const SCHEDULER_USER: &str = "scheduler";
+
+/// Periodically emit a ProductUpdatedEvent for every product whose publish_at
+/// or unpublish_at has passed since the previous check, so downstream search
+/// indexes and caches pick up the visibility change, and move the product's
+/// category counts with it. Runs until the service shuts down.
+///
+/// Only the instance holding the job lease checks, and the end of the last
+/// checked span is kept with the lease, so crossings during downtime are
+/// caught up on restart. A crash between publishing and saving the watermark
+/// repeats that span, so consumers may see an event more than once. Category
+/// counts are recounted rather than adjusted, so a repeated span leaves them
+/// as they are.
+pub fn spawn_publication_scheduler(
+    nats_client: NatsClient,
+    app_state: AppState,
+    job_lease_dao: Arc<dyn JobLeaseDao + Send + Sync>,
+) {
+    tokio::spawn(async move {
+        let owner = uuid::Uuid::new_v4().to_string();
+        let mut interval = tokio::time::interval(PUBLICATION_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let now = Utc::now();
+            let lease = match job_lease_dao
+                .acquire_lease(PUBLICATION_JOB, &owner, now, PUBLICATION_LEASE_TTL)
+                .await
+            {
+                Ok(Some(lease)) => lease,
+                Ok(None) => {
+                    debug!("Publication check is running on another instance");
+                    continue;
+                }
+                Err(e) => {
+                    error!("Failed to take the publication scheduler lease: {e}");
+                    continue;
+                }
+            };
+
+            let (after, up_to) = lease.pending_span(now);
+            if !run_publication_check(&nats_client, &app_state, after, up_to).await {
+                continue;
+            }
+            match job_lease_dao
+                .advance_watermark(PUBLICATION_JOB, &owner, up_to)
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => warn!("Publication scheduler lease was taken over mid-check"),
+                Err(e) => error!("Failed to save the publication scheduler watermark: {e}"),
+            }
+        }
+    });
+}
+
+/// The instant category product counts reflect publishing windows as of.
+/// Crossings after it are applied by the scheduler's next check, so writes
+/// evaluate windows at this instant too and no crossing is counted twice.
+/// Before the scheduler has first run, and when the lease cannot be read,
+/// this is now.
+pub async fn publication_watermark(
+    job_lease_dao: &(dyn JobLeaseDao + Send + Sync),
+) -> DateTime<Utc> {
+    match job_lease_dao.get_lease(PUBLICATION_JOB).await {
+        Ok(lease) => lease
+            .and_then(|lease| lease.watermark)
+            .unwrap_or_else(Utc::now),
+        Err(e) => {
+            error!("Failed to read the publication scheduler watermark: {e}");
+            Utc::now()
+        }
+    }
+}
+
+/// Returns false when the check failed, so the same span is retried next tick
+async fn run_publication_check(
+    nats_client: &NatsClient,
+    app_state: &AppState,
+    after: DateTime<Utc>,
+    up_to: DateTime<Utc>,
+) -> bool {
+    let products = match app_state
+        .product_service
+        .find_publish_window_transitions(after, up_to)
+        .await
+    {
+        Ok(products) => products,
+        Err(e) => {
+            error!("Failed to check product publishing windows: {e:?}");
+            return false;
+        }
+    };
+
+    app_state
+        .product_service
+        .recount_categories_of(&products, up_to)
+        .await;
+
+    for product in products {
+        let changed_fields = crossed_window_fields(&product, after, up_to);
+        let Some(product_id) = product.id.clone() else {
+            continue;
+        };
+
+        let event = ProductUpdatedEvent {
+            product_id: product_id.clone(),
+            name: product.name,
+            product_ref: product.product_ref,
+            brand: product.brand,
+            slug: product.slug,
+            changed_fields,
+            updated_at: Some(prost_types::Timestamp {
+                seconds: up_to.timestamp(),
+                nanos: up_to.timestamp_subsec_nanos() as i32,
+            }),
+            updated_by: SCHEDULER_USER.to_string(),
+        };
+
+        if let Err(e) = nats_client
+            .publish(
+                crate::nats_config::events::published::PRODUCT_UPDATED,
+                event.encode_to_vec().into(),
+            )
+            .await
+        {
+            error!("Failed to publish scheduled update for product {product_id}: {e}");
+        } else {
+            info!(
+                "📅 Product {product_id} publishing window changed: {:?}",
+                event.changed_fields
+            );
+        }
+    }
+
+    true
+}
+
+/// Which window bounds fell inside `(after, up_to]`
+fn crossed_window_fields(
+    product: &Product,
+    after: DateTime<Utc>,
+    up_to: DateTime<Utc>,
+) -> Vec<String> {
+    let crossed = |at: Option<DateTime<Utc>>| at.is_some_and(|at| at > after && at <= up_to);
+
+    let mut fields = Vec::new();
+    if crossed(product.publish_at) {
+        fields.push("publish_at".to_string());
+    }
+    if crossed(product.unpublish_at) {
+        fields.push("unpublish_at".to_string());
+    }
+    fields
+}
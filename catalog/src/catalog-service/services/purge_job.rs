@@ -0,0 +1,79 @@
+use crate::persistence::job_lease_dao::JobLeaseDao;
+use crate::AppState;
+use chrono::Utc;
+use log::{debug, error, info};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often the purge job looks for expired tombstones
+pub const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Lease name shared by every catalog instance
+pub const PURGE_JOB: &str = "purge_job";
+
+/// How long an instance that stops purging keeps the others from taking over
+const PURGE_LEASE_TTL: chrono::Duration = chrono::Duration::minutes(90);
+
+/// How long a category merge may run before the purge job assumes it failed
+/// and finishes it
+const UNFINISHED_MERGE_GRACE: chrono::Duration = chrono::Duration::minutes(30);
+
+/// Periodically hard-delete products and categories that were soft deleted
+/// more than `retention` ago, after finishing any category merges that failed
+/// part way. Runs until the service shuts down.
+///
+/// Only the instance holding the job lease purges, so each run happens once
+/// however many instances there are.
+pub fn spawn_purge_job(
+    app_state: AppState,
+    retention: chrono::Duration,
+    job_lease_dao: Arc<dyn JobLeaseDao + Send + Sync>,
+) {
+    tokio::spawn(async move {
+        let owner = uuid::Uuid::new_v4().to_string();
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            match job_lease_dao
+                .acquire_lease(PURGE_JOB, &owner, Utc::now(), PURGE_LEASE_TTL)
+                .await
+            {
+                Ok(Some(_)) => run_purge(&app_state, retention).await,
+                Ok(None) => debug!("Purge is running on another instance"),
+                Err(e) => error!("Failed to take the purge job lease: {e}"),
+            }
+        }
+    });
+}
+
+async fn run_purge(app_state: &AppState, retention: chrono::Duration) {
+    match app_state
+        .category_service
+        .resume_unfinished_merges(UNFINISHED_MERGE_GRACE)
+        .await
+    {
+        Ok(0) => {}
+        Ok(count) => info!("🔀 Finished {count} interrupted category merges"),
+        Err(e) => error!("Failed to resume category merges: {e}"),
+    }
+
+    match app_state
+        .product_service
+        .purge_deleted_products(retention)
+        .await
+    {
+        Ok(0) => {}
+        Ok(count) => info!("🧹 Purged {count} deleted products past retention"),
+        Err(e) => error!("Failed to purge deleted products: {e:?}"),
+    }
+
+    match app_state
+        .category_service
+        .purge_deleted_categories(retention)
+        .await
+    {
+        Ok(0) => {}
+        Ok(count) => info!("🧹 Purged {count} deleted categories past retention"),
+        Err(e) => error!("Failed to purge deleted categories: {e}"),
+    }
+}
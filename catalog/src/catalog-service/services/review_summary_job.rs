@@ -0,0 +1,64 @@
+use crate::persistence::job_lease_dao::JobLeaseDao;
+use crate::AppState;
+use chrono::Utc;
+use log::{debug, error, info};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often every product's review summary is recomputed against the
+/// current catalog-wide prior
+pub const REVIEW_SUMMARY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Lease name shared by every catalog instance
+pub const REVIEW_SUMMARY_JOB: &str = "review_summary_job";
+
+/// How long an instance that stops recomputing keeps the others from taking over
+const REVIEW_SUMMARY_LEASE_TTL: chrono::Duration = chrono::Duration::minutes(90);
+
+/// Periodically recompute the review summaries of all reviewed products, so
+/// their Bayesian averages follow the prior as moderation moves it. Runs until
+/// the service shuts down.
+///
+/// Only the instance holding the job lease recomputes, so each run happens
+/// once however many instances there are.
+pub fn spawn_review_summary_job(
+    app_state: AppState,
+    job_lease_dao: Arc<dyn JobLeaseDao + Send + Sync>,
+) {
+    tokio::spawn(async move {
+        let owner = uuid::Uuid::new_v4().to_string();
+        let mut interval = tokio::time::interval(REVIEW_SUMMARY_INTERVAL);
+        loop {
+            interval.tick().await;
+            match job_lease_dao
+                .acquire_lease(
+                    REVIEW_SUMMARY_JOB,
+                    &owner,
+                    Utc::now(),
+                    REVIEW_SUMMARY_LEASE_TTL,
+                )
+                .await
+            {
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    debug!("Review summaries are recomputed on another instance");
+                    continue;
+                }
+                Err(e) => {
+                    error!("Failed to take the review summary job lease: {e}");
+                    continue;
+                }
+            }
+
+            match app_state
+                .product_service
+                .refresh_all_review_summaries()
+                .await
+            {
+                Ok(0) => {}
+                Ok(count) => info!("⭐ Recomputed review summaries for {count} products"),
+                Err(e) => error!("Failed to recompute review summaries: {e:?}"),
+            }
+        }
+    });
+}
@@ -0,0 +1,247 @@
+use crate::domain::{
+    normalize_locale, plan_sitemap_files, render_sitemap_index, render_urlset, sitemap_url,
+    Category, SitemapFile, SitemapKind, SitemapUrl, MAX_SITEMAP_URLS,
+};
+use crate::persistence::{category_dao::CategoryDao, product_dao::ProductDao};
+use crate::services::product_service::HandlerError;
+use flate2::{write::GzEncoder, Compression};
+use log::{debug, error};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::sync::Arc;
+
+/// Name of the sitemap index file; urlset files are listed relative to the same root
+pub const SITEMAP_INDEX_NAME: &str = "sitemap.xml.gz";
+
+const DEFAULT_PRODUCT_PATH: &str = "products";
+const DEFAULT_CATEGORY_PATH: &str = "categories";
+
+/// Where the sitemap's URLs point
+#[derive(Debug, Clone)]
+pub struct SitemapOptions {
+    pub base_url: String,
+    pub locale_base_urls: BTreeMap<String, String>, // Keyed by normalized locale
+    pub urls_per_file: usize,
+    pub product_path: String,
+    pub category_path: String,
+}
+
+impl SitemapOptions {
+    /// Validate the caller's settings, filling in defaults
+    pub fn new(
+        base_url: String,
+        locale_base_urls: impl IntoIterator<Item = (String, String)>,
+        urls_per_file: Option<i32>,
+        product_path: Option<String>,
+        category_path: Option<String>,
+    ) -> Result<Self, HandlerError> {
+        if !is_absolute_url(&base_url) {
+            return Err(HandlerError::ValidationError(format!(
+                "base_url must be an absolute http(s) URL, got '{base_url}'"
+            )));
+        }
+
+        let mut normalized = BTreeMap::new();
+        for (locale, url) in locale_base_urls {
+            let Some(tag) = normalize_locale(&locale) else {
+                return Err(HandlerError::ValidationError(format!(
+                    "'{locale}' is not a valid locale"
+                )));
+            };
+            if !is_absolute_url(&url) {
+                return Err(HandlerError::ValidationError(format!(
+                    "Base URL for {tag} must be an absolute http(s) URL, got '{url}'"
+                )));
+            }
+            normalized.insert(tag, url);
+        }
+
+        let urls_per_file = match urls_per_file {
+            None => MAX_SITEMAP_URLS,
+            Some(n) if n > 0 && n as usize <= MAX_SITEMAP_URLS => n as usize,
+            Some(n) => {
+                return Err(HandlerError::ValidationError(format!(
+                    "urls_per_file must be between 1 and {MAX_SITEMAP_URLS}, got {n}"
+                )))
+            }
+        };
+
+        Ok(Self {
+            base_url,
+            locale_base_urls: normalized,
+            urls_per_file,
+            product_path: product_path.unwrap_or_else(|| DEFAULT_PRODUCT_PATH.to_string()),
+            category_path: category_path.unwrap_or_else(|| DEFAULT_CATEGORY_PATH.to_string()),
+        })
+    }
+
+    /// The default site followed by each localized one
+    fn sites(&self) -> impl Iterator<Item = (Option<&str>, &str)> {
+        std::iter::once((None, self.base_url.as_str())).chain(
+            self.locale_base_urls
+                .iter()
+                .map(|(locale, url)| (Some(locale.as_str()), url.as_str())),
+        )
+    }
+}
+
+pub(crate) fn is_absolute_url(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+/// A rendered, gzip-compressed sitemap document
+#[derive(Debug)]
+pub struct RenderedSitemap {
+    pub name: String,
+    pub content: Vec<u8>,
+}
+
+pub struct SitemapService {
+    product_dao: Arc<dyn ProductDao + Send + Sync>,
+    category_dao: Arc<dyn CategoryDao + Send + Sync>,
+}
+
+impl SitemapService {
+    pub fn new(
+        product_dao: Arc<dyn ProductDao + Send + Sync>,
+        category_dao: Arc<dyn CategoryDao + Send + Sync>,
+    ) -> Self {
+        Self {
+            product_dao,
+            category_dao,
+        }
+    }
+
+    /// Every urlset file the index lists: live products then active categories,
+    /// for the default site and each localized one
+    pub async fn plan_files(
+        &self,
+        options: &SitemapOptions,
+    ) -> Result<Vec<SitemapFile>, HandlerError> {
+        let products = self.product_dao.count_live_products().await.map_err(|e| {
+            error!("Error counting products for sitemap: {e}");
+            HandlerError::InternalError(format!("Failed to count products: {e}"))
+        })? as usize;
+        let categories = self.active_categories().await?.len();
+
+        let mut files = Vec::new();
+        for (locale, _) in options.sites() {
+            files.extend(plan_sitemap_files(
+                SitemapKind::Products,
+                locale,
+                products,
+                options.urls_per_file,
+            ));
+            files.extend(plan_sitemap_files(
+                SitemapKind::Categories,
+                locale,
+                categories,
+                options.urls_per_file,
+            ));
+        }
+        Ok(files)
+    }
+
+    /// The sitemap index, with the urlset files it lists
+    pub async fn render_index(
+        &self,
+        options: &SitemapOptions,
+    ) -> Result<(RenderedSitemap, Vec<SitemapFile>), HandlerError> {
+        let files = self.plan_files(options).await?;
+        let sitemaps: Vec<SitemapUrl> = files
+            .iter()
+            .map(|file| SitemapUrl {
+                loc: sitemap_url(&options.base_url, "", &file.name),
+                lastmod: None,
+            })
+            .collect();
+
+        let index = RenderedSitemap {
+            name: SITEMAP_INDEX_NAME.to_string(),
+            content: gzip(&render_sitemap_index(&sitemaps))?,
+        };
+        Ok((index, files))
+    }
+
+    /// One urlset file by the name the index lists it under. Returns None for an unknown name.
+    pub async fn render_file(
+        &self,
+        options: &SitemapOptions,
+        name: &str,
+    ) -> Result<Option<RenderedSitemap>, HandlerError> {
+        let files = self.plan_files(options).await?;
+        let Some(file) = files.into_iter().find(|file| file.name == name) else {
+            return Ok(None);
+        };
+        debug!("Rendering sitemap file {name}: {file:?}");
+
+        let root = match &file.locale {
+            Some(locale) => &options.locale_base_urls[locale],
+            None => &options.base_url,
+        };
+        let locale = file.locale.as_deref();
+
+        let urls: Vec<SitemapUrl> = match file.kind {
+            SitemapKind::Products => self
+                .product_dao
+                .list_live_products(file.offset as u64, file.url_count as i64)
+                .await
+                .map_err(|e| {
+                    error!("Error listing products for sitemap: {e}");
+                    HandlerError::InternalError(format!("Failed to list products: {e}"))
+                })?
+                .into_iter()
+                .filter_map(|product| {
+                    let product = match locale {
+                        Some(locale) => product.localized(locale),
+                        None => product,
+                    };
+                    Some(SitemapUrl {
+                        loc: sitemap_url(root, &options.product_path, product.slug.as_ref()?),
+                        lastmod: product.updated_at.or(product.created_at),
+                    })
+                })
+                .collect(),
+            SitemapKind::Categories => self
+                .active_categories()
+                .await?
+                .into_iter()
+                .skip(file.offset)
+                .take(file.url_count)
+                .map(|category| {
+                    let category = match locale {
+                        Some(locale) => category.localized(locale),
+                        None => category,
+                    };
+                    SitemapUrl {
+                        loc: sitemap_url(root, &options.category_path, &category.slug),
+                        lastmod: Some(category.updated_at),
+                    }
+                })
+                .collect(),
+        };
+
+        Ok(Some(RenderedSitemap {
+            name: file.name,
+            content: gzip(&render_urlset(&urls))?,
+        }))
+    }
+
+    async fn active_categories(&self) -> Result<Vec<Category>, HandlerError> {
+        self.category_dao
+            .get_active_categories()
+            .await
+            .map_err(|e| {
+                error!("Error listing categories for sitemap: {e}");
+                HandlerError::InternalError(format!("Failed to list categories: {e}"))
+            })
+    }
+}
+
+fn gzip(xml: &str) -> Result<Vec<u8>, HandlerError> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(xml.as_bytes())
+        .and_then(|_| encoder.finish())
+        .map_err(|e| HandlerError::InternalError(format!("Failed to compress sitemap: {e}")))
+}
@@ -0,0 +1,287 @@
+use crate::domain::{
+    breadcrumb_list_json_ld, json_ld_script_content, normalize_locale, product_json_ld,
+    sitemap_url, BreadcrumbItem, Category, Product, ProductOffers,
+};
+use crate::persistence::{
+    category_dao::CategoryDao, product_dao::ProductDao, review_dao::ReviewDao,
+};
+use crate::services::product_service::HandlerError;
+use crate::services::sitemap_service::is_absolute_url;
+use crate::services::{inventory_client, price_client};
+use async_nats::Client;
+use chrono::Utc;
+use log::{error, warn};
+use std::sync::Arc;
+
+const DEFAULT_PRODUCT_PATH: &str = "products";
+const DEFAULT_CATEGORY_PATH: &str = "categories";
+
+/// Where structured data URLs point, and which language it is written in
+#[derive(Debug, Clone)]
+pub struct StructuredDataOptions {
+    pub base_url: String,
+    pub product_path: String,
+    pub category_path: String,
+    pub locale: Option<String>, // Normalized
+}
+
+impl StructuredDataOptions {
+    /// Validate the caller's settings, filling in defaults
+    pub fn new(
+        base_url: String,
+        product_path: Option<String>,
+        category_path: Option<String>,
+        locale: Option<String>,
+    ) -> Result<Self, HandlerError> {
+        if !is_absolute_url(&base_url) {
+            return Err(HandlerError::ValidationError(format!(
+                "base_url must be an absolute http(s) URL, got '{base_url}'"
+            )));
+        }
+        let locale = match locale {
+            Some(locale) => Some(normalize_locale(&locale).ok_or_else(|| {
+                HandlerError::ValidationError(format!("'{locale}' is not a valid locale"))
+            })?),
+            None => None,
+        };
+
+        Ok(Self {
+            base_url,
+            product_path: product_path.unwrap_or_else(|| DEFAULT_PRODUCT_PATH.to_string()),
+            category_path: category_path.unwrap_or_else(|| DEFAULT_CATEGORY_PATH.to_string()),
+            locale,
+        })
+    }
+}
+
+/// A product's schema.org Product, ready to embed
+#[derive(Debug)]
+pub struct ProductStructuredData {
+    pub json_ld: String,
+    pub unpriced_skus: Vec<String>,
+}
+
+/// A breadcrumb trail, root first, with its schema.org BreadcrumbList ready to embed
+#[derive(Debug)]
+pub struct Breadcrumbs {
+    pub items: Vec<BreadcrumbItem>,
+    pub json_ld: String,
+}
+
+pub struct StructuredDataService {
+    product_dao: Arc<dyn ProductDao + Send + Sync>,
+    category_dao: Arc<dyn CategoryDao + Send + Sync>,
+    review_dao: Arc<dyn ReviewDao + Send + Sync>,
+}
+
+impl StructuredDataService {
+    pub fn new(
+        product_dao: Arc<dyn ProductDao + Send + Sync>,
+        category_dao: Arc<dyn CategoryDao + Send + Sync>,
+        review_dao: Arc<dyn ReviewDao + Send + Sync>,
+    ) -> Self {
+        Self {
+            product_dao,
+            category_dao,
+            review_dao,
+        }
+    }
+
+    /// schema.org Product for a live product, with offers priced by price-service
+    /// in `currency`. Stock levels from inventory-service add availability when
+    /// they can be looked up; a failed price lookup fails the request.
+    pub async fn product_json_ld(
+        &self,
+        client: &Client,
+        product_id: &str,
+        currency: &str,
+        options: &StructuredDataOptions,
+    ) -> Result<ProductStructuredData, HandlerError> {
+        if currency.len() != 3 {
+            return Err(HandlerError::ValidationError(format!(
+                "currency must be a three-letter ISO 4217 code, got '{currency}'"
+            )));
+        }
+        let product = self.live_product(product_id, options).await?;
+        let url = product_url(&product, options)?;
+
+        let skus: Vec<String> = product.variants.iter().map(|v| v.sku.clone()).collect();
+        let prices = price_client::get_best_offer_prices(client, &skus, currency)
+            .await
+            .map_err(|e| e.to_string())
+            .map_err(|e| {
+                error!("Error looking up prices for product {product_id}: {e}");
+                HandlerError::InternalError(format!("Failed to look up prices: {e}"))
+            })?;
+        let available = inventory_client::get_available_quantities(client, &skus)
+            .await
+            .map_err(|e| e.to_string());
+        let available = match available {
+            Ok(available) => Some(available),
+            Err(e) => {
+                warn!("Leaving out availability for product {product_id}: {e}");
+                None
+            }
+        };
+
+        let ratings = self
+            .review_dao
+            .product_rating_totals(product_id)
+            .await
+            .map_err(|e| {
+                error!("Error totalling reviews for product {product_id}: {e}");
+                HandlerError::InternalError(format!("Failed to total reviews: {e}"))
+            })?;
+
+        let unpriced_skus = skus
+            .into_iter()
+            .filter(|sku| !prices.contains_key(sku))
+            .collect();
+        let offers = ProductOffers {
+            currency,
+            prices,
+            available,
+        };
+        Ok(ProductStructuredData {
+            json_ld: json_ld_script_content(&product_json_ld(&product, &url, &offers, ratings)),
+            unpriced_skus,
+        })
+    }
+
+    /// Breadcrumbs from the root down to an active category
+    pub async fn category_breadcrumbs(
+        &self,
+        category_id: &str,
+        options: &StructuredDataOptions,
+    ) -> Result<Breadcrumbs, HandlerError> {
+        let trail = self.category_trail(category_id).await?.ok_or_else(|| {
+            HandlerError::NotFound(format!("Category with ID {category_id} not found"))
+        })?;
+
+        Ok(breadcrumbs(
+            trail
+                .into_iter()
+                .map(|category| category_breadcrumb(category, options))
+                .collect(),
+        ))
+    }
+
+    /// Breadcrumbs for a live product: the trail to the first of its listed
+    /// categories that is active all the way up, then the product itself
+    pub async fn product_breadcrumbs(
+        &self,
+        product_id: &str,
+        options: &StructuredDataOptions,
+    ) -> Result<Breadcrumbs, HandlerError> {
+        let product = self.live_product(product_id, options).await?;
+        let url = product_url(&product, options)?;
+
+        let mut items = Vec::new();
+        for category_ref in &product.list_categories {
+            let category = match self.category_dao.get_category(category_ref).await {
+                Ok(None) => self.category_dao.get_category_by_slug(category_ref).await,
+                found => found,
+            }
+            .map_err(|e| {
+                error!("Error looking up category {category_ref}: {e}");
+                HandlerError::InternalError(format!("Failed to look up category: {e}"))
+            })?;
+            let Some(category_id) = category.and_then(|category| category.id) else {
+                continue;
+            };
+            if let Some(trail) = self.category_trail(&category_id).await? {
+                items = trail
+                    .into_iter()
+                    .map(|category| category_breadcrumb(category, options))
+                    .collect();
+                break;
+            }
+        }
+        items.push(BreadcrumbItem {
+            name: product.name,
+            url,
+        });
+
+        Ok(breadcrumbs(items))
+    }
+
+    async fn live_product(
+        &self,
+        product_id: &str,
+        options: &StructuredDataOptions,
+    ) -> Result<Product, HandlerError> {
+        let product = self
+            .product_dao
+            .get_product(product_id)
+            .await
+            .map_err(|e| {
+                error!("Error getting product {product_id}: {e}");
+                HandlerError::InternalError(format!("Failed to get product: {e}"))
+            })?
+            .filter(|product| {
+                product.display_on_site && product.is_within_publish_window(Utc::now())
+            })
+            .ok_or_else(|| {
+                HandlerError::NotFound(format!("Product with ID {product_id} not found"))
+            })?;
+
+        Ok(match &options.locale {
+            Some(locale) => product.localized(locale),
+            None => product,
+        })
+    }
+
+    /// The category and its ancestors, root first, or None when any of them
+    /// is missing or inactive
+    async fn category_trail(
+        &self,
+        category_id: &str,
+    ) -> Result<Option<Vec<Category>>, HandlerError> {
+        let trail = self
+            .category_dao
+            .get_breadcrumbs(category_id)
+            .await
+            .map_err(|e| {
+                error!("Error getting breadcrumbs for category {category_id}: {e}");
+                HandlerError::InternalError(format!("Failed to get breadcrumbs: {e}"))
+            })?;
+
+        let complete = match trail.last() {
+            Some(category) => {
+                category.id.as_deref() == Some(category_id)
+                    && trail.len() == category.ancestors.len() + 1
+                    && trail.iter().all(|category| category.is_active)
+            }
+            None => false,
+        };
+        Ok(complete.then_some(trail))
+    }
+}
+
+fn product_url(product: &Product, options: &StructuredDataOptions) -> Result<String, HandlerError> {
+    let slug = product.slug.as_deref().ok_or_else(|| {
+        HandlerError::NotFound(format!(
+            "Product {} has no slug to link to",
+            product.id.as_deref().unwrap_or_default()
+        ))
+    })?;
+    Ok(sitemap_url(&options.base_url, &options.product_path, slug))
+}
+
+fn category_breadcrumb(category: Category, options: &StructuredDataOptions) -> BreadcrumbItem {
+    let category = match &options.locale {
+        Some(locale) => category.localized(locale),
+        None => category,
+    };
+    BreadcrumbItem {
+        url: sitemap_url(&options.base_url, &options.category_path, &category.slug),
+        name: category.name,
+    }
+}
+
+fn breadcrumbs(items: Vec<BreadcrumbItem>) -> Breadcrumbs {
+    Breadcrumbs {
+        json_ld: json_ld_script_content(&breadcrumb_list_json_ld(&items)),
+        items,
+    }
+}
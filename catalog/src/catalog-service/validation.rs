@@ -16,7 +16,15 @@ pub async fn validate_catalog_dependencies(
         .await
         .with_context("Failed to list catalog collections")?;
 
-    for required_collection in &["products", "categories", "category_tree_cache"] {
+    for required_collection in &[
+        "products",
+        "categories",
+        "category_tree_cache",
+        "slug_history",
+        "product_revisions",
+        "attribute_schemas",
+        "reviews",
+    ] {
         if collections.contains(&required_collection.to_string()) {
             debug!("✅ Collection '{required_collection}' exists");
         } else {
@@ -21,6 +21,11 @@ pub struct AppState {
     pub category_dao: std::sync::Arc<dyn persistence::category_dao::CategoryDao + Send + Sync>,
     pub product_service: std::sync::Arc<services::product_service::ProductService>,
     pub category_service: std::sync::Arc<services::category_service::CategoryService>,
+    pub sitemap_service: std::sync::Arc<services::sitemap_service::SitemapService>,
+    pub structured_data_service:
+        std::sync::Arc<services::structured_data_service::StructuredDataService>,
+    pub catalog_publishing_service:
+        std::sync::Arc<services::catalog_publishing_service::CatalogPublishingService>,
 }
 
 // Import common module for generated proto code
@@ -35,6 +40,17 @@ pub mod catalog_messages {
     pub use super::common::{Code, Status};
 }
 
+// Inventory-service messages, used to seed stock records for generated variants
+// and to look up availability for the merchant feed
+pub mod inventory_messages {
+    include!(concat!(env!("OUT_DIR"), "/inventory_messages.rs"));
+}
+
+// Price-service messages, used by the merchant feed exporter to look up offer prices
+pub mod offer_messages {
+    include!(concat!(env!("OUT_DIR"), "/offer_messages.rs"));
+}
+
 // Include the generated NATS configuration
 include!(concat!(env!("OUT_DIR"), "/nats_config.rs"));
 
@@ -3,6 +3,7 @@ use crate::helpers::{self, catalog_messages::*};
 use prost::Message;
 use rust_common::test_helpers::fixtures;
 use shared_proto::common::Code;
+use std::collections::HashMap;
 
 // ============================================================================
 // CATEGORY CREATE TESTS
@@ -23,6 +24,8 @@ async fn test_category_create_root_category() {
         seo: None,
         is_active: Some(true),
         parent_slug: None,
+        localizations: HashMap::new(),
+        stores: HashMap::new(),
     };
 
     let response = app
@@ -72,6 +75,8 @@ async fn test_category_create_child_category() {
         seo: None,
         is_active: Some(true),
         parent_slug: None,
+        localizations: HashMap::new(),
+        stores: HashMap::new(),
     };
 
     let response = app
@@ -112,6 +117,8 @@ async fn test_category_get_existing() {
 
     let request = GetCategoryRequest {
         id: category_id.clone(),
+        locale: None,
+        catalog_version: None,
     };
 
     let response = app
@@ -140,6 +147,8 @@ async fn test_category_get_non_existent() {
 
     let request = GetCategoryRequest {
         id: "non-existent-id".to_string(),
+        locale: None,
+        catalog_version: None,
     };
 
     let response = app
@@ -176,7 +185,10 @@ async fn test_category_get_by_slug_existing() {
         .await
         .expect("Should create category");
 
-    let request = GetCategoryBySlugRequest { slug: slug.clone() };
+    let request = GetCategoryBySlugRequest {
+        slug: slug.clone(),
+        locale: None,
+    };
 
     let response = app
         .request(
@@ -221,6 +233,10 @@ async fn test_category_update_name_and_description() {
         display_order: None,
         seo: None,
         is_active: None,
+        localizations: HashMap::new(),
+        remove_locales: vec![],
+        remove_stores: vec![],
+        stores: HashMap::new(),
     };
 
     let response = app
@@ -246,6 +262,69 @@ async fn test_category_update_name_and_description() {
     assert_eq!(category.short_description, "Updated short description");
 }
 
+#[tokio::test]
+async fn test_category_get_by_slug_redirects_after_slug_change() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let builder = fixtures::category::CategoryBuilder::default();
+    let old_slug = builder.slug.clone();
+    let category_id = create_test_category(&app, builder)
+        .await
+        .expect("Should create category");
+
+    let new_slug = format!("{old_slug}-renamed");
+    let request = UpdateCategoryRequest {
+        id: category_id.clone(),
+        name: None,
+        slug: Some(new_slug.clone()),
+        short_description: None,
+        full_description: None,
+        display_order: None,
+        seo: None,
+        is_active: None,
+        localizations: HashMap::new(),
+        remove_locales: vec![],
+        remove_stores: vec![],
+        stores: HashMap::new(),
+    };
+
+    app.request(
+        crate::helpers::nats_config::category::subjects::UPDATE_CATEGORY,
+        request.encode_to_vec(),
+    )
+    .await
+    .expect("Request should succeed");
+
+    let response = get_category_by_slug(&app, &old_slug)
+        .await
+        .expect("Should get response");
+
+    assert_eq!(response.status.unwrap().code, Code::Ok as i32);
+    assert_eq!(response.redirect_slug, Some(new_slug));
+    assert_eq!(response.category.unwrap().id, category_id);
+
+    // The retired slug keeps redirecting; no other category may take it over
+    let other_id = create_test_category(&app, fixtures::category::CategoryBuilder::default())
+        .await
+        .expect("Should create category");
+    let response = update_category(
+        &app,
+        UpdateCategoryRequest {
+            id: other_id,
+            slug: Some(old_slug.clone()),
+            ..request
+        },
+    )
+    .await
+    .expect("Request should succeed");
+    assert_ne!(response.status.unwrap().code, Code::Ok as i32);
+
+    let response = get_category_by_slug(&app, &old_slug)
+        .await
+        .expect("Should get response");
+    assert_eq!(response.category.unwrap().id, category_id);
+}
+
 // ============================================================================
 // CATEGORY DELETE TESTS
 // ============================================================================
@@ -281,7 +360,11 @@ async fn test_category_delete_existing() {
     assert_eq!(status.code, Code::Ok as i32);
 
     // Verify it's deleted by trying to get it
-    let get_request = GetCategoryRequest { id: category_id };
+    let get_request = GetCategoryRequest {
+        id: category_id,
+        locale: None,
+        catalog_version: None,
+    };
 
     let get_response = app
         .request(
@@ -300,6 +383,42 @@ async fn test_category_delete_existing() {
     assert_eq!(response.status.unwrap().code, Code::NotFound as i32);
 }
 
+#[tokio::test]
+async fn test_category_delete_then_restore() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let builder = fixtures::category::CategoryBuilder::default();
+    let category_id = create_test_category(&app, builder)
+        .await
+        .expect("Should create category");
+
+    let response = delete_category(&app, &category_id)
+        .await
+        .expect("Should delete category");
+    assert_eq!(response.status.unwrap().code, Code::Ok as i32);
+
+    let request = RestoreCategoryRequest {
+        id: category_id.clone(),
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::category::subjects::RESTORE_CATEGORY,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let restore_response =
+        RestoreCategoryResponse::decode(&*response.payload).expect("Response should decode");
+
+    assert_eq!(restore_response.status.unwrap().code, Code::Ok as i32);
+    assert_eq!(restore_response.category.unwrap().id, category_id);
+
+    let response = get_category(&app, &category_id)
+        .await
+        .expect("Should get category");
+    assert!(response.category.is_some());
+}
+
 // ============================================================================
 // CATEGORY TREE TESTS
 // ============================================================================
@@ -329,6 +448,8 @@ async fn test_category_tree_with_hierarchy() {
         max_depth: Some(10),
         include_inactive: Some(false),
         rebuild_cache: None,
+        locale: None,
+        store_id: None,
     };
 
     let response = app
@@ -346,3 +467,894 @@ async fn test_category_tree_with_hierarchy() {
     assert_eq!(tree_response.status.unwrap().code, Code::Ok as i32);
     assert!(!tree_response.tree.is_empty());
 }
+
+#[tokio::test]
+async fn test_category_tree_reflects_changes_and_prunes_inactive_and_deep_nodes() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let root_id = create_test_category(&app, fixtures::category::CategoryBuilder::root())
+        .await
+        .expect("Should create root");
+    let child_id = create_test_category(
+        &app,
+        fixtures::category::CategoryBuilder::child_of(root_id.clone()),
+    )
+    .await
+    .expect("Should create child");
+
+    // Load the tree so the service holds it in memory
+    let tree = get_category_tree(&app, None, false).await;
+    assert_eq!(tree.len(), 1);
+    assert_eq!(tree[0].children.len(), 1);
+
+    let rename = UpdateCategoryRequest {
+        id: root_id.clone(),
+        name: Some("Renamed Root".to_string()),
+        slug: None,
+        short_description: None,
+        full_description: None,
+        display_order: None,
+        seo: None,
+        is_active: None,
+        localizations: HashMap::new(),
+        remove_locales: vec![],
+        remove_stores: vec![],
+        stores: HashMap::new(),
+    };
+    update_category(&app, rename)
+        .await
+        .expect("Should rename root");
+    let deactivate = UpdateCategoryRequest {
+        id: child_id.clone(),
+        name: None,
+        slug: None,
+        short_description: None,
+        full_description: None,
+        display_order: None,
+        seo: None,
+        is_active: Some(false),
+        localizations: HashMap::new(),
+        remove_locales: vec![],
+        remove_stores: vec![],
+        stores: HashMap::new(),
+    };
+    update_category(&app, deactivate)
+        .await
+        .expect("Should deactivate child");
+
+    // Writes drop the in-memory copy, so the next read sees them
+    let tree = get_category_tree(&app, None, false).await;
+    assert_eq!(tree[0].name, "Renamed Root");
+    assert!(tree[0].children.is_empty());
+
+    let tree = get_category_tree(&app, None, true).await;
+    assert_eq!(tree[0].children.len(), 1);
+    assert_eq!(tree[0].children[0].id, child_id);
+
+    let tree = get_category_tree(&app, Some(1), true).await;
+    assert_eq!(tree.len(), 1);
+    assert!(tree[0].children.is_empty());
+}
+
+#[tokio::test]
+async fn test_category_tree_for_a_store() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let root_id = create_test_category(&app, fixtures::category::CategoryBuilder::root())
+        .await
+        .expect("Should create root");
+    let eu_id = create_test_category(
+        &app,
+        fixtures::category::CategoryBuilder::child_of(root_id.clone()),
+    )
+    .await
+    .expect("Should create child");
+    let us_id = create_test_category(
+        &app,
+        fixtures::category::CategoryBuilder::child_of(root_id.clone()),
+    )
+    .await
+    .expect("Should create child");
+
+    let assign =
+        |id: &str, store_id: &str, is_active: bool, slug: Option<String>| UpdateCategoryRequest {
+            id: id.to_string(),
+            name: None,
+            slug: None,
+            short_description: None,
+            full_description: None,
+            display_order: None,
+            seo: None,
+            is_active: None,
+            localizations: HashMap::new(),
+            remove_locales: vec![],
+            stores: HashMap::from([(
+                store_id.to_string(),
+                CategoryStoreSettings { is_active, slug },
+            )]),
+            remove_stores: vec![],
+        };
+    let eu_slug = format!("eu-{}", fixtures::random_string(8));
+    update_category(&app, assign(&eu_id, "eu", true, Some(eu_slug.clone())))
+        .await
+        .expect("Should assign child to eu");
+    update_category(&app, assign(&us_id, "us", false, None))
+        .await
+        .expect("Should assign child to us");
+
+    let store_tree = |store_id: &'static str, include_inactive: bool| {
+        let app = &app;
+        async move {
+            let request = CategoryTreeRequest {
+                max_depth: None,
+                include_inactive: Some(include_inactive),
+                rebuild_cache: None,
+                locale: None,
+                store_id: Some(store_id.to_string()),
+            };
+            let response = app
+                .request(
+                    crate::helpers::nats_config::category::subjects::GET_CATEGORY_TREE,
+                    request.encode_to_vec(),
+                )
+                .await
+                .expect("Request should succeed");
+            CategoryTreeResponse::decode(&*response.payload).expect("Response should decode")
+        }
+    };
+
+    // The root is in every store; each child only in its own
+    let eu = store_tree("eu", false).await.tree;
+    assert_eq!(eu.len(), 1);
+    assert_eq!(eu[0].children.len(), 1);
+    assert_eq!(eu[0].children[0].id, eu_id);
+    assert_eq!(eu[0].children[0].slug, eu_slug);
+
+    // The store's own active flag decides, not the category's
+    let us = store_tree("us", false).await.tree;
+    assert!(us[0].children.is_empty());
+    let us = store_tree("us", true).await.tree;
+    assert_eq!(us[0].children[0].id, us_id);
+
+    // Without a store the whole tree is returned
+    let tree = get_category_tree(&app, None, false).await;
+    assert_eq!(tree[0].children.len(), 2);
+
+    let invalid = store_tree("eu.main", false).await;
+    assert_eq!(invalid.status.unwrap().code, Code::InvalidArgument as i32);
+}
+
+// ============================================================================
+// CATEGORY PRODUCT COUNT TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_category_product_counts_follow_product_changes() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let root_id = create_test_category(&app, fixtures::category::CategoryBuilder::root())
+        .await
+        .expect("Should create root");
+    let child_id = create_test_category(
+        &app,
+        fixtures::category::CategoryBuilder::child_of(root_id.clone()),
+    )
+    .await
+    .expect("Should create child");
+    let child_slug = get_category(&app, &child_id)
+        .await
+        .unwrap()
+        .category
+        .unwrap()
+        .slug;
+
+    let counts = |id: String| {
+        let app = &app;
+        async move {
+            let category = get_category(app, &id).await.unwrap().category.unwrap();
+            (category.product_count, category.subtree_product_count)
+        }
+    };
+
+    // List the product in the child by slug and the root by id
+    let product_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create product");
+    let mut product = get_product(&app, &product_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap();
+    product.list_categories = vec![child_slug, root_id.clone()];
+    let request = ProductUpdateRequest {
+        id: product_id.clone(),
+        product: Some(product.clone()),
+    };
+    app.request(
+        crate::helpers::nats_config::product::subjects::UPDATE_PRODUCT,
+        request.encode_to_vec(),
+    )
+    .await
+    .expect("Update should succeed");
+
+    assert_eq!(counts(child_id.clone()).await, (1, 1));
+    assert_eq!(
+        counts(root_id.clone()).await,
+        (1, 1),
+        "Product listed in both root and child counts once in the root subtree"
+    );
+
+    // Hiding the product removes it from every count
+    product.list_categories = vec![child_id.clone()];
+    product.display_on_site = false;
+    let request = ProductUpdateRequest {
+        id: product_id.clone(),
+        product: Some(product),
+    };
+    app.request(
+        crate::helpers::nats_config::product::subjects::UPDATE_PRODUCT,
+        request.encode_to_vec(),
+    )
+    .await
+    .expect("Update should succeed");
+
+    assert_eq!(counts(child_id.clone()).await, (0, 0));
+    assert_eq!(counts(root_id.clone()).await, (0, 0));
+
+    // A displayed product rolls up to the root until it is deleted
+    let other_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create product");
+    let mut other = get_product(&app, &other_id).await.unwrap().product.unwrap();
+    other.list_categories = vec![child_id.clone()];
+    let request = ProductUpdateRequest {
+        id: other_id.clone(),
+        product: Some(other),
+    };
+    app.request(
+        crate::helpers::nats_config::product::subjects::UPDATE_PRODUCT,
+        request.encode_to_vec(),
+    )
+    .await
+    .expect("Update should succeed");
+
+    assert_eq!(counts(child_id.clone()).await, (1, 1));
+    assert_eq!(counts(root_id.clone()).await, (0, 1));
+
+    delete_product(&app, &other_id)
+        .await
+        .expect("Delete should succeed");
+
+    assert_eq!(counts(child_id).await, (0, 0));
+    assert_eq!(counts(root_id).await, (0, 0));
+}
+
+#[tokio::test]
+async fn test_category_product_counts_follow_category_delete_restore_and_schedules() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let root_id = create_test_category(&app, fixtures::category::CategoryBuilder::root())
+        .await
+        .expect("Should create root");
+    let child_id = create_test_category(
+        &app,
+        fixtures::category::CategoryBuilder::child_of(root_id.clone()),
+    )
+    .await
+    .expect("Should create child");
+
+    let counts = |id: String| {
+        let app = &app;
+        async move {
+            let category = get_category(app, &id).await.unwrap().category.unwrap();
+            (category.product_count, category.subtree_product_count)
+        }
+    };
+    let list_in_child = |product_id: String, publish_at: Option<prost_types::Timestamp>| {
+        let app = &app;
+        let child_id = child_id.clone();
+        async move {
+            let mut product = get_product(app, &product_id)
+                .await
+                .unwrap()
+                .product
+                .unwrap();
+            product.list_categories = vec![child_id];
+            product.publish_at = publish_at;
+            let request = ProductUpdateRequest {
+                id: product_id,
+                product: Some(product),
+            };
+            app.request(
+                crate::helpers::nats_config::product::subjects::UPDATE_PRODUCT,
+                request.encode_to_vec(),
+            )
+            .await
+            .expect("Update should succeed");
+        }
+    };
+
+    let live_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create product");
+    list_in_child(live_id, None).await;
+
+    // A product scheduled for tomorrow is not counted yet
+    let scheduled_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create product");
+    let tomorrow = chrono::Utc::now() + chrono::Duration::days(1);
+    list_in_child(
+        scheduled_id,
+        Some(prost_types::Timestamp {
+            seconds: tomorrow.timestamp(),
+            nanos: 0,
+        }),
+    )
+    .await;
+
+    assert_eq!(counts(child_id.clone()).await, (1, 1));
+    assert_eq!(counts(root_id.clone()).await, (0, 1));
+
+    // Deleting the child takes its products out of the root's subtree
+    let response = delete_category(&app, &child_id)
+        .await
+        .expect("Should delete category");
+    assert_eq!(response.status.unwrap().code, Code::Ok as i32);
+    assert_eq!(counts(root_id.clone()).await, (0, 0));
+
+    // Restoring it puts them back
+    let request = RestoreCategoryRequest {
+        id: child_id.clone(),
+    };
+    app.request(
+        crate::helpers::nats_config::category::subjects::RESTORE_CATEGORY,
+        request.encode_to_vec(),
+    )
+    .await
+    .expect("Request should succeed");
+    assert_eq!(counts(child_id).await, (1, 1));
+    assert_eq!(counts(root_id).await, (0, 1));
+}
+
+// ============================================================================
+// CATEGORY PATH TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_product_category_paths_follow_category_renames_and_moves() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let root_id = create_test_category(&app, fixtures::category::CategoryBuilder::root())
+        .await
+        .expect("Should create root");
+    let child_id = create_test_category(
+        &app,
+        fixtures::category::CategoryBuilder::child_of(root_id.clone()),
+    )
+    .await
+    .expect("Should create child");
+    let child_name = get_category(&app, &child_id)
+        .await
+        .unwrap()
+        .category
+        .unwrap()
+        .name;
+
+    let product_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create product");
+    let product = set_product_categories(&app, &product_id, vec![child_id.clone()])
+        .await
+        .expect("Update should succeed");
+    let paths = product
+        .hierarchical_categories
+        .expect("Paths should be derived");
+    assert_eq!(
+        paths.lvl1,
+        Some(format!(
+            "{} > {child_name}",
+            paths.lvl0.clone().expect("Root level should be set")
+        ))
+    );
+
+    // Renaming the root rewrites the product's paths
+    let request = UpdateCategoryRequest {
+        id: root_id.clone(),
+        name: Some("Renamed Root".to_string()),
+        slug: None,
+        short_description: None,
+        full_description: None,
+        display_order: None,
+        seo: None,
+        is_active: None,
+        localizations: HashMap::new(),
+        remove_locales: vec![],
+        remove_stores: vec![],
+        stores: HashMap::new(),
+    };
+    app.request(
+        crate::helpers::nats_config::category::subjects::UPDATE_CATEGORY,
+        request.encode_to_vec(),
+    )
+    .await
+    .expect("Update should succeed");
+
+    let paths = get_product(&app, &product_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap()
+        .hierarchical_categories
+        .unwrap();
+    assert_eq!(paths.lvl0.as_deref(), Some("Renamed Root"));
+    assert_eq!(paths.lvl1, Some(format!("Renamed Root > {child_name}")));
+
+    // Moving the child to the root shortens them
+    let request = MoveCategoryRequest {
+        category_id: child_id.clone(),
+        new_parent_id: None,
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::category::subjects::MOVE_CATEGORY,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let move_response =
+        MoveCategoryResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(move_response.status.unwrap().code, Code::Ok as i32);
+
+    let paths = get_product(&app, &product_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap()
+        .hierarchical_categories
+        .unwrap();
+    assert_eq!(paths.lvl0, Some(child_name));
+    assert_eq!(paths.lvl1, None);
+    assert_eq!(paths.levels.len(), 1);
+}
+
+#[tokio::test]
+async fn test_category_move_under_own_descendant_is_rejected() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let root_id = create_test_category(&app, fixtures::category::CategoryBuilder::root())
+        .await
+        .expect("Should create root");
+    let child_id = create_test_category(
+        &app,
+        fixtures::category::CategoryBuilder::child_of(root_id.clone()),
+    )
+    .await
+    .expect("Should create child");
+
+    let request = MoveCategoryRequest {
+        category_id: root_id,
+        new_parent_id: Some(child_id),
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::category::subjects::MOVE_CATEGORY,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let move_response =
+        MoveCategoryResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(
+        move_response.status.unwrap().code,
+        Code::InvalidArgument as i32
+    );
+}
+
+// ============================================================================
+// CATEGORY MERGE TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_category_merge_moves_children_products_and_slug() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let source = fixtures::category::CategoryBuilder::root();
+    let source_slug = source.slug.clone();
+    let source_id = create_test_category(&app, source)
+        .await
+        .expect("Should create source");
+    let child_id = create_test_category(
+        &app,
+        fixtures::category::CategoryBuilder::child_of(source_id.clone()),
+    )
+    .await
+    .expect("Should create child");
+    let target_id = create_test_category(&app, fixtures::category::CategoryBuilder::root())
+        .await
+        .expect("Should create target");
+
+    let product_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create product");
+    set_product_categories(&app, &product_id, vec![source_id.clone()])
+        .await
+        .expect("Update should succeed");
+
+    // A dry run reports the merge and changes nothing
+    let report = merge_categories(&app, &source_id, &target_id, true)
+        .await
+        .expect("Request should succeed");
+    assert_eq!(report.status.unwrap().code, Code::Ok as i32);
+    assert!(report.dry_run);
+    assert_eq!(report.moved_subcategory_ids, vec![child_id.clone()]);
+    assert_eq!(report.affected_product_ids, vec![product_id.clone()]);
+    assert_eq!(report.redirected_slug, source_slug);
+    assert!(get_category(&app, &source_id)
+        .await
+        .unwrap()
+        .category
+        .is_some());
+
+    let report = merge_categories(&app, &source_id, &target_id, false)
+        .await
+        .expect("Request should succeed");
+    assert_eq!(report.status.unwrap().code, Code::Ok as i32);
+    assert!(!report.dry_run);
+
+    assert!(get_category(&app, &source_id)
+        .await
+        .unwrap()
+        .category
+        .is_none());
+    let child = get_category(&app, &child_id)
+        .await
+        .unwrap()
+        .category
+        .unwrap();
+    assert_eq!(child.parent_id, Some(target_id.clone()));
+
+    let product = get_product(&app, &product_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap();
+    assert_eq!(product.list_categories, vec![target_id.clone()]);
+    let target = get_category(&app, &target_id)
+        .await
+        .unwrap()
+        .category
+        .unwrap();
+    assert_eq!(target.product_count, 1);
+
+    let redirected = get_category_by_slug(&app, &source_slug)
+        .await
+        .expect("Should get response");
+    assert_eq!(redirected.category.unwrap().id, target_id);
+}
+
+#[tokio::test]
+async fn test_category_merge_interrupted_midway_is_finished_by_a_retry() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let source = fixtures::category::CategoryBuilder::root();
+    let source_slug = source.slug.clone();
+    let source_id = create_test_category(&app, source)
+        .await
+        .expect("Should create source");
+    let child_id = create_test_category(
+        &app,
+        fixtures::category::CategoryBuilder::child_of(source_id.clone()),
+    )
+    .await
+    .expect("Should create child");
+    let target_id = create_test_category(&app, fixtures::category::CategoryBuilder::root())
+        .await
+        .expect("Should create target");
+    let other_id = create_test_category(&app, fixtures::category::CategoryBuilder::root())
+        .await
+        .expect("Should create other category");
+
+    let product_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create product");
+    set_product_categories(&app, &product_id, vec![source_id.clone()])
+        .await
+        .expect("Update should succeed");
+
+    // Make product writes fail once the children have been moved
+    app.mongodb_db
+        .run_command(mongodb::bson::doc! {
+            "collMod": "products",
+            "validator": { "list_categories": { "$ne": &target_id } },
+        })
+        .await
+        .expect("Should add validator");
+
+    let response = merge_categories(&app, &source_id, &target_id, false)
+        .await
+        .expect("Request should succeed");
+    assert_eq!(response.status.unwrap().code, Code::Internal as i32);
+    let child = get_category(&app, &child_id)
+        .await
+        .unwrap()
+        .category
+        .unwrap();
+    assert_eq!(child.parent_id, Some(target_id.clone()));
+    assert!(get_category(&app, &source_id)
+        .await
+        .unwrap()
+        .category
+        .is_some());
+
+    // The half-done merge cannot be redirected elsewhere
+    let response = merge_categories(&app, &source_id, &other_id, false)
+        .await
+        .expect("Request should succeed");
+    assert_eq!(response.status.unwrap().code, Code::InvalidArgument as i32);
+
+    app.mongodb_db
+        .run_command(mongodb::bson::doc! { "collMod": "products", "validator": {} })
+        .await
+        .expect("Should remove validator");
+
+    let response = merge_categories(&app, &source_id, &target_id, false)
+        .await
+        .expect("Request should succeed");
+    assert_eq!(response.status.unwrap().code, Code::Ok as i32);
+
+    assert!(get_category(&app, &source_id)
+        .await
+        .unwrap()
+        .category
+        .is_none());
+    let product = get_product(&app, &product_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap();
+    assert_eq!(product.list_categories, vec![target_id.clone()]);
+    let target = get_category(&app, &target_id)
+        .await
+        .unwrap()
+        .category
+        .unwrap();
+    assert_eq!(target.product_count, 1);
+    let redirected = get_category_by_slug(&app, &source_slug)
+        .await
+        .expect("Should get response");
+    assert_eq!(redirected.category.unwrap().id, target_id);
+}
+
+#[tokio::test]
+async fn test_category_merge_into_own_descendant_is_rejected() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let root_id = create_test_category(&app, fixtures::category::CategoryBuilder::root())
+        .await
+        .expect("Should create root");
+    let child_id = create_test_category(
+        &app,
+        fixtures::category::CategoryBuilder::child_of(root_id.clone()),
+    )
+    .await
+    .expect("Should create child");
+
+    let response = merge_categories(&app, &root_id, &child_id, false)
+        .await
+        .expect("Request should succeed");
+    assert_eq!(response.status.unwrap().code, Code::InvalidArgument as i32);
+    assert!(get_category(&app, &root_id)
+        .await
+        .unwrap()
+        .category
+        .is_some());
+}
+
+// ============================================================================
+// CATEGORY CSV IMPORT TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_category_csv_import_creates_paths_and_diffs_existing_categories() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let root = format!("Apparel {}", fixtures::random_string(6));
+    let root_slug = format!("/{}", root.to_lowercase().replace(' ', "-"));
+    let csv = format!(
+        "path,meta_title,keywords\n\
+         \"{root} > Men > Shirts\",Men's shirts,\"shirts, tops\"\n\
+         {root} > Women,,\n"
+    );
+
+    // A dry run plans every node along the paths and writes nothing
+    let preview = import_categories_csv(&app, &csv, true)
+        .await
+        .expect("Request should succeed");
+    assert_eq!(preview.status.unwrap().code, Code::Ok as i32);
+    assert!(!preview.applied);
+    assert!(preview.errors.is_empty());
+    let slugs: Vec<&str> = preview.changes.iter().map(|c| c.slug.as_str()).collect();
+    assert_eq!(
+        slugs,
+        vec![
+            root_slug.clone(),
+            format!("{root_slug}/men"),
+            format!("{root_slug}/men/shirts"),
+            format!("{root_slug}/women"),
+        ]
+    );
+    assert!(preview
+        .changes
+        .iter()
+        .all(|c| c.action() == CategoryImportAction::Create));
+    assert!(get_category_by_slug(&app, &root_slug)
+        .await
+        .unwrap()
+        .category
+        .is_none());
+
+    let imported = import_categories_csv(&app, &csv, false)
+        .await
+        .expect("Request should succeed");
+    assert!(imported.applied);
+    assert!(imported.errors.is_empty());
+
+    let shirts = get_category_by_slug(&app, &format!("{root_slug}/men/shirts"))
+        .await
+        .unwrap()
+        .category
+        .expect("Shirts should exist");
+    let men = get_category_by_slug(&app, &format!("{root_slug}/men"))
+        .await
+        .unwrap()
+        .category
+        .expect("Men should exist");
+    assert_eq!(shirts.parent_id, Some(men.id.clone()));
+    assert_eq!(shirts.level, 2);
+    let seo = shirts.seo.unwrap();
+    assert_eq!(seo.meta_title.as_deref(), Some("Men's shirts"));
+    assert_eq!(seo.keywords, vec!["shirts".to_string(), "tops".to_string()]);
+
+    // Importing again matches by slug and only reports the changed cells
+    let csv = format!("path,meta_title\n\"{root} > Men > Shirts\",Shirts for men\n");
+    let diff = import_categories_csv(&app, &csv, true)
+        .await
+        .expect("Request should succeed");
+    let actions: Vec<CategoryImportAction> = diff.changes.iter().map(|c| c.action()).collect();
+    assert_eq!(
+        actions,
+        vec![
+            CategoryImportAction::Unchanged,
+            CategoryImportAction::Unchanged,
+            CategoryImportAction::Update,
+        ]
+    );
+    assert_eq!(
+        diff.changes[2].changed_fields,
+        vec!["meta_title".to_string()]
+    );
+    assert_eq!(
+        diff.changes[2].category_id.as_deref(),
+        Some(shirts.id.as_str())
+    );
+}
+
+#[tokio::test]
+async fn test_category_csv_import_with_row_errors_writes_nothing() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let root = format!("Footwear {}", fixtures::random_string(6));
+    let csv = format!("path,display_order\n{root} > Boots,first\n{root} > Sandals,2\n");
+
+    let response = import_categories_csv(&app, &csv, false)
+        .await
+        .expect("Request should succeed");
+    assert!(!response.applied);
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0].starts_with("Line 2:"));
+    let root_slug = format!("/{}", root.to_lowercase().replace(' ', "-"));
+    assert!(get_category_by_slug(&app, &root_slug)
+        .await
+        .unwrap()
+        .category
+        .is_none());
+
+    let response = import_categories_csv(&app, "name\nBoots\n", false)
+        .await
+        .expect("Request should succeed");
+    assert_eq!(response.status.unwrap().code, Code::InvalidArgument as i32);
+}
+
+// ============================================================================
+// BREADCRUMB TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_breadcrumbs_for_category_and_product() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let root = fixtures::category::CategoryBuilder::root();
+    let root_name = root.name.clone();
+    let root_id = create_test_category(&app, root)
+        .await
+        .expect("Should create root");
+    let child = fixtures::category::CategoryBuilder::child_of(root_id.clone());
+    let child_name = child.name.clone();
+    let child_slug = child.slug.clone();
+    let child_id = create_test_category(&app, child)
+        .await
+        .expect("Should create child");
+
+    let response = get_breadcrumbs(&app, Some(&child_id), None)
+        .await
+        .expect("Request should succeed");
+    assert_eq!(response.status.unwrap().code, Code::Ok as i32);
+    let names: Vec<&str> = response
+        .breadcrumbs
+        .iter()
+        .map(|crumb| crumb.name.as_str())
+        .collect();
+    assert_eq!(names, vec![root_name.as_str(), child_name.as_str()]);
+    assert_eq!(
+        response.breadcrumbs[1].url,
+        format!(
+            "https://shop.example.com/categories/{}",
+            child_slug.trim_matches('/')
+        )
+    );
+    let json_ld: serde_json::Value = serde_json::from_str(&response.json_ld).unwrap();
+    assert_eq!(json_ld["@type"], "BreadcrumbList");
+    assert_eq!(json_ld["itemListElement"][1]["position"], 2);
+
+    let product = fixtures::product::ProductBuilder::default();
+    let product_name = product.name.clone();
+    let product_id = create_test_product(&app, product)
+        .await
+        .expect("Should create product");
+    set_product_categories(&app, &product_id, vec![child_id.clone()])
+        .await
+        .expect("Update should succeed");
+
+    let response = get_breadcrumbs(&app, None, Some(&product_id))
+        .await
+        .expect("Request should succeed");
+    let names: Vec<&str> = response
+        .breadcrumbs
+        .iter()
+        .map(|crumb| crumb.name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            root_name.as_str(),
+            child_name.as_str(),
+            product_name.as_str()
+        ]
+    );
+
+    // Hidden categories drop out of the trail
+    update_category(
+        &app,
+        UpdateCategoryRequest {
+            id: root_id.clone(),
+            is_active: Some(false),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Update should succeed");
+    let response = get_breadcrumbs(&app, Some(&child_id), None)
+        .await
+        .expect("Request should succeed");
+    assert_eq!(response.status.unwrap().code, Code::NotFound as i32);
+    let response = get_breadcrumbs(&app, None, Some(&product_id))
+        .await
+        .expect("Request should succeed");
+    assert_eq!(response.breadcrumbs.len(), 1);
+
+    let response = get_breadcrumbs(&app, Some(&child_id), Some(&product_id))
+        .await
+        .expect("Request should succeed");
+    assert_eq!(response.status.unwrap().code, Code::InvalidArgument as i32);
+}
@@ -5,14 +5,18 @@ pub mod spawn_app;
 // pub mod test_setup;
 
 use catalog_messages::{
-    Code, CreateCategoryRequest, CreateCategoryResponse, DeleteCategoryRequest,
-    DeleteCategoryResponse, GetCategoryBySlugRequest, GetCategoryBySlugResponse,
-    GetCategoryRequest, GetCategoryResponse, ProductCreateRequest, ProductCreateResponse,
+    CategoryCsvImportRequest, CategoryCsvImportResponse, CategoryTreeNode, CategoryTreeRequest,
+    CategoryTreeResponse, Code, CreateCategoryRequest, CreateCategoryResponse,
+    DeleteCategoryRequest, DeleteCategoryResponse, GetBreadcrumbsRequest, GetBreadcrumbsResponse,
+    GetCategoryBySlugRequest, GetCategoryBySlugResponse, GetCategoryRequest, GetCategoryResponse,
+    MergeCategoriesRequest, MergeCategoriesResponse, ProductCreateRequest, ProductCreateResponse,
     ProductDeleteRequest, ProductDeleteResponse, ProductGetBySlugRequest, ProductGetBySlugResponse,
     ProductGetRequest, ProductGetResponse, ProductSearchRequest, ProductSearchResponse,
+    ProductUpdateRequest, ProductUpdateResponse, UpdateCategoryRequest, UpdateCategoryResponse,
 };
 use prost::Message;
 use rust_common::test_helpers::*;
+use std::collections::HashMap;
 
 // Import common module for generated proto code
 mod common {
@@ -51,11 +55,17 @@ pub async fn create_test_product(
         seo_keywords: Some("test,product".to_string()),
         tax_code: Some("txcd_99999999".to_string()),
         related_products: vec![],
+        relations: vec![],
         reviews: None,
         hierarchical_categories: None,
         list_categories: vec!["Test".to_string()],
         default_variant: None,
         variants: vec![],
+        publish_at: None,
+        unpublish_at: None,
+        localizations: HashMap::new(),
+        bundle: None,
+        stores: HashMap::new(),
     };
 
     let response = app
@@ -83,7 +93,11 @@ pub async fn get_product(
     app: &TestApp,
     id: &str,
 ) -> Result<ProductGetResponse, Box<dyn std::error::Error + Send + Sync>> {
-    let request = ProductGetRequest { id: id.to_string() };
+    let request = ProductGetRequest {
+        id: id.to_string(),
+        locale: None,
+        catalog_version: None,
+    };
 
     let response = app
         .request(
@@ -102,6 +116,7 @@ pub async fn get_product_by_slug(
 ) -> Result<ProductGetBySlugResponse, Box<dyn std::error::Error + Send + Sync>> {
     let request = ProductGetBySlugRequest {
         slug: slug.to_string(),
+        locale: None,
     };
 
     let response = app
@@ -114,6 +129,34 @@ pub async fn get_product_by_slug(
     Ok(ProductGetBySlugResponse::decode(&*response.payload)?)
 }
 
+/// Helper to replace a product's list_categories, returning the updated product
+pub async fn set_product_categories(
+    app: &TestApp,
+    id: &str,
+    list_categories: Vec<String>,
+) -> Result<catalog_messages::Product, Box<dyn std::error::Error + Send + Sync>> {
+    let mut product = get_product(app, id)
+        .await?
+        .product
+        .ok_or("Product not found")?;
+    product.list_categories = list_categories;
+    let request = ProductUpdateRequest {
+        id: id.to_string(),
+        product: Some(product),
+    };
+
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::UPDATE_PRODUCT,
+            request.encode_to_vec(),
+        )
+        .await?;
+
+    ProductUpdateResponse::decode(&*response.payload)?
+        .product
+        .ok_or_else(|| "Failed to update product".into())
+}
+
 /// Helper to delete a product
 pub async fn delete_product(
     app: &TestApp,
@@ -144,6 +187,8 @@ pub async fn search_products(
         brand,
         limit: Some(10),
         offset: None,
+        locale: None,
+        store_id: None,
     };
 
     let response = app
@@ -171,6 +216,8 @@ pub async fn create_test_category(
         seo: None,
         is_active: Some(true),
         parent_slug: None,
+        localizations: HashMap::new(),
+        stores: HashMap::new(),
     };
 
     let response = app
@@ -204,7 +251,11 @@ pub async fn get_category(
     app: &TestApp,
     id: &str,
 ) -> Result<GetCategoryResponse, Box<dyn std::error::Error + Send + Sync>> {
-    let request = GetCategoryRequest { id: id.to_string() };
+    let request = GetCategoryRequest {
+        id: id.to_string(),
+        locale: None,
+        catalog_version: None,
+    };
 
     let response = app
         .request(
@@ -223,6 +274,7 @@ pub async fn get_category_by_slug(
 ) -> Result<GetCategoryBySlugResponse, Box<dyn std::error::Error + Send + Sync>> {
     let request = GetCategoryBySlugRequest {
         slug: slug.to_string(),
+        locale: None,
     };
 
     let response = app
@@ -252,6 +304,118 @@ pub async fn delete_category(
     Ok(DeleteCategoryResponse::decode(&*response.payload)?)
 }
 
+/// Helper to update a category
+pub async fn update_category(
+    app: &TestApp,
+    request: UpdateCategoryRequest,
+) -> Result<UpdateCategoryResponse, Box<dyn std::error::Error + Send + Sync>> {
+    let response = app
+        .request(
+            crate::helpers::nats_config::category::subjects::UPDATE_CATEGORY,
+            request.encode_to_vec(),
+        )
+        .await?;
+
+    Ok(UpdateCategoryResponse::decode(&*response.payload)?)
+}
+
+/// Helper to merge one category into another
+pub async fn merge_categories(
+    app: &TestApp,
+    source_id: &str,
+    target_id: &str,
+    dry_run: bool,
+) -> Result<MergeCategoriesResponse, Box<dyn std::error::Error + Send + Sync>> {
+    let request = MergeCategoriesRequest {
+        source_id: source_id.to_string(),
+        target_id: target_id.to_string(),
+        dry_run,
+    };
+
+    let response = app
+        .request(
+            crate::helpers::nats_config::category::subjects::MERGE_CATEGORIES,
+            request.encode_to_vec(),
+        )
+        .await?;
+
+    Ok(MergeCategoriesResponse::decode(&*response.payload)?)
+}
+
+/// Helper to import categories from a CSV of breadcrumb paths
+pub async fn import_categories_csv(
+    app: &TestApp,
+    csv: &str,
+    dry_run: bool,
+) -> Result<CategoryCsvImportResponse, Box<dyn std::error::Error + Send + Sync>> {
+    let request = CategoryCsvImportRequest {
+        csv: csv.to_string(),
+        dry_run,
+        path_separator: None,
+    };
+
+    let response = app
+        .request(
+            crate::helpers::nats_config::category::subjects::IMPORT_CATEGORIES_CSV,
+            request.encode_to_vec(),
+        )
+        .await?;
+
+    Ok(CategoryCsvImportResponse::decode(&*response.payload)?)
+}
+
+/// Helper to get the breadcrumb trail to a category or a product
+pub async fn get_breadcrumbs(
+    app: &TestApp,
+    category_id: Option<&str>,
+    product_id: Option<&str>,
+) -> Result<GetBreadcrumbsResponse, Box<dyn std::error::Error + Send + Sync>> {
+    let request = GetBreadcrumbsRequest {
+        category_id: category_id.map(str::to_string),
+        product_id: product_id.map(str::to_string),
+        base_url: "https://shop.example.com".to_string(),
+        product_path: None,
+        category_path: None,
+        locale: None,
+    };
+
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::GET_BREADCRUMBS,
+            request.encode_to_vec(),
+        )
+        .await?;
+
+    Ok(GetBreadcrumbsResponse::decode(&*response.payload)?)
+}
+
+/// Helper to get the category tree, asserting the request succeeded
+pub async fn get_category_tree(
+    app: &TestApp,
+    max_depth: Option<i32>,
+    include_inactive: bool,
+) -> Vec<CategoryTreeNode> {
+    let request = CategoryTreeRequest {
+        max_depth,
+        include_inactive: Some(include_inactive),
+        rebuild_cache: None,
+        locale: None,
+        store_id: None,
+    };
+
+    let response = app
+        .request(
+            crate::helpers::nats_config::category::subjects::GET_CATEGORY_TREE,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let tree_response =
+        CategoryTreeResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(tree_response.status.unwrap().code, Code::Ok as i32);
+    tree_response.tree
+}
+
 /// Assertion helpers specific to catalog
 pub mod assertions {
     use super::*;
@@ -18,6 +18,9 @@ use std::collections::HashMap;
 async fn test_product_create_with_all_fields() {
     let app = helpers::spawn_app::spawn_app().await;
     let builder = fixtures::product::ProductBuilder::default();
+    let related_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create related product");
 
     let request = ProductCreateRequest {
         name: builder.name.clone(),
@@ -33,12 +36,18 @@ async fn test_product_create_with_all_fields() {
         seo_description: Some("SEO Description".to_string()),
         seo_keywords: Some("test,product,seo".to_string()),
         tax_code: Some("txcd_99999999".to_string()),
-        related_products: vec!["prod-123".to_string()],
+        related_products: vec![related_id],
+        relations: vec![],
         reviews: None,
         hierarchical_categories: None,
         list_categories: vec!["Electronics".to_string(), "Computers".to_string()],
         default_variant: None,
         variants: vec![],
+        publish_at: None,
+        unpublish_at: None,
+        localizations: HashMap::new(),
+        bundle: None,
+        stores: HashMap::new(),
     };
 
     let response = app
@@ -80,11 +89,17 @@ async fn test_product_create_with_minimal_fields() {
         seo_keywords: None,
         tax_code: None,
         related_products: vec![],
+        relations: vec![],
         reviews: None,
         hierarchical_categories: None,
         list_categories: vec![],
         default_variant: None,
         variants: vec![],
+        publish_at: None,
+        unpublish_at: None,
+        localizations: HashMap::new(),
+        bundle: None,
+        stores: HashMap::new(),
     };
 
     let response = app
@@ -120,11 +135,17 @@ async fn test_product_create_fails_with_missing_name() {
         seo_keywords: None,
         tax_code: None,
         related_products: vec![],
+        relations: vec![],
         reviews: None,
         hierarchical_categories: None,
         list_categories: vec![],
         default_variant: None,
         variants: vec![],
+        publish_at: None,
+        unpublish_at: None,
+        localizations: HashMap::new(),
+        bundle: None,
+        stores: HashMap::new(),
     };
 
     let response = app
@@ -160,11 +181,17 @@ async fn test_product_create_fails_with_missing_product_ref() {
         seo_keywords: None,
         tax_code: None,
         related_products: vec![],
+        relations: vec![],
         reviews: None,
         hierarchical_categories: None,
         list_categories: vec![],
         default_variant: None,
         variants: vec![],
+        publish_at: None,
+        unpublish_at: None,
+        localizations: HashMap::new(),
+        bundle: None,
+        stores: HashMap::new(),
     };
 
     let response = app
@@ -201,11 +228,17 @@ async fn test_product_create_with_sql_injection_attempt() {
             seo_keywords: None,
             tax_code: None,
             related_products: vec![],
+            relations: vec![],
             reviews: None,
             hierarchical_categories: None,
             list_categories: vec![],
             default_variant: None,
             variants: vec![],
+            publish_at: None,
+            unpublish_at: None,
+            localizations: HashMap::new(),
+            bundle: None,
+            stores: HashMap::new(),
         };
 
         let response = app
@@ -383,6 +416,116 @@ async fn test_product_get_by_slug_with_special_characters() {
     }
 }
 
+#[tokio::test]
+async fn test_product_create_rejects_explicit_slug_in_use_or_redirecting() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let create_with_slug = |slug: String| {
+        let builder = fixtures::product::ProductBuilder {
+            slug: Some(slug),
+            ..Default::default()
+        };
+        ProductCreateRequest {
+            name: builder.name,
+            product_ref: builder.product_ref,
+            slug: builder.slug,
+            brand: builder.brand,
+            long_description: builder.long_description,
+            product_type: None,
+            display_on_site: true,
+            defining_attributes: HashMap::new(),
+            descriptive_attributes: HashMap::new(),
+            seo_title: None,
+            seo_description: None,
+            seo_keywords: None,
+            tax_code: None,
+            related_products: vec![],
+            relations: vec![],
+            reviews: None,
+            hierarchical_categories: None,
+            list_categories: vec![],
+            default_variant: None,
+            variants: vec![],
+            publish_at: None,
+            unpublish_at: None,
+            localizations: HashMap::new(),
+            bundle: None,
+            stores: HashMap::new(),
+        }
+    };
+    let create = |request: ProductCreateRequest| {
+        let app = &app;
+        async move {
+            let response = app
+                .request(
+                    crate::helpers::nats_config::product::subjects::CREATE_PRODUCT,
+                    request.encode_to_vec(),
+                )
+                .await
+                .expect("Request should succeed");
+            ProductCreateResponse::decode(&*response.payload)
+                .unwrap()
+                .status
+                .unwrap()
+        }
+    };
+
+    let old_slug = fixtures::valid_slug();
+    let product_id = create_test_product(
+        &app,
+        fixtures::product::ProductBuilder {
+            slug: Some(old_slug.clone()),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Should create product");
+
+    // A live slug is reported as a slug conflict
+    let status = create(create_with_slug(old_slug.clone())).await;
+    assert_eq!(status.code, Code::AlreadyExists as i32);
+    assert!(status.message.contains("slug"), "{}", status.message);
+
+    // Renaming retires the old slug into a redirect that no one else may claim
+    let mut product = get_product(&app, &product_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap();
+    product.slug = Some(format!("{old_slug}-renamed"));
+    let request = ProductUpdateRequest {
+        id: product_id.clone(),
+        product: Some(product.clone()),
+    };
+    app.request(
+        crate::helpers::nats_config::product::subjects::UPDATE_PRODUCT,
+        request.encode_to_vec(),
+    )
+    .await
+    .expect("Update should succeed");
+
+    let status = create(create_with_slug(old_slug.clone())).await;
+    assert_eq!(status.code, Code::AlreadyExists as i32);
+    assert!(status.message.contains("redirects"), "{}", status.message);
+
+    // The product that retired the slug may take it back
+    product.slug = Some(old_slug.clone());
+    let request = ProductUpdateRequest {
+        id: product_id.clone(),
+        product: Some(product),
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::UPDATE_PRODUCT,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Update should succeed");
+    let response = ProductUpdateResponse::decode(&*response.payload).unwrap();
+    assert_eq!(response.status.unwrap().code, Code::Ok as i32);
+    assert_eq!(response.product.unwrap().slug, Some(old_slug));
+}
+
 #[tokio::test]
 async fn test_product_get_by_slug_sql_injection() {
     let app = helpers::spawn_app::spawn_app().await;
@@ -398,6 +541,236 @@ async fn test_product_get_by_slug_sql_injection() {
     }
 }
 
+#[tokio::test]
+async fn test_product_create_generates_unique_slug_from_name() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let name = format!("Trail Runner {}", fixtures::random_string(8));
+    let first_id = create_test_product(
+        &app,
+        fixtures::product::ProductBuilder {
+            name: name.clone(),
+            ..fixtures::product::ProductBuilder::minimal()
+        },
+    )
+    .await
+    .expect("Should create first product");
+    let second_id = create_test_product(
+        &app,
+        fixtures::product::ProductBuilder {
+            name: name.clone(),
+            ..fixtures::product::ProductBuilder::minimal()
+        },
+    )
+    .await
+    .expect("Should create second product");
+
+    let first = get_product(&app, &first_id).await.unwrap().product.unwrap();
+    let second = get_product(&app, &second_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap();
+
+    let first_slug = first.slug.expect("Slug should be generated");
+    assert_eq!(first_slug, name.to_lowercase().replace(' ', "-"));
+    assert_eq!(second.slug, Some(format!("{first_slug}-2")));
+}
+
+#[tokio::test]
+async fn test_product_get_by_slug_redirects_after_slug_change() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let old_slug = fixtures::valid_slug();
+    let builder = fixtures::product::ProductBuilder {
+        slug: Some(old_slug.clone()),
+        ..Default::default()
+    };
+    let product_id = create_test_product(&app, builder)
+        .await
+        .expect("Should create product");
+
+    let mut product = get_product(&app, &product_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap();
+    let new_slug = format!("{old_slug}-renamed");
+    product.slug = Some(new_slug.clone());
+
+    let request = ProductUpdateRequest {
+        id: product_id.clone(),
+        product: Some(product),
+    };
+    app.request(
+        crate::helpers::nats_config::product::subjects::UPDATE_PRODUCT,
+        request.encode_to_vec(),
+    )
+    .await
+    .expect("Update should succeed");
+
+    let response = get_product_by_slug(&app, &old_slug)
+        .await
+        .expect("Should get response");
+
+    assert_eq!(response.status.unwrap().code, Code::Ok as i32);
+    assert_eq!(response.redirect_slug, Some(new_slug));
+    assert_eq!(response.product.unwrap().id, Some(product_id));
+}
+
+#[tokio::test]
+async fn test_product_get_by_slug_hides_product_before_publish_at() {
+    let app = helpers::spawn_app::spawn_app().await;
+    let slug = fixtures::valid_slug();
+    let builder = fixtures::product::ProductBuilder {
+        slug: Some(slug.clone()),
+        ..Default::default()
+    };
+    let product_id = create_test_product(&app, builder)
+        .await
+        .expect("Should create product");
+
+    let mut product = get_product(&app, &product_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap();
+    let tomorrow = chrono::Utc::now() + chrono::Duration::days(1);
+    product.publish_at = Some(prost_types::Timestamp {
+        seconds: tomorrow.timestamp(),
+        nanos: 0,
+    });
+
+    let request = ProductUpdateRequest {
+        id: product_id.clone(),
+        product: Some(product),
+    };
+    app.request(
+        crate::helpers::nats_config::product::subjects::UPDATE_PRODUCT,
+        request.encode_to_vec(),
+    )
+    .await
+    .expect("Update should succeed");
+
+    let response = get_product_by_slug(&app, &slug)
+        .await
+        .expect("Should get response");
+    assert!(
+        response.product.is_none(),
+        "Scheduled product should be hidden"
+    );
+    assert_eq!(response.status.unwrap().code, Code::NotFound as i32);
+
+    // Lookups by id still see the product so it can be edited before it goes live
+    let response = get_product(&app, &product_id)
+        .await
+        .expect("Should get response");
+    assert!(response.product.unwrap().publish_at.is_some());
+}
+
+#[tokio::test]
+async fn test_product_update_rejects_out_of_range_publish_at() {
+    let app = helpers::spawn_app::spawn_app().await;
+    let product_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create product");
+
+    let mut product = get_product(&app, &product_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap();
+    product.publish_at = Some(prost_types::Timestamp {
+        seconds: i64::MAX,
+        nanos: 0,
+    });
+
+    let request = ProductUpdateRequest {
+        id: product_id.clone(),
+        product: Some(product),
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::UPDATE_PRODUCT,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Should get response");
+    let response = ProductUpdateResponse::decode(&*response.payload).unwrap();
+    assert_eq!(response.status.unwrap().code, Code::InvalidArgument as i32);
+
+    // The product was not published early in its place
+    let product = get_product(&app, &product_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap();
+    assert!(product.publish_at.is_none());
+}
+
+#[tokio::test]
+async fn test_product_get_by_slug_resolves_localized_slug_with_fallback() {
+    let app = helpers::spawn_app::spawn_app().await;
+    let slug = fixtures::valid_slug();
+    let product_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create product");
+
+    let mut product = get_product(&app, &product_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap();
+    let default_name = product.name.clone();
+    product.localizations.insert(
+        "fr".to_string(),
+        LocalizedProductContent {
+            name: Some("Veste de pluie".to_string()),
+            slug: Some(slug.clone()),
+            ..Default::default()
+        },
+    );
+
+    let request = ProductUpdateRequest {
+        id: product_id.clone(),
+        product: Some(product),
+    };
+    app.request(
+        crate::helpers::nats_config::product::subjects::UPDATE_PRODUCT,
+        request.encode_to_vec(),
+    )
+    .await
+    .expect("Update should succeed");
+
+    // fr-CA has no content of its own, so it falls back to fr
+    let request = ProductGetBySlugRequest {
+        slug: slug.clone(),
+        locale: Some("fr_CA".to_string()),
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::GET_PRODUCT_BY_SLUG,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Should get response");
+    let response = ProductGetBySlugResponse::decode(&*response.payload).unwrap();
+    let product = response.product.expect("Localized slug should resolve");
+    assert_eq!(product.id.as_deref(), Some(product_id.as_str()));
+    assert_eq!(product.name, "Veste de pluie");
+
+    // Without a locale the localized slug is not matched
+    let response = get_product_by_slug(&app, &slug)
+        .await
+        .expect("Should get response");
+    assert!(response.product.is_none());
+
+    let response = get_product(&app, &product_id)
+        .await
+        .expect("Should get response");
+    assert_eq!(response.product.unwrap().name, default_name);
+}
+
 // ============================================================================
 // PRODUCT DELETE TESTS
 // ============================================================================
@@ -468,6 +841,79 @@ async fn test_product_delete_idempotent() {
     assert!(status.code == Code::Ok as i32 || status.code == Code::NotFound as i32);
 }
 
+#[tokio::test]
+async fn test_product_delete_then_restore() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let builder = fixtures::product::ProductBuilder::default();
+    let product_name = builder.name.clone();
+    let product_id = create_test_product(&app, builder)
+        .await
+        .expect("Should create product");
+
+    let response = delete_product(&app, &product_id)
+        .await
+        .expect("Should delete product");
+    assert_eq!(response.status.unwrap().code, Code::Ok as i32);
+
+    // Tombstoned products are hidden from reads and searches
+    helpers::assertions::assert_product_not_found(&get_product(&app, &product_id).await.unwrap());
+    let search = search_products(&app, Some(product_name), None, None)
+        .await
+        .expect("Should search products");
+    assert!(search
+        .products
+        .iter()
+        .all(|p| p.id.as_deref() != Some(product_id.as_str())));
+
+    let request = ProductRestoreRequest {
+        id: product_id.clone(),
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::RESTORE_PRODUCT,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let restore_response =
+        ProductRestoreResponse::decode(&*response.payload).expect("Response should decode");
+
+    assert_eq!(restore_response.status.unwrap().code, Code::Ok as i32);
+    assert_eq!(
+        restore_response.product.unwrap().id,
+        Some(product_id.clone())
+    );
+
+    let response = get_product(&app, &product_id)
+        .await
+        .expect("Should get product");
+    assert!(response.product.is_some());
+}
+
+#[tokio::test]
+async fn test_product_restore_non_deleted() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let product_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create product");
+
+    let request = ProductRestoreRequest { id: product_id };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::RESTORE_PRODUCT,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let restore_response =
+        ProductRestoreResponse::decode(&*response.payload).expect("Response should decode");
+
+    assert!(restore_response.product.is_none());
+    assert_eq!(restore_response.status.unwrap().code, Code::NotFound as i32);
+}
+
 // ============================================================================
 // PRODUCT SEARCH TESTS
 // ============================================================================
@@ -731,3 +1177,1572 @@ async fn test_product_export_with_pagination() {
 // Note: Import is handled via the client which reads JSON files and creates products
 // individually, not through a bulk import message. We test the create functionality
 // extensively above which covers the import use case.
+
+// ============================================================================
+// PRODUCT REVISION TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_product_revisions_recorded_and_reverted() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let builder = fixtures::product::ProductBuilder::default();
+    let original_name = builder.name.clone();
+    let product_id = create_test_product(&app, builder)
+        .await
+        .expect("Should create product");
+
+    let mut product = get_product(&app, &product_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap();
+    product.name = format!("{original_name} v2");
+    product.updated_by = Some("editor".to_string());
+
+    let request = ProductUpdateRequest {
+        id: product_id.clone(),
+        product: Some(product),
+    };
+    app.request(
+        crate::helpers::nats_config::product::subjects::UPDATE_PRODUCT,
+        request.encode_to_vec(),
+    )
+    .await
+    .expect("Update should succeed");
+
+    let request = ListProductRevisionsRequest {
+        product_id: product_id.clone(),
+        revisions: vec![],
+        limit: None,
+        offset: None,
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::LIST_PRODUCT_REVISIONS,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let revisions_response =
+        ListProductRevisionsResponse::decode(&*response.payload).expect("Response should decode");
+
+    assert_eq!(revisions_response.status.unwrap().code, Code::Ok as i32);
+    let numbers: Vec<i64> = revisions_response
+        .revisions
+        .iter()
+        .map(|r| r.revision)
+        .collect();
+    assert_eq!(numbers, vec![2, 1]);
+    assert_eq!(
+        revisions_response.revisions[0].changed_by.as_deref(),
+        Some("editor")
+    );
+
+    let request = RevertProductRequest {
+        product_id: product_id.clone(),
+        revision: 1,
+        reverted_by: Some("admin".to_string()),
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::REVERT_PRODUCT,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let revert_response =
+        RevertProductResponse::decode(&*response.payload).expect("Response should decode");
+
+    assert_eq!(revert_response.status.unwrap().code, Code::Ok as i32);
+    assert_eq!(revert_response.product.unwrap().name, original_name);
+}
+
+#[tokio::test]
+async fn test_product_revert_to_unknown_revision() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let product_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create product");
+
+    let request = RevertProductRequest {
+        product_id,
+        revision: 42,
+        reverted_by: None,
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::REVERT_PRODUCT,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let revert_response =
+        RevertProductResponse::decode(&*response.payload).expect("Response should decode");
+
+    assert!(revert_response.product.is_none());
+    assert_eq!(revert_response.status.unwrap().code, Code::NotFound as i32);
+}
+
+// ============================================================================
+// VARIANT GENERATION TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_product_generate_variants_skips_existing_combinations() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let product_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create product");
+
+    let generate = |sizes: Vec<&str>| GenerateVariantsRequest {
+        product_id: product_id.clone(),
+        axes: vec![
+            VariantAxis {
+                key: "color".to_string(),
+                values: vec!["Black".to_string(), "White".to_string()],
+                abbreviations: HashMap::from([("Black".to_string(), "BLK".to_string())]),
+            },
+            VariantAxis {
+                key: "size".to_string(),
+                values: sizes.into_iter().map(String::from).collect(),
+                abbreviations: HashMap::new(),
+            },
+        ],
+        sku_pattern: "{product_ref}-{color}-{size}".to_string(),
+        seed_inventory: false,
+        inventory_location: None,
+        updated_by: Some("test".to_string()),
+    };
+
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::GENERATE_VARIANTS,
+            generate(vec!["S", "M"]).encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let first =
+        GenerateVariantsResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(first.status.unwrap().code, Code::Ok as i32);
+    assert_eq!(first.created_skus.len(), 4);
+    assert!(first.skipped_skus.is_empty());
+    assert_eq!(first.product.unwrap().variants.len(), 4);
+
+    // Adding a size only creates the new combinations
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::GENERATE_VARIANTS,
+            generate(vec!["S", "M", "L"]).encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let second =
+        GenerateVariantsResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(second.status.unwrap().code, Code::Ok as i32);
+    assert_eq!(second.created_skus.len(), 2);
+    assert_eq!(second.skipped_skus.len(), 4);
+    assert!(second.created_skus.iter().all(|sku| sku.ends_with("-L")));
+    assert_eq!(second.product.unwrap().variants.len(), 6);
+}
+
+#[tokio::test]
+async fn test_product_generate_variants_rejects_skus_of_another_product() {
+    let app = helpers::spawn_app::spawn_app().await;
+    let prefix = format!("SHARED{}", fixtures::random_string(6)).to_uppercase();
+
+    let generate = |product_id: String| GenerateVariantsRequest {
+        product_id,
+        axes: vec![VariantAxis {
+            key: "size".to_string(),
+            values: vec!["S".to_string(), "M".to_string()],
+            abbreviations: HashMap::new(),
+        }],
+        sku_pattern: format!("{prefix}-{{size}}"),
+        seed_inventory: false,
+        inventory_location: None,
+        updated_by: Some("test".to_string()),
+    };
+    let request = |product_id: String| {
+        let app = &app;
+        let request = generate(product_id);
+        async move {
+            let response = app
+                .request(
+                    crate::helpers::nats_config::product::subjects::GENERATE_VARIANTS,
+                    request.encode_to_vec(),
+                )
+                .await
+                .expect("Request should succeed");
+            GenerateVariantsResponse::decode(&*response.payload).expect("Response should decode")
+        }
+    };
+
+    let first_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create product");
+    let second_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create product");
+
+    let first = request(first_id).await;
+    assert_eq!(first.status.unwrap().code, Code::Ok as i32);
+
+    // The pattern yields the same SKUs for the second product
+    let second = request(second_id.clone()).await;
+    assert_eq!(second.status.unwrap().code, Code::AlreadyExists as i32);
+    let product = get_product(&app, &second_id)
+        .await
+        .expect("Should get product")
+        .product
+        .unwrap();
+    assert!(product.variants.is_empty());
+}
+
+// ============================================================================
+// RELATED PRODUCT TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_product_relations_are_validated_mirrored_and_cleaned_up() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let case_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create accessory");
+    let older_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create related product");
+
+    let create = |related_products: Vec<String>, relations: Vec<ProductRelation>| {
+        let builder = fixtures::product::ProductBuilder::default();
+        ProductCreateRequest {
+            name: builder.name,
+            product_ref: builder.product_ref,
+            slug: builder.slug,
+            brand: builder.brand,
+            long_description: builder.long_description,
+            product_type: None,
+            display_on_site: builder.display_on_site,
+            defining_attributes: HashMap::new(),
+            descriptive_attributes: HashMap::new(),
+            seo_title: None,
+            seo_description: None,
+            seo_keywords: None,
+            tax_code: None,
+            related_products,
+            relations,
+            reviews: None,
+            hierarchical_categories: None,
+            list_categories: vec![],
+            default_variant: None,
+            variants: vec![],
+            publish_at: None,
+            unpublish_at: None,
+            localizations: HashMap::new(),
+            bundle: None,
+            stores: HashMap::new(),
+        }
+    };
+
+    // Links must point at existing products
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::CREATE_PRODUCT,
+            create(vec!["missing-product".to_string()], vec![]).encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let create_response =
+        ProductCreateResponse::decode(&*response.payload).expect("Response should decode");
+    let status = create_response.status.unwrap();
+    assert_eq!(status.code, Code::InvalidArgument as i32);
+    assert!(status.message.contains("missing-product"));
+
+    let accessory = ProductRelation {
+        product_id: case_id.clone(),
+        kind: RelationKind::Accessory.into(),
+        bidirectional: true,
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::CREATE_PRODUCT,
+            create(vec![older_id.clone()], vec![accessory]).encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let create_response =
+        ProductCreateResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(create_response.status.unwrap().code, Code::Ok as i32);
+    let phone_id = create_response.product.unwrap().id.unwrap();
+
+    // The accessory links back to the phone
+    let case = get_product(&app, &case_id)
+        .await
+        .expect("Should get product");
+    let reverse = &case.product.unwrap().relations;
+    assert_eq!(reverse.len(), 1);
+    assert_eq!(reverse[0].product_id, phone_id);
+    assert_eq!(reverse[0].kind(), RelationKind::Accessory);
+
+    let request = GetRelatedProductsRequest {
+        product_id: phone_id.clone(),
+        kinds: vec![],
+        locale: None,
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::GET_RELATED_PRODUCTS,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let related =
+        GetRelatedProductsResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(related.status.unwrap().code, Code::Ok as i32);
+    let related: Vec<(RelationKind, String)> = related
+        .related
+        .iter()
+        .map(|r| (r.kind(), r.product.as_ref().unwrap().id.clone().unwrap()))
+        .collect();
+    assert_eq!(
+        related,
+        vec![
+            (RelationKind::Related, older_id.clone()),
+            (RelationKind::Accessory, case_id.clone()),
+        ]
+    );
+
+    // Deleting a product removes the links pointing at it
+    delete_product(&app, &older_id)
+        .await
+        .expect("Should delete product");
+    let phone = get_product(&app, &phone_id)
+        .await
+        .expect("Should get product");
+    assert!(phone.product.unwrap().related_products.is_empty());
+}
+
+// ============================================================================
+// CLONE TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_product_clone_rewrites_skus_and_hides_the_copy() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let source = fixtures::product::ProductBuilder::default();
+    let source_ref = source.product_ref.clone();
+    let source_id = create_test_product(&app, source)
+        .await
+        .expect("Should create product");
+    let request = GenerateVariantsRequest {
+        product_id: source_id.clone(),
+        axes: vec![VariantAxis {
+            key: "size".to_string(),
+            values: vec!["S".to_string(), "M".to_string()],
+            abbreviations: HashMap::new(),
+        }],
+        sku_pattern: "{product_ref}-{size}".to_string(),
+        seed_inventory: false,
+        inventory_location: None,
+        updated_by: Some("test".to_string()),
+    };
+    app.request(
+        crate::helpers::nats_config::product::subjects::GENERATE_VARIANTS,
+        request.encode_to_vec(),
+    )
+    .await
+    .expect("Request should succeed");
+
+    let clone = |product_id: &str, product_ref: String, sku_rewrites: Vec<SkuRewrite>| {
+        CloneProductRequest {
+            product_id: product_id.to_string(),
+            product_ref,
+            slug: None,
+            name: Some("Next Season".to_string()),
+            sku_rewrites,
+            created_by: Some("test".to_string()),
+        }
+    };
+    let new_ref = format!("CL{}", fixtures::random_string(6));
+
+    // A rewrite that leaves some SKU as it was is refused
+    let unchanged = vec![SkuRewrite {
+        find: "-S".to_string(),
+        replace: "-XS".to_string(),
+    }];
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::CLONE_PRODUCT,
+            clone(&source_id, new_ref.clone(), unchanged).encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let clone_response =
+        CloneProductResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(
+        clone_response.status.unwrap().code,
+        Code::InvalidArgument as i32
+    );
+
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::CLONE_PRODUCT,
+            clone(&source_id, new_ref.clone(), vec![]).encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let clone_response =
+        CloneProductResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(clone_response.status.unwrap().code, Code::Ok as i32);
+    let product = clone_response.product.unwrap();
+    assert_ne!(product.id.as_deref(), Some(source_id.as_str()));
+    assert_eq!(product.name, "Next Season");
+    assert_eq!(product.product_ref, new_ref);
+    assert!(!product.display_on_site);
+    let skus: Vec<&str> = product.variants.iter().map(|v| v.sku.as_str()).collect();
+    assert_eq!(skus, vec![format!("{new_ref}-S"), format!("{new_ref}-M")]);
+    assert!(skus.iter().all(|sku| !sku.starts_with(&source_ref)));
+
+    // Rewritten SKUs that the first copy already uses are refused
+    let colliding = vec![SkuRewrite {
+        find: source_ref.clone(),
+        replace: new_ref.clone(),
+    }];
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::CLONE_PRODUCT,
+            clone(
+                &source_id,
+                format!("CL{}", fixtures::random_string(6)),
+                colliding,
+            )
+            .encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let clone_response =
+        CloneProductResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(
+        clone_response.status.unwrap().code,
+        Code::AlreadyExists as i32
+    );
+
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::CLONE_PRODUCT,
+            clone("missing-product", new_ref, vec![]).encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let clone_response =
+        CloneProductResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(clone_response.status.unwrap().code, Code::NotFound as i32);
+}
+
+// ============================================================================
+// MEASUREMENT TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_product_create_normalizes_units() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let create = |weight_unit: &str| {
+        let builder = fixtures::product::ProductBuilder::default();
+        let sku = format!("{}-1", builder.product_ref);
+        ProductCreateRequest {
+            name: builder.name,
+            product_ref: builder.product_ref,
+            slug: builder.slug,
+            brand: builder.brand,
+            long_description: builder.long_description,
+            product_type: None,
+            display_on_site: builder.display_on_site,
+            defining_attributes: HashMap::new(),
+            descriptive_attributes: HashMap::new(),
+            seo_title: None,
+            seo_description: None,
+            seo_keywords: None,
+            tax_code: None,
+            related_products: vec![],
+            relations: vec![],
+            reviews: None,
+            hierarchical_categories: None,
+            list_categories: vec![],
+            default_variant: None,
+            variants: vec![ProductVariant {
+                sku,
+                weight: Some(1.5),
+                weight_unit: Some(weight_unit.to_string()),
+                packaging: Some(Packaging {
+                    height: Some(10.0),
+                    width: Some(20.0),
+                    length: Some(50.0),
+                    dimension_unit: Some("Centimeters".to_string()),
+                    weight: Some(1.8),
+                    weight_unit: Some("KG".to_string()),
+                    dimensional_weight: None,
+                }),
+                ..Default::default()
+            }],
+            publish_at: None,
+            unpublish_at: None,
+            localizations: HashMap::new(),
+            bundle: None,
+            stores: HashMap::new(),
+        }
+    };
+
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::CREATE_PRODUCT,
+            create("stone").encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let create_response =
+        ProductCreateResponse::decode(&*response.payload).expect("Response should decode");
+    let status = create_response.status.unwrap();
+    assert_eq!(status.code, Code::InvalidArgument as i32);
+    assert!(status.message.contains("stone"));
+
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::CREATE_PRODUCT,
+            create("LBS").encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let create_response =
+        ProductCreateResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(create_response.status.unwrap().code, Code::Ok as i32);
+    let variant = &create_response.product.unwrap().variants[0];
+    assert_eq!(variant.weight_unit.as_deref(), Some("lb"));
+    let packaging = variant.packaging.as_ref().unwrap();
+    assert_eq!(packaging.dimension_unit.as_deref(), Some("cm"));
+    assert_eq!(packaging.weight_unit.as_deref(), Some("kg"));
+    assert_eq!(packaging.dimensional_weight, Some(2.0));
+}
+
+// ============================================================================
+// BUNDLE TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_product_create_bundle_validates_components() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let component = fixtures::product::ProductBuilder::default();
+    let component_ref = component.product_ref.clone();
+    let component_id = create_test_product(&app, component)
+        .await
+        .expect("Should create component product");
+    let request = GenerateVariantsRequest {
+        product_id: component_id,
+        axes: vec![VariantAxis {
+            key: "size".to_string(),
+            values: vec!["S".to_string()],
+            abbreviations: HashMap::new(),
+        }],
+        sku_pattern: "{product_ref}-{size}".to_string(),
+        seed_inventory: false,
+        inventory_location: None,
+        updated_by: Some("test".to_string()),
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::GENERATE_VARIANTS,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let generated =
+        GenerateVariantsResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(generated.status.unwrap().code, Code::Ok as i32);
+    let component_sku = format!("{component_ref}-S");
+
+    let create_bundle = |sku: String| {
+        let builder = fixtures::product::ProductBuilder::default();
+        ProductCreateRequest {
+            name: builder.name,
+            product_ref: builder.product_ref,
+            slug: builder.slug,
+            brand: builder.brand,
+            long_description: builder.long_description,
+            product_type: None,
+            display_on_site: builder.display_on_site,
+            defining_attributes: HashMap::new(),
+            descriptive_attributes: HashMap::new(),
+            seo_title: None,
+            seo_description: None,
+            seo_keywords: None,
+            tax_code: None,
+            related_products: vec![],
+            relations: vec![],
+            reviews: None,
+            hierarchical_categories: None,
+            list_categories: vec![],
+            default_variant: None,
+            variants: vec![],
+            publish_at: None,
+            unpublish_at: None,
+            localizations: HashMap::new(),
+            bundle: Some(ProductBundle {
+                components: vec![BundleComponent { sku, quantity: 2 }],
+                pricing: BundlePricing::ComponentSum.into(),
+            }),
+            stores: HashMap::new(),
+        }
+    };
+
+    // A component must be a SKU some other product sells
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::CREATE_PRODUCT,
+            create_bundle(format!("{component_ref}-XXL")).encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let create_response =
+        ProductCreateResponse::decode(&*response.payload).expect("Response should decode");
+    let status = create_response.status.unwrap();
+    assert_eq!(status.code, Code::InvalidArgument as i32);
+    assert!(status.message.contains("XXL"));
+
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::CREATE_PRODUCT,
+            create_bundle(component_sku.clone()).encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let create_response =
+        ProductCreateResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(create_response.status.unwrap().code, Code::Ok as i32);
+    let bundle = create_response.product.unwrap().bundle.unwrap();
+    assert_eq!(bundle.pricing(), BundlePricing::ComponentSum);
+    assert_eq!(bundle.components[0].sku, component_sku);
+    assert_eq!(bundle.components[0].quantity, 2);
+}
+
+// ============================================================================
+// ATTRIBUTE SCHEMA TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_product_create_enforces_attribute_schema() {
+    let app = helpers::spawn_app::spawn_app().await;
+    let product_type = format!("shoe-{}", fixtures::random_string(6));
+
+    let request = UpsertAttributeSchemaRequest {
+        schema: Some(AttributeSchema {
+            product_type: product_type.clone(),
+            attributes: vec![AttributeDefinition {
+                key: "color".to_string(),
+                scope: AttributeScope::Defining.into(),
+                data_type: AttributeDataType::Enum.into(),
+                allowed_values: vec!["black".to_string(), "white".to_string()],
+                required: true,
+            }],
+            updated_by: None,
+            updated_at: None,
+        }),
+        updated_by: Some("test".to_string()),
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::UPSERT_ATTRIBUTE_SCHEMA,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let upsert_response =
+        UpsertAttributeSchemaResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(upsert_response.status.unwrap().code, Code::Ok as i32);
+
+    // A misspelled key is rejected with the offending field named
+    let invalid = fixtures::product::ProductBuilder {
+        product_type: Some(product_type.clone()),
+        defining_attributes: HashMap::from([("colour".to_string(), "black".to_string())]),
+        ..Default::default()
+    };
+    let request = ProductCreateRequest {
+        name: invalid.name,
+        product_ref: invalid.product_ref,
+        slug: invalid.slug,
+        brand: invalid.brand,
+        long_description: invalid.long_description,
+        product_type: invalid.product_type,
+        display_on_site: invalid.display_on_site,
+        defining_attributes: invalid.defining_attributes,
+        descriptive_attributes: invalid.descriptive_attributes,
+        seo_title: None,
+        seo_description: None,
+        seo_keywords: None,
+        tax_code: None,
+        related_products: vec![],
+        relations: vec![],
+        reviews: None,
+        hierarchical_categories: None,
+        list_categories: vec![],
+        default_variant: None,
+        variants: vec![],
+        publish_at: None,
+        unpublish_at: None,
+        localizations: HashMap::new(),
+        bundle: None,
+        stores: HashMap::new(),
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::CREATE_PRODUCT,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let create_response =
+        ProductCreateResponse::decode(&*response.payload).expect("Response should decode");
+    let status = create_response.status.unwrap();
+    assert_eq!(status.code, Code::InvalidArgument as i32);
+    assert!(status.message.contains("defining_attributes.colour"));
+    assert!(status.message.contains("defining_attributes.color"));
+
+    let valid = fixtures::product::ProductBuilder {
+        product_type: Some(product_type),
+        defining_attributes: HashMap::from([("color".to_string(), "black".to_string())]),
+        ..Default::default()
+    };
+    create_test_product(&app, valid)
+        .await
+        .expect("Product matching the schema should be created");
+}
+
+// ============================================================================
+// REVIEW TESTS
+// ============================================================================
+
+async fn moderate_review(
+    app: &rust_common::test_helpers::TestApp,
+    review_id: &str,
+    status: ReviewStatus,
+) -> ModerateReviewResponse {
+    let request = ModerateReviewRequest {
+        review_id: review_id.to_string(),
+        status: status.into(),
+        moderated_by: Some("moderator".to_string()),
+        note: None,
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::MODERATE_REVIEW,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Should get response");
+    ModerateReviewResponse::decode(&*response.payload).unwrap()
+}
+
+#[tokio::test]
+async fn test_review_approval_recomputes_product_reviews() {
+    let app = helpers::spawn_app::spawn_app().await;
+    let product_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create product");
+
+    let submit = |rating| SubmitReviewRequest {
+        product_id: product_id.clone(),
+        rating,
+        title: Some("Great jacket".to_string()),
+        body: None,
+        author: Some("sam".to_string()),
+    };
+
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::SUBMIT_REVIEW,
+            submit(6).encode_to_vec(),
+        )
+        .await
+        .expect("Should get response");
+    let response = SubmitReviewResponse::decode(&*response.payload).unwrap();
+    assert_eq!(response.status.unwrap().code, Code::InvalidArgument as i32);
+
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::SUBMIT_REVIEW,
+            submit(4).encode_to_vec(),
+        )
+        .await
+        .expect("Should get response");
+    let review = SubmitReviewResponse::decode(&*response.payload)
+        .unwrap()
+        .review
+        .expect("Review should be created");
+    assert_eq!(review.status(), ReviewStatus::Pending);
+
+    // Pending reviews do not count towards the product's summary
+    let product = get_product(&app, &product_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap();
+    assert!(product.reviews.is_none());
+
+    let response = moderate_review(&app, &review.id, ReviewStatus::Approved).await;
+    assert_eq!(response.status.unwrap().code, Code::Ok as i32);
+    let reviews = response.reviews.expect("Summary should be recomputed");
+    assert_eq!(reviews.count, 1);
+    assert_eq!(reviews.rating, 4);
+    // The only reviewed product defines the prior, so its average is its own rating
+    assert_eq!(reviews.bayesian_avg, 4.0);
+
+    let product = get_product(&app, &product_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap();
+    assert_eq!(product.reviews.unwrap().count, 1);
+
+    let response = moderate_review(&app, &review.id, ReviewStatus::Rejected).await;
+    assert_eq!(response.reviews.unwrap().count, 0);
+
+    let request = ListReviewsRequest {
+        product_id: Some(product_id.clone()),
+        status: Some(ReviewStatus::Rejected.into()),
+        limit: None,
+        offset: None,
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::LIST_REVIEWS,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Should get response");
+    let response = ListReviewsResponse::decode(&*response.payload).unwrap();
+    assert_eq!(response.reviews.len(), 1);
+    assert_eq!(
+        response.reviews[0].moderated_by.as_deref(),
+        Some("moderator")
+    );
+}
+
+// ============================================================================
+// SITEMAP TESTS
+// ============================================================================
+
+async fn generate_sitemap(
+    app: &rust_common::test_helpers::TestApp,
+    file: Option<String>,
+) -> GenerateSitemapResponse {
+    let request = GenerateSitemapRequest {
+        base_url: "https://shop.example.com".to_string(),
+        locale_base_urls: HashMap::from([(
+            "fr-CA".to_string(),
+            "https://shop.example.com/fr-ca".to_string(),
+        )]),
+        file,
+        urls_per_file: None,
+        product_path: None,
+        category_path: None,
+    };
+
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::GENERATE_SITEMAP,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+
+    GenerateSitemapResponse::decode(&*response.payload).expect("Response should decode")
+}
+
+fn gunzip(content: &[u8]) -> String {
+    use std::io::Read;
+    let mut xml = String::new();
+    flate2::read::GzDecoder::new(content)
+        .read_to_string(&mut xml)
+        .expect("Content should be gzip-compressed XML");
+    xml
+}
+
+#[tokio::test]
+async fn test_generate_sitemap_lists_live_products_per_locale() {
+    let app = helpers::spawn_app::spawn_app().await;
+    let slug = fixtures::valid_slug();
+    let builder = fixtures::product::ProductBuilder {
+        slug: Some(slug.clone()),
+        ..Default::default()
+    };
+    create_test_product(&app, builder)
+        .await
+        .expect("Should create product");
+
+    let index = generate_sitemap(&app, None).await;
+    assert_eq!(index.status.unwrap().code, Code::Ok as i32);
+    assert_eq!(index.name, "sitemap.xml.gz");
+    let index_xml = gunzip(&index.content);
+    assert!(index_xml.contains("<loc>https://shop.example.com/sitemap-products-1.xml.gz</loc>"));
+    assert!(index_xml.contains("sitemap-products-fr-ca-1.xml.gz"));
+
+    let file = generate_sitemap(&app, Some("sitemap-products-1.xml.gz".to_string())).await;
+    assert_eq!(file.status.unwrap().code, Code::Ok as i32);
+    let urlset = gunzip(&file.content);
+    assert!(urlset.contains(&format!(
+        "<loc>https://shop.example.com/products/{slug}</loc>"
+    )));
+    assert!(urlset.contains("<lastmod>"));
+
+    let missing = generate_sitemap(&app, Some("sitemap-unknown-1.xml.gz".to_string())).await;
+    assert_eq!(missing.status.unwrap().code, Code::NotFound as i32);
+}
+
+// ============================================================================
+// CATALOG PUBLISHING TESTS
+// ============================================================================
+
+async fn get_product_version(
+    app: &rust_common::test_helpers::TestApp,
+    id: &str,
+    catalog_version: &str,
+) -> ProductGetResponse {
+    let request = ProductGetRequest {
+        id: id.to_string(),
+        locale: None,
+        catalog_version: Some(catalog_version.to_string()),
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::GET_PRODUCT,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    ProductGetResponse::decode(&*response.payload).expect("Response should decode")
+}
+
+async fn stage_product_name(
+    app: &rust_common::test_helpers::TestApp,
+    id: &str,
+    name: &str,
+    change_set: &str,
+) {
+    let mut product = get_product(app, id)
+        .await
+        .expect("Should get product")
+        .product
+        .unwrap();
+    product.name = name.to_string();
+    let request = StageCatalogChangeRequest {
+        edit: Some(stage_catalog_change_request::Edit::UpdateProduct(
+            ProductUpdateRequest {
+                id: id.to_string(),
+                product: Some(product),
+            },
+        )),
+        change_set: Some(change_set.to_string()),
+        staged_by: Some("test".to_string()),
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::STAGE_CATALOG_CHANGE,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let stage_response =
+        StageCatalogChangeResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(stage_response.status.unwrap().code, Code::Ok as i32);
+}
+
+async fn publish_change_set(
+    app: &rust_common::test_helpers::TestApp,
+    change_set: &str,
+) -> CatalogVersion {
+    let request = PublishCatalogRequest {
+        change_set: Some(change_set.to_string()),
+        published_by: Some("test".to_string()),
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::PUBLISH_CATALOG,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let publish_response =
+        PublishCatalogResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(publish_response.status.unwrap().code, Code::Ok as i32);
+    publish_response.version.unwrap()
+}
+
+#[tokio::test]
+async fn test_catalog_publish_and_rollback() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let builder = fixtures::product::ProductBuilder::default();
+    let original_name = builder.name.clone();
+    let product_id = create_test_product(&app, builder)
+        .await
+        .expect("Should create product");
+    let change_set = format!("cs-{}", fixtures::random_string(8));
+
+    // Staged edits are only visible in the draft
+    stage_product_name(&app, &product_id, "Spring Edition", &change_set).await;
+    let live = get_product(&app, &product_id)
+        .await
+        .expect("Should get product");
+    assert_eq!(live.product.unwrap().name, original_name);
+    let draft = get_product_version(&app, &product_id, "draft").await;
+    assert_eq!(draft.product.unwrap().name, "Spring Edition");
+
+    let first = publish_change_set(&app, &change_set).await;
+    assert_eq!(first.status, CatalogVersionStatus::Published as i32);
+    assert_eq!(first.changes.len(), 1);
+    assert_eq!(first.changes[0].entity_id, product_id);
+    let live = get_product(&app, &product_id)
+        .await
+        .expect("Should get product");
+    assert_eq!(live.product.unwrap().name, "Spring Edition");
+
+    // Nothing left to publish in the change set
+    let request = PublishCatalogRequest {
+        change_set: Some(change_set.clone()),
+        published_by: None,
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::PUBLISH_CATALOG,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let publish_response =
+        PublishCatalogResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(
+        publish_response.status.unwrap().code,
+        Code::InvalidArgument as i32
+    );
+
+    stage_product_name(&app, &product_id, "Summer Edition", &change_set).await;
+    let second = publish_change_set(&app, &change_set).await;
+    assert!(second.version > first.version);
+
+    // Reading an earlier version shows the product as it was then
+    let at_first = get_product_version(&app, &product_id, &first.version.to_string()).await;
+    assert_eq!(at_first.product.unwrap().name, "Spring Edition");
+
+    let request = RollbackCatalogRequest {
+        version: first.version,
+        published_by: Some("test".to_string()),
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::ROLLBACK_CATALOG,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let rollback_response =
+        RollbackCatalogResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(rollback_response.status.unwrap().code, Code::Ok as i32);
+    let rollback = rollback_response.version.unwrap();
+    assert_eq!(rollback.rolled_back_to, Some(first.version));
+
+    let live = get_product(&app, &product_id)
+        .await
+        .expect("Should get product");
+    assert_eq!(live.product.unwrap().name, "Spring Edition");
+}
+
+#[tokio::test]
+async fn test_catalog_publish_rejects_staged_update_of_a_product_changed_since() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let product_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create product");
+    let change_set = format!("cs-{}", fixtures::random_string(8));
+    stage_product_name(&app, &product_id, "Winter Edition", &change_set).await;
+
+    // A direct edit lands after the update was staged
+    let mut product = get_product(&app, &product_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap();
+    product.long_description = Some("Edited directly".to_string());
+    let request = ProductUpdateRequest {
+        id: product_id.clone(),
+        product: Some(product),
+    };
+    app.request(
+        crate::helpers::nats_config::product::subjects::UPDATE_PRODUCT,
+        request.encode_to_vec(),
+    )
+    .await
+    .expect("Update should succeed");
+
+    let request = PublishCatalogRequest {
+        change_set: Some(change_set.clone()),
+        published_by: None,
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::PUBLISH_CATALOG,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let publish_response =
+        PublishCatalogResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(
+        publish_response.status.unwrap().code,
+        Code::AlreadyExists as i32
+    );
+
+    // The direct edit survives, and staging again publishes on top of it
+    let live = get_product(&app, &product_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap();
+    assert_eq!(live.long_description.as_deref(), Some("Edited directly"));
+    stage_product_name(&app, &product_id, "Winter Edition", &change_set).await;
+    publish_change_set(&app, &change_set).await;
+    let live = get_product(&app, &product_id)
+        .await
+        .unwrap()
+        .product
+        .unwrap();
+    assert_eq!(live.name, "Winter Edition");
+    assert_eq!(live.long_description.as_deref(), Some("Edited directly"));
+}
+
+#[tokio::test]
+async fn test_catalog_publish_takes_over_once_an_abandoned_publish_lease_runs_out() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let product_id = create_test_product(&app, fixtures::product::ProductBuilder::default())
+        .await
+        .expect("Should create product");
+    let change_set = format!("cs-{}", fixtures::random_string(8));
+    stage_product_name(&app, &product_id, "Autumn Edition", &change_set).await;
+
+    // A publish left behind by an instance that crashed mid-way
+    let versions = app
+        .mongodb_db
+        .collection::<mongodb::bson::Document>("catalog_versions");
+    versions
+        .insert_one(mongodb::bson::doc! {
+            "_id": fixtures::random_string(12),
+            "version": 1_i64,
+            "status": "publishing",
+            "changes": [],
+            "started_at": mongodb::bson::DateTime::now(),
+        })
+        .await
+        .expect("Should insert version");
+
+    // Still within its lease, it blocks other publishes
+    let request = PublishCatalogRequest {
+        change_set: Some(change_set.clone()),
+        published_by: None,
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::PUBLISH_CATALOG,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let publish_response =
+        PublishCatalogResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(
+        publish_response.status.unwrap().code,
+        Code::AlreadyExists as i32
+    );
+
+    let expired = chrono::Utc::now() - chrono::Duration::hours(1);
+    versions
+        .update_one(
+            mongodb::bson::doc! { "version": 1_i64 },
+            mongodb::bson::doc! { "$set": {
+                "started_at": mongodb::bson::DateTime::from_chrono(expired),
+            } },
+        )
+        .await
+        .expect("Should age version");
+
+    let published = publish_change_set(&app, &change_set).await;
+    assert_eq!(published.version, 2);
+    let live = get_product(&app, &product_id)
+        .await
+        .expect("Should get product");
+    assert_eq!(live.product.unwrap().name, "Autumn Edition");
+
+    let request = ListCatalogVersionsRequest {
+        limit: None,
+        offset: None,
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::LIST_CATALOG_VERSIONS,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let list_response =
+        ListCatalogVersionsResponse::decode(&*response.payload).expect("Response should decode");
+    let abandoned = list_response
+        .versions
+        .iter()
+        .find(|version| version.version == 1)
+        .expect("Abandoned version should be listed");
+    assert_eq!(abandoned.status, CatalogVersionStatus::Failed as i32);
+    assert!(abandoned.error.is_some());
+}
+
+// ============================================================================
+// STORE ASSORTMENT TESTS
+// ============================================================================
+
+async fn assign_stores(
+    app: &rust_common::test_helpers::TestApp,
+    product_id: &str,
+    stores: HashMap<String, ProductStoreSettings>,
+) -> i32 {
+    let mut product = get_product(app, product_id)
+        .await
+        .expect("Should get product")
+        .product
+        .unwrap();
+    product.stores = stores;
+    let request = ProductUpdateRequest {
+        id: product_id.to_string(),
+        product: Some(product),
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::UPDATE_PRODUCT,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let update_response =
+        ProductUpdateResponse::decode(&*response.payload).expect("Response should decode");
+    update_response.status.unwrap().code
+}
+
+async fn search_store(
+    app: &rust_common::test_helpers::TestApp,
+    brand: &str,
+    store_id: &str,
+) -> ProductSearchResponse {
+    let request = ProductSearchRequest {
+        query: None,
+        categories: vec![],
+        brand: Some(brand.to_string()),
+        limit: Some(10),
+        offset: None,
+        locale: None,
+        store_id: Some(store_id.to_string()),
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::SEARCH_PRODUCTS,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    ProductSearchResponse::decode(&*response.payload).expect("Response should decode")
+}
+
+async fn store_slugs(app: &rust_common::test_helpers::TestApp, store_id: &str) -> Vec<String> {
+    let request = GetProductSlugsRequest {
+        batch_size: Some(1000),
+        cursor: None,
+        include_inactive: Some(false),
+        store_id: Some(store_id.to_string()),
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::GET_PRODUCT_SLUGS,
+            request.encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let slugs_response =
+        GetProductSlugsResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(slugs_response.status.unwrap().code, Code::Ok as i32);
+    slugs_response.slugs
+}
+
+#[tokio::test]
+async fn test_store_assortment_filters_search_and_slugs() {
+    let app = helpers::spawn_app::spawn_app().await;
+    let brand = format!("Brand{}", fixtures::random_string(8));
+
+    let mut ids = Vec::new();
+    let mut slugs = Vec::new();
+    for _ in 0..3 {
+        let builder = fixtures::product::ProductBuilder {
+            brand: Some(brand.clone()),
+            ..Default::default()
+        };
+        slugs.push(builder.slug.clone().unwrap());
+        ids.push(
+            create_test_product(&app, builder)
+                .await
+                .expect("Should create product"),
+        );
+    }
+    let (everywhere, eu_only, us_hidden) = (&ids[0], &ids[1], &ids[2]);
+    let eu_slug = format!("eu-{}", fixtures::random_string(8));
+
+    let code = assign_stores(
+        &app,
+        eu_only,
+        HashMap::from([(
+            "EU".to_string(),
+            ProductStoreSettings {
+                display_on_site: true,
+                slug: Some(eu_slug.clone()),
+            },
+        )]),
+    )
+    .await;
+    assert_eq!(code, Code::Ok as i32);
+    let code = assign_stores(
+        &app,
+        us_hidden,
+        HashMap::from([(
+            "us".to_string(),
+            ProductStoreSettings {
+                display_on_site: false,
+                slug: None,
+            },
+        )]),
+    )
+    .await;
+    assert_eq!(code, Code::Ok as i32);
+
+    // Store ids are stored normalized
+    let product = get_product(&app, eu_only).await.unwrap().product.unwrap();
+    assert!(product.stores.contains_key("eu"));
+
+    // Products without stores are in every store; assigned ones only in theirs
+    let eu = search_store(&app, &brand, "eu").await;
+    assert_eq!(eu.status.unwrap().code, Code::Ok as i32);
+    let mut found: Vec<&str> = eu
+        .products
+        .iter()
+        .map(|p| p.id.as_deref().unwrap())
+        .collect();
+    found.sort();
+    let mut expected = vec![everywhere.as_str(), eu_only.as_str()];
+    expected.sort();
+    assert_eq!(found, expected);
+    let eu_product = eu
+        .products
+        .iter()
+        .find(|p| p.id.as_deref() == Some(eu_only.as_str()))
+        .unwrap();
+    assert_eq!(eu_product.slug.as_deref(), Some(eu_slug.as_str()));
+
+    // A product hidden in its store is not found there
+    let us = search_store(&app, &brand, "us").await;
+    let found: Vec<&str> = us
+        .products
+        .iter()
+        .map(|p| p.id.as_deref().unwrap())
+        .collect();
+    assert_eq!(found, vec![everywhere.as_str()]);
+
+    // Slug listings use the store's slugs and display flags
+    let eu_slugs = store_slugs(&app, "eu").await;
+    assert!(eu_slugs.contains(&slugs[0]));
+    assert!(eu_slugs.contains(&eu_slug));
+    assert!(!eu_slugs.contains(&slugs[1]));
+    assert!(!eu_slugs.contains(&slugs[2]));
+    let us_slugs = store_slugs(&app, "us").await;
+    assert!(us_slugs.contains(&slugs[0]));
+    assert!(!us_slugs.contains(&slugs[2]));
+
+    // A store slug is unique within its store
+    let code = assign_stores(
+        &app,
+        us_hidden,
+        HashMap::from([(
+            "eu".to_string(),
+            ProductStoreSettings {
+                display_on_site: true,
+                slug: Some(eu_slug.clone()),
+            },
+        )]),
+    )
+    .await;
+    assert_eq!(code, Code::AlreadyExists as i32);
+
+    let invalid = search_store(&app, &brand, "eu.main").await;
+    assert_eq!(invalid.status.unwrap().code, Code::InvalidArgument as i32);
+}
+
+// ============================================================================
+// PRODUCT COMPARISON TESTS
+// ============================================================================
+
+#[tokio::test]
+async fn test_compare_products_builds_attribute_matrix() {
+    let app = helpers::spawn_app::spawn_app().await;
+
+    let create = |colour: &str, material: &str, variants: Vec<ProductVariant>| {
+        let builder = fixtures::product::ProductBuilder::default();
+        ProductCreateRequest {
+            name: builder.name,
+            product_ref: builder.product_ref,
+            slug: builder.slug,
+            brand: builder.brand,
+            long_description: builder.long_description,
+            product_type: None,
+            display_on_site: true,
+            defining_attributes: HashMap::from([("Colour".to_string(), colour.to_string())]),
+            descriptive_attributes: HashMap::from([("Material".to_string(), material.to_string())]),
+            seo_title: None,
+            seo_description: None,
+            seo_keywords: None,
+            tax_code: None,
+            related_products: vec![],
+            relations: vec![],
+            reviews: None,
+            hierarchical_categories: None,
+            list_categories: vec![],
+            default_variant: None,
+            variants,
+            publish_at: None,
+            unpublish_at: None,
+            localizations: HashMap::new(),
+            bundle: None,
+            stores: HashMap::new(),
+        }
+    };
+    let variant = |sku: String, size: &str, weight_grams: f64| ProductVariant {
+        sku,
+        defining_attributes: HashMap::from([("size".to_string(), size.to_string())]),
+        weight: Some(weight_grams),
+        weight_unit: Some("g".to_string()),
+        ..Default::default()
+    };
+
+    let mut ids = Vec::new();
+    for request in [
+        create(
+            "Black",
+            "Leather",
+            vec![
+                variant(format!("BT-S-{}", fixtures::random_string(6)), "S", 900.0),
+                variant(format!("BT-M-{}", fixtures::random_string(6)), "M", 1100.0),
+            ],
+        ),
+        create(
+            "black",
+            "Suede",
+            vec![variant(
+                format!("SH-M-{}", fixtures::random_string(6)),
+                "M",
+                700.0,
+            )],
+        ),
+    ] {
+        let response = app
+            .request(
+                crate::helpers::nats_config::product::subjects::CREATE_PRODUCT,
+                request.encode_to_vec(),
+            )
+            .await
+            .expect("Request should succeed");
+        let create_response =
+            ProductCreateResponse::decode(&*response.payload).expect("Response should decode");
+        assert_eq!(create_response.status.unwrap().code, Code::Ok as i32);
+        ids.push(create_response.product.unwrap().id.unwrap());
+    }
+
+    let compare = |product_ids: Vec<String>| CompareProductsRequest {
+        product_ids,
+        locale: None,
+        length_unit: None,
+        weight_unit: None,
+    };
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::COMPARE_PRODUCTS,
+            compare(ids.clone()).encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let comparison =
+        CompareProductsResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(comparison.status.unwrap().code, Code::Ok as i32);
+    assert_eq!(comparison.weight_unit, "kg");
+    let product_ids: Vec<&str> = comparison.products.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(product_ids, vec![ids[0].as_str(), ids[1].as_str()]);
+    assert_eq!(comparison.products[0].variants.len(), 2);
+
+    let row = |key: &str| {
+        comparison
+            .rows
+            .iter()
+            .find(|row| row.key == key)
+            .unwrap_or_else(|| panic!("Missing row {key}"))
+    };
+    assert_eq!(row("colour").kind(), ComparisonRowKind::DefiningAttribute);
+    assert!(!row("colour").differs);
+    assert_eq!(row("size").values, vec!["S, M", "M"]);
+    assert!(row("size").differs);
+    assert_eq!(
+        row("material").kind(),
+        ComparisonRowKind::DescriptiveAttribute
+    );
+    assert!(row("material").differs);
+    assert_eq!(row("weight").values, vec!["0.9-1.1 kg", "0.7 kg"]);
+
+    // Hidden products cannot be compared
+    let hidden_id = create_test_product(
+        &app,
+        fixtures::product::ProductBuilder {
+            display_on_site: false,
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Should create product");
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::COMPARE_PRODUCTS,
+            compare(vec![ids[0].clone(), hidden_id]).encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let comparison =
+        CompareProductsResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(comparison.status.unwrap().code, Code::NotFound as i32);
+
+    let response = app
+        .request(
+            crate::helpers::nats_config::product::subjects::COMPARE_PRODUCTS,
+            compare(vec![ids[0].clone(); 2]).encode_to_vec(),
+        )
+        .await
+        .expect("Request should succeed");
+    let comparison =
+        CompareProductsResponse::decode(&*response.payload).expect("Response should decode");
+    assert_eq!(
+        comparison.status.unwrap().code,
+        Code::InvalidArgument as i32
+    );
+}
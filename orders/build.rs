@@ -4,8 +4,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Include the shared proto path
     config.compile_protos(
-        &["proto/orders.proto"],
-        &["proto/", "../shared-proto/proto/"],
+        &["proto/orders.proto", "../price/proto/offer.proto"],
+        &["proto/", "../shared-proto/proto/", "../price/proto/"],
     )?;
     Ok(())
 }
@@ -63,6 +63,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             telephone: "123-456-7890".to_owned(),
             email: Some("john.doe@example.com".to_owned()),
         }),
+        order_items: vec![],
+        coupon_codes: vec![],
     };
 
     let mut buf = vec![];
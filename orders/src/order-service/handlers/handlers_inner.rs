@@ -1,10 +1,16 @@
-use log::{debug, error};
+use async_nats::Client;
+use log::{debug, error, warn};
+use std::collections::HashMap;
 
-use crate::model::{Order, OrderBuilder, OrderCreateRequest};
+use crate::model::{
+    Order, OrderBuilder, OrderCreateRequest, OrderItem, OrderTotals, OrderTotalsBuilder,
+};
+use crate::offer_messages::PromotionLine;
 use crate::persistence::orders_dao::OrdersDao;
+use crate::price_client::{self, PromotionsError};
 
 pub enum HandlerError {
-    // BadRequest(String),
+    BadRequest(String),
     InternalError(String),
 }
 
@@ -14,28 +20,69 @@ impl HandlerError {
     // }
 }
 
+impl From<PromotionsError> for HandlerError {
+    fn from(e: PromotionsError) -> Self {
+        match e {
+            PromotionsError::Rejected(msg) => HandlerError::BadRequest(msg),
+            PromotionsError::Unavailable(e) => {
+                error!("Error calling price service: {e}");
+                HandlerError::InternalError(format!("Failed to apply promotions: {e}"))
+            }
+        }
+    }
+}
+
+/// Place an order. Promotions are evaluated on its items and their discounts
+/// taken into the totals; the promotions that applied are redeemed for the
+/// order before it is saved, and released again if saving fails.
 pub async fn create_order(
     order_create_request: OrderCreateRequest,
     orders_dao: &(dyn OrdersDao + Sync + Send),
+    client: &Client,
 ) -> Result<Order, HandlerError> {
     debug!("Before call to create_order hander_inner");
     let mut order = OrderBuilder::new();
-    if order_create_request.order_ref.is_some() {
-        order.order_ref(order_create_request.order_ref.unwrap());
-    };
-    if order_create_request.sold_to.is_some() {
-        order.sold_to(order_create_request.sold_to.unwrap());
+    if let Some(order_ref) = order_create_request.order_ref {
+        order.order_ref(order_ref);
     };
-    if order_create_request.order_items.is_some() {
-        order.order_items(order_create_request.order_items.unwrap());
+    if let Some(sold_to) = order_create_request.sold_to {
+        order.sold_to(sold_to);
     };
+    let mut order = order.build();
+    let order_id = order.id.clone().unwrap_or_default();
 
-    let result = orders_dao.create_order(order.build()).await;
+    let mut redeemed = false;
+    if let Some(mut order_items) = order_create_request
+        .order_items
+        .filter(|items| !items.is_empty())
+    {
+        let totals = apply_promotions(
+            &order_id,
+            &mut order_items,
+            order_create_request.coupon_codes,
+            client,
+        )
+        .await?;
+        if let Some(detail) = totals.discount_detail.as_ref().filter(|d| !d.is_empty()) {
+            price_client::redeem_promotions(client, &order_id, detail.keys().cloned().collect())
+                .await?;
+            redeemed = true;
+        }
+        order.order_items = Some(order_items);
+        order.order_totals = Some(totals);
+    }
+
+    let result = orders_dao.create_order(order).await;
 
     match result {
         Ok(order) => Ok(order),
         Err(e) => {
             error!("Error creating order: {e}");
+            if redeemed {
+                if let Err(e) = price_client::release_promotions(client, &order_id).await {
+                    warn!("Failed to release promotions of unsaved order {order_id}: {e}");
+                }
+            }
             Err(HandlerError::InternalError(format!(
                 "Failed to create order: {e}"
             )))
@@ -43,6 +90,77 @@ pub async fn create_order(
     }
 }
 
+// Prices the items and works out the order's totals with the discounts of
+// the promotions that apply
+async fn apply_promotions(
+    order_id: &str,
+    order_items: &mut [OrderItem],
+    coupon_codes: Vec<String>,
+    client: &Client,
+) -> Result<OrderTotals, HandlerError> {
+    let currency = order_items[0].price.currency.clone();
+    if order_items
+        .iter()
+        .any(|order_item| order_item.price.currency != currency)
+    {
+        return Err(HandlerError::BadRequest(
+            "All order items must be priced in the same currency".to_string(),
+        ));
+    }
+
+    // Category promotions need the items' categories, which orders do not
+    // carry, so only SKU and order promotions apply
+    let lines = order_items
+        .iter()
+        .map(|order_item| PromotionLine {
+            line_num: order_item.line_num,
+            sku: order_item.item.item_ref.clone(),
+            quantity: order_item.quantity,
+            unit_price: order_item.price.amount.to_string(),
+            categories: vec![],
+        })
+        .collect();
+    let evaluation =
+        price_client::evaluate_promotions(client, lines, &currency, coupon_codes).await?;
+    for rejected in &evaluation.rejected_coupons {
+        debug!(
+            "Coupon {} not applied to order {order_id}: {}",
+            rejected.code, rejected.reason
+        );
+    }
+
+    let mut product_total = 0.0;
+    for order_item in order_items.iter_mut() {
+        let line_total = (order_item.price.amount * order_item.quantity as f64) as f32;
+        let line_discount = evaluation
+            .lines
+            .iter()
+            .find(|line| line.line_num == order_item.line_num)
+            .map_or(0.0, |line| to_amount(&line.discount_total));
+        order_item.order_id = order_id.to_string();
+        order_item.orderitem_totals = Some(
+            OrderTotalsBuilder::new(line_total)
+                .discount_total(line_discount)
+                .build(),
+        );
+        product_total += line_total;
+    }
+
+    let discount_detail: HashMap<String, f32> = evaluation
+        .discount_detail
+        .iter()
+        .map(|(promotion_id, amount)| (promotion_id.clone(), to_amount(amount)))
+        .collect();
+    Ok(OrderTotalsBuilder::new(product_total)
+        .discount_total(to_amount(&evaluation.discount_total))
+        .discount_detail(discount_detail)
+        .build())
+}
+
+fn to_amount(amount: &str) -> f32 {
+    amount.parse().unwrap_or_default()
+}
+
 pub async fn get_order(
     order_id: String,
     orders_dao: &(dyn OrdersDao + Sync + Send),
@@ -66,11 +184,19 @@ pub async fn get_order(
 pub async fn delete_order(
     order_id: String,
     orders_dao: &(dyn OrdersDao + Send + Sync),
+    client: &Client,
 ) -> Result<(), HandlerError> {
-    let result = orders_dao.delete_order(order_id).await;
+    let result = orders_dao.delete_order(order_id.clone()).await;
 
     match result {
-        Ok(()) => Ok(()),
+        Ok(()) => {
+            // The promotions the order used can be used again
+            match price_client::release_promotions(client, &order_id).await {
+                Ok(released) => debug!("Released {released} promotion(s) of order {order_id}"),
+                Err(e) => warn!("Failed to release promotions of deleted order {order_id}: {e}"),
+            }
+            Ok(())
+        }
         Err(e) => {
             error!("Error did not find order to delete: {e}");
             Err(HandlerError::InternalError(format!(
@@ -78,40 +78,39 @@ pub async fn create_order(
     let order = order_messages::OrderCreateRequest::decode(order_create_request.payload.clone());
     match order {
         Ok(order) => {
-            let mut model_addr: Option<model::Address> = None;
-            if order.sold_to.is_some() {
-                let req_addr = order.sold_to.unwrap();
-                let mut addr_bldr = model::AddressBuilder::new(
-                    req_addr.id,
-                    req_addr.name,
-                    req_addr.address_line1,
-                    req_addr.city,
-                    req_addr.postal_code,
-                    req_addr.country,
-                    req_addr.telephone,
-                );
-                if req_addr.address_line2.is_some() {
-                    addr_bldr.address_line2(req_addr.address_line2.unwrap());
-                }
-                if req_addr.company.is_some() {
-                    addr_bldr.company(req_addr.company.unwrap());
-                }
-                if req_addr.state_province.is_some() {
-                    addr_bldr.state_province(req_addr.state_province.unwrap());
-                }
-                if req_addr.email.is_some() {
-                    addr_bldr.email(req_addr.email.unwrap());
-                }
-                model_addr = Some(addr_bldr.build());
-            }
+            let model_addr = order.sold_to.map(map_proto_address_to_model_address);
+            let order_items: Option<Vec<model::OrderItem>> = order
+                .order_items
+                .into_iter()
+                .map(map_proto_order_item_to_model_order_item)
+                .collect();
+            let Some(order_items) = order_items else {
+                warn!("Order item without an item or price");
+                let ocrsp = order_messages::OrderCreateResponse {
+                    order: None,
+                    status: Some(order_messages::Status {
+                        code: order_messages::Code::InvalidArgument.into(),
+                        message: "Every order item needs an item and a price".to_string(),
+                        details: vec![],
+                    }),
+                };
+                let mut buf = vec![];
+                ocrsp.encode(&mut buf).unwrap();
+                client
+                    .publish(order_create_request.reply.unwrap(), buf.into())
+                    .await
+                    .unwrap();
+                return;
+            };
 
             let ocr = model::OrderCreateRequest {
                 order_ref: order.order_ref.clone(),
                 sold_to: model_addr,
-                order_items: None,
+                order_items: Some(order_items),
+                coupon_codes: order.coupon_codes,
             };
 
-            let result = handlers_inner::create_order(ocr, orders_dao.as_ref()).await;
+            let result = handlers_inner::create_order(ocr, orders_dao.as_ref(), &client).await;
             match result {
                 Ok(o) => {
                     let ocrsp = order_messages::OrderCreateResponse {
@@ -133,6 +132,23 @@ pub async fn create_order(
                 }
                 Err(err) => {
                     match err {
+                        handlers_inner::HandlerError::BadRequest(msg) => {
+                            warn!("Order not created: {msg}");
+                            let ocrsp = order_messages::OrderCreateResponse {
+                                order: None,
+                                status: Some(order_messages::Status {
+                                    code: order_messages::Code::FailedPrecondition.into(),
+                                    message: msg,
+                                    details: vec![],
+                                }),
+                            };
+                            let mut buf = vec![];
+                            ocrsp.encode(&mut buf).unwrap();
+                            client
+                                .publish(order_create_request.reply.unwrap(), buf.into())
+                                .await
+                                .unwrap();
+                        }
                         handlers_inner::HandlerError::InternalError(msg) => {
                             error!("Error creating order: {msg}");
                             client
@@ -250,7 +266,7 @@ pub async fn delete_order(
         Ok(request) => {
             let order_id = request.id;
             debug!("order_id: {order_id}");
-            let result = handlers_inner::delete_order(order_id, orders_dao.as_ref()).await;
+            let result = handlers_inner::delete_order(order_id, orders_dao.as_ref(), &client).await;
             match result {
                 Ok(_) => {
                     debug!("start of delete order Ok block");
@@ -270,7 +286,8 @@ pub async fn delete_order(
                 }
                 Err(e) => {
                     match e {
-                        handlers_inner::HandlerError::InternalError(msg) => {
+                        handlers_inner::HandlerError::BadRequest(msg)
+                        | handlers_inner::HandlerError::InternalError(msg) => {
                             error!("Internal error deleting order: {msg:?}");
                             let odresp = order_messages::OrderDeleteResponse {
                                 status: Some(order_messages::Status {
@@ -398,6 +415,7 @@ fn map_model_order_to_proto_order(order: model::Order) -> order_messages::Order
                             tax_total: ot.tax_total,
                             shipping_total: ot.shipping_total,
                             discount_total: ot.discount_total,
+                            discount_detail: ot.discount_detail.unwrap_or_default(),
                         }
                     }),
                 })
@@ -408,6 +426,88 @@ fn map_model_order_to_proto_order(order: model::Order) -> order_messages::Order
             tax_total: ot.tax_total,
             shipping_total: ot.shipping_total,
             discount_total: ot.discount_total,
+            discount_detail: ot.discount_detail.unwrap_or_default(),
         }),
     }
 }
+
+// Translates a protobuf address to a model::Address
+fn map_proto_address_to_model_address(req_addr: order_messages::Address) -> model::Address {
+    let mut addr_bldr = model::AddressBuilder::new(
+        req_addr.id,
+        req_addr.name,
+        req_addr.address_line1,
+        req_addr.city,
+        req_addr.postal_code,
+        req_addr.country,
+        req_addr.telephone,
+    );
+    if let Some(address_line2) = req_addr.address_line2 {
+        addr_bldr.address_line2(address_line2);
+    }
+    if let Some(company) = req_addr.company {
+        addr_bldr.company(company);
+    }
+    if let Some(state_province) = req_addr.state_province {
+        addr_bldr.state_province(state_province);
+    }
+    if let Some(email) = req_addr.email {
+        addr_bldr.email(email);
+    }
+    addr_bldr.build()
+}
+
+// Translates a protobuf order item to a model::OrderItem; None when it has no
+// item or price
+fn map_proto_order_item_to_model_order_item(
+    order_item: order_messages::OrderItem,
+) -> Option<model::OrderItem> {
+    let item = order_item.item?;
+    let price = order_item.price?;
+
+    let mut item_bldr = model::ItemBuilder::new(item.item_ref, item.name);
+    if let Some(id) = item.id {
+        item_bldr.id(id);
+    }
+    if let Some(product_id) = item.product_id {
+        item_bldr.product_id(product_id);
+    }
+    if let Some(product_ref) = item.product_ref {
+        item_bldr.product_ref(product_ref);
+    }
+    if let Some(image_url) = item.image_url {
+        item_bldr.image_url(image_url);
+    }
+    if !item.attributes.is_empty() {
+        item_bldr.attributes(
+            item.attributes
+                .into_iter()
+                .map(|attr| model::Attribute {
+                    seq: attr.seq,
+                    attribute_ref: attr.attribute_ref,
+                    name: attr.name,
+                    value: attr.value,
+                })
+                .collect(),
+        );
+    }
+    if let Some(product_display_url) = item.product_display_url {
+        item_bldr.product_display_url(product_display_url);
+    }
+
+    let mut order_item_bldr = model::OrderItemBuilder::new(
+        order_item.line_num,
+        order_item.order_id,
+        item_bldr.build(),
+        order_item.quantity,
+        model::Price {
+            id: price.id,
+            amount: price.amount,
+            currency: price.currency,
+        },
+    );
+    if let Some(ship_to) = order_item.ship_to {
+        order_item_bldr.ship_to(map_proto_address_to_model_address(ship_to));
+    }
+    Some(order_item_bldr.build())
+}
@@ -2,6 +2,7 @@ pub mod model;
 
 mod handlers;
 mod persistence;
+mod price_client;
 mod validation;
 
 use handlers::{create_order, delete_order, get_order, Router};
@@ -32,6 +33,13 @@ pub mod order_messages {
     pub use super::common::{Code, Status};
 }
 
+// Price-service messages, used to apply and redeem promotions on orders
+pub mod offer_messages {
+    include!(concat!(env!("OUT_DIR"), "/offer_messages.rs"));
+
+    pub use super::common::{Code, Status};
+}
+
 #[derive(Clone)]
 pub struct AppState {
     pub orders_dao: Arc<dyn OrdersDao + Send + Sync>,
@@ -86,6 +86,8 @@ pub struct OrderCreateRequest {
     pub order_ref: Option<String>,
     pub sold_to: Option<Address>,
     pub order_items: Option<Vec<OrderItem>>,
+    #[serde(default)]
+    pub coupon_codes: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -0,0 +1,99 @@
+use async_nats::Client;
+use prost::Message;
+use std::error::Error;
+use thiserror::Error;
+
+use crate::offer_messages::{
+    Code, EvaluatePromotionsRequest, EvaluatePromotionsResponse, PromotionLine,
+    RedeemPromotionsRequest, RedeemPromotionsResponse, ReleasePromotionsRequest,
+    ReleasePromotionsResponse, Status,
+};
+
+/// price-service subjects for the promotions an order uses
+pub const OFFERS_EVALUATE_PROMOTIONS_SUBJECT: &str = "offers.evaluate_promotions";
+pub const OFFERS_REDEEM_PROMOTIONS_SUBJECT: &str = "offers.redeem_promotions";
+pub const OFFERS_RELEASE_PROMOTIONS_SUBJECT: &str = "offers.release_promotions";
+
+/// Why price-service turned a promotions request down
+#[derive(Debug, Error)]
+pub enum PromotionsError {
+    /// The request was rejected, e.g. a promotion has reached its usage limit
+    #[error("promotions rejected: {0}")]
+    Rejected(String),
+    /// price-service could not be reached or failed
+    #[error("promotions unavailable: {0}")]
+    Unavailable(Box<dyn Error + Send + Sync>),
+}
+
+/// Discounts the promotions running now, and those behind the coupon codes,
+/// give the lines. Amounts are returned as price-service formats them.
+pub async fn evaluate_promotions(
+    client: &Client,
+    lines: Vec<PromotionLine>,
+    currency: &str,
+    coupon_codes: Vec<String>,
+) -> Result<EvaluatePromotionsResponse, PromotionsError> {
+    let request = EvaluatePromotionsRequest {
+        lines,
+        currency: currency.to_string(),
+        coupon_codes,
+        date: None,
+    };
+    let response: EvaluatePromotionsResponse =
+        request_promotions(client, OFFERS_EVALUATE_PROMOTIONS_SUBJECT, &request).await?;
+    check_status(response.status.as_ref())?;
+    Ok(response)
+}
+
+/// Count the order's use of each promotion; retrying for the same order
+/// counts nothing twice
+pub async fn redeem_promotions(
+    client: &Client,
+    order_id: &str,
+    promotion_ids: Vec<String>,
+) -> Result<(), PromotionsError> {
+    let request = RedeemPromotionsRequest {
+        promotion_ids,
+        order_id: order_id.to_string(),
+    };
+    let response: RedeemPromotionsResponse =
+        request_promotions(client, OFFERS_REDEEM_PROMOTIONS_SUBJECT, &request).await?;
+    check_status(response.status.as_ref())
+}
+
+/// Give back every use the order redeemed; returns how many were released
+pub async fn release_promotions(client: &Client, order_id: &str) -> Result<i32, PromotionsError> {
+    let request = ReleasePromotionsRequest {
+        order_id: order_id.to_string(),
+        promotion_ids: vec![],
+    };
+    let response: ReleasePromotionsResponse =
+        request_promotions(client, OFFERS_RELEASE_PROMOTIONS_SUBJECT, &request).await?;
+    check_status(response.status.as_ref())?;
+    Ok(response.released_count)
+}
+
+async fn request_promotions<Req: Message, Resp: Message + Default>(
+    client: &Client,
+    subject: &'static str,
+    request: &Req,
+) -> Result<Resp, PromotionsError> {
+    let response = client
+        .request(subject, request.encode_to_vec().into())
+        .await
+        .map_err(|e| PromotionsError::Unavailable(Box::new(e)))?;
+    Resp::decode(&*response.payload).map_err(|e| PromotionsError::Unavailable(Box::new(e)))
+}
+
+fn check_status(status: Option<&Status>) -> Result<(), PromotionsError> {
+    match status {
+        Some(status) if status.code == Code::Ok as i32 => Ok(()),
+        Some(status) if status.code == Code::Internal as i32 => {
+            Err(PromotionsError::Unavailable(status.message.clone().into()))
+        }
+        Some(status) => Err(PromotionsError::Rejected(status.message.clone())),
+        None => Err(PromotionsError::Unavailable(
+            "response without a status".into(),
+        )),
+    }
+}
@@ -17,10 +17,16 @@ pub mod offer_messages {
 #[path = "price-service/model.rs"]
 pub mod model;
 
+// Promotions engine
+#[path = "price-service/promotions.rs"]
+pub mod promotions;
+
 // Persistence layer
 #[path = "price-service/persistence"]
 pub mod persistence {
     pub mod offer_dao;
+    pub mod price_list_dao;
+    pub mod promotion_dao;
 }
 
 // Handlers for gRPC/NATS
@@ -29,5 +35,8 @@ pub mod handlers;
 pub use handlers::handlers_inner;
 
 // Re-export commonly used types
-pub use model::{DBError, Offer, OfferPrice};
+pub use model::{DBError, Offer, OfferPrice, PriceList, PriceListContext};
 pub use persistence::offer_dao::{OfferDao, OfferDaoImpl};
+pub use persistence::price_list_dao::{PriceListDao, PriceListDaoImpl};
+pub use persistence::promotion_dao::{PromotionDao, PromotionDaoImpl};
+pub use promotions::{evaluate_promotions, CartLine, Promotion, PromotionEvaluation};
@@ -4,9 +4,15 @@ use clap::{Parser, Subcommand};
 use iso_currency::Currency;
 use log::debug;
 use offer_messages::{
-    GetBestOfferPriceRequest, GetBestOfferPriceResponse, GetBestOfferPricesRequest,
-    GetBestOfferPricesResponse, OfferCreateRequest, OfferCreateResponse, OfferDeleteRequest,
-    OfferDeleteResponse, OfferGetRequest, OfferGetResponse,
+    DiscountType, EvaluatePromotionsRequest, EvaluatePromotionsResponse, GetBestOfferPriceRequest,
+    GetBestOfferPriceResponse, GetBestOfferPricesRequest, GetBestOfferPricesResponse,
+    OfferCreateRequest, OfferCreateResponse, OfferDeleteRequest, OfferDeleteResponse,
+    OfferGetRequest, OfferGetResponse, PriceListContext, PriceListCreateRequest,
+    PriceListCreateResponse, PriceListDeleteRequest, PriceListDeleteResponse, PriceListsGetRequest,
+    PriceListsGetResponse, PromotionCreateRequest, PromotionCreateResponse, PromotionDeleteRequest,
+    PromotionDeleteResponse, PromotionLine, PromotionTarget, PromotionsGetRequest,
+    PromotionsGetResponse, RedeemPromotionsRequest, RedeemPromotionsResponse,
+    ReleasePromotionsRequest, ReleasePromotionsResponse, StackingRule,
 };
 use prost::Message;
 use prost_types::Timestamp;
@@ -38,6 +44,8 @@ pub struct OfferImport {
     pub min_quantity: i32,
     pub max_quantity: Option<i32>,
     pub offer_prices: Vec<OfferPriceImport>,
+    #[serde(default)]
+    pub price_list_id: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -67,6 +75,7 @@ impl OfferImport {
                     })
                 })
                 .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?,
+            price_list_id: self.price_list_id.clone(),
         })
     }
 }
@@ -93,11 +102,63 @@ fn offer_to_create_request(offer: &Offer) -> OfferCreateRequest {
                 currency: op.currency.code().to_string(),
             })
             .collect(),
+        price_list_id: offer.price_list_id.clone(),
     };
     debug!("OfferCreateRequest: {ocr:?}");
     ocr
 }
 
+// A price list context, or None when neither channel nor customer group is given
+fn price_list_context(
+    channel: &Option<String>,
+    customer_group: &Option<String>,
+) -> Option<PriceListContext> {
+    (channel.is_some() || customer_group.is_some()).then(|| PriceListContext {
+        channel: channel.clone(),
+        customer_group: customer_group.clone(),
+    })
+}
+
+// Split a comma-separated argument, dropping blanks
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+// A cart line written as sku:quantity:unit_price, optionally followed by
+// :category|category
+fn parse_promotion_line(line_num: i32, line: &str) -> Result<PromotionLine, String> {
+    let parts: Vec<&str> = line.split(':').map(str::trim).collect();
+    let (sku, quantity, unit_price) = match parts.as_slice() {
+        [sku, quantity, unit_price] | [sku, quantity, unit_price, _] => (sku, quantity, unit_price),
+        _ => {
+            return Err(format!(
+                "Line must be sku:quantity:price[:categories]: {line}"
+            ))
+        }
+    };
+    Ok(PromotionLine {
+        line_num,
+        sku: sku.to_string(),
+        quantity: quantity
+            .parse()
+            .map_err(|_| format!("Invalid quantity in line: {line}"))?,
+        unit_price: unit_price.to_string(),
+        categories: parts
+            .get(3)
+            .map(|categories| {
+                categories
+                    .split('|')
+                    .filter(|c| !c.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 struct Cli {
@@ -118,6 +179,8 @@ enum Commands {
         min_quantity: i32,
         #[arg(short = 'x', long)]
         max_quantity: Option<i32>,
+        #[arg(long)]
+        price_list: Option<String>,
     },
     OfferGet {
         #[arg(short, long)]
@@ -136,6 +199,10 @@ enum Commands {
         currency: String,
         #[arg(short, long)]
         date: Option<String>,
+        #[arg(long)]
+        channel: Option<String>,
+        #[arg(long)]
+        customer_group: Option<String>,
     },
     GetBestOfferPrices {
         #[arg(short, long)]
@@ -146,6 +213,80 @@ enum Commands {
         currency: String,
         #[arg(short, long)]
         date: Option<String>,
+        #[arg(long)]
+        channel: Option<String>,
+        #[arg(long)]
+        customer_group: Option<String>,
+    },
+    PriceListCreate {
+        #[arg(short, long)]
+        id: String,
+        #[arg(long)]
+        channel: Option<String>,
+        #[arg(long)]
+        customer_group: Option<String>,
+        #[arg(short, long, default_value = "0")]
+        priority: i32,
+        #[arg(short, long, default_value = "USD")]
+        currency: String,
+    },
+    PriceListGetAll,
+    PriceListDelete {
+        #[arg(short, long)]
+        id: String,
+    },
+    PromotionCreate {
+        #[arg(short, long)]
+        id: String,
+        #[arg(short, long)]
+        name: String,
+        #[arg(short, long)]
+        value: String,
+        #[arg(long)]
+        amount_off: bool, // Percentage unless set
+        #[arg(short, long, default_value = "USD")]
+        currency: String,
+        #[arg(long)]
+        skus: Option<String>, // comma-separated; targets the order without skus or categories
+        #[arg(long)]
+        categories: Option<String>, // comma-separated
+        #[arg(long)]
+        min_order_total: Option<String>,
+        #[arg(long)]
+        coupon: Option<String>,
+        #[arg(long)]
+        usage_limit: Option<i32>,
+        #[arg(long)]
+        exclusive: bool,
+        #[arg(short, long, default_value = "0")]
+        priority: i32,
+        #[arg(long, default_value = "30")]
+        days: i64,
+    },
+    PromotionGetAll,
+    PromotionDelete {
+        #[arg(short, long)]
+        id: String,
+    },
+    EvaluatePromotions {
+        #[arg(short, long)]
+        lines: String, // comma-separated sku:quantity:price[:category|category]
+        #[arg(short, long, default_value = "USD")]
+        currency: String,
+        #[arg(long)]
+        coupons: Option<String>, // comma-separated
+    },
+    RedeemPromotions {
+        #[arg(short, long)]
+        order_id: String,
+        #[arg(short, long)]
+        ids: String, // comma-separated promotion ids
+    },
+    ReleasePromotions {
+        #[arg(short, long)]
+        order_id: String,
+        #[arg(short, long)]
+        ids: Option<String>, // comma-separated; default: all the order redeemed
     },
     Import {
         #[arg(short, long)]
@@ -178,6 +319,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             currency,
             min_quantity,
             max_quantity,
+            price_list,
         }) => {
             // Validate currency
             Currency::from_code(currency).ok_or_else(|| format!("Invalid currency: {currency}"))?;
@@ -202,6 +344,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     price: price.clone(),
                     currency: currency.clone(),
                 }],
+                price_list_id: price_list.clone(),
             };
 
             let request_bytes = offer_request.encode_to_vec();
@@ -245,6 +388,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             quantity,
             currency,
             date,
+            channel,
+            customer_group,
         }) => {
             // Validate currency
             Currency::from_code(currency).ok_or_else(|| format!("Invalid currency: {currency}"))?;
@@ -254,6 +399,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 quantity: *quantity,
                 date: date.clone(),
                 currency: currency.clone(),
+                price_list_context: price_list_context(channel, customer_group),
             };
 
             let request_bytes = get_best_offer_request.encode_to_vec();
@@ -304,6 +450,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             quantity,
             currency,
             date,
+            channel,
+            customer_group,
         }) => {
             // Validate currency
             Currency::from_code(currency).ok_or_else(|| format!("Invalid currency: {currency}"))?;
@@ -324,6 +472,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 quantity: *quantity,
                 date: date.clone(),
                 currency: currency.clone(),
+                price_list_context: price_list_context(channel, customer_group),
             };
 
             let request_bytes = get_best_offers_request.encode_to_vec();
@@ -396,6 +545,241 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 best_offers_response.sku_results.len()
             );
         }
+        Some(Commands::PriceListCreate {
+            id,
+            channel,
+            customer_group,
+            priority,
+            currency,
+        }) => {
+            let request = PriceListCreateRequest {
+                id: id.clone(),
+                channel: channel.clone(),
+                customer_group: customer_group.clone(),
+                priority: *priority,
+                currency: currency.clone(),
+            };
+
+            println!("Sending create_price_list request for ID: {id}");
+            let response = client
+                .request("offers.create_price_list", request.encode_to_vec().into())
+                .await?;
+
+            let create_response = PriceListCreateResponse::decode(&*response.payload)?;
+            println!("Create response: {create_response:?}");
+        }
+        Some(Commands::PriceListGetAll) => {
+            let response = client
+                .request(
+                    "offers.get_price_lists",
+                    PriceListsGetRequest {}.encode_to_vec().into(),
+                )
+                .await?;
+
+            let get_response = PriceListsGetResponse::decode(&*response.payload)?;
+            println!("📋 {} price list(s):", get_response.price_lists.len());
+            for list in &get_response.price_lists {
+                println!(
+                    "  {} (priority {}, {}): channel {}, customer group {}",
+                    list.id,
+                    list.priority,
+                    list.currency,
+                    list.channel.as_deref().unwrap_or("any"),
+                    list.customer_group.as_deref().unwrap_or("any")
+                );
+            }
+        }
+        Some(Commands::PriceListDelete { id }) => {
+            let request = PriceListDeleteRequest { id: id.clone() };
+
+            println!("Sending delete_price_list request for ID: {id}");
+            let response = client
+                .request("offers.delete_price_list", request.encode_to_vec().into())
+                .await?;
+
+            let delete_response = PriceListDeleteResponse::decode(&*response.payload)?;
+            println!("Delete response: {delete_response:?}");
+        }
+        Some(Commands::PromotionCreate {
+            id,
+            name,
+            value,
+            amount_off,
+            currency,
+            skus,
+            categories,
+            min_order_total,
+            coupon,
+            usage_limit,
+            exclusive,
+            priority,
+            days,
+        }) => {
+            let (target, target_ids) = match (skus, categories) {
+                (Some(skus), _) => (PromotionTarget::Skus, split_list(skus)),
+                (None, Some(categories)) => (PromotionTarget::Categories, split_list(categories)),
+                (None, None) => (PromotionTarget::Order, vec![]),
+            };
+            let now = chrono::Utc::now();
+            let request = PromotionCreateRequest {
+                id: id.clone(),
+                name: name.clone(),
+                discount_type: if *amount_off {
+                    DiscountType::AmountOff
+                } else {
+                    DiscountType::Percentage
+                }
+                .into(),
+                value: value.clone(),
+                currency: currency.clone(),
+                target: target.into(),
+                target_ids,
+                min_order_total: min_order_total.clone(),
+                coupon_code: coupon.clone(),
+                usage_limit: *usage_limit,
+                stacking: if *exclusive {
+                    StackingRule::Exclusive
+                } else {
+                    StackingRule::Stackable
+                }
+                .into(),
+                priority: *priority,
+                start_date: Some(Timestamp {
+                    seconds: now.timestamp(),
+                    nanos: 0,
+                }),
+                end_date: Some(Timestamp {
+                    seconds: (now + chrono::Duration::days(*days)).timestamp(),
+                    nanos: 0,
+                }),
+            };
+
+            println!("Sending create_promotion request for ID: {id}");
+            let response = client
+                .request("offers.create_promotion", request.encode_to_vec().into())
+                .await?;
+
+            let create_response = PromotionCreateResponse::decode(&*response.payload)?;
+            println!("Create response: {create_response:?}");
+        }
+        Some(Commands::PromotionGetAll) => {
+            let response = client
+                .request(
+                    "offers.get_promotions",
+                    PromotionsGetRequest {}.encode_to_vec().into(),
+                )
+                .await?;
+
+            let get_response = PromotionsGetResponse::decode(&*response.payload)?;
+            println!("🏷️  {} promotion(s):", get_response.promotions.len());
+            for promotion in &get_response.promotions {
+                println!(
+                    "  {} - {} ({:?} {} {}, {:?} {}): coupon {}, used {}/{}",
+                    promotion.id,
+                    promotion.name,
+                    promotion.discount_type(),
+                    promotion.value,
+                    promotion.currency,
+                    promotion.target(),
+                    promotion.target_ids.join(","),
+                    promotion.coupon_code.as_deref().unwrap_or("none"),
+                    promotion.usage_count,
+                    promotion
+                        .usage_limit
+                        .map_or("unlimited".to_string(), |limit| limit.to_string())
+                );
+            }
+        }
+        Some(Commands::PromotionDelete { id }) => {
+            let request = PromotionDeleteRequest { id: id.clone() };
+
+            println!("Sending delete_promotion request for ID: {id}");
+            let response = client
+                .request("offers.delete_promotion", request.encode_to_vec().into())
+                .await?;
+
+            let delete_response = PromotionDeleteResponse::decode(&*response.payload)?;
+            println!("Delete response: {delete_response:?}");
+        }
+        Some(Commands::EvaluatePromotions {
+            lines,
+            currency,
+            coupons,
+        }) => {
+            let lines = split_list(lines)
+                .iter()
+                .enumerate()
+                .map(|(i, line)| parse_promotion_line(i as i32 + 1, line))
+                .collect::<Result<Vec<_>, _>>()?;
+            let request = EvaluatePromotionsRequest {
+                lines,
+                currency: currency.clone(),
+                coupon_codes: coupons.as_deref().map(split_list).unwrap_or_default(),
+                date: None,
+            };
+
+            let response = client
+                .request("offers.evaluate_promotions", request.encode_to_vec().into())
+                .await?;
+
+            let evaluation = EvaluatePromotionsResponse::decode(&*response.payload)?;
+            if let Some(status) = &evaluation.status {
+                if status.code != 0 {
+                    println!("❌ Error: {} (code: {})", status.message, status.code);
+                    return Ok(());
+                }
+            }
+
+            for line in &evaluation.lines {
+                println!(
+                    "Line {} {}: {} - {}",
+                    line.line_num, line.sku, line.subtotal, line.discount_total
+                );
+                for discount in &line.discounts {
+                    println!(
+                        "    {} ({}): -{}",
+                        discount.name, discount.promotion_id, discount.amount
+                    );
+                }
+            }
+            for discount in &evaluation.order_discounts {
+                println!(
+                    "Order {} ({}): -{}",
+                    discount.name, discount.promotion_id, discount.amount
+                );
+            }
+            for rejected in &evaluation.rejected_coupons {
+                println!("⚠️ Coupon {}: {}", rejected.code, rejected.reason);
+            }
+            println!("Subtotal: {} {currency}", evaluation.subtotal);
+            println!("Discount total: {} {currency}", evaluation.discount_total);
+        }
+        Some(Commands::RedeemPromotions { order_id, ids }) => {
+            let request = RedeemPromotionsRequest {
+                promotion_ids: split_list(ids),
+                order_id: order_id.clone(),
+            };
+
+            let response = client
+                .request("offers.redeem_promotions", request.encode_to_vec().into())
+                .await?;
+
+            let redeem_response = RedeemPromotionsResponse::decode(&*response.payload)?;
+            println!("Redeem response: {redeem_response:?}");
+        }
+        Some(Commands::ReleasePromotions { order_id, ids }) => {
+            let request = ReleasePromotionsRequest {
+                order_id: order_id.clone(),
+                promotion_ids: ids.as_deref().map(split_list).unwrap_or_default(),
+            };
+
+            let response = client
+                .request("offers.release_promotions", request.encode_to_vec().into())
+                .await?;
+
+            let release_response = ReleasePromotionsResponse::decode(&*response.payload)?;
+            println!("Release response: {release_response:?}");
+        }
         Some(Commands::Import { file, dry_run }) => {
             println!("Importing offers from file: {file:?}");
 
@@ -1,9 +1,19 @@
-use chrono::NaiveDate;
-use log::{debug, error};
+use chrono::{DateTime, NaiveDate, Utc};
+use iso_currency::Currency;
+use log::{debug, error, warn};
+use rust_decimal::Decimal;
 use std::collections::HashMap;
 
-use crate::model::Offer;
+use crate::model::{
+    resolve_price_lists, DBError, Offer, PriceList, PriceListContext, DEFAULT_PRICE_LIST_ID,
+};
 use crate::persistence::offer_dao::OfferDao;
+use crate::persistence::price_list_dao::PriceListDao;
+use crate::persistence::promotion_dao::PromotionDao;
+use crate::promotions::{
+    evaluate_promotions as evaluate, normalize_category, normalize_coupon_code, to_decimal,
+    CartLine, DiscountType, Promotion, PromotionEvaluation, PromotionRedemption, PromotionTarget,
+};
 
 pub enum HandlerError {
     InternalError(String),
@@ -11,11 +21,31 @@ pub enum HandlerError {
 }
 
 pub async fn create_offer(
-    offer: Offer,
+    mut offer: Offer,
     offer_dao: &(dyn OfferDao + Sync + Send),
+    price_list_dao: &(dyn PriceListDao + Sync + Send),
 ) -> Result<Offer, HandlerError> {
     debug!("Before call to create_offer");
 
+    offer.price_list_id =
+        normalize_label(offer.price_list_id.take()).filter(|id| id != DEFAULT_PRICE_LIST_ID);
+    if let Some(price_list_id) = &offer.price_list_id {
+        match price_list_dao.get_price_list(price_list_id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return Err(HandlerError::ValidationError(format!(
+                    "Price list {price_list_id} does not exist"
+                )));
+            }
+            Err(e) => {
+                error!("Error getting price list: {e}");
+                return Err(HandlerError::InternalError(format!(
+                    "Failed to get price list: {e}"
+                )));
+            }
+        }
+    }
+
     let result = offer_dao.create_offer(offer).await;
     match result {
         Ok(offer) => Ok(offer),
@@ -69,7 +99,9 @@ pub async fn get_best_offer_price(
     quantity: i32,
     date: Option<String>,
     currency: String,
+    price_list_context: Option<PriceListContext>,
     offer_dao: &(dyn OfferDao + Send + Sync),
+    price_list_dao: &(dyn PriceListDao + Send + Sync),
 ) -> Result<Option<Offer>, HandlerError> {
     debug!("Before call to get_best_offer_price");
 
@@ -108,9 +140,12 @@ pub async fn get_best_offer_price(
 
     debug!("Validated parameters - sku: {sku}, quantity: {quantity}, date: {parsed_date}, currency: {currency}");
 
+    let price_list_ids =
+        resolve_price_list_ids(price_list_context, &currency, price_list_dao).await?;
+
     // Call DAO method
     let result = offer_dao
-        .find_best_offer_price(&sku, quantity, parsed_date, &currency)
+        .find_best_offer_price(&sku, quantity, parsed_date, &currency, &price_list_ids)
         .await;
 
     match result {
@@ -132,7 +167,9 @@ pub async fn get_best_offer_prices(
     quantity: i32,
     date: Option<String>,
     currency: String,
+    price_list_context: Option<PriceListContext>,
     offer_dao: &(dyn OfferDao + Send + Sync),
+    price_list_dao: &(dyn PriceListDao + Send + Sync),
 ) -> Result<HashMap<String, Option<Offer>>, HandlerError> {
     debug!(
         "Before call to get_best_offer_prices for {} SKUs",
@@ -190,9 +227,12 @@ pub async fn get_best_offer_prices(
 
     debug!("Validated parameters - {} SKUs, quantity: {quantity}, date: {parsed_date}, currency: {currency}", skus.len());
 
+    let price_list_ids =
+        resolve_price_list_ids(price_list_context, &currency, price_list_dao).await?;
+
     // Call DAO method
     let result = offer_dao
-        .find_best_offer_prices(&skus, quantity, parsed_date, &currency)
+        .find_best_offer_prices(&skus, quantity, parsed_date, &currency, &price_list_ids)
         .await;
 
     match result {
@@ -208,3 +248,510 @@ pub async fn get_best_offer_prices(
         }
     }
 }
+
+pub async fn create_price_list(
+    id: String,
+    channel: Option<String>,
+    customer_group: Option<String>,
+    priority: i32,
+    currency: String,
+    price_list_dao: &(dyn PriceListDao + Send + Sync),
+) -> Result<PriceList, HandlerError> {
+    debug!("Before call to create_price_list");
+
+    let id = normalize_label(Some(id))
+        .filter(|id| {
+            id.len() <= 64
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+        .ok_or_else(|| {
+            HandlerError::ValidationError(
+                "Price list id must be 1 to 64 letters, digits, - or _".to_string(),
+            )
+        })?;
+    if id == DEFAULT_PRICE_LIST_ID {
+        return Err(HandlerError::ValidationError(format!(
+            "Price list id {DEFAULT_PRICE_LIST_ID} is reserved"
+        )));
+    }
+    let currency = Currency::from_code(currency.trim())
+        .ok_or_else(|| HandlerError::ValidationError(format!("Invalid currency: {currency}")))?;
+
+    match price_list_dao.get_price_list(&id).await {
+        Ok(None) => {}
+        Ok(Some(_)) => {
+            return Err(HandlerError::ValidationError(format!(
+                "Price list {id} already exists"
+            )));
+        }
+        Err(e) => {
+            error!("Error getting price list: {e}");
+            return Err(HandlerError::InternalError(format!(
+                "Failed to get price list: {e}"
+            )));
+        }
+    }
+
+    let price_list = PriceList {
+        id,
+        channel: normalize_label(channel),
+        customer_group: normalize_label(customer_group),
+        priority,
+        currency,
+    };
+    price_list_dao
+        .create_price_list(price_list)
+        .await
+        .map_err(|e| {
+            error!("Error creating price list: {e}");
+            HandlerError::InternalError(format!("Failed to create price list: {e}"))
+        })
+}
+
+pub async fn get_price_lists(
+    price_list_dao: &(dyn PriceListDao + Send + Sync),
+) -> Result<Vec<PriceList>, HandlerError> {
+    price_list_dao.list_price_lists().await.map_err(|e| {
+        error!("Error listing price lists: {e}");
+        HandlerError::InternalError(format!("Failed to list price lists: {e}"))
+    })
+}
+
+/// Returns false when there is no such price list
+pub async fn delete_price_list(
+    id: String,
+    offer_dao: &(dyn OfferDao + Send + Sync),
+    price_list_dao: &(dyn PriceListDao + Send + Sync),
+) -> Result<bool, HandlerError> {
+    let Some(id) = normalize_label(Some(id)) else {
+        return Err(HandlerError::ValidationError(
+            "Price list id cannot be empty".to_string(),
+        ));
+    };
+
+    let offers = offer_dao
+        .count_offers_in_price_list(&id)
+        .await
+        .map_err(|e| {
+            error!("Error counting offers in price list: {e}");
+            HandlerError::InternalError(format!("Failed to count offers in price list: {e}"))
+        })?;
+    if offers > 0 {
+        return Err(HandlerError::ValidationError(format!(
+            "Price list {id} still has {offers} offer(s)"
+        )));
+    }
+
+    price_list_dao.delete_price_list(&id).await.map_err(|e| {
+        error!("Error deleting price list: {e}");
+        HandlerError::InternalError(format!("Failed to delete price list: {e}"))
+    })
+}
+
+pub async fn create_promotion(
+    mut promotion: Promotion,
+    promotion_dao: &(dyn PromotionDao + Send + Sync),
+) -> Result<Promotion, HandlerError> {
+    debug!("Before call to create_promotion");
+
+    promotion.id = promotion.id.trim().to_string();
+    let valid_id = (1..=64).contains(&promotion.id.len())
+        && promotion
+            .id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_id {
+        return Err(HandlerError::ValidationError(
+            "Promotion id must be 1 to 64 letters, digits, - or _".to_string(),
+        ));
+    }
+    promotion.name = promotion.name.trim().to_string();
+    if promotion.name.is_empty() {
+        return Err(HandlerError::ValidationError(
+            "Promotion name cannot be empty".to_string(),
+        ));
+    }
+
+    let value = to_decimal(&promotion.value).unwrap_or_default();
+    if value <= Decimal::ZERO {
+        return Err(HandlerError::ValidationError(
+            "Promotion value must be positive".to_string(),
+        ));
+    }
+    if promotion.discount_type == DiscountType::Percentage && value > Decimal::ONE_HUNDRED {
+        return Err(HandlerError::ValidationError(
+            "Percentage discounts cannot exceed 100".to_string(),
+        ));
+    }
+    if let Some(minimum) = &promotion.min_order_total {
+        if to_decimal(minimum).is_none_or(|minimum| minimum < Decimal::ZERO) {
+            return Err(HandlerError::ValidationError(
+                "Minimum order total cannot be negative".to_string(),
+            ));
+        }
+    }
+
+    promotion.target = match promotion.target {
+        PromotionTarget::Skus(skus) => {
+            PromotionTarget::Skus(target_ids(skus, |sku| sku.trim().to_string())?)
+        }
+        PromotionTarget::Categories(categories) => {
+            PromotionTarget::Categories(target_ids(categories, normalize_category)?)
+        }
+        PromotionTarget::Order => PromotionTarget::Order,
+    };
+    if promotion.usage_limit.is_some_and(|limit| limit <= 0) {
+        return Err(HandlerError::ValidationError(
+            "Usage limit must be positive".to_string(),
+        ));
+    }
+    if promotion.start_date >= promotion.end_date {
+        return Err(HandlerError::ValidationError(
+            "Start date must be before end date".to_string(),
+        ));
+    }
+    promotion.usage_count = 0;
+
+    let existing = promotion_dao
+        .get_promotion(&promotion.id)
+        .await
+        .map_err(|e| {
+            error!("Error getting promotion: {e}");
+            HandlerError::InternalError(format!("Failed to get promotion: {e}"))
+        })?;
+    if existing.is_some() {
+        return Err(HandlerError::ValidationError(format!(
+            "Promotion {} already exists",
+            promotion.id
+        )));
+    }
+
+    promotion.coupon_code = promotion
+        .coupon_code
+        .map(|code| normalize_coupon_code(&code))
+        .filter(|code| !code.is_empty());
+    if let Some(code) = &promotion.coupon_code {
+        let existing = promotion_dao
+            .get_promotion_by_coupon_code(code)
+            .await
+            .map_err(|e| {
+                error!("Error getting promotion by coupon code: {e}");
+                HandlerError::InternalError(format!("Failed to get promotion: {e}"))
+            })?;
+        if let Some(existing) = existing {
+            return Err(HandlerError::ValidationError(format!(
+                "Coupon code {code} is already used by promotion {}",
+                existing.id
+            )));
+        }
+    }
+
+    promotion_dao
+        .create_promotion(promotion)
+        .await
+        .map_err(|e| {
+            error!("Error creating promotion: {e}");
+            HandlerError::InternalError(format!("Failed to create promotion: {e}"))
+        })
+}
+
+pub async fn get_promotions(
+    promotion_dao: &(dyn PromotionDao + Send + Sync),
+) -> Result<Vec<Promotion>, HandlerError> {
+    promotion_dao.list_promotions().await.map_err(|e| {
+        error!("Error listing promotions: {e}");
+        HandlerError::InternalError(format!("Failed to list promotions: {e}"))
+    })
+}
+
+/// Returns false when there is no such promotion
+pub async fn delete_promotion(
+    id: String,
+    promotion_dao: &(dyn PromotionDao + Send + Sync),
+) -> Result<bool, HandlerError> {
+    let id = id.trim();
+    if id.is_empty() {
+        return Err(HandlerError::ValidationError(
+            "Promotion id cannot be empty".to_string(),
+        ));
+    }
+
+    promotion_dao.delete_promotion(id).await.map_err(|e| {
+        error!("Error deleting promotion: {e}");
+        HandlerError::InternalError(format!("Failed to delete promotion: {e}"))
+    })
+}
+
+pub async fn evaluate_promotions(
+    lines: Vec<CartLine>,
+    currency: String,
+    coupon_codes: Vec<String>,
+    date: Option<DateTime<Utc>>,
+    promotion_dao: &(dyn PromotionDao + Send + Sync),
+) -> Result<PromotionEvaluation, HandlerError> {
+    debug!(
+        "Before call to evaluate_promotions for {} lines",
+        lines.len()
+    );
+
+    if lines.is_empty() {
+        return Err(HandlerError::ValidationError(
+            "Lines cannot be empty".to_string(),
+        ));
+    }
+    if lines.len() > 100 {
+        return Err(HandlerError::ValidationError(format!(
+            "Too many lines provided. Maximum is 100, got {}",
+            lines.len()
+        )));
+    }
+    for line in &lines {
+        if line.sku.trim().is_empty() {
+            return Err(HandlerError::ValidationError(
+                "All lines must have a SKU".to_string(),
+            ));
+        }
+        if line.quantity <= 0 {
+            return Err(HandlerError::ValidationError(format!(
+                "Quantity must be positive on line {}",
+                line.line_num
+            )));
+        }
+        if line.unit_price < Decimal::ZERO {
+            return Err(HandlerError::ValidationError(format!(
+                "Unit price cannot be negative on line {}",
+                line.line_num
+            )));
+        }
+    }
+    let currency = Currency::from_code(currency.trim())
+        .ok_or_else(|| HandlerError::ValidationError(format!("Invalid currency: {currency}")))?;
+    let coupon_codes: Vec<String> = coupon_codes
+        .iter()
+        .map(|code| normalize_coupon_code(code))
+        .filter(|code| !code.is_empty())
+        .fold(Vec::new(), |mut codes, code| {
+            if !codes.contains(&code) {
+                codes.push(code);
+            }
+            codes
+        });
+    let now = date.unwrap_or_else(Utc::now);
+
+    let promotions = promotion_dao
+        .find_applicable_promotions(currency, &coupon_codes, now)
+        .await
+        .map_err(|e| {
+            error!("Error finding promotions: {e}");
+            HandlerError::InternalError(format!("Failed to find promotions: {e}"))
+        })?;
+    debug!("Evaluating {} candidate promotions", promotions.len());
+
+    Ok(evaluate(&promotions, &lines, currency, &coupon_codes, now))
+}
+
+/// Count one use of each promotion for the order. Promotions the order has
+/// already redeemed are skipped, so retrying changes nothing. When one cannot
+/// be redeemed, the uses already counted by this call are released again and
+/// nothing is redeemed.
+pub async fn redeem_promotions(
+    order_id: String,
+    promotion_ids: Vec<String>,
+    promotion_dao: &(dyn PromotionDao + Send + Sync),
+) -> Result<(), HandlerError> {
+    let order_id = order_id.trim();
+    if order_id.is_empty() {
+        return Err(HandlerError::ValidationError(
+            "Order id cannot be empty".to_string(),
+        ));
+    }
+    let promotion_ids = promotion_id_list(promotion_ids);
+    if promotion_ids.is_empty() {
+        return Err(HandlerError::ValidationError(
+            "Promotion ids cannot be empty".to_string(),
+        ));
+    }
+
+    let mut redeemed: Vec<&str> = Vec::new();
+    let mut failure = None;
+    for id in &promotion_ids {
+        // Recorded before counting, so that a retry never counts a use twice
+        match promotion_dao
+            .record_redemption(PromotionRedemption::new(id, order_id))
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                debug!("Promotion {id} was already redeemed for order {order_id}");
+                continue;
+            }
+            Err(e) => {
+                error!("Error recording promotion redemption: {e}");
+                failure = Some(HandlerError::InternalError(format!(
+                    "Failed to redeem promotion: {e}"
+                )));
+                break;
+            }
+        }
+        match promotion_dao.redeem_promotion(id).await {
+            Ok(true) => redeemed.push(id),
+            Ok(false) => {
+                failure = Some(HandlerError::ValidationError(format!(
+                    "Promotion {id} does not exist or has reached its usage limit"
+                )));
+            }
+            Err(e) => {
+                error!("Error redeeming promotion: {e}");
+                failure = Some(HandlerError::InternalError(format!(
+                    "Failed to redeem promotion: {e}"
+                )));
+            }
+        }
+        if failure.is_some() {
+            if let Err(e) = promotion_dao.delete_redemption(id, order_id).await {
+                warn!("Failed to delete redemption of promotion {id} for order {order_id}: {e}");
+            }
+            break;
+        }
+    }
+
+    let Some(failure) = failure else {
+        return Ok(());
+    };
+    for id in redeemed {
+        if let Err(e) = release_redemption(id, order_id, promotion_dao).await {
+            warn!("Failed to release promotion {id} after a failed redeem: {e}");
+        }
+    }
+    Err(failure)
+}
+
+/// Give back the uses an order redeemed, of the listed promotions or of all
+/// of them. Returns how many were released; releasing again releases none.
+pub async fn release_promotions(
+    order_id: String,
+    promotion_ids: Vec<String>,
+    promotion_dao: &(dyn PromotionDao + Send + Sync),
+) -> Result<usize, HandlerError> {
+    let order_id = order_id.trim();
+    if order_id.is_empty() {
+        return Err(HandlerError::ValidationError(
+            "Order id cannot be empty".to_string(),
+        ));
+    }
+    let mut promotion_ids = promotion_id_list(promotion_ids);
+    if promotion_ids.is_empty() {
+        promotion_ids = promotion_dao
+            .list_order_redemptions(order_id)
+            .await
+            .map_err(|e| {
+                error!("Error listing promotion redemptions: {e}");
+                HandlerError::InternalError(format!("Failed to list redemptions: {e}"))
+            })?
+            .into_iter()
+            .map(|redemption| redemption.promotion_id)
+            .collect();
+    }
+
+    let mut released = 0;
+    for id in &promotion_ids {
+        let was_redeemed = release_redemption(id, order_id, promotion_dao)
+            .await
+            .map_err(|e| {
+                error!("Error releasing promotion: {e}");
+                HandlerError::InternalError(format!("Failed to release promotion: {e}"))
+            })?;
+        if was_redeemed {
+            released += 1;
+        }
+    }
+    Ok(released)
+}
+
+// Only a redemption that is still recorded gives its use back, so a use is
+// never released twice
+async fn release_redemption(
+    promotion_id: &str,
+    order_id: &str,
+    promotion_dao: &(dyn PromotionDao + Send + Sync),
+) -> Result<bool, DBError> {
+    if !promotion_dao
+        .delete_redemption(promotion_id, order_id)
+        .await?
+    {
+        return Ok(false);
+    }
+    promotion_dao.release_promotion(promotion_id).await?;
+    Ok(true)
+}
+
+// Blanks and repeats are dropped
+fn promotion_id_list(promotion_ids: Vec<String>) -> Vec<String> {
+    promotion_ids
+        .into_iter()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .fold(Vec::new(), |mut ids, id| {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+            ids
+        })
+}
+
+// SKU and category promotions need at least one target; blanks and repeats are dropped
+fn target_ids(
+    ids: Vec<String>,
+    normalize: fn(&str) -> String,
+) -> Result<Vec<String>, HandlerError> {
+    let mut normalized: Vec<String> = Vec::new();
+    for id in ids.iter().map(|id| normalize(id)) {
+        if !id.is_empty() && !normalized.contains(&id) {
+            normalized.push(id);
+        }
+    }
+    if normalized.is_empty() {
+        return Err(HandlerError::ValidationError(
+            "SKU and category promotions need at least one target id".to_string(),
+        ));
+    }
+    Ok(normalized)
+}
+
+// Ids of the price lists that apply to the context, most preferred first.
+// Without a context only the default list applies.
+async fn resolve_price_list_ids(
+    price_list_context: Option<PriceListContext>,
+    currency: &str,
+    price_list_dao: &(dyn PriceListDao + Send + Sync),
+) -> Result<Vec<String>, HandlerError> {
+    let Some(context) = price_list_context else {
+        return Ok(vec![]);
+    };
+    let Some(currency) = Currency::from_code(currency) else {
+        return Err(HandlerError::ValidationError(format!(
+            "Invalid currency: {currency}"
+        )));
+    };
+    let context = PriceListContext {
+        channel: normalize_label(context.channel),
+        customer_group: normalize_label(context.customer_group),
+    };
+
+    let price_lists = price_list_dao.list_price_lists().await.map_err(|e| {
+        error!("Error listing price lists: {e}");
+        HandlerError::InternalError(format!("Failed to list price lists: {e}"))
+    })?;
+    let price_list_ids = resolve_price_lists(&price_lists, &context, currency);
+    debug!("Resolved price lists for {context:?}: {price_list_ids:?}");
+    Ok(price_list_ids)
+}
+
+// Ids, channels and customer groups compare case-insensitively; blank means unset
+fn normalize_label(label: Option<String>) -> Option<String> {
+    label
+        .map(|label| label.trim().to_lowercase())
+        .filter(|label| !label.is_empty())
+}
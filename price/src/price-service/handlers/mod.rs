@@ -16,7 +16,10 @@ use uuid::Uuid;
 use crate::{
     model,
     offer_messages::{self},
-    persistence::offer_dao::OfferDaoImpl,
+    persistence::{
+        offer_dao::OfferDaoImpl, price_list_dao::PriceListDaoImpl, promotion_dao::PromotionDaoImpl,
+    },
+    promotions,
 };
 
 pub mod handlers_inner;
@@ -29,17 +32,24 @@ pub struct Response {
     pub payload: Bytes,
 }
 
+/// The DAOs every handler can use
+pub struct AppState {
+    pub offer_dao: Arc<OfferDaoImpl>,
+    pub price_list_dao: Arc<PriceListDaoImpl>,
+    pub promotion_dao: Arc<PromotionDaoImpl>,
+}
+
 pub trait HandlerFn: Send + Sync {
-    fn call(&self, dao: Arc<OfferDaoImpl>, req: Request) -> BoxFuture<'static, Response>;
+    fn call(&self, app_state: Arc<AppState>, req: Request) -> BoxFuture<'static, Response>;
 }
 
 impl<T, F> HandlerFn for T
 where
-    T: Fn(Arc<OfferDaoImpl>, Request) -> F + Sync + Send + 'static,
+    T: Fn(Arc<AppState>, Request) -> F + Sync + Send + 'static,
     F: Future<Output = Response> + 'static + Send,
 {
-    fn call(&self, dao: Arc<OfferDaoImpl>, req: Request) -> BoxFuture<'static, Response> {
-        Box::pin(self(dao, req))
+    fn call(&self, app_state: Arc<AppState>, req: Request) -> BoxFuture<'static, Response> {
+        Box::pin(self(app_state, req))
     }
 }
 
@@ -72,11 +82,11 @@ impl Router {
         client: Client,
         routes: &RouteMap,
         path: String,
-        offer_dao: Arc<OfferDaoImpl>,
+        app_state: Arc<AppState>,
         request: Request,
     ) {
         let r = routes.get(&path).unwrap();
-        let response = r.call(offer_dao, request).await;
+        let response = r.call(app_state, request).await;
         client
             .publish(response.subject, response.payload)
             .await
@@ -84,7 +94,7 @@ impl Router {
     }
 }
 
-pub async fn create_offer(offer_dao: Arc<OfferDaoImpl>, offer_create_request: Request) -> Response {
+pub async fn create_offer(app_state: Arc<AppState>, offer_create_request: Request) -> Response {
     let offer = offer_messages::OfferCreateRequest::decode(offer_create_request.payload.clone());
     let mut offer_create_response = offer_messages::OfferCreateResponse {
         ..Default::default()
@@ -94,7 +104,12 @@ pub async fn create_offer(offer_dao: Arc<OfferDaoImpl>, offer_create_request: Re
             debug!("offer: {offer:?}");
             let model_offer = map_proto_offer_to_model_offer(offer);
 
-            let result = handlers_inner::create_offer(model_offer, offer_dao.as_ref()).await;
+            let result = handlers_inner::create_offer(
+                model_offer,
+                app_state.offer_dao.as_ref(),
+                app_state.price_list_dao.as_ref(),
+            )
+            .await;
             match result {
                 Ok(o) => {
                     offer_create_response.offer = Some(map_model_offer_to_proto_offer(o));
@@ -145,7 +160,7 @@ pub async fn create_offer(offer_dao: Arc<OfferDaoImpl>, offer_create_request: Re
     }
 }
 
-pub async fn get_offer(offer_dao: Arc<OfferDaoImpl>, offer_get_request: Request) -> Response {
+pub async fn get_offer(app_state: Arc<AppState>, offer_get_request: Request) -> Response {
     let request = offer_messages::OfferGetRequest::decode(offer_get_request.payload.clone());
     let mut offer_get_response = offer_messages::OfferGetResponse {
         offer: None,
@@ -157,7 +172,8 @@ pub async fn get_offer(offer_dao: Arc<OfferDaoImpl>, offer_get_request: Request)
     };
     match request {
         Ok(request) => {
-            let result = handlers_inner::get_offer(request.id.clone(), offer_dao.as_ref()).await;
+            let result =
+                handlers_inner::get_offer(request.id.clone(), app_state.offer_dao.as_ref()).await;
             match result {
                 Ok(Some(o)) => {
                     offer_get_response.offer = Some(map_model_offer_to_proto_offer(o));
@@ -216,12 +232,14 @@ pub async fn get_offer(offer_dao: Arc<OfferDaoImpl>, offer_get_request: Request)
     }
 }
 
-pub async fn delete_offer(offer_dao: Arc<OfferDaoImpl>, offer_delete_request: Request) -> Response {
+pub async fn delete_offer(app_state: Arc<AppState>, offer_delete_request: Request) -> Response {
     let request = offer_messages::OfferDeleteRequest::decode(offer_delete_request.payload.clone());
     let mut offer_delete_response = offer_messages::OfferDeleteResponse { status: None };
     match request {
         Ok(request) => {
-            let result = handlers_inner::delete_offer(request.id.clone(), offer_dao.as_ref()).await;
+            let result =
+                handlers_inner::delete_offer(request.id.clone(), app_state.offer_dao.as_ref())
+                    .await;
             match result {
                 Ok(_) => {
                     offer_delete_response.status = Some(offer_messages::Status {
@@ -271,7 +289,7 @@ pub async fn delete_offer(offer_dao: Arc<OfferDaoImpl>, offer_delete_request: Re
     }
 }
 
-pub async fn get_best_offer_price(offer_dao: Arc<OfferDaoImpl>, request: Request) -> Response {
+pub async fn get_best_offer_price(app_state: Arc<AppState>, request: Request) -> Response {
     let decoded_request = offer_messages::GetBestOfferPriceRequest::decode(request.payload.clone());
     let mut response = offer_messages::GetBestOfferPriceResponse {
         offer: None,
@@ -287,7 +305,10 @@ pub async fn get_best_offer_price(offer_dao: Arc<OfferDaoImpl>, request: Request
                 req.quantity,
                 req.date,
                 req.currency,
-                offer_dao.as_ref(),
+                req.price_list_context
+                    .map(map_proto_context_to_model_context),
+                app_state.offer_dao.as_ref(),
+                app_state.price_list_dao.as_ref(),
             )
             .await;
 
@@ -330,7 +351,7 @@ pub async fn get_best_offer_price(offer_dao: Arc<OfferDaoImpl>, request: Request
     }
 }
 
-pub async fn get_best_offer_prices(offer_dao: Arc<OfferDaoImpl>, request: Request) -> Response {
+pub async fn get_best_offer_prices(app_state: Arc<AppState>, request: Request) -> Response {
     let decoded_request =
         offer_messages::GetBestOfferPricesRequest::decode(request.payload.clone());
     let mut response = offer_messages::GetBestOfferPricesResponse {
@@ -347,7 +368,10 @@ pub async fn get_best_offer_prices(offer_dao: Arc<OfferDaoImpl>, request: Reques
                 req.quantity,
                 req.date,
                 req.currency,
-                offer_dao.as_ref(),
+                req.price_list_context
+                    .map(map_proto_context_to_model_context),
+                app_state.offer_dao.as_ref(),
+                app_state.price_list_dao.as_ref(),
             )
             .await;
 
@@ -410,6 +434,554 @@ pub async fn get_best_offer_prices(offer_dao: Arc<OfferDaoImpl>, request: Reques
     }
 }
 
+pub async fn create_price_list(app_state: Arc<AppState>, request: Request) -> Response {
+    let decoded_request = offer_messages::PriceListCreateRequest::decode(request.payload.clone());
+    let mut response = offer_messages::PriceListCreateResponse {
+        price_list: None,
+        status: None,
+    };
+
+    match decoded_request {
+        Ok(req) => {
+            debug!("CreatePriceList request: {req:?}");
+
+            let result = handlers_inner::create_price_list(
+                req.id,
+                req.channel,
+                req.customer_group,
+                req.priority,
+                req.currency,
+                app_state.price_list_dao.as_ref(),
+            )
+            .await;
+
+            match result {
+                Ok(price_list) => {
+                    response.price_list =
+                        Some(map_model_price_list_to_proto_price_list(price_list));
+                    response.status = Some(offer_messages::Status {
+                        code: offer_messages::Code::Ok.into(),
+                        message: "Price list created successfully".to_string(),
+                        details: vec![],
+                    });
+                }
+                Err(handlers_inner::HandlerError::ValidationError(msg)) => {
+                    error!("Validation error in create_price_list: {msg}");
+                    response.status = Some(offer_messages::Status {
+                        code: offer_messages::Code::InvalidArgument.into(),
+                        message: msg,
+                        details: vec![],
+                    });
+                }
+                Err(handlers_inner::HandlerError::InternalError(msg)) => {
+                    error!("Internal error in create_price_list: {msg}");
+                    response.status = Some(offer_messages::Status {
+                        code: offer_messages::Code::Internal.into(),
+                        message: "Internal server error".to_string(),
+                        details: vec![],
+                    });
+                }
+            }
+        }
+        Err(err) => {
+            error!("Error decoding PriceListCreateRequest: {err}");
+            response.status = Some(offer_messages::Status {
+                code: offer_messages::Code::InvalidArgument.into(),
+                message: "Invalid request format".to_string(),
+                details: vec![],
+            });
+        }
+    }
+
+    let mut buf = vec![];
+    response.encode(&mut buf).unwrap();
+    Response {
+        subject: request.reply.unwrap(),
+        payload: buf.into(),
+    }
+}
+
+pub async fn get_price_lists(app_state: Arc<AppState>, request: Request) -> Response {
+    let mut response = offer_messages::PriceListsGetResponse {
+        price_lists: vec![],
+        status: None,
+    };
+
+    match handlers_inner::get_price_lists(app_state.price_list_dao.as_ref()).await {
+        Ok(price_lists) => {
+            response.price_lists = price_lists
+                .into_iter()
+                .map(map_model_price_list_to_proto_price_list)
+                .collect();
+            response.status = Some(offer_messages::Status {
+                code: offer_messages::Code::Ok.into(),
+                message: "Success".to_string(),
+                details: vec![],
+            });
+        }
+        Err(handlers_inner::HandlerError::ValidationError(msg))
+        | Err(handlers_inner::HandlerError::InternalError(msg)) => {
+            error!("Error in get_price_lists: {msg}");
+            response.status = Some(offer_messages::Status {
+                code: offer_messages::Code::Internal.into(),
+                message: "Internal server error".to_string(),
+                details: vec![],
+            });
+        }
+    }
+
+    let mut buf = vec![];
+    response.encode(&mut buf).unwrap();
+    Response {
+        subject: request.reply.unwrap(),
+        payload: buf.into(),
+    }
+}
+
+pub async fn delete_price_list(app_state: Arc<AppState>, request: Request) -> Response {
+    let decoded_request = offer_messages::PriceListDeleteRequest::decode(request.payload.clone());
+    let mut response = offer_messages::PriceListDeleteResponse { status: None };
+
+    match decoded_request {
+        Ok(req) => {
+            let result = handlers_inner::delete_price_list(
+                req.id,
+                app_state.offer_dao.as_ref(),
+                app_state.price_list_dao.as_ref(),
+            )
+            .await;
+
+            let (code, message) = match result {
+                Ok(true) => (
+                    offer_messages::Code::Ok,
+                    "Price list deleted successfully".to_string(),
+                ),
+                Ok(false) => (
+                    offer_messages::Code::NotFound,
+                    "Price list not found".to_string(),
+                ),
+                Err(handlers_inner::HandlerError::ValidationError(msg)) => {
+                    error!("Validation error in delete_price_list: {msg}");
+                    (offer_messages::Code::InvalidArgument, msg)
+                }
+                Err(handlers_inner::HandlerError::InternalError(msg)) => {
+                    error!("Internal error in delete_price_list: {msg}");
+                    (
+                        offer_messages::Code::Internal,
+                        "Internal server error".to_string(),
+                    )
+                }
+            };
+            response.status = Some(offer_messages::Status {
+                code: code.into(),
+                message,
+                details: vec![],
+            });
+        }
+        Err(err) => {
+            error!("Error decoding PriceListDeleteRequest: {err}");
+            response.status = Some(offer_messages::Status {
+                code: offer_messages::Code::InvalidArgument.into(),
+                message: "Invalid request format".to_string(),
+                details: vec![],
+            });
+        }
+    }
+
+    let mut buf = vec![];
+    response.encode(&mut buf).unwrap();
+    Response {
+        subject: request.reply.unwrap(),
+        payload: buf.into(),
+    }
+}
+
+pub async fn create_promotion(app_state: Arc<AppState>, request: Request) -> Response {
+    let decoded_request = offer_messages::PromotionCreateRequest::decode(request.payload.clone());
+    let mut response = offer_messages::PromotionCreateResponse {
+        promotion: None,
+        status: None,
+    };
+
+    match decoded_request {
+        Ok(req) => {
+            debug!("CreatePromotion request: {req:?}");
+
+            let result = match map_proto_promotion_to_model_promotion(req) {
+                Ok(promotion) => {
+                    handlers_inner::create_promotion(promotion, app_state.promotion_dao.as_ref())
+                        .await
+                }
+                Err(msg) => Err(handlers_inner::HandlerError::ValidationError(msg)),
+            };
+
+            match result {
+                Ok(promotion) => {
+                    response.promotion = Some(map_model_promotion_to_proto_promotion(promotion));
+                    response.status = Some(offer_messages::Status {
+                        code: offer_messages::Code::Ok.into(),
+                        message: "Promotion created successfully".to_string(),
+                        details: vec![],
+                    });
+                }
+                Err(handlers_inner::HandlerError::ValidationError(msg)) => {
+                    error!("Validation error in create_promotion: {msg}");
+                    response.status = Some(offer_messages::Status {
+                        code: offer_messages::Code::InvalidArgument.into(),
+                        message: msg,
+                        details: vec![],
+                    });
+                }
+                Err(handlers_inner::HandlerError::InternalError(msg)) => {
+                    error!("Internal error in create_promotion: {msg}");
+                    response.status = Some(offer_messages::Status {
+                        code: offer_messages::Code::Internal.into(),
+                        message: "Internal server error".to_string(),
+                        details: vec![],
+                    });
+                }
+            }
+        }
+        Err(err) => {
+            error!("Error decoding PromotionCreateRequest: {err}");
+            response.status = Some(offer_messages::Status {
+                code: offer_messages::Code::InvalidArgument.into(),
+                message: "Invalid request format".to_string(),
+                details: vec![],
+            });
+        }
+    }
+
+    let mut buf = vec![];
+    response.encode(&mut buf).unwrap();
+    Response {
+        subject: request.reply.unwrap(),
+        payload: buf.into(),
+    }
+}
+
+pub async fn get_promotions(app_state: Arc<AppState>, request: Request) -> Response {
+    let mut response = offer_messages::PromotionsGetResponse {
+        promotions: vec![],
+        status: None,
+    };
+
+    match handlers_inner::get_promotions(app_state.promotion_dao.as_ref()).await {
+        Ok(promotions) => {
+            response.promotions = promotions
+                .into_iter()
+                .map(map_model_promotion_to_proto_promotion)
+                .collect();
+            response.status = Some(offer_messages::Status {
+                code: offer_messages::Code::Ok.into(),
+                message: "Success".to_string(),
+                details: vec![],
+            });
+        }
+        Err(handlers_inner::HandlerError::ValidationError(msg))
+        | Err(handlers_inner::HandlerError::InternalError(msg)) => {
+            error!("Error in get_promotions: {msg}");
+            response.status = Some(offer_messages::Status {
+                code: offer_messages::Code::Internal.into(),
+                message: "Internal server error".to_string(),
+                details: vec![],
+            });
+        }
+    }
+
+    let mut buf = vec![];
+    response.encode(&mut buf).unwrap();
+    Response {
+        subject: request.reply.unwrap(),
+        payload: buf.into(),
+    }
+}
+
+pub async fn delete_promotion(app_state: Arc<AppState>, request: Request) -> Response {
+    let decoded_request = offer_messages::PromotionDeleteRequest::decode(request.payload.clone());
+    let mut response = offer_messages::PromotionDeleteResponse { status: None };
+
+    match decoded_request {
+        Ok(req) => {
+            let result =
+                handlers_inner::delete_promotion(req.id, app_state.promotion_dao.as_ref()).await;
+
+            let (code, message) = match result {
+                Ok(true) => (
+                    offer_messages::Code::Ok,
+                    "Promotion deleted successfully".to_string(),
+                ),
+                Ok(false) => (
+                    offer_messages::Code::NotFound,
+                    "Promotion not found".to_string(),
+                ),
+                Err(handlers_inner::HandlerError::ValidationError(msg)) => {
+                    error!("Validation error in delete_promotion: {msg}");
+                    (offer_messages::Code::InvalidArgument, msg)
+                }
+                Err(handlers_inner::HandlerError::InternalError(msg)) => {
+                    error!("Internal error in delete_promotion: {msg}");
+                    (
+                        offer_messages::Code::Internal,
+                        "Internal server error".to_string(),
+                    )
+                }
+            };
+            response.status = Some(offer_messages::Status {
+                code: code.into(),
+                message,
+                details: vec![],
+            });
+        }
+        Err(err) => {
+            error!("Error decoding PromotionDeleteRequest: {err}");
+            response.status = Some(offer_messages::Status {
+                code: offer_messages::Code::InvalidArgument.into(),
+                message: "Invalid request format".to_string(),
+                details: vec![],
+            });
+        }
+    }
+
+    let mut buf = vec![];
+    response.encode(&mut buf).unwrap();
+    Response {
+        subject: request.reply.unwrap(),
+        payload: buf.into(),
+    }
+}
+
+pub async fn evaluate_promotions(app_state: Arc<AppState>, request: Request) -> Response {
+    let decoded_request =
+        offer_messages::EvaluatePromotionsRequest::decode(request.payload.clone());
+    let mut response = offer_messages::EvaluatePromotionsResponse {
+        lines: vec![],
+        order_discounts: vec![],
+        subtotal: String::new(),
+        discount_total: String::new(),
+        discount_detail: HashMap::new(),
+        rejected_coupons: vec![],
+        status: None,
+    };
+
+    match decoded_request {
+        Ok(req) => {
+            debug!("EvaluatePromotions request: {req:?}");
+
+            let lines: Result<Vec<promotions::CartLine>, String> = req
+                .lines
+                .into_iter()
+                .map(map_proto_line_to_cart_line)
+                .collect();
+            let result = match lines {
+                Ok(lines) => {
+                    handlers_inner::evaluate_promotions(
+                        lines,
+                        req.currency,
+                        req.coupon_codes,
+                        req.date.and_then(|date| {
+                            DateTime::from_timestamp(date.seconds, date.nanos as u32)
+                        }),
+                        app_state.promotion_dao.as_ref(),
+                    )
+                    .await
+                }
+                Err(msg) => Err(handlers_inner::HandlerError::ValidationError(msg)),
+            };
+
+            match result {
+                Ok(evaluation) => {
+                    response.subtotal = evaluation.subtotal().to_string();
+                    response.discount_total = evaluation.discount_total().to_string();
+                    response.discount_detail = evaluation
+                        .discount_detail()
+                        .into_iter()
+                        .map(|(id, amount)| (id, amount.to_string()))
+                        .collect();
+                    response.lines = evaluation
+                        .lines
+                        .into_iter()
+                        .map(|line| offer_messages::LineDiscounts {
+                            line_num: line.line_num,
+                            sku: line.sku.clone(),
+                            subtotal: line.subtotal.to_string(),
+                            discount_total: line.discount_total().to_string(),
+                            discounts: line
+                                .discounts
+                                .into_iter()
+                                .map(map_model_discount_to_proto_discount)
+                                .collect(),
+                        })
+                        .collect();
+                    response.order_discounts = evaluation
+                        .order_discounts
+                        .into_iter()
+                        .map(map_model_discount_to_proto_discount)
+                        .collect();
+                    response.rejected_coupons = evaluation
+                        .rejected_coupons
+                        .into_iter()
+                        .map(|rejected| offer_messages::RejectedCoupon {
+                            code: rejected.code,
+                            reason: rejected.reason,
+                        })
+                        .collect();
+                    response.status = Some(offer_messages::Status {
+                        code: offer_messages::Code::Ok.into(),
+                        message: "Success".to_string(),
+                        details: vec![],
+                    });
+                }
+                Err(handlers_inner::HandlerError::ValidationError(msg)) => {
+                    error!("Validation error in evaluate_promotions: {msg}");
+                    response.status = Some(offer_messages::Status {
+                        code: offer_messages::Code::InvalidArgument.into(),
+                        message: msg,
+                        details: vec![],
+                    });
+                }
+                Err(handlers_inner::HandlerError::InternalError(msg)) => {
+                    error!("Internal error in evaluate_promotions: {msg}");
+                    response.status = Some(offer_messages::Status {
+                        code: offer_messages::Code::Internal.into(),
+                        message: "Internal server error".to_string(),
+                        details: vec![],
+                    });
+                }
+            }
+        }
+        Err(err) => {
+            error!("Error decoding EvaluatePromotionsRequest: {err}");
+            response.status = Some(offer_messages::Status {
+                code: offer_messages::Code::InvalidArgument.into(),
+                message: "Invalid request format".to_string(),
+                details: vec![],
+            });
+        }
+    }
+
+    let mut buf = vec![];
+    response.encode(&mut buf).unwrap();
+    Response {
+        subject: request.reply.unwrap(),
+        payload: buf.into(),
+    }
+}
+
+pub async fn redeem_promotions(app_state: Arc<AppState>, request: Request) -> Response {
+    let decoded_request = offer_messages::RedeemPromotionsRequest::decode(request.payload.clone());
+    let mut response = offer_messages::RedeemPromotionsResponse { status: None };
+
+    match decoded_request {
+        Ok(req) => {
+            let result = handlers_inner::redeem_promotions(
+                req.order_id,
+                req.promotion_ids,
+                app_state.promotion_dao.as_ref(),
+            )
+            .await;
+
+            let (code, message) = match result {
+                Ok(()) => (
+                    offer_messages::Code::Ok,
+                    "Promotions redeemed successfully".to_string(),
+                ),
+                Err(handlers_inner::HandlerError::ValidationError(msg)) => {
+                    error!("Validation error in redeem_promotions: {msg}");
+                    (offer_messages::Code::FailedPrecondition, msg)
+                }
+                Err(handlers_inner::HandlerError::InternalError(msg)) => {
+                    error!("Internal error in redeem_promotions: {msg}");
+                    (
+                        offer_messages::Code::Internal,
+                        "Internal server error".to_string(),
+                    )
+                }
+            };
+            response.status = Some(offer_messages::Status {
+                code: code.into(),
+                message,
+                details: vec![],
+            });
+        }
+        Err(err) => {
+            error!("Error decoding RedeemPromotionsRequest: {err}");
+            response.status = Some(offer_messages::Status {
+                code: offer_messages::Code::InvalidArgument.into(),
+                message: "Invalid request format".to_string(),
+                details: vec![],
+            });
+        }
+    }
+
+    let mut buf = vec![];
+    response.encode(&mut buf).unwrap();
+    Response {
+        subject: request.reply.unwrap(),
+        payload: buf.into(),
+    }
+}
+
+pub async fn release_promotions(app_state: Arc<AppState>, request: Request) -> Response {
+    let decoded_request = offer_messages::ReleasePromotionsRequest::decode(request.payload.clone());
+    let mut response = offer_messages::ReleasePromotionsResponse {
+        released_count: 0,
+        status: None,
+    };
+
+    match decoded_request {
+        Ok(req) => {
+            let result = handlers_inner::release_promotions(
+                req.order_id,
+                req.promotion_ids,
+                app_state.promotion_dao.as_ref(),
+            )
+            .await;
+
+            let (code, message) = match result {
+                Ok(released) => {
+                    response.released_count = released as i32;
+                    (
+                        offer_messages::Code::Ok,
+                        "Promotions released successfully".to_string(),
+                    )
+                }
+                Err(handlers_inner::HandlerError::ValidationError(msg)) => {
+                    error!("Validation error in release_promotions: {msg}");
+                    (offer_messages::Code::InvalidArgument, msg)
+                }
+                Err(handlers_inner::HandlerError::InternalError(msg)) => {
+                    error!("Internal error in release_promotions: {msg}");
+                    (
+                        offer_messages::Code::Internal,
+                        "Internal server error".to_string(),
+                    )
+                }
+            };
+            response.status = Some(offer_messages::Status {
+                code: code.into(),
+                message,
+                details: vec![],
+            });
+        }
+        Err(err) => {
+            error!("Error decoding ReleasePromotionsRequest: {err}");
+            response.status = Some(offer_messages::Status {
+                code: offer_messages::Code::InvalidArgument.into(),
+                message: "Invalid request format".to_string(),
+                details: vec![],
+            });
+        }
+    }
+
+    let mut buf = vec![];
+    response.encode(&mut buf).unwrap();
+    Response {
+        subject: request.reply.unwrap(),
+        payload: buf.into(),
+    }
+}
+
 fn map_proto_offer_to_model_offer(offer: offer_messages::OfferCreateRequest) -> model::Offer {
     model::Offer {
         id: Some(Uuid::new_v4().to_string()),
@@ -436,6 +1008,157 @@ fn map_proto_offer_to_model_offer(offer: offer_messages::OfferCreateRequest) ->
                 currency: Currency::from_code(op.currency.as_str()).expect("currency is not valid"),
             })
             .collect(),
+        price_list_id: offer.price_list_id,
+    }
+}
+
+fn map_proto_context_to_model_context(
+    context: offer_messages::PriceListContext,
+) -> model::PriceListContext {
+    model::PriceListContext {
+        channel: context.channel,
+        customer_group: context.customer_group,
+    }
+}
+
+fn map_model_price_list_to_proto_price_list(
+    price_list: model::PriceList,
+) -> offer_messages::PriceList {
+    offer_messages::PriceList {
+        id: price_list.id,
+        channel: price_list.channel,
+        customer_group: price_list.customer_group,
+        priority: price_list.priority,
+        currency: price_list.currency.code().to_string(),
+    }
+}
+
+// Amounts and dates in the request are checked here; the rest is validated by
+// handlers_inner::create_promotion
+fn map_proto_promotion_to_model_promotion(
+    promotion: offer_messages::PromotionCreateRequest,
+) -> Result<promotions::Promotion, String> {
+    let amount = |value: &str, field: &str| {
+        Decimal128::from_str(value.trim())
+            .ok()
+            .filter(|amount| promotions::to_decimal(amount).is_some())
+            .ok_or_else(|| format!("{field} is not a valid amount: {value}"))
+    };
+    let date = |timestamp: Option<Timestamp>, field: &str| {
+        timestamp
+            .and_then(|t| DateTime::from_timestamp(t.seconds, t.nanos as u32))
+            .ok_or_else(|| format!("{field} is required"))
+    };
+
+    let discount_type = match promotion.discount_type() {
+        offer_messages::DiscountType::Percentage => promotions::DiscountType::Percentage,
+        offer_messages::DiscountType::AmountOff => promotions::DiscountType::AmountOff,
+    };
+    let target = match promotion.target() {
+        offer_messages::PromotionTarget::Skus => {
+            promotions::PromotionTarget::Skus(promotion.target_ids.clone())
+        }
+        offer_messages::PromotionTarget::Categories => {
+            promotions::PromotionTarget::Categories(promotion.target_ids.clone())
+        }
+        offer_messages::PromotionTarget::Order => promotions::PromotionTarget::Order,
+    };
+    let stacking = match promotion.stacking() {
+        offer_messages::StackingRule::Stackable => promotions::StackingRule::Stackable,
+        offer_messages::StackingRule::Exclusive => promotions::StackingRule::Exclusive,
+    };
+
+    Ok(promotions::Promotion {
+        id: promotion.id,
+        name: promotion.name,
+        discount_type,
+        value: amount(&promotion.value, "Value")?,
+        currency: Currency::from_code(promotion.currency.trim())
+            .ok_or_else(|| format!("Invalid currency: {}", promotion.currency))?,
+        target,
+        min_order_total: promotion
+            .min_order_total
+            .as_deref()
+            .map(|minimum| amount(minimum, "Minimum order total"))
+            .transpose()?,
+        coupon_code: promotion.coupon_code,
+        usage_limit: promotion.usage_limit,
+        usage_count: 0,
+        stacking,
+        priority: promotion.priority,
+        start_date: date(promotion.start_date, "Start date")?,
+        end_date: date(promotion.end_date, "End date")?,
+    })
+}
+
+fn map_model_promotion_to_proto_promotion(
+    promotion: promotions::Promotion,
+) -> offer_messages::Promotion {
+    let (target, target_ids) = match promotion.target {
+        promotions::PromotionTarget::Skus(skus) => (offer_messages::PromotionTarget::Skus, skus),
+        promotions::PromotionTarget::Categories(categories) => {
+            (offer_messages::PromotionTarget::Categories, categories)
+        }
+        promotions::PromotionTarget::Order => (offer_messages::PromotionTarget::Order, vec![]),
+    };
+    let discount_type = match promotion.discount_type {
+        promotions::DiscountType::Percentage => offer_messages::DiscountType::Percentage,
+        promotions::DiscountType::AmountOff => offer_messages::DiscountType::AmountOff,
+    };
+    let stacking = match promotion.stacking {
+        promotions::StackingRule::Stackable => offer_messages::StackingRule::Stackable,
+        promotions::StackingRule::Exclusive => offer_messages::StackingRule::Exclusive,
+    };
+    let timestamp = |date: DateTime<Utc>| Timestamp {
+        seconds: date.timestamp(),
+        nanos: date.timestamp_subsec_nanos() as i32,
+    };
+
+    offer_messages::Promotion {
+        id: promotion.id,
+        name: promotion.name,
+        discount_type: discount_type.into(),
+        value: promotion.value.to_string(),
+        currency: promotion.currency.code().to_string(),
+        target: target.into(),
+        target_ids,
+        min_order_total: promotion.min_order_total.map(|minimum| minimum.to_string()),
+        coupon_code: promotion.coupon_code,
+        usage_limit: promotion.usage_limit,
+        usage_count: promotion.usage_count,
+        stacking: stacking.into(),
+        priority: promotion.priority,
+        start_date: Some(timestamp(promotion.start_date)),
+        end_date: Some(timestamp(promotion.end_date)),
+    }
+}
+
+fn map_proto_line_to_cart_line(
+    line: offer_messages::PromotionLine,
+) -> Result<promotions::CartLine, String> {
+    let unit_price = rust_decimal::Decimal::from_str(line.unit_price.trim()).map_err(|_| {
+        format!(
+            "Unit price on line {} is not a valid amount: {}",
+            line.line_num, line.unit_price
+        )
+    })?;
+    Ok(promotions::CartLine {
+        line_num: line.line_num,
+        sku: line.sku.trim().to_string(),
+        quantity: line.quantity,
+        unit_price,
+        categories: line.categories,
+    })
+}
+
+fn map_model_discount_to_proto_discount(
+    discount: promotions::AppliedDiscount,
+) -> offer_messages::AppliedDiscount {
+    offer_messages::AppliedDiscount {
+        promotion_id: discount.promotion_id,
+        name: discount.name,
+        coupon_code: discount.coupon_code,
+        amount: discount.amount.to_string(),
     }
 }
 
@@ -462,6 +1185,7 @@ fn map_model_offer_to_proto_offer(offer: model::Offer) -> offer_messages::Offer
                 currency: op.currency.to_string(),
             })
             .collect(),
+        price_list_id: offer.price_list_id,
     }
 }
 
@@ -488,6 +1212,7 @@ mod tests {
                 price: "10.5".to_string(),
                 currency: "USD".to_string(),
             }],
+            price_list_id: None,
         };
         let model_offer = map_proto_offer_to_model_offer(offer_create_request);
         println!("model_offer: {model_offer:?}");
@@ -506,6 +1231,7 @@ mod tests {
                 price: Decimal128::from_str("10.5").unwrap(),
                 currency: Currency::USD,
             }],
+            price_list_id: None,
         };
         let proto_offer = map_model_offer_to_proto_offer(model_offer);
         println!("proto_offer: {proto_offer:?}");
@@ -519,6 +1245,7 @@ mod tests {
             quantity: 5,
             date: None, // Optional field
             currency: "USD".to_string(),
+            price_list_context: None,
         };
 
         assert_eq!(request.sku, "TEST-SKU-001");
@@ -1,12 +1,17 @@
 mod handlers;
 mod model;
 mod persistence;
+mod promotions;
 mod validation;
 
 use handlers::{
-    create_offer, delete_offer, get_best_offer_price, get_best_offer_prices, get_offer, Router,
+    create_offer, create_price_list, create_promotion, delete_offer, delete_price_list,
+    delete_promotion, evaluate_promotions, get_best_offer_price, get_best_offer_prices, get_offer,
+    get_price_lists, get_promotions, redeem_promotions, release_promotions, AppState, Router,
+};
+use persistence::{
+    offer_dao::OfferDaoImpl, price_list_dao::PriceListDaoImpl, promotion_dao::PromotionDaoImpl,
 };
-use persistence::offer_dao::OfferDaoImpl;
 use std::{env, error::Error, sync::Arc};
 
 use log::{debug, error, info};
@@ -17,8 +22,9 @@ use validation::validate_price_dependencies;
 
 use bson::doc;
 use futures::StreamExt;
-use model::Offer;
-use mongodb::{Client, Collection, IndexModel};
+use model::{Offer, PriceList};
+use mongodb::{options::IndexOptions, Client, Collection, IndexModel};
+use promotions::{Promotion, PromotionRedemption};
 
 // Import common module for generated proto code
 mod common {
@@ -110,6 +116,10 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
         IndexModel::builder()
             .keys(doc! { "offer_prices.currency": 1, "offer_prices.price": 1 })
             .build(),
+        // Index for offers by price list
+        IndexModel::builder()
+            .keys(doc! { "price_list_id": 1 })
+            .build(),
     ];
     info!("🔍 Creating {} price indexes...", indexes.len());
     match price_coll.create_indexes(indexes).await {
@@ -118,7 +128,7 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
                 "✅ Created {} price indexes successfully",
                 result.index_names.len()
             );
-            debug!("Price indexes: sku, sku+dates, quantity_ranges, currency, currency+price");
+            debug!("Price indexes: sku, sku+dates, quantity_ranges, currency, currency+price, price_list");
         }
         Err(e) => {
             error!("❌ Failed to create price indexes: {e}");
@@ -126,10 +136,66 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
         }
     }
 
+    let price_list_coll: Collection<PriceList> = database.collection("price_lists");
+
+    info!("📦 Setting up promotions collection...");
+    let promotion_coll: Collection<Promotion> = database.collection("promotions");
+    let promotion_indexes = vec![
+        // Coupon codes are unique; automatic promotions have none
+        IndexModel::builder()
+            .keys(doc! { "coupon_code": 1 })
+            .options(
+                IndexOptions::builder()
+                    .unique(true)
+                    .partial_filter_expression(doc! { "coupon_code": { "$type": "string" } })
+                    .build(),
+            )
+            .build(),
+        // Active automatic promotions per currency
+        IndexModel::builder()
+            .keys(doc! { "currency": 1, "start_date": 1, "end_date": 1 })
+            .build(),
+    ];
+    match promotion_coll.create_indexes(promotion_indexes).await {
+        Ok(result) => {
+            info!(
+                "✅ Created {} promotion indexes successfully",
+                result.index_names.len()
+            );
+        }
+        Err(e) => {
+            error!("❌ Failed to create promotion indexes: {e}");
+            return Err(e.into());
+        }
+    }
+
+    let redemption_coll: Collection<PromotionRedemption> =
+        database.collection("promotion_redemptions");
+    let redemption_indexes = vec![
+        // Redemptions of an order, to release them
+        IndexModel::builder().keys(doc! { "order_id": 1 }).build(),
+    ];
+    match redemption_coll.create_indexes(redemption_indexes).await {
+        Ok(result) => {
+            info!(
+                "✅ Created {} promotion redemption indexes successfully",
+                result.index_names.len()
+            );
+        }
+        Err(e) => {
+            error!("❌ Failed to create promotion redemption indexes: {e}");
+            return Err(e.into());
+        }
+    }
+
     // Phase 2.1: DAO Setup Logging
     info!("🏗️  Initializing data access objects...");
-    let offer_dao = Arc::new(OfferDaoImpl::new(price_coll));
-    debug!("✅ Offer DAO initialized");
+    let app_state = Arc::new(AppState {
+        offer_dao: Arc::new(OfferDaoImpl::new(price_coll)),
+        price_list_dao: Arc::new(PriceListDaoImpl::new(price_list_coll)),
+        promotion_dao: Arc::new(PromotionDaoImpl::new(promotion_coll, redemption_coll)),
+    });
+    debug!("✅ Offer, price list and promotion DAOs initialized");
 
     // Phase 2.2: Router Setup Logging
     info!("🛣️  Setting up message router...");
@@ -154,11 +220,47 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
         .add_route(
             "get_best_offer_prices".to_owned(),
             Box::new(|d, m| Box::pin(get_best_offer_prices(d, m))),
+        )
+        .add_route(
+            "create_price_list".to_owned(),
+            Box::new(|d, m| Box::pin(create_price_list(d, m))),
+        )
+        .add_route(
+            "get_price_lists".to_owned(),
+            Box::new(|d, m| Box::pin(get_price_lists(d, m))),
+        )
+        .add_route(
+            "delete_price_list".to_owned(),
+            Box::new(|d, m| Box::pin(delete_price_list(d, m))),
+        )
+        .add_route(
+            "create_promotion".to_owned(),
+            Box::new(|d, m| Box::pin(create_promotion(d, m))),
+        )
+        .add_route(
+            "get_promotions".to_owned(),
+            Box::new(|d, m| Box::pin(get_promotions(d, m))),
+        )
+        .add_route(
+            "delete_promotion".to_owned(),
+            Box::new(|d, m| Box::pin(delete_promotion(d, m))),
+        )
+        .add_route(
+            "evaluate_promotions".to_owned(),
+            Box::new(|d, m| Box::pin(evaluate_promotions(d, m))),
+        )
+        .add_route(
+            "redeem_promotions".to_owned(),
+            Box::new(|d, m| Box::pin(redeem_promotions(d, m))),
+        )
+        .add_route(
+            "release_promotions".to_owned(),
+            Box::new(|d, m| Box::pin(release_promotions(d, m))),
         );
 
-    let route_count = 5;
+    let route_count = router.route_map.len();
     info!("✅ Configured {route_count} price routes");
-    debug!("Price routes: create_offer, get_offer, delete_offer, get_best_offer_price, get_best_offer_prices");
+    debug!("Price routes: create_offer, get_offer, delete_offer, get_best_offer_price, get_best_offer_prices, create_price_list, get_price_lists, delete_price_list, create_promotion, get_promotions, delete_promotion, evaluate_promotions, redeem_promotions, release_promotions");
 
     // Phase 1.4: NATS Connection Logging
     info!("🔗 Connecting to NATS server: {nats_url}");
@@ -209,7 +311,7 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
     // Phase 3.2: Request Processing Logging
     requests
         .for_each_concurrent(25, |request| {
-            let od = offer_dao.clone();
+            let state = app_state.clone();
             let routes = routes.clone();
             let client_clone = nats_client.clone();
 
@@ -231,7 +333,7 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
 
                 let result = if let Some(handler) = routes.get(&operation) {
                     // Note: Price service handlers return Response objects that need to be published
-                    let response = handler.call(od, request).await;
+                    let response = handler.call(state, request).await;
                     // Publish response manually here since we don't have the Router::route method integrated
                     if let Err(e) = client_clone
                         .publish(response.subject, response.payload)
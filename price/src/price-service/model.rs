@@ -16,6 +16,9 @@ pub struct Offer {
     pub min_quantity: i32,
     pub max_quantity: Option<i32>,
     pub offer_prices: Vec<OfferPrice>,
+    // None puts the offer in the default price list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_list_id: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -31,6 +34,57 @@ impl Offer {
     }
 }
 
+/// Id reserved for the default price list, which holds every offer not
+/// assigned to a list and applies to every lookup
+pub const DEFAULT_PRICE_LIST_ID: &str = "default";
+
+/// A set of offers for one channel and/or customer group, e.g. B2B prices.
+/// Lists without a channel or customer group apply to every channel or group.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PriceList {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub channel: Option<String>,
+    pub customer_group: Option<String>,
+    pub priority: i32, // Higher wins when several lists apply
+    pub currency: iso_currency::Currency,
+}
+
+/// Who is asking for a price
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PriceListContext {
+    pub channel: Option<String>,
+    pub customer_group: Option<String>,
+}
+
+impl PriceList {
+    pub fn applies_to(&self, context: &PriceListContext, currency: iso_currency::Currency) -> bool {
+        let matches = |required: &Option<String>, given: &Option<String>| match required {
+            Some(required) => given.as_deref() == Some(required.as_str()),
+            None => true,
+        };
+        self.currency == currency
+            && matches(&self.channel, &context.channel)
+            && matches(&self.customer_group, &context.customer_group)
+    }
+}
+
+/// Ids of the lists that apply to `context` in `currency`, highest priority
+/// first. Ties go to the lower id so lookups are stable. The default list is
+/// not included; it is always the last fallback.
+pub fn resolve_price_lists(
+    price_lists: &[PriceList],
+    context: &PriceListContext,
+    currency: iso_currency::Currency,
+) -> Vec<String> {
+    let mut applicable: Vec<&PriceList> = price_lists
+        .iter()
+        .filter(|list| list.applies_to(context, currency))
+        .collect();
+    applicable.sort_by(|a, b| b.priority.cmp(&a.priority).then_with(|| a.id.cmp(&b.id)));
+    applicable.into_iter().map(|list| list.id.clone()).collect()
+}
+
 #[derive(Default)]
 #[allow(dead_code)]
 pub struct OfferBuilder {
@@ -80,6 +134,7 @@ impl OfferBuilder {
             min_quantity: self.min_quantity,
             max_quantity: self.max_quantity,
             offer_prices: self.offer_prices.clone(),
+            price_list_id: None,
         }
     }
 }
@@ -125,4 +180,46 @@ mod tests {
         .build();
         println!("Offer: {offer:?}");
     }
+
+    fn price_list(
+        id: &str,
+        channel: Option<&str>,
+        group: Option<&str>,
+        priority: i32,
+    ) -> PriceList {
+        PriceList {
+            id: id.to_string(),
+            channel: channel.map(String::from),
+            customer_group: group.map(String::from),
+            priority,
+            currency: iso_currency::Currency::USD,
+        }
+    }
+
+    #[test]
+    fn price_lists_resolve_by_context_and_priority() {
+        let lists = vec![
+            price_list("b2b", Some("b2b"), None, 10),
+            price_list("b2b-gold", Some("b2b"), Some("gold"), 20),
+            price_list("retail-sale", Some("web"), None, 5),
+            price_list("everyone", None, None, 1),
+        ];
+        let gold = PriceListContext {
+            channel: Some("b2b".to_string()),
+            customer_group: Some("gold".to_string()),
+        };
+        assert_eq!(
+            resolve_price_lists(&lists, &gold, iso_currency::Currency::USD),
+            vec!["b2b-gold", "b2b", "everyone"]
+        );
+        assert_eq!(
+            resolve_price_lists(
+                &lists,
+                &PriceListContext::default(),
+                iso_currency::Currency::USD
+            ),
+            vec!["everyone"]
+        );
+        assert!(resolve_price_lists(&lists, &gold, iso_currency::Currency::EUR).is_empty());
+    }
 }
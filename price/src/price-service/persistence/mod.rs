@@ -1 +1,3 @@
 pub mod offer_dao;
+pub mod price_list_dao;
+pub mod promotion_dao;
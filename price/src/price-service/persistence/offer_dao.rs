@@ -19,6 +19,7 @@ pub trait OfferDao {
         quantity: i32,
         date: NaiveDate,
         currency: &str,
+        price_list_ids: &[String],
     ) -> Result<Option<Offer>, DBError>;
     async fn find_best_offer_prices(
         &self,
@@ -26,7 +27,38 @@ pub trait OfferDao {
         quantity: i32,
         date: NaiveDate,
         currency: &str,
+        price_list_ids: &[String],
     ) -> Result<HashMap<String, Option<Offer>>, DBError>;
+    async fn count_offers_in_price_list(&self, price_list_id: &str) -> Result<u64, DBError>;
+}
+
+// Offers in any of the given lists or in the default list, which is where
+// offers without a price_list_id belong
+fn price_list_filter(price_list_ids: &[String]) -> bson::Document {
+    doc! { "$in": ranked_price_lists(price_list_ids) }
+}
+
+// The given lists in order of preference, then the default list
+fn ranked_price_lists(price_list_ids: &[String]) -> Vec<bson::Bson> {
+    let mut ids: Vec<bson::Bson> = price_list_ids
+        .iter()
+        .map(|id| bson::Bson::String(id.clone()))
+        .collect();
+    ids.push(bson::Bson::Null);
+    ids
+}
+
+// Rank each matching offer by its list's position in `price_list_ids`, the
+// default list last, and sort by rank then price so the best offer comes first
+fn ranked_offer_stages(query: bson::Document, price_list_ids: &[String]) -> Vec<bson::Document> {
+    vec![
+        doc! { "$match": query },
+        doc! { "$addFields": { "list_rank": { "$indexOfArray": [
+            ranked_price_lists(price_list_ids),
+            { "$ifNull": ["$price_list_id", null] },
+        ] } } },
+        doc! { "$sort": { "sku": 1, "list_rank": 1, "offer_prices.price": 1 } },
+    ]
 }
 
 pub struct OfferDaoImpl {
@@ -96,17 +128,19 @@ impl OfferDao for OfferDaoImpl {
         Ok(())
     }
 
-    // Find the best offer price for given parameters
+    // Find the best offer price for given parameters, preferring the price
+    // lists in the order given over the default list
     async fn find_best_offer_price(
         &self,
         sku: &str,
         quantity: i32,
         date: NaiveDate,
         currency: &str,
+        price_list_ids: &[String],
     ) -> Result<Option<Offer>, DBError> {
         debug!(
-            "Finding best offer price for sku: {}, quantity: {}, date: {}, currency: {}",
-            sku, quantity, date, currency
+            "Finding best offer price for sku: {}, quantity: {}, date: {}, currency: {}, price lists: {:?}",
+            sku, quantity, date, currency, price_list_ids
         );
 
         // Convert NaiveDate to BSON DateTime for MongoDB query
@@ -124,43 +158,37 @@ impl OfferDao for OfferDaoImpl {
             "max_quantity": { "$gte": quantity },
             "start_date": { "$lte": bson_date },
             "end_date": { "$gte": bson_date },
-            "offer_prices": { "$elemMatch": { "currency": currency } }
+            "offer_prices": { "$elemMatch": { "currency": currency } },
+            "price_list_id": price_list_filter(price_list_ids)
         };
 
         debug!("MongoDB query: {query:?}");
 
-        // Execute the query with sort and limit using find() instead of find_one()
-        // because find_one() doesn't support sorting
-        let find_options = mongodb::options::FindOptions::builder()
-            .sort(doc! { "offer_prices.price": 1 })
-            .limit(1)
-            .build();
+        let mut pipeline = ranked_offer_stages(query, price_list_ids);
+        pipeline.push(doc! { "$limit": 1 });
+        pipeline.push(doc! { "$unset": "list_rank" });
 
         let mut cursor = self
             .collection
-            .find(query)
-            .with_options(find_options)
+            .aggregate(pipeline)
+            .with_type::<Offer>()
             .await
             .map_err(|error| {
                 error!("DB error in find_best_offer_price: {error:?}");
                 DBError::Other(Box::new(error))
             })?;
 
-        // Get the first result from the cursor
-        use futures::stream::StreamExt;
-        let find_result = cursor.next().await;
+        use futures::stream::TryStreamExt;
+        let best = cursor.try_next().await.map_err(|error| {
+            error!("DB cursor error in find_best_offer_price: {error:?}");
+            DBError::Other(Box::new(error))
+        })?;
 
-        match find_result {
-            Some(result) => match result {
-                Ok(offer) => {
-                    debug!("Found best offer: {offer:?}");
-                    Ok(Some(offer))
-                }
-                Err(error) => {
-                    error!("DB cursor error in find_best_offer_price: {error:?}");
-                    Err(DBError::Other(Box::new(error)))
-                }
-            },
+        match best {
+            Some(offer) => {
+                debug!("Found best offer: {offer:?}");
+                Ok(Some(offer))
+            }
             None => {
                 debug!(
                     "No offer found for sku: {sku}, quantity: {quantity}, date: {date}, currency: {currency}"
@@ -176,13 +204,15 @@ impl OfferDao for OfferDaoImpl {
         quantity: i32,
         date: NaiveDate,
         currency: &str,
+        price_list_ids: &[String],
     ) -> Result<HashMap<String, Option<Offer>>, DBError> {
         debug!(
-            "Finding best offer prices for {} SKUs, quantity: {}, date: {}, currency: {}",
+            "Finding best offer prices for {} SKUs, quantity: {}, date: {}, currency: {}, price lists: {:?}",
             skus.len(),
             quantity,
             date,
-            currency
+            currency,
+            price_list_ids
         );
 
         // Convert NaiveDate to BSON DateTime for MongoDB query
@@ -200,44 +230,34 @@ impl OfferDao for OfferDaoImpl {
             "max_quantity": { "$gte": quantity },
             "start_date": { "$lte": bson_date },
             "end_date": { "$gte": bson_date },
-            "offer_prices": { "$elemMatch": { "currency": currency } }
+            "offer_prices": { "$elemMatch": { "currency": currency } },
+            "price_list_id": price_list_filter(price_list_ids)
         };
 
         debug!("MongoDB multi-SKU query: {query:?}");
 
-        // Execute the query with sort to get best prices (lowest first)
-        let find_options = mongodb::options::FindOptions::builder()
-            .sort(doc! { "sku": 1, "offer_prices.price": 1 })
-            .build();
+        // Keep the best offer of each SKU
+        let mut pipeline = ranked_offer_stages(query, price_list_ids);
+        pipeline.push(doc! { "$group": { "_id": "$sku", "offer": { "$first": "$$ROOT" } } });
+        pipeline.push(doc! { "$replaceRoot": { "newRoot": "$offer" } });
+        pipeline.push(doc! { "$unset": "list_rank" });
 
         let mut cursor = self
             .collection
-            .find(query)
-            .with_options(find_options)
+            .aggregate(pipeline)
+            .with_type::<Offer>()
             .await
             .map_err(|error| {
                 error!("DB error in find_best_offer_prices: {error:?}");
                 DBError::Other(Box::new(error))
             })?;
 
-        // Initialize results map with all SKUs set to None
-        let mut results: HashMap<String, Option<Offer>> = HashMap::new();
-        for sku in skus {
-            results.insert(sku.clone(), None);
-        }
-
-        // Process cursor results and keep only the best (first) offer for each SKU
+        let mut best_offers: HashMap<String, Offer> = HashMap::new();
         use futures::stream::StreamExt;
         while let Some(result) = cursor.next().await {
             match result {
                 Ok(offer) => {
-                    let sku = &offer.sku;
-                    // Only update if we haven't found an offer for this SKU yet
-                    // (since results are sorted by price, first one is the best)
-                    if results.get(sku).unwrap().is_none() {
-                        debug!("Found best offer for SKU {sku}: {:?}", offer.id);
-                        results.insert(sku.clone(), Some(offer));
-                    }
+                    best_offers.insert(offer.sku.clone(), offer);
                 }
                 Err(error) => {
                     error!("DB cursor error in find_best_offer_prices: {error:?}");
@@ -246,6 +266,16 @@ impl OfferDao for OfferDaoImpl {
             }
         }
 
+        // Every requested SKU gets an entry, None when it has no offer
+        let mut results: HashMap<String, Option<Offer>> = HashMap::new();
+        for sku in skus {
+            let best = best_offers.remove(sku);
+            if let Some(offer) = &best {
+                debug!("Found best offer for SKU {sku}: {:?}", offer.id);
+            }
+            results.insert(sku.clone(), best);
+        }
+
         debug!(
             "Found best offers for {} out of {} SKUs",
             results.values().filter(|v| v.is_some()).count(),
@@ -253,4 +283,14 @@ impl OfferDao for OfferDaoImpl {
         );
         Ok(results)
     }
+
+    async fn count_offers_in_price_list(&self, price_list_id: &str) -> Result<u64, DBError> {
+        self.collection
+            .count_documents(doc! { "price_list_id": price_list_id })
+            .await
+            .map_err(|error| {
+                error!("DB error counting offers in price list: {error:?}");
+                DBError::Other(Box::new(error))
+            })
+    }
 }
@@ -0,0 +1,89 @@
+use log::{debug, error, info};
+
+use async_trait::async_trait;
+use bson::doc;
+use futures::stream::TryStreamExt;
+use mongodb::Collection;
+
+use crate::model::{DBError, PriceList};
+
+#[async_trait]
+pub trait PriceListDao {
+    async fn create_price_list(&self, price_list: PriceList) -> Result<PriceList, DBError>;
+    async fn get_price_list(&self, price_list_id: &str) -> Result<Option<PriceList>, DBError>;
+    async fn list_price_lists(&self) -> Result<Vec<PriceList>, DBError>;
+    async fn delete_price_list(&self, price_list_id: &str) -> Result<bool, DBError>;
+}
+
+pub struct PriceListDaoImpl {
+    collection: Collection<PriceList>,
+}
+
+impl PriceListDaoImpl {
+    pub fn new(collection: Collection<PriceList>) -> Self {
+        PriceListDaoImpl { collection }
+    }
+}
+
+#[async_trait]
+impl PriceListDao for PriceListDaoImpl {
+    async fn create_price_list(&self, price_list: PriceList) -> Result<PriceList, DBError> {
+        let insert_result = self
+            .collection
+            .insert_one(&price_list)
+            .await
+            .map_err(|error| {
+                error!("Error on price list insert: {error:?}");
+                DBError::Other(Box::new(error))
+            })?;
+
+        info!("Inserted price list result: {insert_result:?}");
+        Ok(price_list)
+    }
+
+    async fn get_price_list(&self, price_list_id: &str) -> Result<Option<PriceList>, DBError> {
+        debug!("before call to find_one - price_list_id: {price_list_id:?}");
+        self.collection
+            .find_one(doc! {"_id": price_list_id})
+            .await
+            .map_err(|error| {
+                error!("DB error: {error:?}");
+                DBError::Other(Box::new(error))
+            })
+    }
+
+    // Price lists are few, so lookups resolve them in memory
+    async fn list_price_lists(&self) -> Result<Vec<PriceList>, DBError> {
+        let find_options = mongodb::options::FindOptions::builder()
+            .sort(doc! { "priority": -1, "_id": 1 })
+            .build();
+        let cursor = self
+            .collection
+            .find(doc! {})
+            .with_options(find_options)
+            .await
+            .map_err(|error| {
+                error!("DB error listing price lists: {error:?}");
+                DBError::Other(Box::new(error))
+            })?;
+
+        cursor.try_collect().await.map_err(|error| {
+            error!("DB cursor error listing price lists: {error:?}");
+            DBError::Other(Box::new(error))
+        })
+    }
+
+    async fn delete_price_list(&self, price_list_id: &str) -> Result<bool, DBError> {
+        let delete_result = self
+            .collection
+            .delete_one(doc! {"_id": price_list_id})
+            .await
+            .map_err(|error| {
+                error!("Error on price list delete: {error:?}");
+                DBError::Other(Box::new(error))
+            })?;
+
+        info!("Deleted price list result: {delete_result:?}");
+        Ok(delete_result.deleted_count > 0)
+    }
+}
@@ -0,0 +1,257 @@
+use log::{debug, error, info};
+
+use async_trait::async_trait;
+use bson::doc;
+use chrono::{DateTime, Utc};
+use futures::stream::TryStreamExt;
+use iso_currency::Currency;
+use mongodb::Collection;
+
+use crate::model::DBError;
+use crate::promotions::{Promotion, PromotionRedemption};
+
+#[async_trait]
+pub trait PromotionDao {
+    async fn create_promotion(&self, promotion: Promotion) -> Result<Promotion, DBError>;
+    async fn get_promotion(&self, promotion_id: &str) -> Result<Option<Promotion>, DBError>;
+    async fn get_promotion_by_coupon_code(
+        &self,
+        coupon_code: &str,
+    ) -> Result<Option<Promotion>, DBError>;
+    async fn list_promotions(&self) -> Result<Vec<Promotion>, DBError>;
+    async fn delete_promotion(&self, promotion_id: &str) -> Result<bool, DBError>;
+    async fn find_applicable_promotions(
+        &self,
+        currency: Currency,
+        coupon_codes: &[String],
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Promotion>, DBError>;
+    async fn redeem_promotion(&self, promotion_id: &str) -> Result<bool, DBError>;
+    async fn release_promotion(&self, promotion_id: &str) -> Result<(), DBError>;
+    /// Returns false when the order already has a redemption of the promotion
+    async fn record_redemption(&self, redemption: PromotionRedemption) -> Result<bool, DBError>;
+    /// Returns false when there was no such redemption
+    async fn delete_redemption(&self, promotion_id: &str, order_id: &str) -> Result<bool, DBError>;
+    async fn list_order_redemptions(
+        &self,
+        order_id: &str,
+    ) -> Result<Vec<PromotionRedemption>, DBError>;
+}
+
+pub struct PromotionDaoImpl {
+    collection: Collection<Promotion>,
+    redemption_collection: Collection<PromotionRedemption>,
+}
+
+impl PromotionDaoImpl {
+    pub fn new(
+        collection: Collection<Promotion>,
+        redemption_collection: Collection<PromotionRedemption>,
+    ) -> Self {
+        PromotionDaoImpl {
+            collection,
+            redemption_collection,
+        }
+    }
+}
+
+#[async_trait]
+impl PromotionDao for PromotionDaoImpl {
+    async fn create_promotion(&self, promotion: Promotion) -> Result<Promotion, DBError> {
+        let insert_result = self
+            .collection
+            .insert_one(&promotion)
+            .await
+            .map_err(|error| {
+                error!("Error on promotion insert: {error:?}");
+                DBError::Other(Box::new(error))
+            })?;
+
+        info!("Inserted promotion result: {insert_result:?}");
+        Ok(promotion)
+    }
+
+    async fn get_promotion(&self, promotion_id: &str) -> Result<Option<Promotion>, DBError> {
+        debug!("before call to find_one - promotion_id: {promotion_id:?}");
+        self.collection
+            .find_one(doc! {"_id": promotion_id})
+            .await
+            .map_err(|error| {
+                error!("DB error: {error:?}");
+                DBError::Other(Box::new(error))
+            })
+    }
+
+    async fn get_promotion_by_coupon_code(
+        &self,
+        coupon_code: &str,
+    ) -> Result<Option<Promotion>, DBError> {
+        debug!("before call to find_one - coupon_code: {coupon_code:?}");
+        self.collection
+            .find_one(doc! {"coupon_code": coupon_code})
+            .await
+            .map_err(|error| {
+                error!("DB error: {error:?}");
+                DBError::Other(Box::new(error))
+            })
+    }
+
+    async fn list_promotions(&self) -> Result<Vec<Promotion>, DBError> {
+        let find_options = mongodb::options::FindOptions::builder()
+            .sort(doc! { "priority": -1, "_id": 1 })
+            .build();
+        let cursor = self
+            .collection
+            .find(doc! {})
+            .with_options(find_options)
+            .await
+            .map_err(|error| {
+                error!("DB error listing promotions: {error:?}");
+                DBError::Other(Box::new(error))
+            })?;
+
+        cursor.try_collect().await.map_err(|error| {
+            error!("DB cursor error listing promotions: {error:?}");
+            DBError::Other(Box::new(error))
+        })
+    }
+
+    async fn delete_promotion(&self, promotion_id: &str) -> Result<bool, DBError> {
+        let delete_result = self
+            .collection
+            .delete_one(doc! {"_id": promotion_id})
+            .await
+            .map_err(|error| {
+                error!("Error on promotion delete: {error:?}");
+                DBError::Other(Box::new(error))
+            })?;
+
+        info!("Deleted promotion result: {delete_result:?}");
+        Ok(delete_result.deleted_count > 0)
+    }
+
+    // Automatic promotions that are running now, plus any promotion behind one
+    // of the coupon codes whatever its state, so the engine can say why a
+    // coupon does not apply
+    async fn find_applicable_promotions(
+        &self,
+        currency: Currency,
+        coupon_codes: &[String],
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Promotion>, DBError> {
+        let now = bson::DateTime::from_chrono(now);
+        let filter = doc! {
+            "$or": [
+                {
+                    "coupon_code": null,
+                    "currency": currency.code(),
+                    "start_date": { "$lte": now },
+                    "end_date": { "$gte": now },
+                },
+                { "coupon_code": { "$in": coupon_codes } },
+            ]
+        };
+        debug!("Finding applicable promotions with filter: {filter:?}");
+
+        let cursor = self.collection.find(filter).await.map_err(|error| {
+            error!("DB error finding promotions: {error:?}");
+            DBError::Other(Box::new(error))
+        })?;
+
+        cursor.try_collect().await.map_err(|error| {
+            error!("DB cursor error finding promotions: {error:?}");
+            DBError::Other(Box::new(error))
+        })
+    }
+
+    // Counts one use, unless that would go over the usage limit
+    async fn redeem_promotion(&self, promotion_id: &str) -> Result<bool, DBError> {
+        let update_result = self
+            .collection
+            .update_one(
+                doc! {
+                    "_id": promotion_id,
+                    "$or": [
+                        { "usage_limit": null },
+                        { "$expr": { "$lt": ["$usage_count", "$usage_limit"] } },
+                    ]
+                },
+                doc! { "$inc": { "usage_count": 1 } },
+            )
+            .await
+            .map_err(|error| {
+                error!("Error on promotion redeem: {error:?}");
+                DBError::Other(Box::new(error))
+            })?;
+
+        debug!("Redeemed promotion result: {update_result:?}");
+        Ok(update_result.modified_count > 0)
+    }
+
+    async fn release_promotion(&self, promotion_id: &str) -> Result<(), DBError> {
+        self.collection
+            .update_one(
+                doc! { "_id": promotion_id, "usage_count": { "$gt": 0 } },
+                doc! { "$inc": { "usage_count": -1 } },
+            )
+            .await
+            .map_err(|error| {
+                error!("Error on promotion release: {error:?}");
+                DBError::Other(Box::new(error))
+            })?;
+        Ok(())
+    }
+
+    async fn record_redemption(&self, redemption: PromotionRedemption) -> Result<bool, DBError> {
+        let update_result = self
+            .redemption_collection
+            .update_one(
+                doc! { "_id": &redemption.id },
+                doc! { "$setOnInsert": {
+                    "promotion_id": &redemption.promotion_id,
+                    "order_id": &redemption.order_id,
+                    "redeemed_at": bson::DateTime::from_chrono(redemption.redeemed_at),
+                } },
+            )
+            .upsert(true)
+            .await
+            .map_err(|error| {
+                error!("Error on promotion redemption insert: {error:?}");
+                DBError::Other(Box::new(error))
+            })?;
+
+        debug!("Recorded promotion redemption result: {update_result:?}");
+        Ok(update_result.upserted_id.is_some())
+    }
+
+    async fn delete_redemption(&self, promotion_id: &str, order_id: &str) -> Result<bool, DBError> {
+        let delete_result = self
+            .redemption_collection
+            .delete_one(doc! { "_id": format!("{promotion_id}:{order_id}") })
+            .await
+            .map_err(|error| {
+                error!("Error on promotion redemption delete: {error:?}");
+                DBError::Other(Box::new(error))
+            })?;
+        Ok(delete_result.deleted_count > 0)
+    }
+
+    async fn list_order_redemptions(
+        &self,
+        order_id: &str,
+    ) -> Result<Vec<PromotionRedemption>, DBError> {
+        let cursor = self
+            .redemption_collection
+            .find(doc! { "order_id": order_id })
+            .await
+            .map_err(|error| {
+                error!("DB error listing promotion redemptions: {error:?}");
+                DBError::Other(Box::new(error))
+            })?;
+
+        cursor.try_collect().await.map_err(|error| {
+            error!("DB cursor error listing promotion redemptions: {error:?}");
+            DBError::Other(Box::new(error))
+        })
+    }
+}
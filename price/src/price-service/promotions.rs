@@ -0,0 +1,556 @@
+use bson::Decimal128;
+use chrono::{DateTime, Utc};
+use iso_currency::Currency;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscountType {
+    Percentage, // value is a percentage, 0 to 100
+    AmountOff,  // value is an amount in the promotion's currency
+}
+
+/// What a promotion discounts. SKU and category promotions discount the
+/// matching lines; amount-off takes the value off each unit. Order
+/// promotions discount what is left of the order after line discounts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", content = "ids")]
+pub enum PromotionTarget {
+    Skus(Vec<String>),
+    Categories(Vec<String>),
+    Order,
+}
+
+/// Stackable promotions combine with each other. An exclusive promotion only
+/// applies alone, and only when it saves more than the stackable ones together.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackingRule {
+    Stackable,
+    Exclusive,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Promotion {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub name: String,
+    pub discount_type: DiscountType,
+    pub value: Decimal128,
+    pub currency: Currency,
+    pub target: PromotionTarget,
+    pub min_order_total: Option<Decimal128>, // Order subtotal needed before it applies
+    pub coupon_code: Option<String>,         // None applies it automatically
+    pub usage_limit: Option<i32>,
+    pub usage_count: i32,
+    pub stacking: StackingRule,
+    pub priority: i32, // Higher applies first
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub start_date: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub end_date: DateTime<Utc>,
+}
+
+impl Promotion {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.start_date <= now && now <= self.end_date
+    }
+
+    pub fn usage_exhausted(&self) -> bool {
+        self.usage_limit
+            .is_some_and(|limit| self.usage_count >= limit)
+    }
+
+    fn applies_to_line(&self, line: &CartLine) -> bool {
+        match &self.target {
+            PromotionTarget::Skus(skus) => skus.contains(&line.sku),
+            PromotionTarget::Categories(categories) => line
+                .categories
+                .iter()
+                .any(|category| categories.contains(&normalize_category(category))),
+            PromotionTarget::Order => false,
+        }
+    }
+}
+
+/// One order's use of a promotion. Recording it is what makes redeeming
+/// for the same order again change nothing.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PromotionRedemption {
+    #[serde(rename = "_id")]
+    pub id: String, // "<promotion id>:<order id>"
+    pub promotion_id: String,
+    pub order_id: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub redeemed_at: DateTime<Utc>,
+}
+
+impl PromotionRedemption {
+    pub fn new(promotion_id: &str, order_id: &str) -> Self {
+        PromotionRedemption {
+            id: format!("{promotion_id}:{order_id}"),
+            promotion_id: promotion_id.to_string(),
+            order_id: order_id.to_string(),
+            redeemed_at: Utc::now(),
+        }
+    }
+}
+
+/// One order line as promotions see it
+#[derive(Debug, Clone, PartialEq)]
+pub struct CartLine {
+    pub line_num: i32,
+    pub sku: String,
+    pub quantity: i32,
+    pub unit_price: Decimal,
+    pub categories: Vec<String>,
+}
+
+impl CartLine {
+    pub fn subtotal(&self) -> Decimal {
+        self.unit_price * Decimal::from(self.quantity)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppliedDiscount {
+    pub promotion_id: String,
+    pub name: String,
+    pub coupon_code: Option<String>,
+    pub amount: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineDiscounts {
+    pub line_num: i32,
+    pub sku: String,
+    pub subtotal: Decimal,
+    pub discounts: Vec<AppliedDiscount>,
+}
+
+impl LineDiscounts {
+    pub fn discount_total(&self) -> Decimal {
+        self.discounts.iter().map(|d| d.amount).sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RejectedCoupon {
+    pub code: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromotionEvaluation {
+    pub lines: Vec<LineDiscounts>,
+    pub order_discounts: Vec<AppliedDiscount>,
+    pub rejected_coupons: Vec<RejectedCoupon>,
+}
+
+impl PromotionEvaluation {
+    pub fn subtotal(&self) -> Decimal {
+        self.lines.iter().map(|line| line.subtotal).sum()
+    }
+
+    pub fn discount_total(&self) -> Decimal {
+        self.lines
+            .iter()
+            .map(LineDiscounts::discount_total)
+            .chain(self.order_discounts.iter().map(|d| d.amount))
+            .sum()
+    }
+
+    /// Discount per promotion id across lines and order, the shape of an
+    /// order's discount_detail
+    pub fn discount_detail(&self) -> HashMap<String, Decimal> {
+        let mut detail: HashMap<String, Decimal> = HashMap::new();
+        for discount in self
+            .lines
+            .iter()
+            .flat_map(|line| &line.discounts)
+            .chain(&self.order_discounts)
+        {
+            *detail.entry(discount.promotion_id.clone()).or_default() += discount.amount;
+        }
+        detail
+    }
+
+    /// Ids of the promotions that gave a discount, for redeeming once the order is placed
+    pub fn applied_promotion_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.discount_detail().into_keys().collect();
+        ids.sort();
+        ids
+    }
+}
+
+/// Coupon codes compare case-insensitively
+pub fn normalize_coupon_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+pub fn normalize_category(category: &str) -> String {
+    category.trim().to_lowercase()
+}
+
+pub fn to_decimal(value: &Decimal128) -> Option<Decimal> {
+    let value = value.to_string();
+    Decimal::from_str(&value)
+        .or_else(|_| Decimal::from_scientific(&value))
+        .ok()
+}
+
+/// Work out the discounts `promotions` give the order at `now`. Promotions
+/// that are inactive, in another currency, used up, below their minimum
+/// order total or behind a coupon that was not given are skipped; coupons
+/// given but not applied come back with the reason.
+pub fn evaluate_promotions(
+    promotions: &[Promotion],
+    lines: &[CartLine],
+    currency: Currency,
+    coupon_codes: &[String],
+    now: DateTime<Utc>,
+) -> PromotionEvaluation {
+    let codes: Vec<String> = coupon_codes
+        .iter()
+        .map(|code| normalize_coupon_code(code))
+        .filter(|code| !code.is_empty())
+        .collect();
+    let subtotal: Decimal = lines.iter().map(CartLine::subtotal).sum();
+    let mut rejected: Vec<RejectedCoupon> = Vec::new();
+
+    let mut candidates: Vec<&Promotion> = Vec::new();
+    for promotion in promotions {
+        let coupon = promotion.coupon_code.as_deref();
+        if coupon.is_some_and(|code| !codes.iter().any(|c| c == code)) {
+            continue;
+        }
+        let reason = if !promotion.is_active(now) {
+            Some("Coupon is not active".to_string())
+        } else if promotion.currency != currency {
+            Some(format!(
+                "Coupon is only valid in {}",
+                promotion.currency.code()
+            ))
+        } else if promotion.usage_exhausted() {
+            Some("Coupon usage limit reached".to_string())
+        } else {
+            match promotion.min_order_total.as_ref().and_then(to_decimal) {
+                Some(minimum) if subtotal < minimum => Some(format!(
+                    "Order total must be at least {minimum} {}",
+                    currency.code()
+                )),
+                _ => None,
+            }
+        };
+        match (reason, coupon) {
+            (None, _) => candidates.push(promotion),
+            (Some(reason), Some(code)) => rejected.push(RejectedCoupon {
+                code: code.to_string(),
+                reason,
+            }),
+            (Some(_), None) => {}
+        }
+    }
+    candidates.sort_by(|a, b| b.priority.cmp(&a.priority).then_with(|| a.id.cmp(&b.id)));
+
+    // The stackable promotions together, or the best exclusive one alone
+    let stackable: Vec<&Promotion> = candidates
+        .iter()
+        .copied()
+        .filter(|p| p.stacking == StackingRule::Stackable)
+        .collect();
+    let mut best = apply_promotions(&stackable, lines, currency);
+    for exclusive in candidates
+        .iter()
+        .filter(|p| p.stacking == StackingRule::Exclusive)
+    {
+        let alone = apply_promotions(&[exclusive], lines, currency);
+        if alone.discount_total() > best.discount_total() {
+            best = alone;
+        }
+    }
+
+    let applied = best.applied_promotion_ids();
+    for code in &codes {
+        let matching: Vec<&&Promotion> = candidates
+            .iter()
+            .filter(|p| p.coupon_code.as_deref() == Some(code.as_str()))
+            .collect();
+        let reason = if matching.is_empty() {
+            if rejected.iter().any(|r| r.code == *code) {
+                continue;
+            }
+            "Unknown coupon code".to_string()
+        } else if matching.iter().any(|p| applied.contains(&p.id)) {
+            continue;
+        } else if matching
+            .iter()
+            .all(|p| apply_promotions(&[p], lines, currency).discount_total() == Decimal::ZERO)
+        {
+            "No items in the order qualify".to_string()
+        } else {
+            "A better promotion that cannot be combined was applied".to_string()
+        };
+        rejected.push(RejectedCoupon {
+            code: code.clone(),
+            reason,
+        });
+    }
+
+    best.rejected_coupons = rejected;
+    best
+}
+
+// Apply promotions in order: line discounts first, each on what the line
+// has left, then order discounts on what the order has left
+fn apply_promotions(
+    promotions: &[&Promotion],
+    lines: &[CartLine],
+    currency: Currency,
+) -> PromotionEvaluation {
+    let round = |amount: Decimal| {
+        amount.round_dp_with_strategy(
+            u32::from(currency.exponent().unwrap_or(2)),
+            RoundingStrategy::MidpointAwayFromZero,
+        )
+    };
+    let applied = |promotion: &Promotion, amount: Decimal| AppliedDiscount {
+        promotion_id: promotion.id.clone(),
+        name: promotion.name.clone(),
+        coupon_code: promotion.coupon_code.clone(),
+        amount,
+    };
+
+    let mut results: Vec<LineDiscounts> = lines
+        .iter()
+        .map(|line| LineDiscounts {
+            line_num: line.line_num,
+            sku: line.sku.clone(),
+            subtotal: line.subtotal(),
+            discounts: vec![],
+        })
+        .collect();
+
+    for promotion in promotions
+        .iter()
+        .filter(|p| p.target != PromotionTarget::Order)
+    {
+        let Some(value) = to_decimal(&promotion.value) else {
+            continue;
+        };
+        for (line, result) in lines.iter().zip(results.iter_mut()) {
+            if !promotion.applies_to_line(line) {
+                continue;
+            }
+            let remaining = result.subtotal - result.discount_total();
+            let amount = match promotion.discount_type {
+                DiscountType::Percentage => remaining * value / Decimal::ONE_HUNDRED,
+                DiscountType::AmountOff => value * Decimal::from(line.quantity),
+            };
+            let amount = round(amount.min(remaining));
+            if amount > Decimal::ZERO {
+                result.discounts.push(applied(promotion, amount));
+            }
+        }
+    }
+
+    let mut order_discounts: Vec<AppliedDiscount> = Vec::new();
+    for promotion in promotions
+        .iter()
+        .filter(|p| p.target == PromotionTarget::Order)
+    {
+        let Some(value) = to_decimal(&promotion.value) else {
+            continue;
+        };
+        let remaining = results
+            .iter()
+            .map(|r| r.subtotal - r.discount_total())
+            .sum::<Decimal>()
+            - order_discounts.iter().map(|d| d.amount).sum::<Decimal>();
+        let amount = match promotion.discount_type {
+            DiscountType::Percentage => remaining * value / Decimal::ONE_HUNDRED,
+            DiscountType::AmountOff => value,
+        };
+        let amount = round(amount.min(remaining));
+        if amount > Decimal::ZERO {
+            order_discounts.push(applied(promotion, amount));
+        }
+    }
+
+    PromotionEvaluation {
+        lines: results,
+        order_discounts,
+        rejected_coupons: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn promotion(
+        id: &str,
+        discount_type: DiscountType,
+        value: &str,
+        target: PromotionTarget,
+    ) -> Promotion {
+        Promotion {
+            id: id.to_string(),
+            name: id.to_string(),
+            discount_type,
+            value: Decimal128::from_str(value).unwrap(),
+            currency: Currency::USD,
+            target,
+            min_order_total: None,
+            coupon_code: None,
+            usage_limit: None,
+            usage_count: 0,
+            stacking: StackingRule::Stackable,
+            priority: 0,
+            start_date: Utc::now() - Duration::days(1),
+            end_date: Utc::now() + Duration::days(1),
+        }
+    }
+
+    fn line(line_num: i32, sku: &str, quantity: i32, price: &str, category: &str) -> CartLine {
+        CartLine {
+            line_num,
+            sku: sku.to_string(),
+            quantity,
+            unit_price: Decimal::from_str(price).unwrap(),
+            categories: vec![category.to_string()],
+        }
+    }
+
+    fn cart() -> Vec<CartLine> {
+        vec![
+            line(1, "TENT-1", 1, "200.00", "Tents"),
+            line(2, "SOCK-1", 3, "10.00", "Clothing"),
+        ]
+    }
+
+    fn evaluate(promotions: &[Promotion], codes: &[&str]) -> PromotionEvaluation {
+        let codes: Vec<String> = codes.iter().map(|c| c.to_string()).collect();
+        evaluate_promotions(promotions, &cart(), Currency::USD, &codes, Utc::now())
+    }
+
+    #[test]
+    fn line_and_order_discounts_stack() {
+        let mut tents = promotion(
+            "tents-10",
+            DiscountType::Percentage,
+            "10",
+            PromotionTarget::Categories(vec!["tents".to_string()]),
+        );
+        tents.priority = 10;
+        let socks = promotion(
+            "socks-2-off",
+            DiscountType::AmountOff,
+            "2.00",
+            PromotionTarget::Skus(vec!["SOCK-1".to_string()]),
+        );
+        let mut order = promotion(
+            "order-5",
+            DiscountType::Percentage,
+            "5",
+            PromotionTarget::Order,
+        );
+        order.min_order_total = Some(Decimal128::from_str("150").unwrap());
+
+        let evaluation = evaluate(&[order, socks, tents], &[]);
+        assert_eq!(evaluation.subtotal(), Decimal::from(230));
+        assert_eq!(evaluation.lines[0].discount_total(), Decimal::from(20));
+        assert_eq!(evaluation.lines[1].discount_total(), Decimal::from(6));
+        // 5% of the 204 left after line discounts
+        assert_eq!(evaluation.order_discounts[0].amount.to_string(), "10.20");
+        assert_eq!(evaluation.discount_total().to_string(), "36.20");
+        assert_eq!(evaluation.discount_detail().len(), 3);
+    }
+
+    #[test]
+    fn coupons_are_checked_and_rejections_explained() {
+        let mut welcome = promotion(
+            "welcome",
+            DiscountType::AmountOff,
+            "15",
+            PromotionTarget::Order,
+        );
+        welcome.coupon_code = Some("WELCOME".to_string());
+        let mut used_up = promotion(
+            "vip",
+            DiscountType::Percentage,
+            "50",
+            PromotionTarget::Order,
+        );
+        used_up.coupon_code = Some("VIP".to_string());
+        used_up.usage_limit = Some(1);
+        used_up.usage_count = 1;
+
+        let without = evaluate(&[welcome.clone(), used_up.clone()], &[]);
+        assert_eq!(without.discount_total(), Decimal::ZERO);
+
+        let with = evaluate(&[welcome, used_up], &[" welcome ", "VIP", "NOPE"]);
+        assert_eq!(with.discount_total(), Decimal::from(15));
+        assert_eq!(
+            with.order_discounts[0].coupon_code.as_deref(),
+            Some("WELCOME")
+        );
+        let reasons: Vec<(&str, &str)> = with
+            .rejected_coupons
+            .iter()
+            .map(|r| (r.code.as_str(), r.reason.as_str()))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                ("VIP", "Coupon usage limit reached"),
+                ("NOPE", "Unknown coupon code")
+            ]
+        );
+    }
+
+    #[test]
+    fn exclusive_promotion_wins_only_when_it_saves_more() {
+        let stackable = promotion(
+            "all-10",
+            DiscountType::Percentage,
+            "10",
+            PromotionTarget::Order,
+        );
+        let mut exclusive = promotion(
+            "tent-deal",
+            DiscountType::AmountOff,
+            "50",
+            PromotionTarget::Skus(vec!["TENT-1".to_string()]),
+        );
+        exclusive.stacking = StackingRule::Exclusive;
+        exclusive.coupon_code = Some("TENT50".to_string());
+
+        let evaluation = evaluate(&[stackable.clone(), exclusive.clone()], &["TENT50"]);
+        assert_eq!(evaluation.applied_promotion_ids(), vec!["tent-deal"]);
+        assert_eq!(evaluation.discount_total(), Decimal::from(50));
+
+        exclusive.value = Decimal128::from_str("5").unwrap();
+        let evaluation = evaluate(&[stackable, exclusive], &["TENT50"]);
+        assert_eq!(evaluation.applied_promotion_ids(), vec!["all-10"]);
+        assert_eq!(
+            evaluation.rejected_coupons[0].reason,
+            "A better promotion that cannot be combined was applied"
+        );
+    }
+
+    #[test]
+    fn discounts_never_exceed_the_line() {
+        let big = promotion(
+            "socks-free",
+            DiscountType::AmountOff,
+            "25",
+            PromotionTarget::Skus(vec!["SOCK-1".to_string()]),
+        );
+        let evaluation = evaluate(&[big], &[]);
+        assert_eq!(evaluation.lines[1].discount_total(), Decimal::from(30));
+        assert!(evaluation.lines[0].discounts.is_empty());
+    }
+}
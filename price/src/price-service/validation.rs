@@ -16,7 +16,7 @@ pub async fn validate_price_dependencies(
         .await
         .with_context("Failed to list price collections")?;
 
-    for required_collection in &["prices", "offers"] {
+    for required_collection in &["prices", "offers", "price_lists", "promotions"] {
         if collections.contains(&required_collection.to_string()) {
             debug!("✅ Collection '{required_collection}' exists");
         } else {
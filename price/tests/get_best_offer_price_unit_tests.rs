@@ -10,6 +10,7 @@ mod tests {
             quantity: 5,
             date: None, // Optional field
             currency: "USD".to_string(),
+            price_list_context: None,
         };
 
         assert_eq!(request.sku, "TEST-SKU-001");
@@ -3,8 +3,9 @@ mod tests {
     use chrono::NaiveDate;
     use prost::Message;
     use rust_price::{
-        model::Offer,
+        model::{DBError, Offer, PriceList, PriceListContext},
         offer_messages::{GetBestOfferPricesRequest, GetBestOfferPricesResponse, SkuOfferResult},
+        persistence::price_list_dao::PriceListDao,
     };
     use std::collections::HashMap;
 
@@ -16,6 +17,7 @@ mod tests {
             quantity: 5,
             date: None,
             currency: "USD".to_string(),
+            price_list_context: None,
         };
 
         assert_eq!(request.skus.len(), 2);
@@ -94,6 +96,7 @@ mod tests {
             quantity: 10,
             date: Some("2024-12-01".to_string()),
             currency: "EUR".to_string(),
+            price_list_context: None,
         };
 
         // Encode to bytes
@@ -148,6 +151,7 @@ mod tests {
                 _quantity: i32,
                 _date: NaiveDate,
                 _currency: &str,
+                _price_list_ids: &[String],
             ) -> Result<Option<Offer>, rust_price::model::DBError> {
                 unimplemented!()
             }
@@ -158,14 +162,23 @@ mod tests {
                 _quantity: i32,
                 _date: NaiveDate,
                 _currency: &str,
+                _price_list_ids: &[String],
             ) -> Result<HashMap<String, Option<Offer>>, rust_price::model::DBError> {
                 Err(rust_price::model::DBError::Other(Box::new(
                     std::io::Error::other("Mock error"),
                 )))
             }
+
+            async fn count_offers_in_price_list(
+                &self,
+                _price_list_id: &str,
+            ) -> Result<u64, rust_price::model::DBError> {
+                unimplemented!()
+            }
         }
 
         let mock_dao = MockErrorDao;
+        let price_list_dao = MockPriceListDao(vec![]);
 
         // Test validation errors
         let result = get_best_offer_prices(
@@ -173,7 +186,9 @@ mod tests {
             5,
             None,
             "USD".to_string(),
+            None,
             &mock_dao,
+            &price_list_dao,
         )
         .await;
 
@@ -189,7 +204,16 @@ mod tests {
 
         // Test too many SKUs
         let many_skus: Vec<String> = (0..101).map(|i| format!("SKU-{i:03}")).collect();
-        let result = get_best_offer_prices(many_skus, 5, None, "USD".to_string(), &mock_dao).await;
+        let result = get_best_offer_prices(
+            many_skus,
+            5,
+            None,
+            "USD".to_string(),
+            None,
+            &mock_dao,
+            &price_list_dao,
+        )
+        .await;
 
         match result {
             Err(HandlerError::ValidationError(msg)) => {
@@ -207,7 +231,9 @@ mod tests {
             5,
             None,
             "USD".to_string(),
+            None,
             &mock_dao,
+            &price_list_dao,
         )
         .await;
 
@@ -222,6 +248,122 @@ mod tests {
         }
     }
 
+    // Price list DAO that serves a fixed set of lists
+    struct MockPriceListDao(Vec<PriceList>);
+
+    #[async_trait::async_trait]
+    impl PriceListDao for MockPriceListDao {
+        async fn create_price_list(&self, price_list: PriceList) -> Result<PriceList, DBError> {
+            Ok(price_list)
+        }
+
+        async fn get_price_list(&self, price_list_id: &str) -> Result<Option<PriceList>, DBError> {
+            Ok(self.0.iter().find(|list| list.id == price_list_id).cloned())
+        }
+
+        async fn list_price_lists(&self) -> Result<Vec<PriceList>, DBError> {
+            Ok(self.0.clone())
+        }
+
+        async fn delete_price_list(&self, _price_list_id: &str) -> Result<bool, DBError> {
+            Ok(true)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_price_list_context_is_resolved_by_priority() {
+        use rust_price::handlers::handlers_inner::get_best_offer_prices;
+        use rust_price::persistence::offer_dao::OfferDao;
+        use std::sync::Mutex;
+
+        // Offer DAO that records the price lists it was asked to search
+        #[derive(Default)]
+        struct RecordingDao(Mutex<Vec<String>>);
+
+        #[async_trait::async_trait]
+        impl OfferDao for RecordingDao {
+            async fn create_offer(&self, offer: Offer) -> Result<Offer, DBError> {
+                Ok(offer)
+            }
+
+            async fn delete_offer(&self, _offer_id: String) -> Result<(), DBError> {
+                Ok(())
+            }
+
+            async fn get_offer(&self, _offer_id: String) -> Result<Option<Offer>, DBError> {
+                Ok(None)
+            }
+
+            async fn find_best_offer_price(
+                &self,
+                _sku: &str,
+                _quantity: i32,
+                _date: NaiveDate,
+                _currency: &str,
+                _price_list_ids: &[String],
+            ) -> Result<Option<Offer>, DBError> {
+                Ok(None)
+            }
+
+            async fn find_best_offer_prices(
+                &self,
+                skus: &[String],
+                _quantity: i32,
+                _date: NaiveDate,
+                _currency: &str,
+                price_list_ids: &[String],
+            ) -> Result<HashMap<String, Option<Offer>>, DBError> {
+                *self.0.lock().unwrap() = price_list_ids.to_vec();
+                Ok(skus.iter().map(|sku| (sku.clone(), None)).collect())
+            }
+
+            async fn count_offers_in_price_list(
+                &self,
+                _price_list_id: &str,
+            ) -> Result<u64, DBError> {
+                Ok(0)
+            }
+        }
+
+        let price_list = |id: &str, channel: Option<&str>, priority: i32| PriceList {
+            id: id.to_string(),
+            channel: channel.map(String::from),
+            customer_group: None,
+            priority,
+            currency: iso_currency::Currency::USD,
+        };
+        let price_list_dao = MockPriceListDao(vec![
+            price_list("web-sale", Some("web"), 5),
+            price_list("b2b", Some("b2b"), 10),
+            price_list("clearance", None, 20),
+        ]);
+        let offer_dao = RecordingDao::default();
+
+        let lookup = |context: Option<PriceListContext>| {
+            get_best_offer_prices(
+                vec!["TEST-SKU-001".to_string()],
+                5,
+                None,
+                "USD".to_string(),
+                context,
+                &offer_dao,
+                &price_list_dao,
+            )
+        };
+
+        // Context values compare case-insensitively
+        let b2b = PriceListContext {
+            channel: Some(" B2B ".to_string()),
+            customer_group: None,
+        };
+        assert!(lookup(Some(b2b)).await.is_ok());
+        assert_eq!(*offer_dao.0.lock().unwrap(), vec!["clearance", "b2b"]);
+
+        // Without a context only the default list is searched
+        assert!(lookup(None).await.is_ok());
+        assert!(offer_dao.0.lock().unwrap().is_empty());
+    }
+
     #[test]
     fn test_sku_parsing_and_deduplication() {
         // Test parsing comma-separated SKUs (simulating CLI input parsing)
@@ -296,6 +438,7 @@ mod tests {
                         min_quantity: 1,
                         max_quantity: None,
                         offer_prices: vec![],
+                        price_list_id: None,
                     }),
                     found,
                 }
@@ -329,6 +472,7 @@ mod tests {
                 price: Decimal128::from_str("10.00").unwrap(),
                 currency: Currency::from_code("USD").unwrap(),
             }],
+            price_list_id: None,
         }
     }
 }
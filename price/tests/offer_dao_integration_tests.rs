@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod tests {
+    use bson::Decimal128;
+    use chrono::{Duration, NaiveDate, TimeZone, Utc};
+    use iso_currency::Currency;
+    use rust_common::test_helpers::{cleanup_test_db, TestConfig};
+    use rust_price::{
+        model::{Offer, OfferBuilder, OfferPrice},
+        persistence::offer_dao::{OfferDao, OfferDaoImpl},
+    };
+    use std::str::FromStr;
+
+    fn offer(sku: &str, price_list_id: Option<&str>, price: &str) -> Offer {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut offer = OfferBuilder::new(
+            sku.to_string(),
+            start,
+            start + Duration::days(365),
+            1,
+            vec![OfferPrice {
+                price: Decimal128::from_str(price).unwrap(),
+                currency: Currency::USD,
+            }],
+        )
+        .max_quantity(100)
+        .build();
+        offer.price_list_id = price_list_id.map(String::from);
+        offer
+    }
+
+    // Requires MongoDB at MONGODB_TEST_URL
+    #[tokio::test]
+    async fn test_best_offer_ignores_cheaper_offers_in_lists_that_do_not_apply() {
+        let config = TestConfig::default();
+        let client = mongodb::Client::with_uri_str(&config.mongodb_url)
+            .await
+            .expect("Failed to connect to MongoDB");
+        let dao = OfferDaoImpl::new(
+            client
+                .database(&config.test_db_name)
+                .collection::<Offer>("offers"),
+        );
+
+        // Priced so that the cheapest offer is never the right one
+        for offer in [
+            offer("SKU-A", Some("other"), "1.00"),
+            offer("SKU-A", None, "5.00"),
+            offer("SKU-A", Some("everyone"), "8.00"),
+            offer("SKU-A", Some("b2b"), "9.50"),
+            offer("SKU-A", Some("b2b"), "9.00"),
+            offer("SKU-B", Some("other"), "1.00"),
+            offer("SKU-B", None, "5.00"),
+            offer("SKU-C", Some("other"), "1.00"),
+        ] {
+            dao.create_offer(offer).await.expect("Should create offer");
+        }
+
+        let lists = vec!["b2b".to_string(), "everyone".to_string()];
+        let date = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let price = |offer: Option<Offer>| {
+            offer.map(|offer| (offer.price_list_id, offer.offer_prices[0].price.to_string()))
+        };
+
+        let best = dao
+            .find_best_offer_price("SKU-A", 1, date, "USD", &lists)
+            .await
+            .expect("Lookup should succeed");
+        assert_eq!(
+            price(best),
+            Some((Some("b2b".to_string()), "9.00".to_string()))
+        );
+
+        let mut best = dao
+            .find_best_offer_prices(
+                &[
+                    "SKU-A".to_string(),
+                    "SKU-B".to_string(),
+                    "SKU-C".to_string(),
+                ],
+                1,
+                date,
+                "USD",
+                &lists,
+            )
+            .await
+            .expect("Lookup should succeed");
+        assert_eq!(
+            price(best.remove("SKU-A").flatten()),
+            Some((Some("b2b".to_string()), "9.00".to_string()))
+        );
+        // Falls back to the default list
+        assert_eq!(
+            price(best.remove("SKU-B").flatten()),
+            Some((None, "5.00".to_string()))
+        );
+        assert_eq!(price(best.remove("SKU-C").flatten()), None);
+
+        cleanup_test_db(&client, &config.test_db_name)
+            .await
+            .expect("Failed to drop test database");
+    }
+}
@@ -0,0 +1,266 @@
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use bson::Decimal128;
+    use chrono::{DateTime, Duration, Utc};
+    use iso_currency::Currency;
+    use rust_decimal::Decimal;
+    use rust_price::{
+        handlers::handlers_inner::{
+            create_promotion, evaluate_promotions, redeem_promotions, release_promotions,
+            HandlerError,
+        },
+        model::DBError,
+        persistence::promotion_dao::PromotionDao,
+        promotions::{
+            CartLine, DiscountType, Promotion, PromotionRedemption, PromotionTarget, StackingRule,
+        },
+    };
+    use std::str::FromStr;
+    use std::sync::Mutex;
+
+    // Promotion DAO that keeps promotions and their redemptions in memory
+    #[derive(Default)]
+    struct InMemoryPromotionDao(Mutex<Vec<Promotion>>, Mutex<Vec<PromotionRedemption>>);
+
+    #[async_trait]
+    impl PromotionDao for InMemoryPromotionDao {
+        async fn create_promotion(&self, promotion: Promotion) -> Result<Promotion, DBError> {
+            self.0.lock().unwrap().push(promotion.clone());
+            Ok(promotion)
+        }
+
+        async fn get_promotion(&self, promotion_id: &str) -> Result<Option<Promotion>, DBError> {
+            Ok(self
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|p| p.id == promotion_id)
+                .cloned())
+        }
+
+        async fn get_promotion_by_coupon_code(
+            &self,
+            coupon_code: &str,
+        ) -> Result<Option<Promotion>, DBError> {
+            Ok(self
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|p| p.coupon_code.as_deref() == Some(coupon_code))
+                .cloned())
+        }
+
+        async fn list_promotions(&self) -> Result<Vec<Promotion>, DBError> {
+            Ok(self.0.lock().unwrap().clone())
+        }
+
+        async fn delete_promotion(&self, promotion_id: &str) -> Result<bool, DBError> {
+            let mut promotions = self.0.lock().unwrap();
+            let before = promotions.len();
+            promotions.retain(|p| p.id != promotion_id);
+            Ok(promotions.len() < before)
+        }
+
+        async fn find_applicable_promotions(
+            &self,
+            _currency: Currency,
+            _coupon_codes: &[String],
+            _now: DateTime<Utc>,
+        ) -> Result<Vec<Promotion>, DBError> {
+            Ok(self.0.lock().unwrap().clone())
+        }
+
+        async fn redeem_promotion(&self, promotion_id: &str) -> Result<bool, DBError> {
+            let mut promotions = self.0.lock().unwrap();
+            match promotions.iter_mut().find(|p| p.id == promotion_id) {
+                Some(p) if !p.usage_exhausted() => {
+                    p.usage_count += 1;
+                    Ok(true)
+                }
+                _ => Ok(false),
+            }
+        }
+
+        async fn release_promotion(&self, promotion_id: &str) -> Result<(), DBError> {
+            if let Some(p) = self
+                .0
+                .lock()
+                .unwrap()
+                .iter_mut()
+                .find(|p| p.id == promotion_id)
+            {
+                p.usage_count -= 1;
+            }
+            Ok(())
+        }
+
+        async fn record_redemption(
+            &self,
+            redemption: PromotionRedemption,
+        ) -> Result<bool, DBError> {
+            let mut redemptions = self.1.lock().unwrap();
+            if redemptions.iter().any(|r| r.id == redemption.id) {
+                return Ok(false);
+            }
+            redemptions.push(redemption);
+            Ok(true)
+        }
+
+        async fn delete_redemption(
+            &self,
+            promotion_id: &str,
+            order_id: &str,
+        ) -> Result<bool, DBError> {
+            let mut redemptions = self.1.lock().unwrap();
+            let before = redemptions.len();
+            redemptions.retain(|r| r.promotion_id != promotion_id || r.order_id != order_id);
+            Ok(redemptions.len() < before)
+        }
+
+        async fn list_order_redemptions(
+            &self,
+            order_id: &str,
+        ) -> Result<Vec<PromotionRedemption>, DBError> {
+            Ok(self
+                .1
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|r| r.order_id == order_id)
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn promotion(id: &str, coupon_code: Option<&str>, usage_limit: Option<i32>) -> Promotion {
+        Promotion {
+            id: id.to_string(),
+            name: format!("Promotion {id}"),
+            discount_type: DiscountType::Percentage,
+            value: Decimal128::from_str("10").unwrap(),
+            currency: Currency::USD,
+            target: PromotionTarget::Order,
+            min_order_total: None,
+            coupon_code: coupon_code.map(String::from),
+            usage_limit,
+            usage_count: 0,
+            stacking: StackingRule::Stackable,
+            priority: 0,
+            start_date: Utc::now() - Duration::days(1),
+            end_date: Utc::now() + Duration::days(30),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_and_evaluate_coupon_promotion() {
+        let dao = InMemoryPromotionDao::default();
+
+        let created = create_promotion(promotion("spring", Some(" spring10 "), None), &dao)
+            .await
+            .unwrap_or_else(|_| panic!("Expected the promotion to be created"));
+        assert_eq!(created.coupon_code.as_deref(), Some("SPRING10"));
+
+        let duplicate = create_promotion(promotion("spring-2", Some("Spring10"), None), &dao).await;
+        match duplicate {
+            Err(HandlerError::ValidationError(msg)) => assert!(msg.contains("already used")),
+            _ => panic!("Expected ValidationError for a duplicate coupon code"),
+        }
+
+        let mut over_100 = promotion("half", None, None);
+        over_100.value = Decimal128::from_str("150").unwrap();
+        assert!(matches!(
+            create_promotion(over_100, &dao).await,
+            Err(HandlerError::ValidationError(_))
+        ));
+
+        let lines = vec![CartLine {
+            line_num: 1,
+            sku: "TENT-1".to_string(),
+            quantity: 2,
+            unit_price: Decimal::from(50),
+            categories: vec![],
+        }];
+        let evaluation = evaluate_promotions(
+            lines,
+            "USD".to_string(),
+            vec!["spring10".to_string()],
+            None,
+            &dao,
+        )
+        .await
+        .unwrap_or_else(|_| panic!("Expected an evaluation"));
+        assert_eq!(evaluation.discount_total(), Decimal::from(10));
+        assert_eq!(
+            evaluation.discount_detail().get("spring"),
+            Some(&Decimal::from(10))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_redeem_releases_uses_when_one_promotion_is_used_up() {
+        let dao = InMemoryPromotionDao::default();
+        let mut used_up = promotion("vip", Some("VIP"), Some(1));
+        used_up.usage_count = 1;
+        dao.0
+            .lock()
+            .unwrap()
+            .extend([promotion("spring", Some("SPRING10"), Some(5)), used_up]);
+
+        let result = redeem_promotions(
+            "order-1".to_string(),
+            vec!["spring".to_string(), "vip".to_string()],
+            &dao,
+        )
+        .await;
+        assert!(matches!(result, Err(HandlerError::ValidationError(_))));
+        assert_eq!(dao.0.lock().unwrap()[0].usage_count, 0);
+        assert!(dao.1.lock().unwrap().is_empty());
+
+        assert!(
+            redeem_promotions("order-1".to_string(), vec!["spring".to_string()], &dao)
+                .await
+                .is_ok()
+        );
+        assert_eq!(dao.0.lock().unwrap()[0].usage_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_redeem_counts_once_per_order_and_release_gives_the_use_back() {
+        let dao = InMemoryPromotionDao::default();
+        dao.0
+            .lock()
+            .unwrap()
+            .push(promotion("spring", Some("SPRING10"), Some(5)));
+        let spring = || vec!["spring".to_string()];
+
+        // A retried redeem for the same order changes nothing
+        for _ in 0..2 {
+            assert!(redeem_promotions("order-1".to_string(), spring(), &dao)
+                .await
+                .is_ok());
+        }
+        assert_eq!(dao.0.lock().unwrap()[0].usage_count, 1);
+        assert!(redeem_promotions("order-2".to_string(), spring(), &dao)
+            .await
+            .is_ok());
+        assert_eq!(dao.0.lock().unwrap()[0].usage_count, 2);
+
+        // Releasing all of an order's redemptions, then again, gives back one use
+        for expected in [1, 0] {
+            match release_promotions("order-1".to_string(), vec![], &dao).await {
+                Ok(released) => assert_eq!(released, expected),
+                Err(_) => panic!("Expected the release to succeed"),
+            }
+        }
+        assert_eq!(dao.0.lock().unwrap()[0].usage_count, 1);
+
+        // Once released, the order can redeem the promotion again
+        assert!(redeem_promotions("order-1".to_string(), spring(), &dao)
+            .await
+            .is_ok());
+        assert_eq!(dao.0.lock().unwrap()[0].usage_count, 2);
+    }
+}
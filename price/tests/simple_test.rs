@@ -25,6 +25,7 @@ mod tests {
                 currency: Currency::USD,
                 price: Decimal128::from_str("19.99").unwrap(),
             }],
+            price_list_id: None,
         };
 
         assert_eq!(offer.sku, "TEST-001");